@@ -0,0 +1,57 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Exposes the commit and build date to `BuildInfo::engine`
+fn main() {
+    let git_hash = git(&["rev-parse", "--short", "HEAD"]).unwrap_or_default();
+    println!("cargo:rustc-env=LASTOR_GIT_HASH={}", git_hash);
+
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible
+    let seconds = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs()));
+    println!("cargo:rustc-env=LASTOR_BUILD_DATE={}", format_date(seconds));
+
+    // HEAD lives in the worktree's own git dir, branches in the shared one. Missing
+    // paths are skipped, since cargo would rerun this script on every build for them.
+    let git_dir = git(&["rev-parse", "--git-dir"]).map(PathBuf::from);
+    let common_dir = git(&["rev-parse", "--git-common-dir"]).map(PathBuf::from);
+    let watched = [
+        git_dir.map(|dir| dir.join("HEAD")),
+        common_dir.as_ref().map(|dir| dir.join("refs")),
+        common_dir.as_ref().map(|dir| dir.join("packed-refs")),
+    ];
+    for path in watched.into_iter().flatten().filter(|path| path.exists()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
+/// Trimmed stdout of a git command, or None outside a repo or without git
+fn git(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|output| output.trim().to_string())
+}
+
+/// YYYY-MM-DD (UTC) for seconds since the Unix epoch
+fn format_date(seconds: u64) -> String {
+    // Days to civil date, from Howard Hinnant's date algorithms
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
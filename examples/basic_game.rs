@@ -18,7 +18,8 @@ impl Player {
 }
 
 impl Entity for Player {
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let dt = ctx.dt;
         let mut movement = Vec2::ZERO;
         
         if is_key_down(KeyCode::Right) || is_key_down(KeyCode::D) {
@@ -103,7 +104,8 @@ impl Enemy {
 }
 
 impl Entity for Enemy {
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let dt = ctx.dt;
         // Simple AI: move toward a random target
         let distance_to_target = self.transform.position.distance_to(self.target_position);
         
@@ -163,7 +165,7 @@ impl Entity for Enemy {
 struct CameraController;
 
 impl Entity for CameraController {
-    fn update(&mut self, _dt: f32) {
+    fn update(&mut self, _ctx: &mut UpdateContext) {
         // Camera logic will be handled in main loop
     }
     
@@ -198,14 +200,10 @@ async fn main() {
 
     // Add player in the world center
     let player_pos = Vec2::new(1000.0, 1000.0);
-    let player = Box::new(Player::new(player_pos));
-    let player_ref: *const Player = &*player; // raw pointer to access later
-    game.add_entity(player);
+    let player = game.add_entity(Box::new(Player::new(player_pos)));
 
     // Set camera to follow player dynamically
-    game.get_scene_mut().camera.set_follow_target(move || unsafe {
-        (*player_ref).transform.position
-    });
+    game.get_scene_mut().camera.follow_entity(player);
     game.get_scene_mut().camera.set_follow_speed(6.0);
                 game.get_scene_mut()
                 .camera
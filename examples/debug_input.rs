@@ -16,12 +16,9 @@ impl TestPlayer {
 }
 
 impl Entity for TestPlayer {
-    fn update(&mut self, _dt: f32) {
-        // Default update does nothing
-    }
-    
-    fn update_with_input(&mut self, dt: f32, input: &InputManager) {
-        println!("update_with_input called!"); // Debug print
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let (dt, input) = (ctx.dt, ctx.input);
+        println!("update called!"); // Debug print
         
         // Test raw input first
         if is_key_down(KeyCode::W) {
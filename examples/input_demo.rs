@@ -22,12 +22,9 @@ impl Player {
 }
 
 impl Entity for Player {
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let (dt, input) = (ctx.dt, ctx.input);
         self.last_shot_time += dt;
-    }
-    
-    fn update_with_input(&mut self, dt: f32, input: &InputManager) {
-        self.update(dt);
         
         // Movement using the input manager
         let movement = input.get_movement_input();
@@ -162,7 +159,8 @@ impl MovingTarget {
 }
 
 impl Entity for MovingTarget {
-    fn update(&mut self, dt: f32) {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let dt = ctx.dt;
         // Move in current direction
         self.transform.translate(self.direction * self.speed * dt);
         
@@ -246,7 +244,7 @@ impl InstructionsUI {
 }
 
 impl Entity for InstructionsUI {
-    fn update(&mut self, _dt: f32) {
+    fn update(&mut self, _ctx: &mut UpdateContext) {
         // Nothing to update for UI
     }
     
@@ -279,12 +277,6 @@ impl Entity for InstructionsUI {
             }
             y += 18.0;
         }
-        
-        // Draw mouse crosshair
-        let (mouse_x, mouse_y) = mouse_position();
-        draw_circle_lines(mouse_x, mouse_y, 12.0, 2.0, RED);
-        draw_line(mouse_x - 6.0, mouse_y, mouse_x + 6.0, mouse_y, 2.0, RED);
-        draw_line(mouse_x, mouse_y - 6.0, mouse_x, mouse_y + 6.0, 2.0, RED);
     }
     
     fn is_active(&self) -> bool {
@@ -305,6 +297,7 @@ async fn main() {
     };
 
     let mut game = Game::with_config(config);
+    game.set_cursor(Cursor::Crosshair { color: RED, size: 24.0 });
 
     // Customize input bindings
     {
@@ -324,14 +317,10 @@ async fn main() {
     }
 
     // --- Player setup ---
-    let player = Box::new(Player::new(Vec2::new(200.0, 400.0)));
-    let player_ref: *const Player = &*player;
-    game.add_entity(player);
+    let player = game.add_entity(Box::new(Player::new(Vec2::new(200.0, 400.0))));
 
     // --- Camera setup ---
-    game.get_scene_mut().camera.set_follow_target(move || unsafe {
-        (*player_ref).transform.position
-    });
+    game.get_scene_mut().camera.follow_entity(player);
 
     game.get_scene_mut().camera.set_follow_speed(6.0);
 
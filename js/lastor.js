@@ -0,0 +1,16 @@
+// miniquad plugin for WASM builds: load it after gl.js so screenshots can be downloaded
+// (see `Screenshot::save`)
+register_plugin = function (importObject) {
+    importObject.env.lastor_download = function (name_ptr, name_len, data_ptr, data_len) {
+        const name = UTF8ToString(name_ptr, name_len);
+        const data = new Uint8Array(wasm_memory.buffer, data_ptr, data_len).slice();
+        const link = document.createElement("a");
+        link.href = URL.createObjectURL(new Blob([data], { type: "image/png" }));
+        link.download = name;
+        link.click();
+        // Some browsers start the download after click() returns
+        setTimeout(() => URL.revokeObjectURL(link.href), 1000);
+    };
+}
+
+miniquad_add_plugin({ register_plugin, version: 1, name: "lastor" });
@@ -0,0 +1,365 @@
+use macroquad::audio::{load_sound, Sound};
+use macroquad::miniquad::fs::Error as FsError;
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+#[cfg(feature = "hot-reload")]
+use std::time::SystemTime;
+
+/// Error returned when an asset fails to load
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetError {
+    NotFound(String),
+    Load { path: String, message: String },
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetError::NotFound(path) => write!(f, "asset not found: '{}'", path),
+            AssetError::Load { path, message } => write!(f, "failed to load '{}': {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl AssetError {
+    fn from_macroquad(path: &str, err: macroquad::Error) -> Self {
+        match err {
+            macroquad::Error::FileError { kind: FsError::IOError(err), .. } if err.kind() == std::io::ErrorKind::NotFound => {
+                AssetError::NotFound(path.to_string())
+            }
+            macroquad::Error::FileError { kind: FsError::IOSAssetNoSuchFile, .. } => AssetError::NotFound(path.to_string()),
+            err => AssetError::Load { path: path.to_string(), message: err.to_string() },
+        }
+    }
+}
+
+/// Kind of asset waiting in the load queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetKind {
+    Texture,
+    Sound,
+    Font,
+}
+
+/// How far a batch load has come, for loading screens
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadProgress {
+    /// Assets finished (including failed ones) out of `total`
+    pub loaded: usize,
+    pub total: usize,
+    /// Path of the asset being loaded next
+    pub current: Option<String>,
+    pub errors: Vec<AssetError>,
+}
+
+impl LoadProgress {
+    /// 0.0 to 1.0 (1.0 when there was nothing to load)
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 { 1.0 } else { self.loaded as f32 / self.total as f32 }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.loaded >= self.total
+    }
+}
+
+/// Loads textures, sounds and fonts once and hands out the cached handles.
+///
+/// Macroquad handles are cheap to clone, so every entity using a texture can keep its
+/// own copy of the same one. Assets can be loaded on demand or queued and loaded as a
+/// batch behind a loading screen (`Game::load_assets`):
+///
+/// ```ignore
+/// game.set_assets(AssetManager::new().with_root("assets"));
+/// game.get_assets_mut().queue_texture("player.png");
+/// game.get_assets_mut().queue_sound("jump.wav");
+/// game.load_assets().await;
+/// let player = spawn().sprite(game.get_assets().texture("player.png").unwrap().clone());
+/// ```
+#[derive(Default)]
+pub struct AssetManager {
+    root: Option<PathBuf>,
+    filter: Option<FilterMode>,
+    textures: HashMap<String, Texture2D>,
+    sounds: HashMap<String, Sound>,
+    fonts: HashMap<String, Font>,
+    queue: Vec<(AssetKind, String)>,
+    progress: LoadProgress,
+    // Modification times of loaded textures and watched data files, by path
+    #[cfg(feature = "hot-reload")]
+    modified: HashMap<String, Option<SystemTime>>,
+}
+
+impl AssetManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Directory that asset paths are relative to
+    pub fn with_root(mut self, root: &str) -> Self {
+        self.root = Some(PathBuf::from(root));
+        self
+    }
+
+    /// Filter set on every texture loaded from now on (`FilterMode::Nearest` for pixel art)
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    pub async fn load_texture(&mut self, path: &str) -> Result<Texture2D, AssetError> {
+        if let Some(texture) = self.textures.get(path) {
+            return Ok(texture.clone());
+        }
+        let full_path = self.resolve(path);
+        let texture = macroquad::texture::load_texture(&full_path)
+            .await
+            .map_err(|err| AssetError::from_macroquad(&full_path, err))?;
+        if let Some(filter) = self.filter {
+            texture.set_filter(filter);
+        }
+        #[cfg(feature = "hot-reload")]
+        self.modified.insert(path.to_string(), modified_time(&full_path));
+        self.textures.insert(path.to_string(), texture.clone());
+        Ok(texture)
+    }
+
+    pub async fn load_sound(&mut self, path: &str) -> Result<Sound, AssetError> {
+        if let Some(sound) = self.sounds.get(path) {
+            return Ok(sound.clone());
+        }
+        let full_path = self.resolve(path);
+        let sound = load_sound(&full_path).await.map_err(|err| AssetError::from_macroquad(&full_path, err))?;
+        self.sounds.insert(path.to_string(), sound.clone());
+        Ok(sound)
+    }
+
+    pub async fn load_font(&mut self, path: &str) -> Result<Font, AssetError> {
+        if let Some(font) = self.fonts.get(path) {
+            return Ok(font.clone());
+        }
+        let full_path = self.resolve(path);
+        let font = load_ttf_font(&full_path).await.map_err(|err| AssetError::from_macroquad(&full_path, err))?;
+        self.fonts.insert(path.to_string(), font.clone());
+        Ok(font)
+    }
+
+    /// A loaded texture (None if it hasn't been loaded)
+    pub fn texture(&self, path: &str) -> Option<&Texture2D> {
+        self.textures.get(path)
+    }
+
+    pub fn sound(&self, path: &str) -> Option<&Sound> {
+        self.sounds.get(path)
+    }
+
+    pub fn font(&self, path: &str) -> Option<&Font> {
+        self.fonts.get(path)
+    }
+
+    pub fn is_loaded(&self, kind: AssetKind, path: &str) -> bool {
+        match kind {
+            AssetKind::Texture => self.textures.contains_key(path),
+            AssetKind::Sound => self.sounds.contains_key(path),
+            AssetKind::Font => self.fonts.contains_key(path),
+        }
+    }
+
+    /// Drop a cached asset; handles already given out stay valid
+    pub fn unload(&mut self, kind: AssetKind, path: &str) -> bool {
+        match kind {
+            AssetKind::Texture => {
+                #[cfg(feature = "hot-reload")]
+                self.modified.remove(path);
+                self.textures.remove(path).is_some()
+            }
+            AssetKind::Sound => self.sounds.remove(path).is_some(),
+            AssetKind::Font => self.fonts.remove(path).is_some(),
+        }
+    }
+
+    /// Drop every cached asset, the queue and watched files
+    pub fn clear(&mut self) {
+        self.textures.clear();
+        self.sounds.clear();
+        self.fonts.clear();
+        self.queue.clear();
+        self.progress = LoadProgress::default();
+        #[cfg(feature = "hot-reload")]
+        self.modified.clear();
+    }
+
+    // Batch loading
+
+    /// Add an asset to the next batch load (skipped if loaded or queued already)
+    pub fn queue(&mut self, kind: AssetKind, path: &str) {
+        let queued = self.queue.iter().any(|(queued_kind, queued_path)| *queued_kind == kind && queued_path == path);
+        if queued || self.is_loaded(kind, path) {
+            return;
+        }
+        if self.progress.is_done() {
+            self.progress = LoadProgress::default();
+        }
+        self.queue.push((kind, path.to_string()));
+        self.progress.total += 1;
+        if self.progress.current.is_none() {
+            self.progress.current = Some(path.to_string());
+        }
+    }
+
+    pub fn queue_texture(&mut self, path: &str) {
+        self.queue(AssetKind::Texture, path);
+    }
+
+    pub fn queue_sound(&mut self, path: &str) {
+        self.queue(AssetKind::Sound, path);
+    }
+
+    pub fn queue_font(&mut self, path: &str) {
+        self.queue(AssetKind::Font, path);
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn progress(&self) -> &LoadProgress {
+        &self.progress
+    }
+
+    /// Load the next queued asset; false once the queue is empty. Failures are kept in
+    /// `progress().errors` and the batch carries on.
+    pub async fn load_next(&mut self) -> bool {
+        if self.queue.is_empty() {
+            return false;
+        }
+        let (kind, path) = self.queue.remove(0);
+        let result = match kind {
+            AssetKind::Texture => self.load_texture(&path).await.map(|_| ()),
+            AssetKind::Sound => self.load_sound(&path).await.map(|_| ()),
+            AssetKind::Font => self.load_font(&path).await.map(|_| ()),
+        };
+        if let Err(err) = result {
+            self.progress.errors.push(err);
+        }
+        self.progress.loaded += 1;
+        self.progress.current = self.queue.first().map(|(_, path)| path.clone());
+        true
+    }
+
+    /// Load everything queued without drawing anything in between
+    pub async fn load_queued(&mut self) -> Vec<AssetError> {
+        while self.load_next().await {}
+        self.progress.errors.clone()
+    }
+
+    // Hot reload
+
+    /// Report changes to a data file (level, tilemap, particle or tuning file) from
+    /// `reload_if_changed`
+    #[cfg(feature = "hot-reload")]
+    pub fn watch(&mut self, path: &str) {
+        let modified = modified_time(&self.resolve(path));
+        self.modified.insert(path.to_string(), modified);
+    }
+
+    #[cfg(feature = "hot-reload")]
+    pub fn unwatch(&mut self, path: &str) {
+        if !self.textures.contains_key(path) {
+            self.modified.remove(path);
+        }
+    }
+
+    /// Swap in textures whose files changed on disk and report changed data files.
+    ///
+    /// A texture with the same size is updated in place, so every handle given out shows
+    /// the new image. One whose size changed gets a new handle (`AssetReload::Replaced`);
+    /// look it up again to use it. Files that fail to decode keep the old texture.
+    #[cfg(feature = "hot-reload")]
+    pub fn reload_if_changed(&mut self) -> Vec<AssetReload> {
+        let mut reloads = vec![];
+        let paths: Vec<String> = self.modified.keys().cloned().collect();
+        for path in paths {
+            let full_path = self.resolve(&path);
+            let modified = modified_time(&full_path);
+            if modified.is_none() || self.modified.get(&path) == Some(&modified) {
+                continue;
+            }
+            // Remember the timestamp even if decoding fails, so a broken file isn't re-read every check
+            self.modified.insert(path.clone(), modified);
+            let Some(texture) = self.textures.get(&path) else {
+                reloads.push(AssetReload::Data(path));
+                continue;
+            };
+
+            let image = std::fs::read(&full_path)
+                .map_err(|err| err.to_string())
+                .and_then(|bytes| Image::from_file_with_format(&bytes, None).map_err(|err| err.to_string()));
+            let image = match image {
+                Ok(image) => image,
+                Err(message) => {
+                    reloads.push(AssetReload::Failed(AssetError::Load { path: full_path, message }));
+                    continue;
+                }
+            };
+            if texture.width() as u16 == image.width && texture.height() as u16 == image.height {
+                texture.update(&image);
+                reloads.push(AssetReload::Texture(path));
+            } else {
+                let texture = Texture2D::from_image(&image);
+                if let Some(filter) = self.filter {
+                    texture.set_filter(filter);
+                }
+                self.textures.insert(path.clone(), texture);
+                reloads.push(AssetReload::Replaced(path));
+            }
+        }
+        reloads
+    }
+
+    fn resolve(&self, path: &str) -> String {
+        match &self.root {
+            Some(root) => root.join(path).to_string_lossy().into_owned(),
+            None => path.to_string(),
+        }
+    }
+}
+
+/// A file that changed on disk, from `AssetManager::reload_if_changed`
+#[cfg(feature = "hot-reload")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetReload {
+    /// Updated in place; existing handles show the new image
+    Texture(String),
+    /// The size changed, so the texture got a new handle
+    Replaced(String),
+    /// A watched data file changed; reload it
+    Data(String),
+    Failed(AssetError),
+}
+
+#[cfg(feature = "hot-reload")]
+fn modified_time(path: &str) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// The default loading screen: a progress bar with the asset being loaded
+pub fn draw_loading_bar(progress: &LoadProgress, color: Color) {
+    let width = (screen_width() * 0.5).min(480.0);
+    let height = 12.0;
+    let x = (screen_width() - width) * 0.5;
+    let y = screen_height() * 0.5;
+    draw_rectangle_lines(x - 2.0, y - 2.0, width + 4.0, height + 4.0, 2.0, color);
+    draw_rectangle(x, y, width * progress.fraction(), height, color);
+
+    let label = match &progress.current {
+        Some(path) => format!("Loading {} ({}/{})", path, progress.loaded, progress.total),
+        None => format!("Loading ({}/{})", progress.loaded, progress.total),
+    };
+    let size = measure_text(&label, None, 16, 1.0);
+    draw_text(&label, (screen_width() - size.width) * 0.5, y + height + 24.0, 16.0, color);
+}
@@ -0,0 +1,8 @@
+pub mod manager;
+/// Startup (or `--validate`) checks of prefabs, levels and other content files
+pub mod validation;
+
+pub use manager::{draw_loading_bar, AssetError, AssetKind, AssetManager, LoadProgress};
+pub use validation::{validate_requested, ContentValidator, FileReport, Severity, ValidationIssue, ValidationReport};
+#[cfg(feature = "hot-reload")]
+pub use manager::AssetReload;
@@ -0,0 +1,430 @@
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use crate::audio::SoundDef;
+use crate::bullets::BulletPattern;
+use crate::core::{EntityRegistry, PrefabDef, SceneFile, Timeline};
+
+/// How bad a content problem is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// One problem found in a content file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub file: PathBuf,
+    /// 1-based, where the problem could be pinned down
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    /// e.g. "assets/levels/1-1.ron:12:9: error: unknown entity kind 'batt'"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        write!(f, ": {}: {}", self.severity, self.message)
+    }
+}
+
+/// Everything a validation pass found
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+    /// Content files read
+    pub files: usize,
+}
+
+impl ValidationReport {
+    /// True if there are no errors (warnings are allowed)
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    /// Send every issue to the game log (category "content")
+    pub fn log(&self) {
+        for issue in &self.issues {
+            match issue.severity {
+                Severity::Warning => crate::log_warn!("content", "{}", issue),
+                Severity::Error => crate::log_error!("content", "{}", issue),
+            }
+        }
+    }
+
+    /// Process exit code for `--validate` runs: 0 without errors, 1 with
+    pub fn exit_code(&self) -> i32 {
+        if self.is_ok() { 0 } else { 1 }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        write!(f, "checked {} files: {} errors, {} warnings", self.files, self.errors().count(), self.warnings().count())
+    }
+}
+
+/// Issues found in one file, handed to custom checks (see `ContentValidator::with_check`)
+pub struct FileReport<'t> {
+    file: PathBuf,
+    text: &'t str,
+    // Byte offset `error_at` and `warning_at` search from, so repeated names get
+    // successive lines
+    cursor: usize,
+    issues: Vec<ValidationIssue>,
+}
+
+impl<'t> FileReport<'t> {
+    fn new(file: &Path, text: &'t str) -> Self {
+        Self { file: file.to_path_buf(), text, cursor: 0, issues: vec![] }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.file
+    }
+
+    /// Parse the file as RON, reporting schema errors with their position
+    pub fn parse<T: DeserializeOwned>(&mut self) -> Option<T> {
+        match ron::from_str(self.text) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                self.push(Severity::Error, Some((err.position.line, err.position.col)), err.code.to_string());
+                None
+            }
+        }
+    }
+
+    pub fn error(&mut self, line: Option<usize>, message: &str) {
+        self.push(Severity::Error, line.map(|line| (line, 0)), message.to_string());
+    }
+
+    pub fn warning(&mut self, line: Option<usize>, message: &str) {
+        self.push(Severity::Warning, line.map(|line| (line, 0)), message.to_string());
+    }
+
+    /// Report an error on the line of the next `"name"` in the file
+    pub fn error_at(&mut self, name: &str, message: &str) {
+        let position = self.locate(name);
+        self.push(Severity::Error, position, message.to_string());
+    }
+
+    pub fn warning_at(&mut self, name: &str, message: &str) {
+        let position = self.locate(name);
+        self.push(Severity::Warning, position, message.to_string());
+    }
+
+    /// Line and column of the next quoted `name` after the cursor, moving the cursor past it
+    fn locate(&mut self, name: &str) -> Option<(usize, usize)> {
+        let quoted = format!("\"{}\"", name);
+        let found = self.text[self.cursor..].find(&quoted)
+            .map(|offset| self.cursor + offset)
+            .or_else(|| self.text.find(&quoted))?;
+        self.cursor = found + quoted.len();
+        let before = &self.text[..found];
+        let line = before.matches('\n').count() + 1;
+        let column = found - before.rfind('\n').map_or(0, |newline| newline + 1) + 1;
+        Some((line, column))
+    }
+
+    fn push(&mut self, severity: Severity, position: Option<(usize, usize)>, message: String) {
+        self.issues.push(ValidationIssue {
+            severity,
+            file: self.file.clone(),
+            line: position.map(|(line, _)| line),
+            // Column 0 = only the line is known
+            column: position.map(|(_, column)| column).filter(|column| *column > 0),
+            message,
+        });
+    }
+}
+
+type ContentCheck<'a> = Box<dyn Fn(&mut FileReport) + 'a>;
+
+#[cfg(feature = "json")]
+const LEVEL_EXTENSIONS: &[&str] = &["ron", "json"];
+#[cfg(not(feature = "json"))]
+const LEVEL_EXTENSIONS: &[&str] = &["ron"];
+
+/// Loads every content file up front and reports missing assets, broken references and
+/// schema errors with file and line, so data bugs show up before playtesting. Paths are
+/// relative to the asset root; directories are searched for `.ron` files (and `.json`
+/// levels with the `json` feature).
+///
+/// ```ignore
+/// let report = ContentValidator::new("assets")
+///     .with_prefabs("prefabs")
+///     .with_levels("levels")
+///     .with_registry(&registry)
+///     .with_sound_events("sounds.ron")
+///     .with_schema::<LootTable>("loot")
+///     .with_check("lang", |file| {
+///         let Some(strings) = file.parse::<HashMap<String, String>>() else { return };
+///         for key in REQUIRED_KEYS.iter().filter(|key| !strings.contains_key(**key)) {
+///             file.error(None, &format!("missing string '{}'", key));
+///         }
+///     })
+///     .run();
+/// report.log();
+/// if validate_requested() {
+///     println!("{}", report);
+///     std::process::exit(report.exit_code());
+/// }
+/// ```
+pub struct ContentValidator<'a> {
+    root: PathBuf,
+    prefabs: Vec<PathBuf>,
+    levels: Vec<PathBuf>,
+    timelines: Vec<PathBuf>,
+    patterns: Vec<PathBuf>,
+    sound_events: Vec<PathBuf>,
+    checks: Vec<(PathBuf, ContentCheck<'a>)>,
+    registry: Option<&'a EntityRegistry>,
+}
+
+impl<'a> ContentValidator<'a> {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            prefabs: vec![],
+            levels: vec![],
+            timelines: vec![],
+            patterns: vec![],
+            sound_events: vec![],
+            checks: vec![],
+            registry: None,
+        }
+    }
+
+    /// Prefab definition files: sprites must exist under the root
+    pub fn with_prefabs<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.prefabs.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Scene files: with `with_registry`, every entity must spawn from its record
+    pub fn with_levels<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.levels.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Factories for level entities; each record is spawned from its data to check it, so
+    /// enum fields are read by variant name like when the level loads
+    pub fn with_registry(mut self, registry: &'a EntityRegistry) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    pub fn with_timelines<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.timelines.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Bullet pattern files
+    pub fn with_patterns<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.patterns.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sound event maps: sound files must exist under the root
+    pub fn with_sound_events<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.sound_events.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Game-specific files that only need to parse as `T` (loot tables, animators, ...)
+    pub fn with_schema<T: DeserializeOwned>(self, path: impl AsRef<Path>) -> Self {
+        self.with_check(path, |file| {
+            file.parse::<T>();
+        })
+    }
+
+    /// Game-specific files checked by `check`, which parses the file and reports issues
+    pub fn with_check<P, F>(mut self, path: P, check: F) -> Self
+    where
+        P: AsRef<Path>,
+        F: Fn(&mut FileReport) + 'a,
+    {
+        self.checks.push((path.as_ref().to_path_buf(), Box::new(check)));
+        self
+    }
+
+    /// Read and check every file
+    pub fn run(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        self.each_file(&self.prefabs, &["ron"], &mut report, |file| {
+            let Some(defs) = file.parse::<BTreeMap<String, PrefabDef>>() else { return };
+            for (name, def) in &defs {
+                if let Some(sprite) = &def.sprite {
+                    self.check_asset(file, sprite, &format!("prefab '{}'", name));
+                }
+            }
+        });
+        self.each_file(&self.levels, LEVEL_EXTENSIONS, &mut report, |file| self.check_level(file));
+        self.each_file(&self.timelines, &["ron"], &mut report, |file| {
+            file.parse::<Timeline>();
+        });
+        self.each_file(&self.patterns, &["ron"], &mut report, |file| {
+            file.parse::<HashMap<String, BulletPattern>>();
+        });
+        self.each_file(&self.sound_events, &["ron"], &mut report, |file| {
+            let Some(events) = file.parse::<BTreeMap<String, SoundDef>>() else { return };
+            for (name, def) in &events {
+                if def.sounds.is_empty() {
+                    file.warning_at(name, &format!("sound event '{}' has no sounds", name));
+                }
+                for sound in &def.sounds {
+                    self.check_asset(file, sound, &format!("sound event '{}'", name));
+                }
+            }
+        });
+        for (path, check) in &self.checks {
+            self.each_file(std::slice::from_ref(path), &["ron"], &mut report, |file| check(file));
+        }
+        report
+    }
+
+    fn check_level(&self, file: &mut FileReport) {
+        let level = match file.path().extension().and_then(|ext| ext.to_str()) {
+            #[cfg(feature = "json")]
+            Some(ext) if ext.eq_ignore_ascii_case("json") => {
+                let value = match serde_json::from_str(file.text) {
+                    Ok(value) => value,
+                    Err(err) => {
+                        file.push(Severity::Error, Some((err.line(), err.column())), err.to_string());
+                        return;
+                    }
+                };
+                match SceneFile::from_json_value(value) {
+                    Ok(level) => level,
+                    Err(err) => {
+                        file.push(Severity::Error, None, err.to_string());
+                        return;
+                    }
+                }
+            }
+            _ => match file.parse::<SceneFile>() {
+                Some(level) => level,
+                None => return,
+            },
+        };
+        let Some(registry) = self.registry else {
+            return;
+        };
+        for record in &level.entities {
+            // Unknown kinds and data the factory can't read
+            match registry.spawn(record) {
+                Ok(_) => {
+                    // Keep the search position in step with the records
+                    file.locate(&record.kind);
+                }
+                Err(err) => file.error_at(&record.kind, &err.to_string()),
+            }
+        }
+    }
+
+    /// Report `asset` if it isn't a file under the root
+    fn check_asset(&self, file: &mut FileReport, asset: &str, owner: &str) {
+        if !self.root.join(asset).is_file() {
+            file.error_at(asset, &format!("{} uses missing asset '{}'", owner, asset));
+        }
+    }
+
+    /// Run `check` on every content file under each path
+    fn each_file<F>(&self, paths: &[PathBuf], extensions: &[&str], report: &mut ValidationReport, mut check: F)
+    where
+        F: FnMut(&mut FileReport),
+    {
+        for path in paths {
+            let path = self.root.join(path);
+            let files = match content_files(&path, extensions) {
+                Ok(files) => files,
+                Err(err) => {
+                    report.issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        file: path,
+                        line: None,
+                        column: None,
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            for file in files {
+                report.files += 1;
+                let text = match std::fs::read_to_string(&file) {
+                    Ok(text) => text,
+                    Err(err) => {
+                        let mut file = FileReport::new(&file, "");
+                        file.error(None, &err.to_string());
+                        report.issues.append(&mut file.issues);
+                        continue;
+                    }
+                };
+                let mut file = FileReport::new(&file, &text);
+                check(&mut file);
+                report.issues.append(&mut file.issues);
+            }
+        }
+    }
+}
+
+/// `path` itself if it's a file, otherwise the files with one of `extensions` under it, sorted
+fn content_files(path: &Path, extensions: &[&str]) -> std::io::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = vec![];
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?.path();
+        if entry.is_dir() {
+            files.extend(content_files(&entry, extensions)?);
+        } else if has_extension(&entry, extensions) {
+            files.push(entry);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| extensions.iter().any(|wanted| ext.eq_ignore_ascii_case(wanted)))
+}
+
+/// Whether the game was started with `--validate`, to check content and exit instead of
+/// playing (e.g. in CI)
+pub fn validate_requested() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--validate")
+}
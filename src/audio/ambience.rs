@@ -0,0 +1,372 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::AudioManager;
+use crate::assets::AssetManager;
+use crate::core::rng;
+
+/// A sound looping under a scene for as long as its bed plays
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbientLayer {
+    pub sound: String,
+    pub volume: f32,
+}
+
+impl Default for AmbientLayer {
+    fn default() -> Self {
+        Self { sound: String::new(), volume: 1.0 }
+    }
+}
+
+/// One-shots played over a bed at random intervals, e.g. a distant howl or dripping water
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stinger {
+    /// Sound files, one picked at random per play
+    pub sounds: Vec<String>,
+    /// Seconds between plays, picked per play
+    pub interval: (f32, f32),
+    pub volume: (f32, f32),
+    /// How long the sounds play, in seconds, for the voice budget
+    pub length: f32,
+}
+
+impl Default for Stinger {
+    fn default() -> Self {
+        Self { sounds: vec![], interval: (10.0, 30.0), volume: (0.6, 1.0), length: 3.0 }
+    }
+}
+
+/// Looping layers and random stingers that make up the background sound of a place
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbientBed {
+    pub layers: Vec<AmbientLayer>,
+    pub stingers: Vec<Stinger>,
+    pub volume: f32,
+}
+
+impl Default for AmbientBed {
+    fn default() -> Self {
+        Self { layers: vec![], stingers: vec![], volume: 1.0 }
+    }
+}
+
+impl AmbientBed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_layer(mut self, sound: &str, volume: f32) -> Self {
+        self.layers.push(AmbientLayer { sound: sound.to_string(), volume });
+        self
+    }
+
+    pub fn with_stinger(mut self, stinger: Stinger) -> Self {
+        self.stingers.push(stinger);
+        self
+    }
+
+    pub fn with_volume(mut self, volume: f32) -> Self {
+        self.volume = volume;
+        self
+    }
+}
+
+/// How sound effects are played while the listener is in a space. Macroquad has no
+/// effects, so reverb is approximated with quieter, delayed repeats of each sound.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reverb {
+    /// Volume of the sound itself (below 1 muffles it)
+    pub dry: f32,
+    /// Volume of the first echo, relative to the sound
+    pub wet: f32,
+    /// Seconds between echoes
+    pub delay: f32,
+    /// Volume of each echo relative to the one before
+    pub decay: f32,
+    pub echoes: u32,
+}
+
+impl Default for Reverb {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl Reverb {
+    /// Sounds play as they are
+    pub fn none() -> Self {
+        Self { dry: 1.0, wet: 0.0, delay: 0.1, decay: 0.5, echoes: 0 }
+    }
+
+    /// A small room: one quick, quiet echo
+    pub fn room() -> Self {
+        Self { dry: 1.0, wet: 0.25, delay: 0.06, decay: 0.5, echoes: 1 }
+    }
+
+    /// A large hall: a few spread-out echoes
+    pub fn hall() -> Self {
+        Self { dry: 0.9, wet: 0.4, delay: 0.15, decay: 0.55, echoes: 3 }
+    }
+
+    /// A cave: long, slowly fading echoes
+    pub fn cave() -> Self {
+        Self { dry: 0.85, wet: 0.5, delay: 0.25, decay: 0.7, echoes: 5 }
+    }
+
+    pub fn is_none(&self) -> bool {
+        self.wet <= 0.0 || self.echoes == 0
+    }
+
+    /// Blend toward another reverb (`t` from 0 to 1)
+    pub fn lerp(&self, other: &Reverb, t: f32) -> Reverb {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        // Fade echoes in and out with the wet level instead of popping them
+        let (wet, echoes) = match (self.echoes, other.echoes) {
+            (0, _) => (other.wet * t, other.echoes),
+            (_, 0) => (self.wet * (1.0 - t), self.echoes),
+            _ => (mix(self.wet, other.wet), mix(self.echoes as f32, other.echoes as f32).round() as u32),
+        };
+        let (delay, decay) = match (self.echoes, other.echoes) {
+            (0, _) => (other.delay, other.decay),
+            (_, 0) => (self.delay, self.decay),
+            _ => (mix(self.delay, other.delay), mix(self.decay, other.decay)),
+        };
+        Reverb { dry: mix(self.dry, other.dry), wet, delay, decay, echoes }
+    }
+}
+
+/// A part of a level with its own ambience and reverb, e.g. a cave off a forest
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioZone {
+    /// Where the listener has to be, as (min, max) in world units
+    pub area: (Vec2, Vec2),
+    /// Replaces the scene's bed inside the zone
+    pub ambience: Option<AmbientBed>,
+    /// Replaces the scene's reverb inside the zone
+    pub reverb: Option<Reverb>,
+}
+
+impl AudioZone {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self { area: (min, max), ..Default::default() }
+    }
+
+    pub fn with_ambience(mut self, bed: AmbientBed) -> Self {
+        self.ambience = Some(bed);
+        self
+    }
+
+    pub fn with_reverb(mut self, reverb: Reverb) -> Self {
+        self.reverb = Some(reverb);
+        self
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        let (min, max) = self.area;
+        point.x >= min.x && point.x <= max.x && point.y >= min.y && point.y <= max.y
+    }
+}
+
+/// Ambient sound of a scene, usually part of its level file (`SceneFile::audio`):
+///
+/// ```ron
+/// audio: Some((
+///     ambience: Some((
+///         layers: [(sound: "amb/forest.ogg", volume: 0.6), (sound: "amb/wind.ogg", volume: 0.3)],
+///         stingers: [(sounds: ["amb/owl1.ogg", "amb/owl2.ogg"], interval: (15.0, 40.0))],
+///     )),
+///     zones: [
+///         (area: ((800.0, 0.0), (1400.0, 600.0)), ambience: Some((layers: [(sound: "amb/drips.ogg")])), reverb: Some((wet: 0.5, delay: 0.25, decay: 0.7, echoes: 5))),
+///     ],
+/// )),
+/// ```
+///
+/// Where zones overlap, the first one listed that sets a bed or reverb wins. `Game` plays
+/// the base scene's audio around the main camera and cross-fades over `fade_time` when
+/// the camera moves between zones or the scene's audio changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneAudio {
+    pub ambience: Option<AmbientBed>,
+    pub reverb: Option<Reverb>,
+    pub zones: Vec<AudioZone>,
+    /// Seconds to cross-fade beds and reverb
+    pub fade_time: f32,
+}
+
+impl Default for SceneAudio {
+    fn default() -> Self {
+        Self { ambience: None, reverb: None, zones: vec![], fade_time: 2.0 }
+    }
+}
+
+impl SceneAudio {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_ambience(mut self, bed: AmbientBed) -> Self {
+        self.ambience = Some(bed);
+        self
+    }
+
+    pub fn with_reverb(mut self, reverb: Reverb) -> Self {
+        self.reverb = Some(reverb);
+        self
+    }
+
+    pub fn with_zone(mut self, zone: AudioZone) -> Self {
+        self.zones.push(zone);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ambience.is_none() && self.reverb.is_none() && self.zones.is_empty()
+    }
+
+    /// Bed playing at a listener position
+    pub fn ambience_at(&self, point: Vec2) -> Option<&AmbientBed> {
+        self.zones.iter()
+            .filter(|zone| zone.contains(point))
+            .find_map(|zone| zone.ambience.as_ref())
+            .or(self.ambience.as_ref())
+    }
+
+    /// Reverb at a listener position
+    pub fn reverb_at(&self, point: Vec2) -> Reverb {
+        self.zones.iter()
+            .filter(|zone| zone.contains(point))
+            .find_map(|zone| zone.reverb)
+            .or(self.reverb)
+            .unwrap_or_default()
+    }
+
+    /// Register every sound file the beds use with the `AudioManager`, taking them from
+    /// `assets` (load them first). Returns the files that aren't loaded.
+    pub fn register_sounds(&self, audio: &AudioManager, assets: &AssetManager) -> Vec<String> {
+        let beds = self.ambience.iter().chain(self.zones.iter().filter_map(|zone| zone.ambience.as_ref()));
+        let mut missing = vec![];
+        for bed in beds {
+            let layers = bed.layers.iter().map(|layer| (&layer.sound, 0.0));
+            let stingers = bed.stingers.iter().flat_map(|stinger| stinger.sounds.iter().map(|file| (file, stinger.length)));
+            for (file, length) in layers.chain(stingers) {
+                if audio.contains(file) {
+                    continue;
+                }
+                match assets.sound(file) {
+                    Some(sound) => audio.register(file, sound.clone(), length),
+                    None if !missing.contains(file) => missing.push(file.clone()),
+                    None => {}
+                }
+            }
+        }
+        missing
+    }
+}
+
+/// A bed fading in or out
+struct PlayingBed {
+    bed: AmbientBed,
+    level: f32,
+    fading_out: bool,
+    // Seconds until each stinger plays next
+    stinger_timers: Vec<f32>,
+}
+
+/// Plays a scene's beds and applies its reverb around a listener (`Game` runs one for the
+/// base scene)
+#[derive(Default)]
+pub(crate) struct AmbiencePlayer {
+    beds: Vec<PlayingBed>,
+    // Loops started, with the volume last set
+    loops: HashMap<String, f32>,
+    reverb: Reverb,
+}
+
+impl AmbiencePlayer {
+    pub(crate) fn update(&mut self, audio: &AudioManager, scene: &SceneAudio, listener: Vec2, dt: f32) {
+        let step = if scene.fade_time > 0.0 { (dt / scene.fade_time).min(1.0) } else { 1.0 };
+
+        // Fade out beds that are no longer wanted and in the one that is
+        let wanted = scene.ambience_at(listener);
+        for playing in &mut self.beds {
+            playing.fading_out = Some(&playing.bed) != wanted;
+        }
+        let beds = &self.beds;
+        if let Some(bed) = wanted.filter(|bed| !beds.iter().any(|playing| &playing.bed == *bed)) {
+            let stinger_timers = bed.stingers.iter().map(|stinger| random_in(stinger.interval)).collect();
+            self.beds.push(PlayingBed { bed: bed.clone(), level: 0.0, fading_out: false, stinger_timers });
+        }
+        for playing in &mut self.beds {
+            let target = if playing.fading_out { 0.0 } else { 1.0 };
+            playing.level += (target - playing.level).clamp(-step, step);
+        }
+        self.beds.retain(|playing| !playing.fading_out || playing.level > 0.0);
+
+        // Loops shared by two beds play once, at the louder volume
+        let mut volumes: HashMap<&str, f32> = HashMap::new();
+        for playing in &self.beds {
+            for layer in &playing.bed.layers {
+                let volume = layer.volume * playing.bed.volume * playing.level;
+                let entry = volumes.entry(layer.sound.as_str()).or_insert(0.0);
+                *entry = entry.max(volume);
+            }
+        }
+        let stopped: Vec<String> = self.loops.keys().filter(|sound| !volumes.contains_key(sound.as_str())).cloned().collect();
+        for sound in stopped {
+            audio.stop(&sound);
+            self.loops.remove(&sound);
+        }
+        for (sound, volume) in volumes {
+            match self.loops.get(sound) {
+                Some(last) if *last == volume => {}
+                Some(_) => audio.set_volume(sound, volume),
+                None => {
+                    if !audio.play_looped(sound, volume) {
+                        continue;
+                    }
+                }
+            }
+            self.loops.insert(sound.to_string(), volume);
+        }
+
+        for playing in self.beds.iter_mut().filter(|playing| !playing.fading_out) {
+            for (stinger, timer) in playing.bed.stingers.iter().zip(&mut playing.stinger_timers) {
+                *timer -= dt;
+                if *timer > 0.0 || stinger.sounds.is_empty() {
+                    continue;
+                }
+                *timer = random_in(stinger.interval);
+                let file = &stinger.sounds[rng::with_shared(|rng| rng.index(stinger.sounds.len()))];
+                audio.play(file, random_in(stinger.volume) * playing.bed.volume * playing.level);
+            }
+        }
+
+        let target = scene.reverb_at(listener);
+        if self.reverb != target {
+            self.reverb = self.reverb.lerp(&target, step);
+            // Snap once close enough, so the blend ends
+            if step >= 1.0 || ((self.reverb.wet - target.wet).abs() < 0.01 && (self.reverb.dry - target.dry).abs() < 0.01) {
+                self.reverb = target;
+            }
+            audio.set_reverb(self.reverb);
+        }
+    }
+
+    /// Stop every loop, e.g. when the game quits
+    pub(crate) fn stop(&mut self, audio: &AudioManager) {
+        for (sound, _) in self.loops.drain() {
+            audio.stop(&sound);
+        }
+        self.beds.clear();
+    }
+}
+
+fn random_in((min, max): (f32, f32)) -> f32 {
+    rng::with_shared(|rng| rng.range(min, max))
+}
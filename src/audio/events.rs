@@ -0,0 +1,244 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+use super::{AudioManager, PlayResult};
+use crate::assets::AssetManager;
+use crate::core::rng;
+
+/// Error returned when loading a sound event map
+#[derive(Debug)]
+pub enum SoundEventError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for SoundEventError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SoundEventError::Io(err) => write!(f, "failed to read sound events: {}", err),
+            SoundEventError::Format(msg) => write!(f, "invalid sound events: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SoundEventError {}
+
+impl From<std::io::Error> for SoundEventError {
+    fn from(err: std::io::Error) -> Self {
+        SoundEventError::Io(err)
+    }
+}
+
+/// How a gameplay event sounds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundDef {
+    /// Sound files, one picked at random per play. Each is played through the
+    /// `AudioManager` under its path (see `SoundEvents::register_sounds`).
+    pub sounds: Vec<String>,
+    /// Volume range, picked per play
+    pub volume: (f32, f32),
+    /// Pitch range, picked per play. Macroquad can't change pitch, so playback ignores it
+    /// for now.
+    pub pitch: (f32, f32),
+    /// Seconds before the event can sound again
+    pub cooldown: f32,
+    /// Most voices of this event playing at once
+    pub max_instances: Option<usize>,
+    /// How long the sounds play, in seconds, for the voice budget
+    pub length: f32,
+}
+
+impl Default for SoundDef {
+    fn default() -> Self {
+        Self { sounds: vec![], volume: (1.0, 1.0), pitch: (1.0, 1.0), cooldown: 0.0, max_instances: None, length: 1.0 }
+    }
+}
+
+impl SoundDef {
+    pub fn new(sound: &str) -> Self {
+        Self { sounds: vec![sound.to_string()], ..Default::default() }
+    }
+
+    /// Another file to pick from
+    pub fn with_sound(mut self, sound: &str) -> Self {
+        self.sounds.push(sound.to_string());
+        self
+    }
+
+    pub fn with_volume(mut self, min: f32, max: f32) -> Self {
+        self.volume = (min, max);
+        self
+    }
+
+    pub fn with_pitch(mut self, min: f32, max: f32) -> Self {
+        self.pitch = (min, max);
+        self
+    }
+
+    pub fn with_cooldown(mut self, seconds: f32) -> Self {
+        self.cooldown = seconds.max(0.0);
+        self
+    }
+
+    pub fn with_max_instances(mut self, max: usize) -> Self {
+        self.max_instances = Some(max);
+        self
+    }
+
+    pub fn with_length(mut self, seconds: f32) -> Self {
+        self.length = seconds.max(0.0);
+        self
+    }
+}
+
+struct EventMap {
+    audio: AudioManager,
+    defs: HashMap<String, SoundDef>,
+    // Seconds left before each event can sound again
+    cooldowns: HashMap<String, f32>,
+}
+
+/// Named gameplay events bound to sounds, so entity code says what happened and the
+/// sound design lives in a data file:
+///
+/// ```ron
+/// {
+///     "Jump": (sounds: ["sfx/jump.wav"], volume: (0.7, 0.9), cooldown: 0.05, length: 0.3),
+///     "CoinCollected": (sounds: ["sfx/coin1.wav", "sfx/coin2.wav"], max_instances: Some(3), length: 0.4),
+///     "EnemyDied": (sounds: ["sfx/pop.wav"], pitch: (0.9, 1.1), length: 0.6),
+/// }
+/// ```
+///
+/// ```ignore
+/// let events = game.get_sound_events();
+/// events.load("data/sound_events.ron")?;
+/// events.register_sounds(game.get_assets());
+/// // in entity code
+/// events.trigger_at("EnemyDied", self.transform.position);
+/// ```
+///
+/// Like `AudioManager`, this is a shared handle; `Game` hands out clones and ticks the
+/// cooldowns.
+#[derive(Clone)]
+pub struct SoundEvents {
+    map: Rc<RefCell<EventMap>>,
+}
+
+impl SoundEvents {
+    /// Events played through `audio`
+    pub fn new(audio: AudioManager) -> Self {
+        Self {
+            map: Rc::new(RefCell::new(EventMap { audio, defs: HashMap::new(), cooldowns: HashMap::new() })),
+        }
+    }
+
+    pub fn bind(&self, event: &str, def: SoundDef) {
+        self.map.borrow_mut().defs.insert(event.to_string(), def);
+    }
+
+    pub fn unbind(&self, event: &str) -> bool {
+        let mut map = self.map.borrow_mut();
+        map.cooldowns.remove(event);
+        map.defs.remove(event).is_some()
+    }
+
+    pub fn contains(&self, event: &str) -> bool {
+        self.map.borrow().defs.contains_key(event)
+    }
+
+    pub fn get(&self, event: &str) -> Option<SoundDef> {
+        self.map.borrow().defs.get(event).cloned()
+    }
+
+    /// Bind every event in a RON file, replacing existing bindings. Returns the events loaded.
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<String>, SoundEventError> {
+        let data = std::fs::read_to_string(path)?;
+        self.load_from_str(&data)
+    }
+
+    /// Bind every event in a RON string
+    pub fn load_from_str(&self, data: &str) -> Result<Vec<String>, SoundEventError> {
+        let defs: BTreeMap<String, SoundDef> = ron::from_str(data).map_err(|err| SoundEventError::Format(err.to_string()))?;
+        Ok(defs.into_iter().map(|(event, def)| {
+            self.bind(&event, def);
+            event
+        }).collect())
+    }
+
+    /// Register every sound file the events use with the `AudioManager`, taking them from
+    /// `assets` (load them first). Returns the files that aren't loaded.
+    pub fn register_sounds(&self, assets: &AssetManager) -> Vec<String> {
+        let map = self.map.borrow();
+        let mut missing = vec![];
+        for def in map.defs.values() {
+            for file in &def.sounds {
+                if map.audio.contains(file) {
+                    continue;
+                }
+                match assets.sound(file) {
+                    Some(sound) => map.audio.register(file, sound.clone(), def.length),
+                    None if !missing.contains(file) => missing.push(file.clone()),
+                    None => {}
+                }
+            }
+        }
+        missing
+    }
+
+    /// Sound an event that isn't tied to a place
+    pub fn trigger(&self, event: &str) -> PlayResult {
+        self.map.borrow_mut().trigger(event, None)
+    }
+
+    /// Sound an event at `position`, quieter the farther it is off-camera
+    pub fn trigger_at(&self, event: &str, position: Vec2) -> PlayResult {
+        self.map.borrow_mut().trigger(event, Some(position))
+    }
+
+    /// Seconds before an event can sound again (0.0 when ready)
+    pub fn cooldown(&self, event: &str) -> f32 {
+        self.map.borrow().cooldowns.get(event).copied().unwrap_or(0.0)
+    }
+
+    /// Tick cooldowns (`Game` calls this every frame)
+    pub fn update(&self, dt: f32) {
+        let mut map = self.map.borrow_mut();
+        for remaining in map.cooldowns.values_mut() {
+            *remaining -= dt;
+        }
+        map.cooldowns.retain(|_, remaining| *remaining > 0.0);
+    }
+}
+
+impl EventMap {
+    fn trigger(&mut self, event: &str, position: Option<Vec2>) -> PlayResult {
+        let Some(def) = self.defs.get(event) else {
+            return PlayResult::UnknownSound;
+        };
+        if def.sounds.is_empty() {
+            return PlayResult::UnknownSound;
+        }
+        if self.cooldowns.contains_key(event) {
+            return PlayResult::CoolingDown;
+        }
+        let playing: usize = def.sounds.iter().map(|file| self.audio.voices_of(file)).sum();
+        if def.max_instances.is_some_and(|max| playing >= max) {
+            return PlayResult::OverBudget;
+        }
+
+        let (file, volume) = rng::with_shared(|rng| (&def.sounds[rng.index(def.sounds.len())], rng.range(def.volume.0, def.volume.1)));
+        let result = match position {
+            Some(position) => self.audio.play_at(file, position, volume),
+            None => self.audio.play(file, volume),
+        };
+        if result.started() && def.cooldown > 0.0 {
+            self.cooldowns.insert(event.to_string(), def.cooldown);
+        }
+        result
+    }
+}
@@ -0,0 +1,7 @@
+pub mod voices;
+pub mod events;
+pub mod ambience;
+
+pub use voices::{AudioManager, PlayResult, VoiceStats};
+pub use events::{SoundDef, SoundEventError, SoundEvents};
+pub use ambience::{AmbientBed, AmbientLayer, AudioZone, Reverb, SceneAudio, Stinger};
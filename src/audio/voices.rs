@@ -0,0 +1,309 @@
+use macroquad::audio::{play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use super::Reverb;
+
+/// What happened to a play request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayResult {
+    Started,
+    /// Started after stopping a quieter or farther voice
+    StoleVoice,
+    /// Over budget, and every playing voice is louder
+    OverBudget,
+    /// The source is too far off-camera to hear
+    Culled,
+    /// A sound event played too recently (see `SoundEvents`)
+    CoolingDown,
+    UnknownSound,
+}
+
+impl PlayResult {
+    pub fn started(self) -> bool {
+        matches!(self, PlayResult::Started | PlayResult::StoleVoice)
+    }
+}
+
+/// Counts of play requests since the last `AudioManager::take_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VoiceStats {
+    pub started: u32,
+    pub stolen: u32,
+    pub over_budget: u32,
+    pub culled: u32,
+}
+
+struct SoundEntry {
+    sound: Sound,
+    // Macroquad can't tell how long a sound is or when it stops, so voices expire after this
+    length: f32,
+    max_voices: Option<usize>,
+}
+
+struct Voice {
+    name: String,
+    // Volume after distance falloff, compared when picking a voice to steal
+    loudness: f32,
+    remaining: f32,
+}
+
+/// A delayed repeat of a sound, for reverb
+struct Echo {
+    name: String,
+    loudness: f32,
+    delay: f32,
+}
+
+struct Mixer {
+    sounds: HashMap<String, SoundEntry>,
+    voices: Vec<Voice>,
+    reverb: Reverb,
+    echoes: Vec<Echo>,
+    max_voices: usize,
+    // Distance past the edge of the view where positional sounds fade out completely
+    hearing_range: f32,
+    // Views the player sees the world through; a sound is as loud as in its nearest one
+    listeners: Vec<(Vec2, Vec2)>,
+    stats: VoiceStats,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        Self {
+            sounds: HashMap::new(),
+            voices: vec![],
+            reverb: Reverb::none(),
+            echoes: vec![],
+            max_voices: 32,
+            hearing_range: 400.0,
+            listeners: vec![],
+            stats: VoiceStats::default(),
+        }
+    }
+}
+
+/// Plays sounds within a voice budget, so a hundred enemies dying at once doesn't play a
+/// hundred explosions.
+///
+/// Each sound can cap its own simultaneous voices, and there's a global cap. When a cap
+/// is reached, the quietest voice (after distance falloff) is stolen if the new one is
+/// louder; otherwise the new one is dropped. Positional sounds fade with distance
+/// from the camera view and aren't started at all once out of hearing range.
+///
+/// Macroquad stops every instance of a sound at once, so stealing a voice also ends the
+/// other voices of the same sound. The manager is a shared handle: `Game::get_audio`
+/// hands out clones, and the game keeps the listeners on the scene's camera views.
+///
+/// ```ignore
+/// let audio = game.get_audio();
+/// audio.register("explosion", explosion_sound, 1.2);
+/// audio.set_max_voices("explosion", 4);
+/// audio.play_at("explosion", enemy_position, 0.8);
+/// ```
+#[derive(Clone, Default)]
+pub struct AudioManager {
+    mixer: Rc<RefCell<Mixer>>,
+}
+
+impl AudioManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a sound; `length` is how long it plays, in seconds
+    pub fn register(&self, name: &str, sound: Sound, length: f32) {
+        self.mixer.borrow_mut().sounds.insert(name.to_string(), SoundEntry { sound, length: length.max(0.0), max_voices: None });
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.mixer.borrow().sounds.contains_key(name)
+    }
+
+    /// Cap simultaneous voices of one sound
+    pub fn set_max_voices(&self, name: &str, max: usize) {
+        if let Some(entry) = self.mixer.borrow_mut().sounds.get_mut(name) {
+            entry.max_voices = Some(max);
+        }
+    }
+
+    /// Cap simultaneous voices of all sounds together (32 by default)
+    pub fn set_max_voices_total(&self, max: usize) {
+        self.mixer.borrow_mut().max_voices = max;
+    }
+
+    /// How far past the edge of the view positional sounds can still be heard, in world units
+    pub fn set_hearing_range(&self, range: f32) {
+        self.mixer.borrow_mut().hearing_range = range.max(0.0);
+    }
+
+    /// The visible world area, as (min, max); positional sounds inside it play at full volume
+    pub fn set_listener(&self, view: (Vec2, Vec2)) {
+        self.set_listeners([view]);
+    }
+
+    /// Several visible areas (split-screen views), each as (min, max); a positional sound
+    /// falls off from the nearest one. `Game` sets these to `Scene::views` every frame.
+    pub fn set_listeners(&self, views: impl IntoIterator<Item = (Vec2, Vec2)>) {
+        self.mixer.borrow_mut().listeners = views.into_iter().collect();
+    }
+
+    /// Play a sound that isn't tied to a place (UI, music stings)
+    pub fn play(&self, name: &str, volume: f32) -> PlayResult {
+        self.mixer.borrow_mut().play_with_reverb(name, volume)
+    }
+
+    /// Loop a sound outside the voice budget, e.g. an ambient bed, until `stop`.
+    /// Returns false for unknown sounds.
+    pub fn play_looped(&self, name: &str, volume: f32) -> bool {
+        let mixer = self.mixer.borrow();
+        let Some(entry) = mixer.sounds.get(name) else {
+            return false;
+        };
+        play_sound(&entry.sound, PlaySoundParams { looped: true, volume: volume.clamp(0.0, 1.0) });
+        true
+    }
+
+    /// Change the volume of every playing instance of a sound
+    pub fn set_volume(&self, name: &str, volume: f32) {
+        if let Some(entry) = self.mixer.borrow().sounds.get(name) {
+            set_sound_volume(&entry.sound, volume.clamp(0.0, 1.0));
+        }
+    }
+
+    /// Reverb applied to sounds started from now on. `Game` sets this from the base
+    /// scene's audio zones (see `SceneAudio`).
+    pub fn set_reverb(&self, reverb: Reverb) {
+        self.mixer.borrow_mut().reverb = reverb;
+    }
+
+    pub fn reverb(&self) -> Reverb {
+        self.mixer.borrow().reverb
+    }
+
+    /// Play a sound coming from `position`, quieter the farther it is off-camera
+    pub fn play_at(&self, name: &str, position: Vec2, volume: f32) -> PlayResult {
+        let mut mixer = self.mixer.borrow_mut();
+        let falloff = mixer.falloff(position);
+        if falloff <= 0.0 {
+            mixer.stats.culled += 1;
+            return PlayResult::Culled;
+        }
+        mixer.play_with_reverb(name, volume * falloff)
+    }
+
+    /// Stop every voice of a sound
+    pub fn stop(&self, name: &str) {
+        let mut mixer = self.mixer.borrow_mut();
+        if let Some(entry) = mixer.sounds.get(name) {
+            stop_sound(&entry.sound);
+        }
+        mixer.voices.retain(|voice| voice.name != name);
+        mixer.echoes.retain(|echo| echo.name != name);
+    }
+
+    pub fn stop_all(&self) {
+        let mut mixer = self.mixer.borrow_mut();
+        for entry in mixer.sounds.values() {
+            stop_sound(&entry.sound);
+        }
+        mixer.voices.clear();
+        mixer.echoes.clear();
+    }
+
+    pub fn active_voices(&self) -> usize {
+        self.mixer.borrow().voices.len()
+    }
+
+    pub fn voices_of(&self, name: &str) -> usize {
+        self.mixer.borrow().voices.iter().filter(|voice| voice.name == name).count()
+    }
+
+    pub fn take_stats(&self) -> VoiceStats {
+        std::mem::take(&mut self.mixer.borrow_mut().stats)
+    }
+
+    /// Expire finished voices and play due echoes (`Game` calls this every frame)
+    pub fn update(&self, dt: f32) {
+        let mut mixer = self.mixer.borrow_mut();
+        for voice in &mut mixer.voices {
+            voice.remaining -= dt;
+        }
+        mixer.voices.retain(|voice| voice.remaining > 0.0);
+
+        for echo in &mut mixer.echoes {
+            echo.delay -= dt;
+        }
+        let (due, waiting) = std::mem::take(&mut mixer.echoes).into_iter().partition(|echo| echo.delay <= 0.0);
+        mixer.echoes = waiting;
+        for echo in due {
+            mixer.play(&echo.name, echo.loudness);
+        }
+    }
+}
+
+impl Mixer {
+    /// Play the sound through the current reverb: quieter when muffled, and echoed
+    fn play_with_reverb(&mut self, name: &str, loudness: f32) -> PlayResult {
+        let reverb = self.reverb;
+        let result = self.play(name, loudness * reverb.dry);
+        if result.started() && !reverb.is_none() {
+            let mut echo = loudness * reverb.wet;
+            for index in 1..=reverb.echoes {
+                self.echoes.push(Echo { name: name.to_string(), loudness: echo, delay: reverb.delay * index as f32 });
+                echo *= reverb.decay;
+            }
+        }
+        result
+    }
+
+    /// 1.0 inside a view, fading to 0.0 at the hearing range of the nearest one
+    fn falloff(&self, position: Vec2) -> f32 {
+        if self.listeners.is_empty() {
+            return 1.0;
+        }
+        let distance = self.listeners.iter()
+            .map(|&(min, max)| (min - position).max(position - max).max(Vec2::ZERO).length())
+            .fold(f32::INFINITY, f32::min);
+        if self.hearing_range <= 0.0 {
+            return if distance > 0.0 { 0.0 } else { 1.0 };
+        }
+        (1.0 - distance / self.hearing_range).clamp(0.0, 1.0)
+    }
+
+    fn play(&mut self, name: &str, loudness: f32) -> PlayResult {
+        let Some(entry) = self.sounds.get(name) else {
+            return PlayResult::UnknownSound;
+        };
+        let (sound, length, max_voices) = (entry.sound.clone(), entry.length, entry.max_voices);
+
+        // Check the sound's own cap first, then the global one
+        let same_sound = self.voices.iter().filter(|voice| voice.name == name).count();
+        let over_sound_cap = max_voices.is_some_and(|max| same_sound >= max);
+        let over_total_cap = self.voices.len() >= self.max_voices;
+        let mut result = PlayResult::Started;
+        if over_sound_cap || over_total_cap {
+            let victim = self.voices.iter()
+                .filter(|voice| !over_sound_cap || voice.name == name)
+                .min_by(|a, b| a.loudness.total_cmp(&b.loudness))
+                .filter(|voice| voice.loudness < loudness)
+                .map(|voice| voice.name.clone());
+            let Some(victim) = victim else {
+                self.stats.over_budget += 1;
+                return PlayResult::OverBudget;
+            };
+            if let Some(entry) = self.sounds.get(&victim) {
+                stop_sound(&entry.sound);
+            }
+            self.voices.retain(|voice| voice.name != victim);
+            self.stats.stolen += 1;
+            result = PlayResult::StoleVoice;
+        }
+
+        play_sound(&sound, PlaySoundParams { looped: false, volume: loudness.clamp(0.0, 1.0) });
+        self.voices.push(Voice { name: name.to_string(), loudness, remaining: length });
+        self.stats.started += 1;
+        result
+    }
+}
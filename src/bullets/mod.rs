@@ -0,0 +1,5 @@
+pub mod pattern;
+pub mod system;
+
+pub use pattern::{BulletPattern, BulletPatternLibrary, EmitterDef, EmitterShape, PatternError, SubEmitter};
+pub use system::{Bullet, BulletSystem, EmitterHandle};
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How each volley of an emitter is laid out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EmitterShape {
+    /// Bullets evenly spaced around a full circle
+    Ring { count: u32 },
+    /// `arms` evenly spaced bullets, rotated by `step` radians each volley
+    Spiral { arms: u32, step: f32 },
+    /// A fan of bullets centered on the system's target
+    AimedSpread { count: u32, spread: f32 },
+    /// A fan whose center sweeps back and forth by `sweep` radians, `frequency` times per second
+    Wave { count: u32, spread: f32, sweep: f32, frequency: f32 },
+}
+
+/// A child pattern fired from every bullet of an emitter after `after` seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubEmitter {
+    pub after: f32,
+    pub pattern: BulletPattern,
+    /// Rotate the child pattern to the parent bullet's heading
+    #[serde(default)]
+    pub inherit_direction: bool,
+    /// Remove the parent bullet when the child pattern fires (a "splitting" bullet)
+    #[serde(default)]
+    pub replace_parent: bool,
+}
+
+/// One declarative bullet emitter. Missing fields in data files use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmitterDef {
+    pub shape: EmitterShape,
+    /// Seconds before the first volley
+    pub start_delay: f32,
+    /// Seconds between volleys
+    pub interval: f32,
+    /// Number of volleys (0 = keep firing until stopped)
+    pub volleys: u32,
+    /// Base direction in radians (0 = right, PI/2 = down)
+    pub direction: f32,
+    /// How fast the base direction turns, in radians per second
+    pub angular_velocity: f32,
+    pub speed: f32,
+    /// Change in bullet speed per second (negative slows bullets down)
+    pub acceleration: f32,
+    /// How fast each bullet's heading curves, in radians per second
+    pub bullet_angular_velocity: f32,
+    pub lifetime: f32,
+    pub radius: f32,
+    /// RGBA color
+    pub color: [f32; 4],
+    pub sub_emitters: Vec<SubEmitter>,
+}
+
+impl Default for EmitterDef {
+    fn default() -> Self {
+        Self {
+            shape: EmitterShape::Ring { count: 12 },
+            start_delay: 0.0,
+            interval: 0.5,
+            volleys: 1,
+            direction: std::f32::consts::FRAC_PI_2,
+            angular_velocity: 0.0,
+            speed: 150.0,
+            acceleration: 0.0,
+            bullet_angular_velocity: 0.0,
+            lifetime: 5.0,
+            radius: 4.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            sub_emitters: vec![],
+        }
+    }
+}
+
+/// A set of emitters fired together
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct BulletPattern {
+    pub emitters: Vec<EmitterDef>,
+}
+
+impl BulletPattern {
+    pub fn new(emitters: Vec<EmitterDef>) -> Self {
+        Self { emitters }
+    }
+}
+
+/// Error returned when loading bullet patterns
+#[derive(Debug)]
+pub enum PatternError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::Io(err) => write!(f, "failed to read pattern file: {}", err),
+            PatternError::Format(msg) => write!(f, "invalid pattern data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+impl From<std::io::Error> for PatternError {
+    fn from(err: std::io::Error) -> Self {
+        PatternError::Io(err)
+    }
+}
+
+/// Named bullet patterns loaded from a RON file, which can be reloaded while the game runs:
+///
+/// ```ignore
+/// let mut patterns = BulletPatternLibrary::load("assets/patterns.ron")?;
+/// // every frame (or on a timer)
+/// if let Err(err) = patterns.reload_if_changed() {
+///     lastor::log_warn!("patterns", "{}", err); // keep the last good patterns
+/// }
+/// bullets.fire(patterns.get("boss_spiral").unwrap(), boss_pos);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BulletPatternLibrary {
+    patterns: HashMap<String, BulletPattern>,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl BulletPatternLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load patterns from a RON map of name -> pattern and remember the file for reloading
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PatternError> {
+        let mut library = Self::new();
+        library.path = Some(path.as_ref().to_path_buf());
+        library.reload()?;
+        Ok(library)
+    }
+
+    /// Parse patterns from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, PatternError> {
+        let mut library = Self::new();
+        library.patterns = parse_patterns(data)?;
+        Ok(library)
+    }
+
+    /// Re-read the pattern file. On error the current patterns are kept.
+    pub fn reload(&mut self) -> Result<(), PatternError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let data = std::fs::read_to_string(path)?;
+        // Remember the timestamp even if parsing fails, so a broken file isn't re-parsed every frame
+        self.modified = modified;
+        self.patterns = parse_patterns(&data)?;
+        Ok(())
+    }
+
+    /// Reload if the file changed on disk. Returns true if patterns were reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool, PatternError> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        if modified.is_none() || modified == self.modified {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Add or replace a pattern
+    pub fn register(&mut self, name: &str, pattern: BulletPattern) {
+        self.patterns.insert(name.to_string(), pattern);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BulletPattern> {
+        self.patterns.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.patterns.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.patterns.keys().map(|name| name.as_str())
+    }
+}
+
+fn parse_patterns(data: &str) -> Result<HashMap<String, BulletPattern>, PatternError> {
+    ron::from_str(data).map_err(|err| PatternError::Format(err.to_string()))
+}
@@ -0,0 +1,300 @@
+use macroquad::prelude::*;
+use std::f32::consts::TAU;
+use std::rc::Rc;
+use crate::core::{Entity, UpdateContext};
+use super::pattern::{BulletPattern, EmitterDef, EmitterShape, SubEmitter};
+
+/// Handle to the emitters started by one `BulletSystem::fire` call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EmitterHandle(u32);
+
+/// A live projectile (read-only view for collision checks)
+#[derive(Debug, Clone)]
+pub struct Bullet {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+    pub color: Color,
+    angle: f32,
+    speed: f32,
+    acceleration: f32,
+    angular_velocity: f32,
+    age: f32,
+    lifetime: f32,
+    sub_emitters: Option<Rc<[SubEmitter]>>,
+    alive: bool,
+}
+
+/// A running emitter
+struct ActiveEmitter {
+    def: EmitterDef,
+    handle: EmitterHandle,
+    origin: Vec2,
+    /// Added to the def's direction (used by sub-emitters that inherit a heading)
+    rotation: f32,
+    elapsed: f32,
+    volleys_fired: u32,
+    sub_emitters: Option<Rc<[SubEmitter]>>,
+}
+
+impl ActiveEmitter {
+    fn is_finished(&self) -> bool {
+        self.def.volleys > 0 && self.volleys_fired >= self.def.volleys
+    }
+}
+
+/// Runs bullet patterns on a fixed pool of projectiles.
+/// Dead bullets are recycled, so a bullet-hell screen doesn't allocate per shot.
+///
+/// ```ignore
+/// let mut bullets = BulletSystem::new(4096);
+/// bullets.set_target(player_pos);
+/// let handle = bullets.fire(&pattern, boss_pos);
+/// bullets.set_origin(handle, boss_pos); // emitters can follow their owner
+/// if bullets.collide_circle(player_pos, 4.0, true) > 0 { /* hit */ }
+/// ```
+pub struct BulletSystem {
+    bullets: Vec<Bullet>,
+    free: Vec<usize>,
+    emitters: Vec<ActiveEmitter>,
+    capacity: usize,
+    next_handle: u32,
+    target: Vec2,
+    bounds: Option<Rect>,
+}
+
+impl BulletSystem {
+    /// Create a system that can hold up to `capacity` bullets (extra shots are dropped)
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            bullets: Vec::with_capacity(capacity),
+            free: vec![],
+            emitters: vec![],
+            capacity,
+            next_handle: 0,
+            target: Vec2::ZERO,
+            bounds: None,
+        }
+    }
+
+    /// Start every emitter of a pattern at `origin`
+    pub fn fire(&mut self, pattern: &BulletPattern, origin: Vec2) -> EmitterHandle {
+        self.fire_rotated(pattern, origin, 0.0)
+    }
+
+    /// Start a pattern with every emitter direction rotated by `rotation` radians
+    pub fn fire_rotated(&mut self, pattern: &BulletPattern, origin: Vec2, rotation: f32) -> EmitterHandle {
+        let handle = EmitterHandle(self.next_handle);
+        self.next_handle = self.next_handle.wrapping_add(1);
+
+        for def in &pattern.emitters {
+            let sub_emitters = (!def.sub_emitters.is_empty()).then(|| Rc::from(def.sub_emitters.clone()));
+            self.emitters.push(ActiveEmitter {
+                def: def.clone(),
+                handle,
+                origin,
+                rotation,
+                elapsed: 0.0,
+                volleys_fired: 0,
+                sub_emitters,
+            });
+        }
+        handle
+    }
+
+    /// Move the emitters started by `fire`
+    pub fn set_origin(&mut self, handle: EmitterHandle, origin: Vec2) {
+        for emitter in self.emitters.iter_mut().filter(|e| e.handle == handle) {
+            emitter.origin = origin;
+        }
+    }
+
+    /// Stop emitters started by `fire` (bullets already fired keep flying)
+    pub fn stop(&mut self, handle: EmitterHandle) {
+        self.emitters.retain(|emitter| emitter.handle != handle);
+    }
+
+    /// Check if any emitter from `fire` is still running
+    pub fn is_firing(&self, handle: EmitterHandle) -> bool {
+        self.emitters.iter().any(|emitter| emitter.handle == handle)
+    }
+
+    /// Point that aimed spreads shoot at (usually the player)
+    pub fn set_target(&mut self, target: Vec2) {
+        self.target = target;
+    }
+
+    /// Bullets leaving this area are recycled
+    pub fn set_bounds(&mut self, bounds: Option<Rect>) {
+        self.bounds = bounds;
+    }
+
+    /// Iterate over live bullets
+    pub fn bullets(&self) -> impl Iterator<Item = &Bullet> {
+        self.bullets.iter().filter(|bullet| bullet.alive)
+    }
+
+    pub fn bullet_count(&self) -> usize {
+        self.bullets.len() - self.free.len()
+    }
+
+    pub fn emitter_count(&self) -> usize {
+        self.emitters.len()
+    }
+
+    /// Count bullets touching a circle, optionally removing them
+    pub fn collide_circle(&mut self, center: Vec2, radius: f32, remove: bool) -> usize {
+        let mut hits = 0;
+        for (index, bullet) in self.bullets.iter_mut().enumerate() {
+            if !bullet.alive {
+                continue;
+            }
+            let reach = bullet.radius + radius;
+            if bullet.position.distance_squared(center) <= reach * reach {
+                hits += 1;
+                if remove {
+                    bullet.alive = false;
+                    self.free.push(index);
+                }
+            }
+        }
+        hits
+    }
+
+    /// Remove every bullet and emitter
+    pub fn clear(&mut self) {
+        self.bullets.clear();
+        self.free.clear();
+        self.emitters.clear();
+    }
+
+    fn update_emitters(&mut self, dt: f32) {
+        let mut spawned = vec![];
+
+        for emitter in &mut self.emitters {
+            emitter.elapsed += dt;
+            let def = &emitter.def;
+
+            while !emitter.is_finished() {
+                let volley_time = def.start_delay + emitter.volleys_fired as f32 * def.interval.max(0.001);
+                if emitter.elapsed < volley_time {
+                    break;
+                }
+
+                let base = def.direction + emitter.rotation + def.angular_velocity * volley_time;
+                let aim = (self.target - emitter.origin).to_angle();
+                for angle in volley_angles(&def.shape, base, aim, emitter.volleys_fired, volley_time) {
+                    spawned.push(Bullet {
+                        position: emitter.origin,
+                        velocity: Vec2::from_angle(angle) * def.speed,
+                        radius: def.radius,
+                        color: Color::from(def.color),
+                        angle,
+                        speed: def.speed,
+                        acceleration: def.acceleration,
+                        angular_velocity: def.bullet_angular_velocity,
+                        age: 0.0,
+                        lifetime: def.lifetime,
+                        sub_emitters: emitter.sub_emitters.clone(),
+                        alive: true,
+                    });
+                }
+                emitter.volleys_fired += 1;
+            }
+        }
+
+        self.emitters.retain(|emitter| !emitter.is_finished());
+        for bullet in spawned {
+            self.spawn_bullet(bullet);
+        }
+    }
+
+    fn update_bullets(&mut self, dt: f32) {
+        let mut children = vec![];
+
+        for (index, bullet) in self.bullets.iter_mut().enumerate() {
+            if !bullet.alive {
+                continue;
+            }
+
+            let previous_age = bullet.age;
+            bullet.age += dt;
+            bullet.speed = (bullet.speed + bullet.acceleration * dt).max(0.0);
+            bullet.angle += bullet.angular_velocity * dt;
+            bullet.velocity = Vec2::from_angle(bullet.angle) * bullet.speed;
+            bullet.position += bullet.velocity * dt;
+
+            let mut remove = bullet.age >= bullet.lifetime
+                || self.bounds.is_some_and(|bounds| !bounds.contains(bullet.position));
+
+            if let Some(sub_emitters) = &bullet.sub_emitters {
+                for sub in sub_emitters.iter() {
+                    if previous_age < sub.after && bullet.age >= sub.after {
+                        let rotation = if sub.inherit_direction { bullet.angle } else { 0.0 };
+                        children.push((sub.pattern.clone(), bullet.position, rotation));
+                        remove |= sub.replace_parent;
+                    }
+                }
+            }
+
+            if remove {
+                bullet.alive = false;
+                self.free.push(index);
+            }
+        }
+
+        for (pattern, origin, rotation) in children {
+            self.fire_rotated(&pattern, origin, rotation);
+        }
+    }
+
+    fn spawn_bullet(&mut self, bullet: Bullet) {
+        if let Some(index) = self.free.pop() {
+            self.bullets[index] = bullet;
+        } else if self.bullets.len() < self.capacity {
+            self.bullets.push(bullet);
+        }
+    }
+}
+
+/// Directions (radians) of each bullet in one volley
+fn volley_angles(shape: &EmitterShape, base: f32, aim: f32, volley: u32, time: f32) -> Vec<f32> {
+    match *shape {
+        EmitterShape::Ring { count } => spaced(base, TAU, count, true),
+        EmitterShape::Spiral { arms, step } => spaced(base + step * volley as f32, TAU, arms, true),
+        EmitterShape::AimedSpread { count, spread } => spaced(aim, spread, count, false),
+        EmitterShape::Wave { count, spread, sweep, frequency } => {
+            let center = base + (time * frequency * TAU).sin() * sweep;
+            spaced(center, spread, count, false)
+        }
+    }
+}
+
+/// `count` angles spread over `arc` around `center` (full circles don't repeat the end angle)
+fn spaced(center: f32, arc: f32, count: u32, full_circle: bool) -> Vec<f32> {
+    match count {
+        0 => vec![],
+        1 => vec![center],
+        _ => {
+            let (start, step) = if full_circle {
+                (center, arc / count as f32)
+            } else {
+                (center - arc * 0.5, arc / (count - 1) as f32)
+            };
+            (0..count).map(|i| start + step * i as f32).collect()
+        }
+    }
+}
+
+impl Entity for BulletSystem {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        self.update_emitters(ctx.dt);
+        self.update_bullets(ctx.dt);
+    }
+
+    fn draw(&self) {
+        for bullet in self.bullets() {
+            draw_circle(bullet.position.x, bullet.position.y, bullet.radius, bullet.color);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use crate::save::SaveData;
+
+// Share of camera shake kept with reduced motion
+const REDUCED_SHAKE: f32 = 0.25;
+// Shortest full-screen fade with reduced flashing, so it never reads as a flash
+const MIN_FADE_SECONDS: f32 = 1.0;
+
+/// Player settings for motion and flashing, read from `GameConfig::motion` or a save
+/// slot. `Game` applies them to camera shake and portal fades whenever they change, and
+/// puts a copy in its resources for the game's own effects:
+///
+/// ```ignore
+/// game.set_motion_settings(saves.load_or_default("motion")?);
+///
+/// // in an entity's update
+/// let motion = ctx.resources.get::<MotionSettings>().copied().unwrap_or_default();
+/// if !motion.reduce_flashing {
+///     self.flash_timer = 0.1;
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotionSettings {
+    /// Strength of camera shake (1 = full, 0 = off)
+    pub shake_scale: f32,
+    /// Dampen camera shake further
+    pub reduced_motion: bool,
+    /// Stretch full-screen fades out so they never read as a flash (photosensitivity);
+    /// the game's own flashes and strobes should check it too
+    pub reduce_flashing: bool,
+}
+
+impl Default for MotionSettings {
+    fn default() -> Self {
+        Self {
+            shake_scale: 1.0,
+            reduced_motion: false,
+            reduce_flashing: false,
+        }
+    }
+}
+
+impl SaveData for MotionSettings {}
+
+impl MotionSettings {
+    /// Camera shake multiplier, after reduced motion
+    pub fn effective_shake_scale(&self) -> f32 {
+        let reduced = if self.reduced_motion { REDUCED_SHAKE } else { 1.0 };
+        self.shake_scale.max(0.0) * reduced
+    }
+
+    /// Length to play a full-screen fade of `seconds`, stretched with reduced flashing
+    pub fn fade_duration(&self, seconds: f32) -> f32 {
+        if self.reduce_flashing { seconds.max(MIN_FADE_SECONDS) } else { seconds }
+    }
+}
@@ -0,0 +1,144 @@
+/// Handle to a value in an `Arena`. Keys of removed values stay invalid even after their
+/// slot is reused, because the slot's generation moves on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ArenaKey {
+    index: u32,
+    generation: u32,
+}
+
+impl ArenaKey {
+    /// Slot the value lives in
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// Generational arena: values live in slots of one `Vec` and are reached by `ArenaKey`.
+/// Inserting and removing are O(1), removing never moves other values (their keys and
+/// slots stay put), and freed slots are reused. Iteration goes in slot order, which only
+/// depends on the order of inserts and removes.
+///
+/// ```ignore
+/// let mut arena = Arena::new();
+/// let a = arena.insert("a");
+/// let b = arena.insert("b");
+/// arena.remove(a);
+/// assert_eq!(arena.get(a), None);
+/// assert_eq!(arena.get(b), Some(&"b"));
+/// ```
+pub struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    // Empty slots, reused last freed first
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self { slots: vec![], free: vec![], len: 0 }
+    }
+}
+
+impl<T> Arena<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { slots: Vec::with_capacity(capacity), free: vec![], len: 0 }
+    }
+
+    pub fn insert(&mut self, value: T) -> ArenaKey {
+        self.len += 1;
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.value = Some(value);
+            return ArenaKey { index, generation: slot.generation };
+        }
+        let index = u32::try_from(self.slots.len()).expect("arena is full");
+        self.slots.push(Slot { generation: 0, value: Some(value) });
+        ArenaKey { index, generation: 0 }
+    }
+
+    /// Take a value out, freeing its slot (None if the key is stale)
+    pub fn remove(&mut self, key: ArenaKey) -> Option<T> {
+        let slot = self.slots.get_mut(key.index as usize).filter(|slot| slot.generation == key.generation)?;
+        let value = slot.value.take()?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(key.index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, key: ArenaKey) -> Option<&T> {
+        self.slots.get(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)?
+            .value
+            .as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: ArenaKey) -> Option<&mut T> {
+        self.slots.get_mut(key.index as usize)
+            .filter(|slot| slot.generation == key.generation)?
+            .value
+            .as_mut()
+    }
+
+    pub fn contains(&self, key: ArenaKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Values with their keys, in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (ArenaKey, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let key = ArenaKey { index: index as u32, generation: slot.generation };
+            slot.value.as_ref().map(|value| (key, value))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ArenaKey, &mut T)> {
+        self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+            let key = ArenaKey { index: index as u32, generation: slot.generation };
+            slot.value.as_mut().map(|value| (key, value))
+        })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.slots.iter().filter_map(|slot| slot.value.as_ref())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.slots.iter_mut().filter_map(|slot| slot.value.as_mut())
+    }
+
+    /// Remove every value, in slot order. Old keys stay invalid.
+    pub fn drain(&mut self) -> Vec<T> {
+        let mut values = Vec::with_capacity(self.len);
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(value) = slot.value.take() {
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+                values.push(value);
+            }
+        }
+        self.len = 0;
+        values
+    }
+}
@@ -0,0 +1,170 @@
+use crate::rendering::DebugTheme;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Mutex;
+
+/// Which build of a game is running, for bug reports from playtesters.
+///
+/// Shown in a corner with `GameConfig::show_build_info`, printed by the panic hook
+/// `Game::set_build_info` installs, written into frame stats exports, and recorded in
+/// the header of save files (see `SaveManager::with_build_info`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    pub name: String,
+    pub version: String,
+    /// Short commit hash, if the build script could read it
+    pub git_hash: Option<String>,
+    /// YYYY-MM-DD
+    pub build_date: Option<String>,
+    /// "debug" or "release"
+    pub profile: String,
+}
+
+impl BuildInfo {
+    pub fn new(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version: version.to_string(),
+            git_hash: None,
+            build_date: None,
+            profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+        }
+    }
+
+    /// Empty values (a build script that couldn't run git) are treated as missing
+    pub fn with_git_hash(mut self, hash: Option<&str>) -> Self {
+        self.git_hash = hash.filter(|hash| !hash.is_empty()).map(str::to_string);
+        self
+    }
+
+    pub fn with_build_date(mut self, date: Option<&str>) -> Self {
+        self.build_date = date.filter(|date| !date.is_empty()).map(str::to_string);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: &str) -> Self {
+        self.profile = profile.to_string();
+        self
+    }
+
+    /// The lastor build the game is linked against
+    pub fn engine() -> Self {
+        Self::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            .with_git_hash(option_env!("LASTOR_GIT_HASH"))
+            .with_build_date(option_env!("LASTOR_BUILD_DATE"))
+    }
+
+    /// Serialize as a JSON object
+    pub fn to_json(&self) -> String {
+        let optional = |value: &Option<String>| value.as_deref().map_or("null".to_string(), json_string);
+        format!(
+            "{{\"name\":{},\"version\":{},\"git_hash\":{},\"build_date\":{},\"profile\":{}}}",
+            json_string(&self.name),
+            json_string(&self.version),
+            optional(&self.git_hash),
+            optional(&self.build_date),
+            json_string(&self.profile)
+        )
+    }
+
+    /// Draw the build line in the bottom-right corner of the screen
+    pub fn draw_overlay(&self, theme: &DebugTheme) {
+        let text = self.to_string();
+        let font_size = 16.0;
+        let size = measure_text(&text, None, font_size as u16, 1.0);
+        let x = screen_width() - size.width - 8.0;
+        let y = screen_height() - 8.0;
+        draw_text(&text, x + 1.0, y + 1.0, font_size, theme.shadow());
+        draw_text(&text, x, y, font_size, theme.muted());
+    }
+}
+
+impl fmt::Display for BuildInfo {
+    /// e.g. "mygame 0.3.1 (a1b2c3d, 2026-10-16, release)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} (", self.name, self.version)?;
+        for part in [&self.git_hash, &self.build_date].into_iter().flatten() {
+            write!(f, "{}, ", part)?;
+        }
+        write!(f, "{})", self.profile)
+    }
+}
+
+/// A JSON string literal, with quotes, backslashes and control characters escaped
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for character in value.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            control if control.is_control() => json.push_str(&format!("\\u{:04x}", control as u32)),
+            other => json.push(other),
+        }
+    }
+    json.push('"');
+    json
+}
+
+/// `BuildInfo` for the crate this is expanded in.
+///
+/// Reads `GIT_HASH` and `BUILD_DATE` at compile time; set them from the game's build.rs:
+///
+/// ```ignore
+/// // build.rs
+/// let git = |args: &[&str]| std::process::Command::new("git").args(args).output().ok()
+///     .filter(|output| output.status.success())
+///     .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+/// println!("cargo:rustc-env=GIT_HASH={}", git(&["rev-parse", "--short", "HEAD"]).unwrap_or_default());
+/// // HEAD is in the worktree's git dir, branches in the shared one; a commit only
+/// // touches the branch, so watch refs and packed-refs too
+/// let git_dir = git(&["rev-parse", "--git-dir"]).map(std::path::PathBuf::from);
+/// let common_dir = git(&["rev-parse", "--git-common-dir"]).map(std::path::PathBuf::from);
+/// let watched = [
+///     git_dir.map(|dir| dir.join("HEAD")),
+///     common_dir.as_ref().map(|dir| dir.join("refs")),
+///     common_dir.as_ref().map(|dir| dir.join("packed-refs")),
+/// ];
+/// for path in watched.into_iter().flatten().filter(|path| path.exists()) {
+///     println!("cargo:rerun-if-changed={}", path.display());
+/// }
+///
+/// // main.rs
+/// game.set_build_info(lastor::build_info!());
+/// ```
+#[macro_export]
+macro_rules! build_info {
+    () => {
+        $crate::core::BuildInfo::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+            .with_git_hash(option_env!("GIT_HASH"))
+            .with_build_date(option_env!("BUILD_DATE"))
+            .with_profile(if cfg!(debug_assertions) { "debug" } else { "release" })
+    };
+}
+
+// Build line printed by the panic hook; the hook itself is installed once
+static PANIC_BUILD: Mutex<Option<String>> = Mutex::new(None);
+
+/// Print `info` (and the engine build) before the default panic message
+pub(crate) fn install_panic_hook(info: &BuildInfo) {
+    let first = PANIC_BUILD
+        .lock()
+        .map(|mut build| build.replace(info.to_string()).is_none())
+        .unwrap_or(false);
+    if !first {
+        return;
+    }
+    let engine = BuildInfo::engine().to_string();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |panic| {
+        if let Some(game) = PANIC_BUILD.lock().ok().and_then(|build| build.clone()) {
+            eprintln!("=== {} ===", game);
+        }
+        eprintln!("engine: {}", engine);
+        previous(panic);
+    }));
+}
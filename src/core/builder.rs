@@ -0,0 +1,351 @@
+use macroquad::prelude::*;
+use crate::math::Transform;
+use crate::movement::{MovementPattern, PatternMover};
+use crate::physics::{Body, Collider};
+use crate::rendering::{Lod, LodLevel, Sprite, SpriteBatch};
+use super::{Entity, EntityId, Scene, ScriptAction, UpdateContext};
+
+/// Per-frame logic hook for a built entity
+pub type UpdateFn = Box<dyn FnMut(&mut SimpleEntity, f32)>;
+
+/// Start building an entity.
+///
+/// ```ignore
+/// spawn()
+///     .at(vec2(100.0, 200.0))
+///     .sprite(hero_texture)
+///     .collider_circle(12.0)
+///     .tag("player")
+///     .body(1.0)
+///     .build(&mut scene);
+/// ```
+pub fn spawn() -> EntityBuilder {
+    EntityBuilder::new()
+}
+
+/// Fluent builder for simple entities that don't need a custom `Entity` impl
+pub struct EntityBuilder {
+    entity: SimpleEntity,
+}
+
+impl EntityBuilder {
+    pub fn new() -> Self {
+        Self {
+            entity: SimpleEntity::new(Transform::default()),
+        }
+    }
+
+    pub fn at(mut self, position: Vec2) -> Self {
+        self.entity.transform.position = position;
+        self
+    }
+
+    pub fn rotation(mut self, rotation: f32) -> Self {
+        self.entity.transform.rotation = rotation;
+        self
+    }
+
+    pub fn scale(mut self, scale: Vec2) -> Self {
+        self.entity.transform.scale = scale;
+        self
+    }
+
+    /// Draw this texture centered on the entity
+    pub fn sprite(mut self, texture: Texture2D) -> Self {
+        self.entity.sprite = Some(texture);
+        self
+    }
+
+    /// Tint for the sprite, or fill color for the placeholder shape
+    pub fn color(mut self, color: Color) -> Self {
+        self.entity.color = color;
+        self
+    }
+
+    pub fn collider(mut self, collider: Collider) -> Self {
+        self.entity.collider = Some(collider);
+        self
+    }
+
+    pub fn collider_circle(self, radius: f32) -> Self {
+        self.collider(Collider::circle(radius))
+    }
+
+    pub fn collider_rect(self, width: f32, height: f32) -> Self {
+        self.collider(Collider::rect(width, height))
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.entity.add_tag(tag);
+        self
+    }
+
+    /// Render layer (higher draws on top)
+    pub fn layer(mut self, layer: i32) -> Self {
+        self.entity.draw_layer = layer;
+        self
+    }
+
+    /// Order within the render layer, e.g. a material id to keep batches together
+    pub fn sort_key(mut self, key: i64) -> Self {
+        self.entity.sort_key = key;
+        self
+    }
+
+    /// Add a physics body with the given gravity scale (0 = no gravity)
+    pub fn body(mut self, gravity_scale: f32) -> Self {
+        self.entity.body = Some(Body::new(gravity_scale));
+        self
+    }
+
+    /// Add a fully configured physics body
+    pub fn with_body(mut self, body: Body) -> Self {
+        self.entity.body = Some(body);
+        self
+    }
+
+    /// Starting velocity (adds a weightless body if none was set)
+    pub fn velocity(mut self, velocity: Vec2) -> Self {
+        self.entity.body.get_or_insert_with(|| Body::new(0.0)).velocity = velocity;
+        self
+    }
+
+    /// Follow a movement pattern starting from the spawn position (overrides the body's motion)
+    pub fn movement(mut self, pattern: MovementPattern) -> Self {
+        self.entity.mover = Some(PatternMover::new(pattern, Vec2::ZERO));
+        self
+    }
+
+    /// Custom per-frame logic, run after the entity has moved
+    pub fn on_update<F>(mut self, update: F) -> Self
+    where
+        F: FnMut(&mut SimpleEntity, f32) + 'static,
+    {
+        self.entity.on_update = Some(Box::new(update));
+        self
+    }
+
+    /// Finish without adding to a scene
+    pub fn finish(mut self) -> SimpleEntity {
+        let position = self.entity.transform.position;
+        if let Some(mover) = &mut self.entity.mover {
+            mover.restart(position);
+        }
+        self.entity
+    }
+
+    /// Finish and add the entity to a scene
+    pub fn build(self, scene: &mut Scene) -> EntityId {
+        scene.add_entity(Box::new(self.finish()))
+    }
+}
+
+impl Default for EntityBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Entity assembled from optional parts by `EntityBuilder`
+pub struct SimpleEntity {
+    pub transform: Transform,
+    pub sprite: Option<Texture2D>,
+    pub color: Color,
+    pub collider: Option<Collider>,
+    /// A disabled collider doesn't overlap anything (e.g. retracted spikes)
+    pub collider_enabled: bool,
+    pub visible: bool,
+    pub body: Option<Body>,
+    pub mover: Option<PatternMover>,
+    pub active: bool,
+    pub draw_layer: i32,
+    pub sort_key: i64,
+    tags: Vec<String>,
+    on_update: Option<UpdateFn>,
+}
+
+impl SimpleEntity {
+    pub fn new(transform: Transform) -> Self {
+        Self {
+            transform,
+            sprite: None,
+            color: WHITE,
+            collider: None,
+            collider_enabled: true,
+            visible: true,
+            body: None,
+            mover: None,
+            active: true,
+            draw_layer: 0,
+            sort_key: 0,
+            tags: vec![],
+            on_update: None,
+        }
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.has_tag(tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Check if this entity's collider overlaps another's
+    pub fn overlaps(&self, other: &SimpleEntity) -> bool {
+        if !self.collider_enabled || !other.collider_enabled {
+            return false;
+        }
+        match (&self.collider, &other.collider) {
+            (Some(a), Some(b)) => a.overlaps(self.transform.position, b, other.transform.position),
+            _ => false,
+        }
+    }
+
+    /// Deactivate this entity (will be cleaned up by scene)
+    pub fn deactivate(&mut self) {
+        self.active = false;
+    }
+
+    /// The collider shape, drawn when there's no sprite
+    fn draw_shape(&self) {
+        let position = self.transform.position;
+        match self.collider {
+            Some(Collider::Circle { radius }) => draw_circle(position.x, position.y, radius, self.color),
+            Some(Collider::Rect { size }) => {
+                draw_rectangle(position.x - size.x * 0.5, position.y - size.y * 0.5, size.x, size.y, self.color)
+            }
+            None => draw_circle(position.x, position.y, 5.0, self.color),
+        }
+    }
+}
+
+impl Entity for SimpleEntity {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let dt = ctx.dt;
+        if let Some(mover) = &mut self.mover {
+            self.transform.position = mover.update(dt);
+        } else if let Some(body) = &mut self.body {
+            self.transform.position += body.integrate(dt);
+        }
+
+        // Take the hook out so it can borrow the entity mutably
+        if let Some(mut on_update) = self.on_update.take() {
+            on_update(self, dt);
+            self.on_update = Some(on_update);
+        }
+    }
+
+    fn draw(&self) {
+        if !self.visible {
+            return;
+        }
+        let position = self.transform.position;
+
+        if let Some(texture) = &self.sprite {
+            let size = texture.size() * self.transform.scale;
+            draw_texture_ex(
+                texture,
+                position.x - size.x * 0.5,
+                position.y - size.y * 0.5,
+                self.color,
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    rotation: self.transform.rotation,
+                    ..Default::default()
+                },
+            );
+            return;
+        }
+
+        // No sprite: draw the collider shape as a placeholder
+        self.draw_shape();
+    }
+
+    fn queue_sprites(&self, batch: &mut SpriteBatch) -> bool {
+        match &self.sprite {
+            Some(texture) if self.visible => {
+                let size = texture.size() * self.transform.scale;
+                batch.push(
+                    Sprite::centered(texture, self.transform.position, size)
+                        .with_rotation(self.transform.rotation)
+                        .with_color(self.color),
+                );
+                true
+            }
+            // Nothing to draw when hidden; the collider placeholder draws itself
+            _ => !self.visible,
+        }
+    }
+
+    fn draw_lod(&self, lod: Lod) {
+        if !self.visible {
+            return;
+        }
+        match lod.level {
+            LodLevel::Full => self.draw(),
+            // A flat rectangle in place of the sprite
+            LodLevel::Simple => match (&self.sprite, self.get_bounds()) {
+                (Some(_), Some((min, size))) => draw_rectangle(min.x, min.y, size.x, size.y, self.color),
+                _ => self.draw_shape(),
+            },
+            LodLevel::Point => {
+                let size = lod.pixel_size * 2.0;
+                let position = self.transform.position;
+                draw_rectangle(position.x - size * 0.5, position.y - size * 0.5, size, size, self.color);
+            }
+        }
+    }
+
+    fn get_transform(&self) -> Option<&Transform> {
+        Some(&self.transform)
+    }
+
+    fn get_transform_mut(&mut self) -> Option<&mut Transform> {
+        Some(&mut self.transform)
+    }
+
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn get_bounds(&self) -> Option<(Vec2, Vec2)> {
+        if let Some(texture) = &self.sprite {
+            let size = texture.size() * self.transform.scale;
+            return Some((self.transform.position - size * 0.5, size));
+        }
+        self.collider.map(|collider| collider.bounds(self.transform.position))
+    }
+
+    fn get_collider(&self) -> Option<Collider> {
+        self.collider.filter(|_| self.collider_enabled)
+    }
+
+    fn get_velocity(&self) -> Option<Vec2> {
+        self.body.map(|body| body.velocity)
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    fn draw_layer(&self) -> i32 {
+        self.draw_layer
+    }
+
+    fn sort_key(&self) -> i64 {
+        self.sort_key
+    }
+
+    fn on_script_action(&mut self, action: &ScriptAction) {
+        match action {
+            ScriptAction::EnableCollider => self.collider_enabled = true,
+            ScriptAction::DisableCollider => self.collider_enabled = false,
+            ScriptAction::Show => self.visible = true,
+            ScriptAction::Hide => self.visible = false,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use super::EntityId;
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// A component value with the frames it was added and last changed
+#[doc(hidden)]
+pub struct Tracked<T> {
+    value: T,
+    added: u64,
+    changed: u64,
+}
+
+/// Storage for one component type
+struct Column<T> {
+    entries: HashMap<EntityId, Tracked<T>>,
+    /// Entities that lost this component this frame
+    removed: Vec<EntityId>,
+}
+
+/// Type-erased column so the store can remove an entity from every column
+trait AnyColumn {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn remove(&mut self, id: EntityId);
+    fn contains(&self, id: EntityId) -> bool;
+    fn clear_removed(&mut self);
+}
+
+impl<T: 'static> AnyColumn for Column<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn remove(&mut self, id: EntityId) {
+        if self.entries.remove(&id).is_some() {
+            self.removed.push(id);
+        }
+    }
+
+    fn contains(&self, id: EntityId) -> bool {
+        self.entries.contains_key(&id)
+    }
+
+    fn clear_removed(&mut self) {
+        self.removed.clear();
+    }
+}
+
+/// Mutable access to a component that marks it changed when written through
+pub struct Mut<'a, T> {
+    value: &'a mut T,
+    changed: &'a mut u64,
+    frame: u64,
+}
+
+impl<T> Deref for Mut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T> DerefMut for Mut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        *self.changed = self.frame;
+        self.value
+    }
+}
+
+/// Plain data attached to entities by type, alongside the `Entity` trait objects.
+///
+/// Any `'static` type can be a component: `struct Velocity(Vec2)`, a marker
+/// `struct EnemyTag;`, or a copy of a `Transform`.
+///
+/// Components remember the frame they were added and last written (through `Mut`),
+/// so systems can react to `Added`/`Changed` components and `removed` entities
+/// instead of polling everything. A frame runs from the start of one `Scene` update
+/// to the start of the next; changes made before the first update count toward it.
+#[derive(Default)]
+pub struct ComponentStore {
+    columns: HashMap<TypeId, Box<dyn AnyColumn>>,
+    frame: u64,
+    started: bool,
+}
+
+impl ComponentStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attach a component, returning the one it replaced
+    pub fn insert<T: 'static>(&mut self, id: EntityId, component: T) -> Option<T> {
+        let frame = self.frame;
+        let column = self.columns.entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Column::<T> { entries: HashMap::new(), removed: vec![] }))
+            .as_any_mut()
+            .downcast_mut::<Column<T>>()?;
+        match column.entries.get_mut(&id) {
+            Some(tracked) => {
+                tracked.changed = frame;
+                Some(std::mem::replace(&mut tracked.value, component))
+            }
+            None => {
+                column.entries.insert(id, Tracked { value: component, added: frame, changed: frame });
+                None
+            }
+        }
+    }
+
+    pub fn remove<T: 'static>(&mut self, id: EntityId) -> Option<T> {
+        let column = self.column_mut::<T>()?;
+        let tracked = column.entries.remove(&id)?;
+        column.removed.push(id);
+        Some(tracked.value)
+    }
+
+    pub fn get<T: 'static>(&self, id: EntityId) -> Option<&T> {
+        self.column::<T>()?.entries.get(&id).map(|tracked| &tracked.value)
+    }
+
+    pub fn get_mut<T: 'static>(&mut self, id: EntityId) -> Option<Mut<'_, T>> {
+        let frame = self.frame;
+        let tracked = self.column_mut::<T>()?.entries.get_mut(&id)?;
+        Some(Mut { value: &mut tracked.value, changed: &mut tracked.changed, frame })
+    }
+
+    pub fn contains<T: 'static>(&self, id: EntityId) -> bool {
+        self.contains_type(TypeId::of::<T>(), id)
+    }
+
+    /// Check if an entity's `T` was added this frame
+    pub fn is_added<T: 'static>(&self, id: EntityId) -> bool {
+        self.tracked::<T>(id).is_some_and(|tracked| tracked.added == self.frame)
+    }
+
+    /// Check if an entity's `T` was added or written to this frame
+    pub fn is_changed<T: 'static>(&self, id: EntityId) -> bool {
+        self.tracked::<T>(id).is_some_and(|tracked| tracked.changed == self.frame)
+    }
+
+    /// Entities that lost their `T` this frame (removed, or the entity went away)
+    pub fn removed<T: 'static>(&self) -> &[EntityId] {
+        self.column::<T>().map_or(&[], |column| &column.removed)
+    }
+
+    /// Remove every component of an entity
+    pub fn remove_entity(&mut self, id: EntityId) {
+        for column in self.columns.values_mut() {
+            column.remove(id);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.columns.clear();
+    }
+
+    /// Start a new frame of change detection (called by the Scene at the start of each update)
+    pub(crate) fn begin_frame(&mut self) {
+        // Setup before the first update belongs to the first frame
+        if !self.started {
+            self.started = true;
+            return;
+        }
+        self.frame += 1;
+        for column in self.columns.values_mut() {
+            column.clear_removed();
+        }
+    }
+
+    /// Entities in `ids` (in that order) that have every component of `Q` and pass `F`
+    pub fn query<Q: Query, F: QueryFilter>(&mut self, ids: Vec<EntityId>) -> impl Iterator<Item = (EntityId, Q::Item<'_>)> {
+        let mut types = vec![];
+        Q::component_types(&mut types);
+        for (index, ty) in types.iter().enumerate() {
+            assert!(!types[..index].contains(ty), "query {} uses a component type twice", type_name::<Q>());
+        }
+
+        let matching: Vec<EntityId> = ids.into_iter()
+            .filter(|id| F::matches(self, *id))
+            .collect();
+
+        // Every column is borrowed at once; each query term takes the one it needs
+        let mut columns = QueryColumns {
+            columns: self.columns.iter_mut().map(|(ty, column)| (*ty, column)).collect(),
+            frame: self.frame,
+        };
+        let mut state = Q::borrow(&mut columns);
+        matching.into_iter().filter_map(move |id| Q::fetch(&mut state, id).map(|item| (id, item)))
+    }
+
+    fn contains_type(&self, ty: TypeId, id: EntityId) -> bool {
+        self.columns.get(&ty).is_some_and(|column| column.contains(id))
+    }
+
+    fn tracked<T: 'static>(&self, id: EntityId) -> Option<&Tracked<T>> {
+        self.column::<T>()?.entries.get(&id)
+    }
+
+    fn column<T: 'static>(&self) -> Option<&Column<T>> {
+        self.columns.get(&TypeId::of::<T>())?.as_any().downcast_ref()
+    }
+
+    fn column_mut<T: 'static>(&mut self) -> Option<&mut Column<T>> {
+        self.columns.get_mut(&TypeId::of::<T>())?.as_any_mut().downcast_mut()
+    }
+}
+
+/// Columns lent to a query, by component type
+#[doc(hidden)]
+pub struct QueryColumns<'a> {
+    columns: HashMap<TypeId, &'a mut Box<dyn AnyColumn>>,
+    frame: u64,
+}
+
+impl<'a> QueryColumns<'a> {
+    fn take<T: 'static>(&mut self) -> Option<&'a mut HashMap<EntityId, Tracked<T>>> {
+        let column = self.columns.remove(&TypeId::of::<T>())?;
+        column.as_any_mut().downcast_mut::<Column<T>>().map(|column| &mut column.entries)
+    }
+}
+
+/// Components fetched together by `Scene::query`: `&T`, `&mut T` (as `Mut<T>`), or a tuple of them
+pub trait Query {
+    type Item<'a>;
+    #[doc(hidden)]
+    type State<'a>;
+
+    #[doc(hidden)]
+    fn component_types(types: &mut Vec<TypeId>);
+    #[doc(hidden)]
+    fn borrow<'a>(columns: &mut QueryColumns<'a>) -> Self::State<'a>;
+    #[doc(hidden)]
+    fn fetch<'a>(state: &mut Self::State<'a>, id: EntityId) -> Option<Self::Item<'a>>;
+}
+
+impl<T: 'static> Query for &T {
+    type Item<'a> = &'a T;
+    type State<'a> = Option<&'a HashMap<EntityId, Tracked<T>>>;
+
+    fn component_types(types: &mut Vec<TypeId>) {
+        types.push(TypeId::of::<T>());
+    }
+
+    fn borrow<'a>(columns: &mut QueryColumns<'a>) -> Self::State<'a> {
+        columns.take::<T>().map(|column| &*column)
+    }
+
+    fn fetch<'a>(state: &mut Self::State<'a>, id: EntityId) -> Option<Self::Item<'a>> {
+        (*state)?.get(&id).map(|tracked| &tracked.value)
+    }
+}
+
+impl<T: 'static> Query for &mut T {
+    type Item<'a> = Mut<'a, T>;
+    type State<'a> = HashMap<EntityId, Mut<'a, T>>;
+
+    fn component_types(types: &mut Vec<TypeId>) {
+        types.push(TypeId::of::<T>());
+    }
+
+    fn borrow<'a>(columns: &mut QueryColumns<'a>) -> Self::State<'a> {
+        let frame = columns.frame;
+        // Split the column into per-entity borrows, each handed out once
+        columns.take::<T>()
+            .map(|column| {
+                column.iter_mut()
+                    .map(|(id, tracked)| (*id, Mut { value: &mut tracked.value, changed: &mut tracked.changed, frame }))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn fetch<'a>(state: &mut Self::State<'a>, id: EntityId) -> Option<Self::Item<'a>> {
+        state.remove(&id)
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Query),+> Query for ($($name,)+) {
+            type Item<'a> = ($($name::Item<'a>,)+);
+            type State<'a> = ($($name::State<'a>,)+);
+
+            fn component_types(types: &mut Vec<TypeId>) {
+                $($name::component_types(types);)+
+            }
+
+            fn borrow<'a>(columns: &mut QueryColumns<'a>) -> Self::State<'a> {
+                ($($name::borrow(columns),)+)
+            }
+
+            fn fetch<'a>(state: &mut Self::State<'a>, id: EntityId) -> Option<Self::Item<'a>> {
+                Some(($($name::fetch(&mut state.$index, id)?,)+))
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A 0);
+impl_query_tuple!(A 0, B 1);
+impl_query_tuple!(A 0, B 1, C 2);
+impl_query_tuple!(A 0, B 1, C 2, D 3);
+impl_query_tuple!(A 0, B 1, C 2, D 3, E 4);
+
+/// Extra conditions on the entities a query visits, without fetching components.
+/// Use `()` for no filter, or a tuple to require several.
+pub trait QueryFilter {
+    fn matches(store: &ComponentStore, id: EntityId) -> bool;
+}
+
+/// Only entities that have a `T` component
+pub struct With<T>(PhantomData<T>);
+
+/// Only entities without a `T` component
+pub struct Without<T>(PhantomData<T>);
+
+/// Only entities whose `T` was added this frame
+pub struct Added<T>(PhantomData<T>);
+
+/// Only entities whose `T` was added or written to this frame
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T: 'static> QueryFilter for With<T> {
+    fn matches(store: &ComponentStore, id: EntityId) -> bool {
+        store.contains::<T>(id)
+    }
+}
+
+impl<T: 'static> QueryFilter for Without<T> {
+    fn matches(store: &ComponentStore, id: EntityId) -> bool {
+        !store.contains::<T>(id)
+    }
+}
+
+impl<T: 'static> QueryFilter for Added<T> {
+    fn matches(store: &ComponentStore, id: EntityId) -> bool {
+        store.is_added::<T>(id)
+    }
+}
+
+impl<T: 'static> QueryFilter for Changed<T> {
+    fn matches(store: &ComponentStore, id: EntityId) -> bool {
+        store.is_changed::<T>(id)
+    }
+}
+
+impl QueryFilter for () {
+    fn matches(_store: &ComponentStore, _id: EntityId) -> bool {
+        true
+    }
+}
+
+macro_rules! impl_filter_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: QueryFilter),+> QueryFilter for ($($name,)+) {
+            fn matches(store: &ComponentStore, id: EntityId) -> bool {
+                $($name::matches(store, id))&&+
+            }
+        }
+    };
+}
+
+impl_filter_tuple!(A);
+impl_filter_tuple!(A, B);
+impl_filter_tuple!(A, B, C);
+impl_filter_tuple!(A, B, C, D);
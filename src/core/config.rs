@@ -0,0 +1,344 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use crate::rendering::DebugTheme;
+use super::{LogLevel, MotionSettings};
+
+/// Error returned when loading or overriding a game config
+#[derive(Debug)]
+pub enum GameConfigError {
+    Io(std::io::Error),
+    Format(String),
+    /// An override named a setting that doesn't exist or gave it a bad value
+    Override { key: String, value: String },
+}
+
+impl fmt::Display for GameConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameConfigError::Io(err) => write!(f, "failed to read game config: {}", err),
+            GameConfigError::Format(msg) => write!(f, "invalid game config: {}", msg),
+            GameConfigError::Override { key, value } => write!(f, "invalid config override {}={}", key, value),
+        }
+    }
+}
+
+impl std::error::Error for GameConfigError {}
+
+impl From<std::io::Error> for GameConfigError {
+    fn from(err: std::io::Error) -> Self {
+        GameConfigError::Io(err)
+    }
+}
+
+/// Configuration for the game.
+///
+/// Can be loaded from a RON file, where missing settings keep their defaults, and then
+/// overridden from the environment and command line:
+///
+/// ```ignore
+/// fn window_conf() -> Conf {
+///     GameConfig::from_file("game.ron").unwrap_or_default().with_overrides().unwrap().window_conf()
+/// }
+///
+/// #[macroquad::main(window_conf)]
+/// async fn main() {
+///     let config = GameConfig::from_file("game.ron").unwrap_or_default().with_overrides().unwrap();
+///     let mut game = Game::with_config(config);
+///     // ...
+/// }
+/// ```
+///
+/// ```ron
+/// (title: "Skyfall", window_width: 1280, window_height: 720, fixed_timestep_hz: Some(60), asset_root: Some("assets"))
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub title: String,
+    pub window_width: i32,
+    pub window_height: i32,
+    pub fullscreen: bool,
+    /// Let the player resize the window
+    pub resizable: bool,
+    /// Smallest window size `Game` allows when resized (0 = no limit)
+    pub min_window_width: i32,
+    pub min_window_height: i32,
+    /// Sync frames to the display's refresh; only read when the window opens
+    pub vsync: bool,
+    pub high_dpi: bool,
+    /// Player-chosen scale of menus, the cheat and radial menus and custom cursors
+    /// (see `ui::ui_scale`)
+    pub ui_scale: f32,
+    /// Camera shake strength, reduced motion and reduced flashing
+    pub motion: MotionSettings,
+    pub target_fps: u32,
+    /// Update entities in fixed steps of 1/hz seconds, several per frame if needed
+    /// (None = one update per frame with the frame's dt)
+    pub fixed_timestep_hz: Option<u32>,
+    /// Run deterministically from this seed: one update of exactly 1/`fixed_timestep_hz`
+    /// seconds (1/60 if unset) per frame, a seeded `Rng` resource, and the engine's shared
+    /// `Rng` (particles, sound variations) seeded too, so the same inputs always give the
+    /// same world (replays, rollback)
+    pub deterministic_seed: Option<u64>,
+    /// Directory the game's `AssetManager` loads from
+    pub asset_root: Option<String>,
+    /// RGBA
+    #[serde(with = "color_rgba")]
+    pub background_color: Color,
+    pub show_fps: bool,
+    pub show_input_debug: bool,
+    /// Show the build line from `Game::set_build_info` in the bottom-right corner
+    pub show_build_info: bool,
+    /// Show a bar graph of each frame's input, update, physics, camera and draw times
+    /// in the top-right corner (see `Game::get_profiler`)
+    pub show_profiler: bool,
+    /// Draw the shapes queued with `debug_draw` over the world; on in debug builds
+    pub show_debug_draw: bool,
+    /// Show the latest log lines in the bottom-left corner (debug builds only; see `core::logging`)
+    pub show_log: bool,
+    pub log_lines: usize,
+    /// Least serious log level kept; Debug in debug builds
+    pub log_level: LogLevel,
+    /// Colors of the debug overlays and the error screen
+    pub debug_theme: DebugTheme,
+    /// Replace the dt of frames far longer than `target_fps` allows with the recent average
+    pub smooth_hitches: bool,
+    /// Print each hitch with the per-section timings of the frame that ran long
+    pub log_hitches: bool,
+    /// Zoom the base scene's camera with two-finger pinches
+    pub pinch_zoom: bool,
+    /// Toggle `Game::pause` with `Action::Pause` (ignored while a layer blocks input)
+    pub pause_with_action: bool,
+    /// Allow opening the cheat menu (see `Game::get_cheats_mut`); on in debug builds
+    pub enable_cheats: bool,
+    /// Save a screenshot to `screenshot_dir` on `Action::Screenshot` (F12); on in debug builds
+    pub screenshot_with_action: bool,
+    pub screenshot_dir: String,
+    /// Show an error screen and write a report to `crash_dir` when the game panics,
+    /// instead of closing the window (native builds; WASM panics abort)
+    pub crash_screen: bool,
+    pub crash_dir: String,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            title: "Lastor Game".to_string(),
+            window_width: 800,
+            window_height: 600,
+            fullscreen: false,
+            resizable: true,
+            min_window_width: 0,
+            min_window_height: 0,
+            vsync: true,
+            high_dpi: false,
+            ui_scale: 1.0,
+            motion: MotionSettings::default(),
+            target_fps: 60,
+            fixed_timestep_hz: None,
+            deterministic_seed: None,
+            asset_root: None,
+            background_color: Color::from_hex(0x1e1e1e),
+            show_fps: false,
+            show_input_debug: false,
+            show_build_info: false,
+            show_profiler: false,
+            show_debug_draw: cfg!(debug_assertions),
+            show_log: false,
+            log_lines: 12,
+            log_level: if cfg!(debug_assertions) { LogLevel::Debug } else { LogLevel::Info },
+            debug_theme: DebugTheme::default(),
+            smooth_hitches: false,
+            log_hitches: false,
+            pinch_zoom: false,
+            pause_with_action: false,
+            enable_cheats: cfg!(debug_assertions),
+            screenshot_with_action: cfg!(debug_assertions),
+            screenshot_dir: "screenshots".to_string(),
+            crash_screen: true,
+            crash_dir: "crashes".to_string(),
+        }
+    }
+}
+
+// Prefix of environment variable overrides, e.g. LASTOR_WINDOW_WIDTH=1920
+const ENV_PREFIX: &str = "LASTOR_";
+
+impl GameConfig {
+    /// Load a config from a RON file. Configs are RON like the engine's other data files;
+    /// TOML isn't supported.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, GameConfigError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    /// Parse a config from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, GameConfigError> {
+        ron::from_str(data).map_err(|err| GameConfigError::Format(err.to_string()))
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, GameConfigError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|err| GameConfigError::Format(err.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), GameConfigError> {
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+
+    /// Apply `LASTOR_*` environment variables, then `--key=value` command line arguments
+    pub fn with_overrides(mut self) -> Result<Self, GameConfigError> {
+        self.apply_env()?;
+        self.apply_args(std::env::args().skip(1))?;
+        Ok(self)
+    }
+
+    /// Apply environment variables named after settings, e.g. `LASTOR_SHOW_FPS=true`
+    pub fn apply_env(&mut self) -> Result<(), GameConfigError> {
+        for (name, value) in std::env::vars() {
+            let Some(key) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            let key = key.to_lowercase();
+            // Other LASTOR_ variables (like the build script's) aren't settings
+            if Self::KEYS.contains(&key.as_str()) {
+                self.set(&key, &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply `--key=value` or `--key value` arguments; a bare `--flag` sets a bool to true.
+    /// A bool only takes the next argument if it's `true` or `false`, so in
+    /// `--fullscreen level2.ron` the file is left for the game like other arguments that
+    /// aren't settings.
+    pub fn apply_args<I: IntoIterator<Item = String>>(&mut self, args: I) -> Result<(), GameConfigError> {
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(setting) = arg.strip_prefix("--") else {
+                continue;
+            };
+            let (key, value) = match setting.split_once('=') {
+                Some((key, value)) => (key.replace('-', "_"), value.to_string()),
+                None => {
+                    let key = setting.replace('-', "_");
+                    if !Self::KEYS.contains(&key.as_str()) {
+                        continue;
+                    }
+                    let is_bool = Self::BOOL_KEYS.contains(&key.as_str());
+                    let value = match args.peek() {
+                        Some(next) if is_bool && next.parse::<bool>().is_err() => "true".to_string(),
+                        Some(next) if !next.starts_with("--") => args.next().unwrap_or_default(),
+                        _ => "true".to_string(),
+                    };
+                    (key, value)
+                }
+            };
+            if Self::KEYS.contains(&key.as_str()) {
+                self.set(&key, &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Settings that can be overridden by name
+    pub const KEYS: &'static [&'static str] = &[
+        "title", "window_width", "window_height", "fullscreen", "resizable", "min_window_width",
+        "min_window_height", "vsync", "high_dpi", "ui_scale", "shake_scale", "reduced_motion",
+        "reduce_flashing", "target_fps", "fixed_timestep_hz", "deterministic_seed", "asset_root", "show_fps",
+        "show_input_debug", "show_build_info",
+        "show_profiler", "show_debug_draw", "show_log", "log_lines", "log_level", "smooth_hitches", "log_hitches",
+        "pinch_zoom", "pause_with_action", "enable_cheats", "screenshot_with_action", "screenshot_dir",
+        "crash_screen", "crash_dir",
+    ];
+
+    // Settings a bare `--flag` turns on
+    const BOOL_KEYS: &'static [&'static str] = &[
+        "fullscreen", "resizable", "vsync", "high_dpi", "reduced_motion", "reduce_flashing", "show_fps",
+        "show_input_debug", "show_build_info", "show_profiler", "show_debug_draw", "show_log",
+        "smooth_hitches", "log_hitches", "pinch_zoom", "pause_with_action", "enable_cheats",
+        "screenshot_with_action", "crash_screen",
+    ];
+
+    /// Set one setting from text, as overrides do ("none" clears optional settings)
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), GameConfigError> {
+        let invalid = || GameConfigError::Override { key: key.to_string(), value: value.to_string() };
+        let optional = (!value.eq_ignore_ascii_case("none")).then_some(value);
+        match key {
+            "title" => self.title = value.to_string(),
+            "window_width" => self.window_width = value.parse().map_err(|_| invalid())?,
+            "window_height" => self.window_height = value.parse().map_err(|_| invalid())?,
+            "fullscreen" => self.fullscreen = value.parse().map_err(|_| invalid())?,
+            "resizable" => self.resizable = value.parse().map_err(|_| invalid())?,
+            "min_window_width" => self.min_window_width = value.parse().map_err(|_| invalid())?,
+            "min_window_height" => self.min_window_height = value.parse().map_err(|_| invalid())?,
+            "vsync" => self.vsync = value.parse().map_err(|_| invalid())?,
+            "high_dpi" => self.high_dpi = value.parse().map_err(|_| invalid())?,
+            "ui_scale" => self.ui_scale = value.parse().map_err(|_| invalid())?,
+            "shake_scale" => self.motion.shake_scale = value.parse().map_err(|_| invalid())?,
+            "reduced_motion" => self.motion.reduced_motion = value.parse().map_err(|_| invalid())?,
+            "reduce_flashing" => self.motion.reduce_flashing = value.parse().map_err(|_| invalid())?,
+            "target_fps" => self.target_fps = value.parse().map_err(|_| invalid())?,
+            "fixed_timestep_hz" => {
+                self.fixed_timestep_hz = optional.map(str::parse).transpose().map_err(|_| invalid())?
+            }
+            "deterministic_seed" => {
+                self.deterministic_seed = optional.map(str::parse).transpose().map_err(|_| invalid())?
+            }
+            "asset_root" => self.asset_root = optional.map(str::to_string),
+            "show_fps" => self.show_fps = value.parse().map_err(|_| invalid())?,
+            "show_input_debug" => self.show_input_debug = value.parse().map_err(|_| invalid())?,
+            "show_build_info" => self.show_build_info = value.parse().map_err(|_| invalid())?,
+            "show_profiler" => self.show_profiler = value.parse().map_err(|_| invalid())?,
+            "show_debug_draw" => self.show_debug_draw = value.parse().map_err(|_| invalid())?,
+            "show_log" => self.show_log = value.parse().map_err(|_| invalid())?,
+            "log_lines" => self.log_lines = value.parse().map_err(|_| invalid())?,
+            "log_level" => self.log_level = value.parse().map_err(|_| invalid())?,
+            "smooth_hitches" => self.smooth_hitches = value.parse().map_err(|_| invalid())?,
+            "log_hitches" => self.log_hitches = value.parse().map_err(|_| invalid())?,
+            "pinch_zoom" => self.pinch_zoom = value.parse().map_err(|_| invalid())?,
+            "pause_with_action" => self.pause_with_action = value.parse().map_err(|_| invalid())?,
+            "enable_cheats" => self.enable_cheats = value.parse().map_err(|_| invalid())?,
+            "screenshot_with_action" => self.screenshot_with_action = value.parse().map_err(|_| invalid())?,
+            "screenshot_dir" => self.screenshot_dir = value.to_string(),
+            "crash_screen" => self.crash_screen = value.parse().map_err(|_| invalid())?,
+            "crash_dir" => self.crash_dir = value.to_string(),
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    /// Window settings for `#[macroquad::main(window_conf)]`
+    pub fn window_conf(&self) -> macroquad::window::Conf {
+        macroquad::window::Conf {
+            window_title: self.title.clone(),
+            window_width: self.window_width,
+            window_height: self.window_height,
+            fullscreen: self.fullscreen,
+            window_resizable: self.resizable,
+            high_dpi: self.high_dpi,
+            platform: macroquad::miniquad::conf::Platform {
+                swap_interval: Some(if self.vsync { 1 } else { 0 }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// `Color` as an `[r, g, b, a]` array
+mod color_rgba {
+    use macroquad::prelude::Color;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b, color.a].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        <[f32; 4]>::deserialize(deserializer).map(Color::from)
+    }
+}
@@ -0,0 +1,62 @@
+use macroquad::prelude::*;
+use super::EntityId;
+
+/// A transform rule tying one entity to another, applied after entity updates
+#[derive(Debug, Clone, PartialEq)]
+pub enum Constraint {
+    /// Circle around `center` (e.g. a shield around the player)
+    Orbit {
+        center: EntityId,
+        radius: f32,
+        /// Radians per second (negative = clockwise on screen)
+        angular_speed: f32,
+        /// Current angle, picked up from the starting offset on first use
+        angle: Option<f32>,
+    },
+    /// Rotate to face `target` (e.g. a turret)
+    LookAt { target: EntityId },
+    /// Stay at `target`'s position plus `offset` (e.g. a held item)
+    CopyPosition { target: EntityId, offset: Vec2 },
+}
+
+impl Constraint {
+    pub fn orbit(center: EntityId, radius: f32, angular_speed: f32) -> Self {
+        Constraint::Orbit { center, radius, angular_speed, angle: None }
+    }
+
+    pub fn look_at(target: EntityId) -> Self {
+        Constraint::LookAt { target }
+    }
+
+    pub fn copy_position(target: EntityId, offset: Vec2) -> Self {
+        Constraint::CopyPosition { target, offset }
+    }
+
+    /// The entity this constraint follows
+    pub fn target(&self) -> EntityId {
+        match self {
+            Constraint::Orbit { center, .. } => *center,
+            Constraint::LookAt { target } | Constraint::CopyPosition { target, .. } => *target,
+        }
+    }
+
+    /// Compute the constrained (position, rotation) from the current ones
+    pub(crate) fn solve(&mut self, dt: f32, position: Vec2, rotation: f32, target: Vec2) -> (Vec2, f32) {
+        match self {
+            Constraint::Orbit { radius, angular_speed, angle, .. } => {
+                let current = angle.get_or_insert_with(|| {
+                    let offset = position - target;
+                    if offset == Vec2::ZERO { 0.0 } else { offset.to_angle() }
+                });
+                *current += *angular_speed * dt;
+                (target + Vec2::from_angle(*current) * *radius, rotation)
+            }
+            Constraint::LookAt { .. } => {
+                let direction = target - position;
+                let rotation = if direction == Vec2::ZERO { rotation } else { direction.to_angle() };
+                (position, rotation)
+            }
+            Constraint::CopyPosition { offset, .. } => (target + *offset, rotation),
+        }
+    }
+}
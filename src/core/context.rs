@@ -0,0 +1,195 @@
+use std::any::Any;
+use std::cell::Cell;
+use super::{headless, Entity, EntityId, OwnerRemoval, Relation, Resources};
+use crate::input::InputManager;
+use crate::rendering::Camera;
+
+/// Everything an entity gets each update:
+///
+/// ```ignore
+/// impl Entity for Turret {
+///     fn update(&mut self, ctx: &mut UpdateContext) {
+///         self.cooldown -= ctx.dt;
+///         if self.cooldown <= 0.0 && ctx.input.is_action_active(&Action::Attack) {
+///             ctx.commands.spawn(Box::new(Bullet::new(self.transform.position, self.aim)));
+///             ctx.events.send(ShotFired { at: self.transform.position });
+///             self.cooldown = 0.5;
+///         }
+///     }
+/// }
+/// ```
+pub struct UpdateContext<'a> {
+    /// The updating entity, e.g. to own what it spawns
+    pub id: EntityId,
+    /// Seconds since the last update, already scaled for this entity
+    pub dt: f32,
+    /// Seconds the scene has been updating, before time scales
+    pub elapsed: f32,
+    /// The entity's input: its player slot's, or the game's
+    pub input: &'a InputManager,
+    /// The scene's main camera, as of the end of the last frame
+    pub camera: &'a Camera,
+    pub resources: &'a mut Resources,
+    pub events: &'a mut Events,
+    pub commands: &'a mut Commands,
+    // Seconds of physics this frame, shown by the profiler
+    pub(crate) physics_time: &'a Cell<f64>,
+}
+
+impl UpdateContext<'_> {
+    /// Run physics work, e.g. integrating a body or resolving collisions, and count its
+    /// time as physics instead of update in the profiler. Nothing an entity does in
+    /// `update` is counted as physics otherwise:
+    ///
+    /// ```ignore
+    /// let motion = ctx.physics(|| self.body.integrate(ctx.dt));
+    /// ```
+    pub fn physics<R>(&self, work: impl FnOnce() -> R) -> R {
+        let start = headless::now();
+        let result = work();
+        self.physics_time.set(self.physics_time.get() + headless::now() - start);
+        result
+    }
+}
+
+/// What an entity gets when it enters or leaves the scene (see `Entity::on_added` and
+/// `on_removed`):
+///
+/// ```ignore
+/// impl Entity for Enemy {
+///     fn on_removed(&mut self, ctx: &mut LifecycleContext) {
+///         ctx.commands.spawn(Box::new(Explosion::new(self.transform.position)));
+///         ctx.events.send(EnemyDied { id: ctx.id });
+///     }
+/// }
+/// ```
+pub struct LifecycleContext<'a> {
+    pub id: EntityId,
+    /// Seconds the scene has been updating, before time scales
+    pub elapsed: f32,
+    /// The game's resources, when the change happens during an update (including despawns
+    /// queued with `commands`, timeline despawns and owned entities removed with their
+    /// owner); None for `Scene::remove_entity` and `clear_all_entities` calls from game code
+    pub resources: Option<&'a mut Resources>,
+    pub events: &'a mut Events,
+    pub commands: &'a mut Commands,
+}
+
+/// A change to the scene queued by `Commands`
+pub(crate) enum Command {
+    Spawn(EntityId, Box<dyn Entity>),
+    Despawn(EntityId),
+    SetEnabled(EntityId, bool),
+    SetOwner(EntityId, EntityId, OwnerRemoval),
+    Relate(EntityId, Relation, EntityId),
+    Unrelate(EntityId, Relation),
+}
+
+/// Changes to the scene queued while entities update, since an entity can't reach the
+/// scene's other entities. The scene applies them in order once every entity has updated,
+/// so bullets, pickups and death effects can be spawned from gameplay code:
+///
+/// ```ignore
+/// fn update(&mut self, ctx: &mut UpdateContext) {
+///     if self.health <= 0.0 {
+///         ctx.commands.spawn(Box::new(Explosion::new(self.transform.position)));
+///         ctx.commands.despawn(self.shield);
+///         self.active = false;
+///     }
+/// }
+/// ```
+pub struct Commands {
+    queue: Vec<Command>,
+    // Id the next spawned entity gets, continuing the scene's ids
+    next_id: u64,
+}
+
+impl Commands {
+    pub(crate) fn new(next_id: u64) -> Self {
+        Self { queue: vec![], next_id }
+    }
+
+    /// Add an entity to the scene after this update. The id is valid right away, e.g. to
+    /// despawn it or give it a constraint later.
+    pub fn spawn(&mut self, entity: Box<dyn Entity>) -> EntityId {
+        let id = EntityId(self.next_id);
+        self.next_id += 1;
+        self.queue.push(Command::Spawn(id, entity));
+        id
+    }
+
+    /// Remove an entity from the scene after this update
+    pub fn despawn(&mut self, id: EntityId) {
+        self.queue.push(Command::Despawn(id));
+    }
+
+    /// Pause or resume an entity after this update: disabled entities stay in the scene
+    /// but are neither updated nor drawn (see `Scene::disable_entity`). This doesn't
+    /// deactivate the entity, so it isn't cleared, its `on_removed` doesn't run and a pooled
+    /// one isn't recycled; despawn it for that. Entities the scene disabled after their
+    /// update panicked stay disabled.
+    pub fn set_enabled(&mut self, id: EntityId, enabled: bool) {
+        self.queue.push(Command::SetEnabled(id, enabled));
+    }
+
+    /// Make `owner` own `entity` after this update (see `Scene::set_owner`)
+    pub fn set_owner(&mut self, entity: EntityId, owner: EntityId, removal: OwnerRemoval) {
+        self.queue.push(Command::SetOwner(entity, owner, removal));
+    }
+
+    /// Link `from` to `to` after this update (see `Scene::relate`)
+    pub fn relate(&mut self, from: EntityId, relation: Relation, to: EntityId) {
+        self.queue.push(Command::Relate(from, relation, to));
+    }
+
+    pub fn unrelate(&mut self, from: EntityId, relation: Relation) {
+        self.queue.push(Command::Unrelate(from, relation));
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub(crate) fn into_parts(self) -> (Vec<Command>, u64) {
+        (self.queue, self.next_id)
+    }
+}
+
+/// Gameplay events of any type. Events sent during one update can be read during the
+/// next one, by entities (`UpdateContext::events`) and by game code (`Scene::events`).
+#[derive(Default)]
+pub struct Events {
+    // Sent during the last update, readable now
+    previous: Vec<Box<dyn Any>>,
+    current: Vec<Box<dyn Any>>,
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send<T: 'static>(&mut self, event: T) {
+        self.current.push(Box::new(event));
+    }
+
+    /// Events of type `T` sent during the last update, in the order they were sent
+    pub fn read<T: 'static>(&self) -> impl Iterator<Item = &T> {
+        self.previous.iter().filter_map(|event| event.downcast_ref())
+    }
+
+    /// Make this update's events readable and drop the older ones (the scene calls this
+    /// before each update)
+    pub(crate) fn swap(&mut self) {
+        self.previous = std::mem::take(&mut self.current);
+    }
+
+    pub fn clear(&mut self) {
+        self.previous.clear();
+        self.current.clear();
+    }
+}
@@ -0,0 +1,91 @@
+use super::EntityId;
+use std::fmt;
+
+/// Where an entity ended up in the draw order, and the keys that put it there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DrawOrderEntry {
+    pub entity: EntityId,
+    /// `Entity::draw_layer`, compared first
+    pub layer: i32,
+    /// `Entity::sort_key`, compared within a layer
+    pub sort_key: i64,
+    /// Order the entity was added in, which breaks ties
+    pub insertion: usize,
+}
+
+impl DrawOrderEntry {
+    /// What entries sort by: layer, then sort key, then insertion
+    pub(crate) fn order(&self) -> (i32, i64, usize) {
+        (self.layer, self.sort_key, self.insertion)
+    }
+}
+
+/// Entities drawn and skipped by culling, summed over the views drawn since the scene's
+/// last update (see `Scene::set_culling`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CullingStats {
+    pub drawn: usize,
+    /// Active entities outside every view, which drawing never touched
+    pub culled: usize,
+}
+
+impl fmt::Display for CullingStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} drawn, {} culled", self.drawn, self.culled)
+    }
+}
+
+/// The order a scene draws its entities in, back to front (see `Scene::draw_order`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DrawOrder {
+    entries: Vec<DrawOrderEntry>,
+}
+
+impl DrawOrder {
+    /// Sort by layer, then sort key, then insertion
+    pub fn new(mut entries: Vec<DrawOrderEntry>) -> Self {
+        entries.sort_by_key(DrawOrderEntry::order);
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[DrawOrderEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Index of an entity in the order, if it's drawn
+    pub fn position(&self, entity: EntityId) -> Option<usize> {
+        self.entries.iter().position(|entry| entry.entity == entity)
+    }
+
+    /// Number of places where the sort key changes within a layer. With material or
+    /// texture ids as keys, this is how many times drawing switches state.
+    pub fn key_changes(&self) -> usize {
+        self.entries
+            .windows(2)
+            .filter(|pair| pair[0].layer == pair[1].layer && pair[0].sort_key != pair[1].sort_key)
+            .count()
+    }
+}
+
+impl fmt::Display for DrawOrder {
+    /// One line per entity, e.g. "  3: EntityId(7)  layer 1  key 42  (added #5)"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} entities drawn, {} key changes", self.entries.len(), self.key_changes())?;
+        for (index, entry) in self.entries.iter().enumerate() {
+            writeln!(
+                f,
+                "{:>4}: {:?}  layer {}  key {}  (added #{})",
+                index, entry.entity, entry.layer, entry.sort_key, entry.insertion
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -1,22 +1,55 @@
 // src/core/entity.rs
 use macroquad::prelude::*;
-use crate::{math::Transform, input::InputManager};
+use crate::{math::Transform, physics::Collider, rendering::{Lod, SpriteBatch}};
+use super::scene_file::EntityRecord;
+use super::timeline::ScriptAction;
+use super::context::{LifecycleContext, UpdateContext};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+
+/// Stable handle to an entity in a `Scene`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EntityId(pub(crate) u64);
+
+/// Lets a `dyn Entity` be downcast to its own type (implemented for every entity)
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
 
 /// The trait that all game objects must implement
-pub trait Entity {
-    /// Update the entity's logic (called every frame)
-    fn update(&mut self, dt: f32);
+pub trait Entity: AsAny {
+    /// Update the entity's logic (called every frame) with its dt, input, the camera,
+    /// the game's resources, events, and a queue for spawning and despawning
+    fn update(&mut self, ctx: &mut UpdateContext);
     
-    /// Draw the entity (called every frame after update)
+    /// Draw the entity (called every frame after update, under the scene's camera)
     fn draw(&self);
-    
-    /// Update with input access - override this for entities that need input
-    fn update_with_input(&mut self, dt: f32, _input: &InputManager) {
-        // Default implementation just calls regular update (ignores input)
-        // Override this method in your entities to use input
-        self.update(dt);
+
+    /// Draw at reduced detail when the scene has `LodSettings` (defaults to `draw` at every level)
+    fn draw_lod(&self, _lod: Lod) {
+        self.draw();
     }
-    
+
+    /// Queue the entity's sprites instead of drawing them, when the scene batches sprites by
+    /// texture (`Scene::set_sprite_batching`). Return false to be drawn with `draw` instead.
+    fn queue_sprites(&self, _batch: &mut SpriteBatch) -> bool {
+        false
+    }
+
+    /// Draw screen-space parts of the entity such as health bars or name tags
+    /// (called after the world pass, with no camera applied)
+    fn draw_ui(&self) {}
 
     /// Get read-only access to this entity's transform (if it has one)
     fn get_transform(&self) -> Option<&Transform> {
@@ -37,6 +70,70 @@ pub trait Entity {
         fn get_bounds(&self) -> Option<(Vec2, Vec2)> {
         None
     }
+
+    /// Collision shape centered on the transform, for debug drawing (see `PhysicsDebug`)
+    fn get_collider(&self) -> Option<Collider> {
+        None
+    }
+
+    /// Current velocity in pixels per second, for debug drawing
+    fn get_velocity(&self) -> Option<Vec2> {
+        None
+    }
+
+    /// Check if this entity carries a tag (e.g. "player", "enemy")
+    fn has_tag(&self, _tag: &str) -> bool {
+        false
+    }
+
+    /// Local player slot controlling this entity (see `PlayerInputs`).
+    /// Entities without one get the game's main `InputManager`.
+    fn player_slot(&self) -> Option<usize> {
+        None
+    }
+
+    /// Render layer; higher layers draw on top
+    fn draw_layer(&self) -> i32 {
+        0
+    }
+
+    /// Order within the draw layer, lowest first. Return a material or texture id to
+    /// keep entities that batch together adjacent, or a priority. Ties draw in the order
+    /// the entities were added (see `Scene::draw_order`).
+    fn sort_key(&self) -> i64 {
+        0
+    }
+
+    /// Keep updating while the scene is paused (pause menus, HUD animations)
+    fn updates_while_paused(&self) -> bool {
+        false
+    }
+
+    /// Called when the entity enters the scene, at the start of the first update after it
+    /// was added, before it updates
+    fn on_added(&mut self, _ctx: &mut LifecycleContext) {}
+
+    /// Called when the entity leaves the scene: removed, despawned or cleared as inactive.
+    /// Dropping the whole scene doesn't run it. Entities removed before they were added
+    /// get neither hook.
+    fn on_removed(&mut self, _ctx: &mut LifecycleContext) {}
+
+    /// React to an action of a timeline the scene runs on this entity (see `Timeline`).
+    /// The scene itself handles `Despawn` and `PlaySound`.
+    fn on_script_action(&mut self, _action: &ScriptAction) {}
+
+    /// Current animation frame, recorded for rewinding (see `Scene::set_rewindable`)
+    fn animation_frame(&self) -> Option<u32> {
+        None
+    }
+
+    /// Jump to an animation frame while rewinding
+    fn set_animation_frame(&mut self, _frame: u32) {}
+
+    /// Describe this entity for a scene file (see `Scene::to_file`); None = not saved
+    fn save(&self) -> Option<EntityRecord> {
+        None
+    }
 }
 
 /// A basic entity implementation with transform component
@@ -77,7 +174,7 @@ impl GameObject {
 }
 
 impl Entity for GameObject {
-    fn update(&mut self, _dt: f32) {
+    fn update(&mut self, _ctx: &mut UpdateContext) {
         // Default implementation does nothing - override this method
     }
     
@@ -0,0 +1,325 @@
+use super::BuildInfo;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const CSV_HEADER: &str = "frame_time,input,update,physics,camera,draw,entities";
+// Written before physics had its own column
+const LEGACY_CSV_HEADER: &str = "frame_time,input,update,camera,draw,entities";
+
+// Rows buffered before the CSV file is flushed
+const FLUSH_INTERVAL: usize = 60;
+
+/// Error returned when saving or loading frame stats
+#[derive(Debug)]
+pub enum StatsError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatsError::Io(err) => write!(f, "failed to access frame stats file: {}", err),
+            StatsError::Format(msg) => write!(f, "invalid frame stats data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+impl From<std::io::Error> for StatsError {
+    fn from(err: std::io::Error) -> Self {
+        StatsError::Io(err)
+    }
+}
+
+/// Timings of one frame in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Time since the previous frame started (includes waiting for vsync)
+    pub frame_time: f32,
+    pub input: f32,
+    /// Entity updates and everything else in the update, except physics. Movement and
+    /// collisions entities run in `Entity::update` count here unless they're wrapped in
+    /// `UpdateContext::physics`.
+    pub update: f32,
+    /// Only the scene's constraints and portal crossings, and the work entities wrap in
+    /// `UpdateContext::physics`
+    pub physics: f32,
+    pub camera: f32,
+    pub draw: f32,
+    pub entities: usize,
+}
+
+impl FrameStats {
+    /// Names and times of the sections a frame is split into, in the order they run
+    pub fn sections(&self) -> [(&'static str, f32); 5] {
+        [
+            ("input", self.input),
+            ("update", self.update),
+            ("physics", self.physics),
+            ("camera", self.camera),
+            ("draw", self.draw),
+        ]
+    }
+
+    fn to_csv_row(self) -> String {
+        format!(
+            "{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{}",
+            self.frame_time, self.input, self.update, self.physics, self.camera, self.draw, self.entities
+        )
+    }
+
+    /// Parse a row; rows without a physics column are from older sessions
+    fn from_csv_row(row: &str, with_physics: bool) -> Result<Self, String> {
+        let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+        let columns = if with_physics { 7 } else { 6 };
+        if fields.len() != columns {
+            return Err(format!("expected {} columns, found {}", columns, fields.len()));
+        }
+        let number = |index: usize| fields[index].parse::<f32>().map_err(|err| format!("'{}': {}", fields[index], err));
+        let skip = with_physics as usize;
+        Ok(Self {
+            frame_time: number(0)?,
+            input: number(1)?,
+            update: number(2)?,
+            physics: if with_physics { number(3)? } else { 0.0 },
+            camera: number(3 + skip)?,
+            draw: number(4 + skip)?,
+            entities: fields[5 + skip].parse().map_err(|err| format!("'{}': {}", fields[5 + skip], err))?,
+        })
+    }
+}
+
+/// Frame time percentiles of one subsystem, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Percentiles {
+    pub mean: f32,
+    pub p50: f32,
+    pub p95: f32,
+    pub p99: f32,
+    pub max: f32,
+}
+
+impl Percentiles {
+    pub fn from_samples(samples: &[f32]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        // Nearest-rank percentile
+        let rank = |p: f32| sorted[((p * sorted.len() as f32).ceil() as usize).clamp(1, sorted.len()) - 1];
+        Self {
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            p50: rank(0.5),
+            p95: rank(0.95),
+            p99: rank(0.99),
+            max: sorted[sorted.len() - 1],
+        }
+    }
+}
+
+/// Percentiles per subsystem for a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatsSummary {
+    pub frames: usize,
+    pub subsystems: Vec<(&'static str, Percentiles)>,
+}
+
+impl StatsSummary {
+    pub fn get(&self, subsystem: &str) -> Option<Percentiles> {
+        self.subsystems.iter().find(|(name, _)| *name == subsystem).map(|(_, stats)| *stats)
+    }
+}
+
+impl fmt::Display for StatsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} frames (ms)", self.frames)?;
+        writeln!(f, "{:<12}{:>9}{:>9}{:>9}{:>9}{:>9}", "", "mean", "p50", "p95", "p99", "max")?;
+        for (name, stats) in &self.subsystems {
+            writeln!(
+                f,
+                "{:<12}{:>9.3}{:>9.3}{:>9.3}{:>9.3}{:>9.3}",
+                name, stats.mean, stats.p50, stats.p95, stats.p99, stats.max
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Per-frame timings of a play session.
+///
+/// Save a session from one engine version, then load it later and compare
+/// `summary()` reports to catch performance regressions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStatsLog {
+    frames: Vec<FrameStats>,
+    build: Option<BuildInfo>,
+}
+
+impl FrameStatsLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, stats: FrameStats) {
+        self.frames.push(stats);
+    }
+
+    /// Tag the session with the build it was recorded on
+    pub fn set_build(&mut self, build: Option<BuildInfo>) {
+        self.build = build;
+    }
+
+    pub fn build(&self) -> Option<&BuildInfo> {
+        self.build.as_ref()
+    }
+
+    pub fn frames(&self) -> &[FrameStats] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    /// p50/p95/p99 for the whole frame and each subsystem
+    pub fn summary(&self) -> StatsSummary {
+        let column = |get: fn(&FrameStats) -> f32| -> Percentiles {
+            Percentiles::from_samples(&self.frames.iter().map(get).collect::<Vec<_>>())
+        };
+        StatsSummary {
+            frames: self.frames.len(),
+            subsystems: vec![
+                ("frame", column(|stats| stats.frame_time)),
+                ("input", column(|stats| stats.input)),
+                ("update", column(|stats| stats.update)),
+                ("physics", column(|stats| stats.physics)),
+                ("camera", column(|stats| stats.camera)),
+                ("draw", column(|stats| stats.draw)),
+            ],
+        }
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        for stats in &self.frames {
+            csv.push_str(&stats.to_csv_row());
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Serialize as a JSON array of frame objects, or as `{"build": ..., "frames": [...]}`
+    /// when the session is tagged with a build
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self.frames.iter()
+            .map(|stats| format!(
+                "{{\"frame_time\":{},\"input\":{},\"update\":{},\"physics\":{},\"camera\":{},\"draw\":{},\"entities\":{}}}",
+                stats.frame_time, stats.input, stats.update, stats.physics, stats.camera, stats.draw, stats.entities
+            ))
+            .collect();
+        match &self.build {
+            Some(build) => format!("{{\"build\":{},\"frames\":[{}]}}", build.to_json(), rows.join(",")),
+            None => format!("[{}]", rows.join(",")),
+        }
+    }
+
+    /// Parse frames from CSV with the header written by `to_csv`. Sessions saved before
+    /// physics was timed separately load with zero physics time.
+    pub fn load_csv_from_str(data: &str) -> Result<Self, StatsError> {
+        let mut lines = data.lines().filter(|line| !line.trim().is_empty());
+        let with_physics = match lines.next().map(str::trim) {
+            Some(CSV_HEADER) => true,
+            Some(LEGACY_CSV_HEADER) => false,
+            _ => return Err(StatsError::Format(format!("missing header '{}'", CSV_HEADER))),
+        };
+        let frames = lines
+            .enumerate()
+            .map(|(index, line)| {
+                FrameStats::from_csv_row(line, with_physics).map_err(|err| StatsError::Format(format!("row {}: {}", index + 1, err)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { frames, build: None })
+    }
+
+    pub fn save_csv<P: AsRef<Path>>(&self, path: P) -> Result<(), StatsError> {
+        std::fs::write(path, self.to_csv())?;
+        Ok(())
+    }
+
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> Result<(), StatsError> {
+        std::fs::write(path, self.to_json())?;
+        Ok(())
+    }
+
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Self, StatsError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_csv_from_str(&data)
+    }
+}
+
+/// Collects frame stats while the game runs, optionally streaming them to a CSV file
+pub(crate) struct FrameStatsRecorder {
+    log: FrameStatsLog,
+    writer: Option<BufWriter<File>>,
+    unflushed: usize,
+}
+
+impl FrameStatsRecorder {
+    pub(crate) fn new<P: AsRef<Path>>(csv_path: Option<P>) -> Result<Self, StatsError> {
+        let writer = match csv_path {
+            Some(path) => {
+                let mut writer = BufWriter::new(File::create(path)?);
+                writeln!(writer, "{}", CSV_HEADER)?;
+                Some(writer)
+            }
+            None => None,
+        };
+        Ok(Self { log: FrameStatsLog::new(), writer, unflushed: 0 })
+    }
+
+    pub(crate) fn push(&mut self, stats: FrameStats) {
+        self.log.push(stats);
+        let Some(writer) = &mut self.writer else {
+            return;
+        };
+        // A failing disk shouldn't stop the game; stop streaming instead
+        let mut result = writeln!(writer, "{}", stats.to_csv_row());
+        self.unflushed += 1;
+        if result.is_ok() && self.unflushed >= FLUSH_INTERVAL {
+            result = writer.flush();
+            self.unflushed = 0;
+        }
+        if result.is_err() {
+            self.writer = None;
+        }
+    }
+
+    pub(crate) fn set_build(&mut self, build: Option<BuildInfo>) {
+        self.log.set_build(build);
+    }
+
+    pub(crate) fn log(&self) -> &FrameStatsLog {
+        &self.log
+    }
+
+    pub(crate) fn finish(mut self) -> FrameStatsLog {
+        if let Some(writer) = &mut self.writer {
+            let _ = writer.flush();
+        }
+        self.log
+    }
+}
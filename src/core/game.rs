@@ -1,38 +1,124 @@
 // src/game.rs
 use macroquad::prelude::*;
-use super::{Entity, Scene, TimeManager};
-use crate::input::InputManager;
-
-/// Configuration for the game
-pub struct GameConfig {
-    pub title: String,
-    pub window_width: i32,
-    pub window_height: i32,
-    pub target_fps: u32,
-    pub background_color: Color,
-    pub show_fps: bool,
-    pub show_input_debug: bool,
-}
+use super::{Entity, EntityId, GameConfig, GameState, Hitch, JobId, JobQueue, JobStatus, MotionSettings, Plugin, PrefabLibrary, Resources, Scene, StressTest, TimeManager};
+use super::layer::{Coverage, SceneLayer, SceneLayers};
+use super::state::StateMachine;
+use super::build_info::{install_panic_hook, BuildInfo};
+use super::remote_config::{ConfigEvent, RemoteConfig};
+use super::panic_screen::{self, CatchUnwind, PanicChoice, PanicScreen};
+use super::frame_stats::{FrameStats, FrameStatsLog, FrameStatsRecorder, StatsError};
+use super::profiler::FrameProfiler;
+use super::snapshot::SnapshotDiff;
+use super::logging;
+use super::rng::{self, Rng};
+use crate::input::{Action, InputManager, PlayerInputs};
+use crate::assets::{draw_loading_bar, AssetError, AssetManager, LoadProgress};
+#[cfg(feature = "hot-reload")]
+use crate::assets::AssetReload;
+use crate::audio::{AudioManager, SoundEvents};
+use crate::audio::ambience::AmbiencePlayer;
+use crate::rendering::{capture_screen, debug_draw, DebugTheme};
+use crate::ui::{self, CheatMenu, Cursor, CursorManager};
+use std::rc::Rc;
+#[cfg(feature = "http")]
+use crate::net::HttpClient;
 
-impl Default for GameConfig {
-    fn default() -> Self {
-        Self {
-            title: "Lastor Game".to_string(),
-            window_width: 800,
-            window_height: 600,
-            target_fps: 60,
-            background_color: Color::from_hex(0x1e1e1e),
-            show_fps: false,
-            show_input_debug: false,
-        }
-    }
+/// Callback run once each time the player goes idle
+struct IdleHook {
+    timeout: f32,
+    callback: Box<dyn FnMut(&mut Scene)>,
+    fired: bool,
 }
 
+// Hitches kept for `Game::take_hitches`
+const MAX_HITCHES: usize = 64;
+
+// Lines of the desync panel drawn by `Game::show_desync`
+const DESYNC_LINES: usize = 24;
+
+// Most fixed steps run in one frame, so a long stall doesn't snowball into more stalls
+const MAX_FIXED_STEPS: u32 = 8;
+
+/// Callback run every frame after input to push or pop scene layers
+type LayerHook = Box<dyn FnMut(&mut SceneLayers, &InputManager)>;
+
+/// Callback run with the base scene when something happens to the game
+type SceneHook = Box<dyn FnMut(&mut Scene)>;
+
+/// Callback run with the new window size and the base scene when the window is resized
+type ResizeHook = Box<dyn FnMut(Vec2, &mut Scene)>;
+
+/// Callback run with the base scene for each remote config event
+type ConfigHook = Box<dyn FnMut(&ConfigEvent, &mut Scene)>;
+
+/// Callback run with the base scene for each file reloaded from disk
+#[cfg(feature = "hot-reload")]
+type ReloadHook = Box<dyn FnMut(&AssetReload, &mut Scene)>;
+
+// Seconds between checks for changed asset files
+#[cfg(feature = "hot-reload")]
+const RELOAD_INTERVAL: f32 = 0.5;
+
 /// The main game runner
 pub struct Game {
     scene: Scene,
+    layers: SceneLayers,
+    layer_hook: Option<LayerHook>,
+    // Handed to scenes whose input is blocked by a layer above
+    blocked_input: InputManager,
     time_manager: TimeManager,
     input_manager: InputManager,
+    players: PlayerInputs,
+    idle_hook: Option<IdleHook>,
+    // Pause state of the base scene as of the last hook call
+    was_paused: bool,
+    pause_hook: Option<SceneHook>,
+    resume_hook: Option<SceneHook>,
+    exit_hook: Option<SceneHook>,
+    // Set by `request_exit` or the OS; the loop ends before the next frame
+    exit_requested: bool,
+    close_requested: bool,
+    // Leave OS close requests to the game instead of exiting
+    confirm_close: bool,
+    frame_stats: Option<FrameStatsRecorder>,
+    // Section timings of the previous frame, blamed for hitches
+    last_frame: Option<FrameStats>,
+    profiler: FrameProfiler,
+    // Shown over the game until cleared
+    desync: Option<SnapshotDiff>,
+    hitches: Vec<Hitch>,
+    // Time not yet simulated with fixed timesteps
+    step_accumulator: f32,
+    build_info: Option<BuildInfo>,
+    panic_screen: Option<PanicScreen>,
+    #[cfg(feature = "http")]
+    http: HttpClient,
+    remote_config: RemoteConfig,
+    audio: AudioManager,
+    sound_events: SoundEvents,
+    ambience: AmbiencePlayer,
+    assets: AssetManager,
+    cheats: CheatMenu,
+    cursor: CursorManager,
+    // Paths of the screenshots to save once this frame is drawn
+    screenshots: Vec<String>,
+    stress: Option<StressTest>,
+    plugins: Vec<Box<dyn Plugin>>,
+    resources: Resources,
+    // Simulation steps run so far
+    tick: u64,
+    jobs: JobQueue,
+    states: StateMachine,
+    #[cfg(feature = "hot-reload")]
+    reload_hook: Option<ReloadHook>,
+    // Real seconds until the next check for changed files
+    #[cfg(feature = "hot-reload")]
+    reload_timer: f32,
+    config_hook: Option<ConfigHook>,
+    resize_hook: Option<ResizeHook>,
+    // Window size as of the last frame, in pixels
+    window_size: Vec2,
+    resized: bool,
     pub config: GameConfig,
 }
 
@@ -42,16 +128,64 @@ impl Game {
     }
     
     pub fn with_config(config: GameConfig) -> Self {
+        let audio = AudioManager::new();
         Self {
             scene: Scene::new(),
+            layers: SceneLayers::new(),
+            layer_hook: None,
+            blocked_input: InputManager::new(),
             time_manager: TimeManager::new(),
             input_manager: InputManager::new(),
+            players: PlayerInputs::new(),
+            idle_hook: None,
+            was_paused: false,
+            pause_hook: None,
+            resume_hook: None,
+            exit_hook: None,
+            exit_requested: false,
+            close_requested: false,
+            confirm_close: false,
+            frame_stats: None,
+            last_frame: None,
+            profiler: FrameProfiler::new(),
+            desync: None,
+            hitches: vec![],
+            step_accumulator: 0.0,
+            build_info: None,
+            panic_screen: None,
+            #[cfg(feature = "http")]
+            http: HttpClient::new(),
+            remote_config: RemoteConfig::new(),
+            sound_events: SoundEvents::new(audio.clone()),
+            ambience: AmbiencePlayer::default(),
+            audio,
+            assets: match &config.asset_root {
+                Some(root) => AssetManager::new().with_root(root),
+                None => AssetManager::new(),
+            },
+            cheats: CheatMenu::new(),
+            cursor: CursorManager::new(),
+            screenshots: vec![],
+            stress: None,
+            plugins: vec![],
+            resources: Resources::new(),
+            tick: 0,
+            jobs: JobQueue::new(),
+            states: StateMachine::default(),
+            #[cfg(feature = "hot-reload")]
+            reload_hook: None,
+            #[cfg(feature = "hot-reload")]
+            reload_timer: RELOAD_INTERVAL,
+            config_hook: None,
+            resize_hook: None,
+            window_size: Vec2::new(screen_width(), screen_height()),
+            resized: false,
             config,
         }
     }
 
-    pub fn add_entity(&mut self, entity: Box<dyn Entity>) {
-        self.scene.add_entity(entity);
+    pub fn add_entity(&mut self, entity: Box<dyn Entity>) -> EntityId {
+        self.scene.add_entity(entity)
     }
     
     pub fn get_scene(&self) -> &Scene {
@@ -62,6 +196,49 @@ impl Game {
         &mut self.scene
     }
     
+    // Scene layers
+    
+    /// Put a scene above the base scene and the other layers. The base scene and
+    /// every layer update and draw bottom to top, as the layers above them allow.
+    pub fn push_layer(&mut self, layer: SceneLayer) {
+        self.layers.push(layer);
+    }
+    
+    /// Put a scene above the others with data for it to read through `Scene::payload`
+    pub fn push_layer_with<T: 'static>(&mut self, layer: SceneLayer, payload: T) {
+        self.layers.push_with(layer, payload);
+    }
+    
+    /// Remove the top layer; its result goes to the scene below
+    pub fn pop_layer(&mut self) -> Option<SceneLayer> {
+        let layer = self.layers.pop();
+        self.deliver_base_results();
+        layer
+    }
+    
+    pub fn get_layers(&self) -> &SceneLayers {
+        &self.layers
+    }
+    
+    pub fn get_layers_mut(&mut self) -> &mut SceneLayers {
+        &mut self.layers
+    }
+    
+    /// Run `callback` every frame after input, to open and close layers while the game runs.
+    ///
+    /// ```ignore
+    /// game.on_update_layers(|layers, input| {
+    ///     if input.is_action_just_activated(&Action::Pause) {
+    ///         if layers.remove("pause").is_none() {
+    ///             layers.push(SceneLayer::modal("pause", pause_menu_scene()));
+    ///         }
+    ///     }
+    /// });
+    /// ```
+    pub fn on_update_layers<F: FnMut(&mut SceneLayers, &InputManager) + 'static>(&mut self, callback: F) {
+        self.layer_hook = Some(Box::new(callback));
+    }
+    
     pub fn get_time(&self) -> &TimeManager {
         &self.time_manager
     }
@@ -74,34 +251,700 @@ impl Game {
         &mut self.input_manager
     }
     
+    /// Enable local multiplayer input; entities pick their player via `Entity::player_slot`
+    pub fn set_players(&mut self, players: PlayerInputs) {
+        self.players = players;
+    }
+    
+    pub fn get_players(&self) -> &PlayerInputs {
+        &self.players
+    }
+    
+    pub fn get_players_mut(&mut self) -> &mut PlayerInputs {
+        &mut self.players
+    }
+    
+    /// Run `callback` after `seconds` without input, e.g. to swap in an attract/demo scene.
+    /// Fires once per idle period; any input re-arms it.
+    pub fn on_idle<F: FnMut(&mut Scene) + 'static>(&mut self, seconds: f32, callback: F) {
+        self.idle_hook = Some(IdleHook { timeout: seconds, callback: Box::new(callback), fired: false });
+    }
+    
+    pub fn clear_idle_hook(&mut self) {
+        self.idle_hook = None;
+    }
+    
+    /// Pause the base scene: its entities stop updating (except those with
+    /// `Entity::updates_while_paused`) while input, cameras, drawing and scene layers carry on.
+    /// Hooks can also pause through `Scene::set_paused`.
+    pub fn pause(&mut self) {
+        self.scene.set_paused(true);
+        self.check_pause();
+    }
+    
+    pub fn resume(&mut self) {
+        self.scene.set_paused(false);
+        self.check_pause();
+    }
+    
+    pub fn toggle_pause(&mut self) {
+        self.scene.set_paused(!self.scene.is_paused());
+        self.check_pause();
+    }
+    
+    pub fn is_paused(&self) -> bool {
+        self.scene.is_paused()
+    }
+    
+    /// Run `callback` each time the game pauses, e.g. to push a pause menu layer or mute music
+    pub fn on_pause<F: FnMut(&mut Scene) + 'static>(&mut self, callback: F) {
+        self.pause_hook = Some(Box::new(callback));
+    }
+    
+    pub fn on_resume<F: FnMut(&mut Scene) + 'static>(&mut self, callback: F) {
+        self.resume_hook = Some(Box::new(callback));
+    }
+    
+    /// Stop `run` once the current frame is done. Plugins' `exit` hooks and the
+    /// `on_exit` callback run before it returns.
+    pub fn request_exit(&mut self) {
+        self.exit_requested = true;
+    }
+    
+    /// Take back an exit request made this frame
+    pub fn cancel_exit(&mut self) {
+        self.exit_requested = false;
+    }
+    
+    pub fn is_exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+    
+    /// Whether the OS asked the window to close (close button, Alt+F4, Cmd+Q)
+    pub fn is_close_requested(&self) -> bool {
+        self.close_requested
+    }
+    
+    /// Forget an OS close request, e.g. when the player answers "no" to "Quit?"
+    pub fn clear_close_request(&mut self) {
+        self.close_requested = false;
+    }
+    
+    /// With `confirm`, OS close requests don't exit on their own: `is_close_requested`
+    /// turns true and the game decides, e.g. shows a dialog then calls `request_exit`
+    pub fn set_confirm_close(&mut self, confirm: bool) {
+        self.confirm_close = confirm;
+    }
+    
+    /// Run `callback` once when the game exits, e.g. to save settings or flush saves.
+    /// It doesn't run when quitting from the error screen.
+    pub fn on_exit<F: FnMut(&mut Scene) + 'static>(&mut self, callback: F) {
+        self.exit_hook = Some(Box::new(callback));
+    }
+    
+    /// Move to another state at the start of the next frame, running the exit hooks of the
+    /// states left and the enter hooks of the new ones. Entering `Paused` pauses the base
+    /// scene and leaving it resumes; pausing while `Playing` moves to `Paused`.
+    ///
+    /// ```ignore
+    /// game.on_state_enter(GameState::Playing, |scene| load_level(scene, 1));
+    /// game.on_state_update(GameState::Playing, |scene, _| {
+    ///     scene.find_by_tag("player").is_empty().then_some(GameState::GameOver)
+    /// });
+    /// game.on_state_exit(GameState::Playing, |scene| save_high_score(scene));
+    /// game.set_state(GameState::Menu);
+    /// ```
+    pub fn set_state(&mut self, state: GameState) {
+        self.states.request(state);
+    }
+    
+    pub fn state(&self) -> GameState {
+        self.states.current()
+    }
+    
+    pub fn previous_state(&self) -> Option<GameState> {
+        self.states.previous()
+    }
+    
+    /// Whether `state` is the current state or one it sits on top of (`Playing` while `Paused`)
+    pub fn is_in_state(&self, state: GameState) -> bool {
+        self.states.is_in(state)
+    }
+    
+    /// Seconds since the current state was entered
+    pub fn time_in_state(&self) -> f32 {
+        self.states.time_in_state()
+    }
+    
+    /// Run `callback` each time `state` is entered (the starting state's run on the first frame)
+    pub fn on_state_enter<F: FnMut(&mut Scene) + 'static>(&mut self, state: GameState, callback: F) {
+        self.states.on_enter(state, Box::new(callback));
+    }
+    
+    pub fn on_state_exit<F: FnMut(&mut Scene) + 'static>(&mut self, state: GameState, callback: F) {
+        self.states.on_exit(state, Box::new(callback));
+    }
+    
+    /// Run `callback` every frame in `state` with the frame's dt; returning a state moves there
+    pub fn on_state_update<F: FnMut(&mut Scene, f32) -> Option<GameState> + 'static>(&mut self, state: GameState, callback: F) {
+        self.states.on_update(state, Box::new(callback));
+    }
+    
+    /// Add an entity to the base scene that's removed when the game leaves `state`
+    pub fn spawn_in_state(&mut self, state: GameState, entity: Box<dyn Entity>) -> EntityId {
+        let id = self.scene.add_entity(entity);
+        self.states.scope(state, id);
+        id
+    }
+    
+    /// Remove an existing entity when the game leaves `state`
+    pub fn scope_to_state(&mut self, state: GameState, id: EntityId) {
+        self.states.scope(state, id);
+    }
+    
+    /// Start recording per-frame timings. With a path, frames are also streamed to
+    /// that CSV file as they happen, so the data survives the window being closed.
+    pub fn record_frame_stats<P: AsRef<std::path::Path>>(&mut self, csv_path: Option<P>) -> Result<(), StatsError> {
+        let mut recorder = FrameStatsRecorder::new(csv_path)?;
+        recorder.set_build(self.build_info.clone());
+        self.frame_stats = Some(recorder);
+        Ok(())
+    }
+    
+    /// Stop recording and return the session's frames
+    pub fn stop_frame_stats(&mut self) -> Option<FrameStatsLog> {
+        self.frame_stats.take().map(FrameStatsRecorder::finish)
+    }
+    
+    /// Frames recorded so far (see `FrameStatsLog::summary`)
+    pub fn frame_stats(&self) -> Option<&FrameStatsLog> {
+        self.frame_stats.as_ref().map(FrameStatsRecorder::log)
+    }
+    
+    /// Timings of the recent frames, drawn with `GameConfig::show_profiler`
+    pub fn get_profiler(&self) -> &FrameProfiler {
+        &self.profiler
+    }
+    
+    pub fn get_profiler_mut(&mut self) -> &mut FrameProfiler {
+        &mut self.profiler
+    }
+    
+    /// Mark a desync's diverged entities and list the differences over the game until
+    /// `clear_desync` (see `SceneSnapshot`)
+    pub fn show_desync(&mut self, diff: SnapshotDiff) {
+        self.desync = Some(diff);
+    }
+    
+    pub fn clear_desync(&mut self) {
+        self.desync = None;
+    }
+    
+    pub fn desync(&self) -> Option<&SnapshotDiff> {
+        self.desync.as_ref()
+    }
+    
+    /// Identify the running build: shown with `GameConfig::show_build_info`, printed
+    /// when the game panics, and attached to recorded frame stats.
+    ///
+    /// ```ignore
+    /// game.set_build_info(lastor::build_info!());
+    /// ```
+    pub fn set_build_info(&mut self, info: BuildInfo) {
+        install_panic_hook(&info);
+        if let Some(recorder) = &mut self.frame_stats {
+            recorder.set_build(Some(info.clone()));
+        }
+        self.build_info = Some(info);
+    }
+    
+    pub fn build_info(&self) -> Option<&BuildInfo> {
+        self.build_info.as_ref()
+    }
+    
+    /// Catch panics in entity updates and show them in an error screen instead of
+    /// closing the window. The screen can copy a report to the clipboard, and in
+    /// debug builds the game can continue with the offending entity disabled.
+    pub fn set_catch_panics(&mut self, enabled: bool) {
+        panic_screen::set_catching(enabled);
+    }
+    
+    /// Hitches detected since the last call (the most recent 64), oldest first
+    pub fn take_hitches(&mut self) -> Vec<Hitch> {
+        std::mem::take(&mut self.hitches)
+    }
+    
     pub fn set_time_scale(&mut self, scale: f32) {
         self.time_manager.set_time_scale(scale);
     }
+    
+    /// Ease into or out of slow motion over `seconds` of real time
+    pub fn set_time_scale_over(&mut self, scale: f32, seconds: f32) {
+        self.time_manager.set_time_scale_over(scale, seconds);
+    }
+    
+    /// Freeze entity updates for `seconds` (cameras and drawing continue)
+    pub fn hitstop(&mut self, seconds: f32) {
+        self.time_manager.hitstop(seconds);
+    }
 
+    /// A handle to the game's HTTP client, polled once a frame before entities update
+    #[cfg(feature = "http")]
+    pub fn get_http(&self) -> HttpClient {
+        self.http.clone()
+    }
+
+    /// Use `config` for tunables and feature flags. A fetch it has in flight is applied
+    /// once it arrives (send it through `get_http`).
+    pub fn set_remote_config(&mut self, config: RemoteConfig) {
+        self.remote_config = config;
+    }
+    
+    pub fn get_remote_config(&self) -> &RemoteConfig {
+        &self.remote_config
+    }
+    
+    pub fn get_remote_config_mut(&mut self) -> &mut RemoteConfig {
+        &mut self.remote_config
+    }
+    
+    /// Run `callback` for each remote config change or fetch result while the game runs,
+    /// e.g. to retune entities already in the scene
+    pub fn on_remote_config<F: FnMut(&ConfigEvent, &mut Scene) + 'static>(&mut self, callback: F) {
+        self.config_hook = Some(Box::new(callback));
+    }
+    
+    /// Switch fullscreen at runtime. Vsync can only be chosen when the window opens
+    /// (`GameConfig::vsync`).
+    pub fn set_fullscreen(&mut self, fullscreen: bool) {
+        self.config.fullscreen = fullscreen;
+        set_fullscreen(fullscreen);
+    }
+    
+    pub fn toggle_fullscreen(&mut self) {
+        self.set_fullscreen(!self.config.fullscreen);
+    }
+    
+    pub fn is_fullscreen(&self) -> bool {
+        self.config.fullscreen
+    }
+    
+    /// Ask for a new window size in pixels, kept above the minimum size. The window
+    /// changes at the next frame, firing `on_resize`.
+    pub fn set_window_size(&mut self, width: f32, height: f32) {
+        let (width, height) = self.clamp_window_size(width, height);
+        request_new_screen_size(width, height);
+    }
+    
+    /// Current window size in pixels
+    pub fn window_size(&self) -> Vec2 {
+        self.window_size
+    }
+    
+    /// Smallest size the window is kept at when resized (0 = no limit on that axis)
+    pub fn set_min_window_size(&mut self, width: i32, height: i32) {
+        self.config.min_window_width = width.max(0);
+        self.config.min_window_height = height.max(0);
+    }
+    
+    /// Whether the window size changed since the last frame
+    pub fn was_resized(&self) -> bool {
+        self.resized
+    }
+    
+    /// Run `callback` with the new size each time the window is resized, e.g. to relayout
+    /// a HUD. Cameras follow the window size on their own (see `Camera::screen_center`).
+    pub fn on_resize<F: FnMut(Vec2, &mut Scene) + 'static>(&mut self, callback: F) {
+        self.resize_hook = Some(Box::new(callback));
+    }
+    
+    /// A handle to the game's audio manager; the scene's camera views are its listeners
+    pub fn get_audio(&self) -> AudioManager {
+        self.audio.clone()
+    }
+    
+    /// A handle to the game's sound event map, played through `get_audio` (see `SoundEvents`)
+    pub fn get_sound_events(&self) -> SoundEvents {
+        self.sound_events.clone()
+    }
+    
+    /// Replace the game's asset manager (e.g. to set a root directory or texture filter)
+    pub fn set_assets(&mut self, assets: AssetManager) {
+        self.assets = assets;
+    }
+    
+    pub fn get_assets(&self) -> &AssetManager {
+        &self.assets
+    }
+    
+    pub fn get_assets_mut(&mut self) -> &mut AssetManager {
+        &mut self.assets
+    }
+    
+    /// The debug cheat menu, shown when `GameConfig::enable_cheats` is set
+    pub fn get_cheats(&self) -> &CheatMenu {
+        &self.cheats
+    }
+    
+    /// Register cheats here (see `CheatMenu`)
+    pub fn get_cheats_mut(&mut self) -> &mut CheatMenu {
+        &mut self.cheats
+    }
+    
+    /// Replace the mouse cursor with an OS icon, a sprite or a crosshair, drawn over
+    /// everything else:
+    ///
+    /// ```ignore
+    /// let texture = load_texture("cursor.png").await.unwrap();
+    /// game.set_cursor(Cursor::Sprite(CursorSprite::new(texture).centered()));
+    /// ```
+    pub fn set_cursor(&mut self, cursor: Cursor) {
+        self.cursor.set(cursor);
+    }
+    
+    pub fn get_cursor(&self) -> &Cursor {
+        self.cursor.get()
+    }
+    
+    /// Change the motion and flashing settings, e.g. from an options screen. They last
+    /// until the game closes; save them (see `MotionSettings`) to keep them.
+    pub fn set_motion_settings(&mut self, settings: MotionSettings) {
+        self.config.motion = settings;
+        self.apply_motion_settings();
+    }
+    
+    pub fn motion_settings(&self) -> MotionSettings {
+        self.config.motion
+    }
+    
+    /// Change the scale of menus, the cheat and radial menus and custom cursors, e.g. from
+    /// an options screen (see `ui::ui_scale`)
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        self.config.ui_scale = scale;
+        ui::set_ui_scale(scale);
+    }
+    
+    pub fn ui_scale(&self) -> f32 {
+        self.config.ui_scale
+    }
+    
+    /// Apply `GameConfig::motion` to every scene and share it as a resource. Scenes skip
+    /// settings they already have, so this only changes anything when the settings do (or
+    /// for a newly pushed layer).
+    fn apply_motion_settings(&mut self) {
+        let motion = self.config.motion;
+        self.scene.set_motion_settings(motion);
+        for layer in self.layers.iter_mut() {
+            layer.scene.set_motion_settings(motion);
+        }
+        if self.resources.get::<MotionSettings>() != Some(&motion) {
+            self.resources.insert(motion);
+        }
+    }
+    
+    /// Save a PNG of this frame, with overlays but without a custom cursor, once it's
+    /// drawn. On WASM the browser downloads it instead (see `Screenshot::save`).
+    pub fn take_screenshot(&mut self, path: &str) {
+        self.screenshots.push(path.to_string());
+    }
+    
+    /// Save any screenshots asked for this frame
+    fn save_screenshots(&mut self) {
+        if self.screenshots.is_empty() {
+            return;
+        }
+        let screenshot = capture_screen();
+        for path in self.screenshots.drain(..) {
+            if let Err(err) = screenshot.save(&path) {
+                crate::log_error!("screenshot", "failed to save {}: {}", path, err);
+            }
+        }
+    }
+    
+    /// Add stress testing tools for these prefabs, also available in the cheat menu's
+    /// "Stress" category (see `StressTest`)
+    pub fn enable_stress_test(&mut self, prefabs: Rc<PrefabLibrary>) -> StressTest {
+        let stress = StressTest::new(prefabs);
+        stress.add_cheats(&mut self.cheats, &self.scene, &[100, 1000]);
+        self.stress = Some(stress.clone());
+        stress
+    }
+    
+    /// Store a global resource, returning the one of the same type it replaced.
+    /// Entities reach resources through `UpdateContext` (see `Resources`).
+    pub fn insert_resource<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.resources.insert(value)
+    }
+    
+    /// Simulation steps run so far: one per frame, or per fixed step with
+    /// `GameConfig::fixed_timestep_hz` (e.g. the frame number for `Scene::snapshot`)
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+    
+    pub fn remove_resource<T: 'static>(&mut self) -> Option<T> {
+        self.resources.remove()
+    }
+    
+    pub fn resource<T: 'static>(&self) -> Option<&T> {
+        self.resources.get()
+    }
+    
+    pub fn resource_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.resources.get_mut()
+    }
+    
+    pub fn get_resources(&self) -> &Resources {
+        &self.resources
+    }
+    
+    pub fn get_resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+    
+    /// Queue a long task split into increments; each frame runs increments within the
+    /// job budget (see `JobQueue`)
+    pub fn add_job<F: FnMut(&mut Scene) -> JobStatus + 'static>(&mut self, name: &str, step: F) -> JobId {
+        self.jobs.push(name, step)
+    }
+    
+    /// Milliseconds of job work per frame
+    pub fn set_job_budget(&mut self, ms: f32) {
+        self.jobs.set_budget(ms);
+    }
+    
+    pub fn get_jobs(&self) -> &JobQueue {
+        &self.jobs
+    }
+    
+    pub fn get_jobs_mut(&mut self) -> &mut JobQueue {
+        &mut self.jobs
+    }
+    
+    /// Add a plugin and run its `build` hook. Plugin hooks run in the order plugins were added.
+    pub fn add_plugin<P: Plugin + 'static>(&mut self, mut plugin: P) {
+        plugin.build(self);
+        self.plugins.push(Box::new(plugin));
+    }
+    
+    pub fn has_plugin(&self, name: &str) -> bool {
+        self.plugins.iter().any(|plugin| plugin.name() == name)
+    }
+    
+    pub fn plugin_names(&self) -> impl Iterator<Item = &str> {
+        self.plugins.iter().map(|plugin| plugin.name())
+    }
+    
+    /// Load everything queued in the game's assets, showing a progress bar meanwhile. Call
+    /// it before `run`; failed assets are returned and the rest still load.
+    pub async fn load_assets(&mut self) -> Vec<AssetError> {
+        let color = self.config.debug_theme.text();
+        self.load_assets_with(|progress| draw_loading_bar(progress, color)).await
+    }
+    
+    /// `load_assets` with a custom loading screen, drawn once per asset
+    pub async fn load_assets_with<F: FnMut(&LoadProgress)>(&mut self, mut draw: F) -> Vec<AssetError> {
+        loop {
+            clear_background(self.config.background_color);
+            draw(self.assets.progress());
+            next_frame().await;
+            if !self.assets.load_next().await {
+                break;
+            }
+        }
+        // Don't count the loading time as one long first frame
+        self.time_manager.update();
+        self.assets.progress().errors.clone()
+    }
+    
+    /// Run `callback` for each texture or watched data file reloaded from disk while the
+    /// game runs (changed files are checked twice a second)
+    #[cfg(feature = "hot-reload")]
+    pub fn on_asset_reload<F: FnMut(&AssetReload, &mut Scene) + 'static>(&mut self, callback: F) {
+        self.reload_hook = Some(Box::new(callback));
+    }
+    
+    /// Run the game until it exits. With `GameConfig::crash_screen`, a panic shows an
+    /// error screen (and writes a crash report) until the player closes it.
     pub async fn run(&mut self) {
+        if !self.config.crash_screen || cfg!(target_arch = "wasm32") {
+            self.run_loop().await;
+            return;
+        }
+        panic_screen::install_hook();
+        let Err(crash) = CatchUnwind(Box::pin(self.run_loop())).await else {
+            return;
+        };
+        crate::log_error!("panic", "{}", crash.message);
+        let saved = match crash.save(&self.config.crash_dir, self.build_info.as_ref()) {
+            Ok(path) => Some(path),
+            Err(err) => {
+                crate::log_error!("panic", "failed to write crash report: {}", err);
+                None
+            }
+        };
+        let mut screen = PanicScreen::crash(crash, self.build_info.as_ref(), saved);
+        while !is_quit_requested() && screen.update() != PanicChoice::Quit {
+            set_default_camera();
+            screen.draw(&self.config.debug_theme);
+            next_frame().await;
+        }
+    }
+    
+    async fn run_loop(&mut self) {
+        self.time_manager.set_target_fps(self.config.target_fps);
+        self.time_manager.set_hitch_smoothing(self.config.smooth_hitches);
+        logging::set_level(self.config.log_level);
+        ui::set_ui_scale(self.config.ui_scale);
+        #[cfg(feature = "log")]
+        let _ = logging::install_log_crate();
+        if self.config.target_fps > 0 {
+            self.profiler.set_budget(1000.0 / self.config.target_fps as f32);
+        }
+        if let Some(seed) = self.config.deterministic_seed {
+            let hz = self.config.fixed_timestep_hz.filter(|hz| *hz > 0).unwrap_or(60);
+            self.time_manager.set_fixed_delta(Some(1.0 / hz as f32));
+            self.resources.insert(Rng::new(seed));
+            rng::seed_shared(seed);
+            // For game code still on macroquad's `rand`
+            macroquad::rand::srand(seed);
+        }
+        let mut frame_start = get_time();
+        // Handle close requests here so exit hooks get to run
+        prevent_quit();
         loop {
+            if is_quit_requested() {
+                self.close_requested = true;
+                self.exit_requested |= !self.confirm_close;
+            }
+            if self.exit_requested {
+                break;
+            }
+            if let Some(screen) = &mut self.panic_screen {
+                match screen.update() {
+                    PanicChoice::Wait => screen.draw(&self.config.debug_theme),
+                    PanicChoice::Continue => self.panic_screen = None,
+                    PanicChoice::Quit => return,
+                }
+                next_frame().await;
+                // Don't count the time spent on the error screen as a frame
+                self.time_manager.update();
+                frame_start = get_time();
+                continue;
+            }
+            
+            // Section timings for frame stats, in seconds since the frame started
+            let start = get_time();
+            
             // Update time
             self.time_manager.update();
+            self.check_hitch();
             let dt = self.time_manager.delta_time();
             
+            self.check_resize();
+            
             // Update input 
             self.input_manager.update(dt);
+            // During input replay, advance by the recorded frame time
+            let dt = self.input_manager.frame_dt();
+            self.check_idle();
+            if let Some(hook) = &mut self.layer_hook {
+                hook(&mut self.layers, &self.input_manager);
+            }
+            self.deliver_base_results();
+            let input_blocked = self.layers.coverage()[0].input_blocked;
+            if self.config.pinch_zoom && !input_blocked {
+                let pinch = self.input_manager.pinch_scale();
+                if pinch != 1.0 {
+                    let camera = self.scene.get_camera_mut();
+                    camera.set_zoom(camera.zoom * pinch);
+                }
+            }
+            if self.config.pause_with_action && !input_blocked && self.input_manager.is_action_just_activated(&Action::Pause) {
+                self.scene.set_paused(!self.scene.is_paused());
+            }
+            self.check_pause();
+            #[cfg(feature = "http")]
+            self.http.update(self.time_manager.unscaled_delta_time());
+            self.check_remote_config();
+            self.apply_motion_settings();
+            #[cfg(feature = "hot-reload")]
+            self.check_reloads();
+            if self.config.enable_cheats {
+                self.cheats.update(&mut self.input_manager, &mut self.scene);
+            }
+            if self.config.screenshot_with_action && self.input_manager.is_action_just_activated(&Action::Screenshot) {
+                let millis = (macroquad::miniquad::date::now() * 1000.0) as u64;
+                self.take_screenshot(&format!("{}/screenshot-{}.png", self.config.screenshot_dir, millis));
+            }
+            if let Some(stress) = &self.stress {
+                stress.update(&mut self.scene, self.last_frame);
+            }
+            self.run_plugins(|plugin, game| plugin.pre_update(game, dt));
+            self.update_states(dt);
+            let coverage = self.layers.coverage();
+            let input_done = get_time();
             
-            // Update scene entities with input
-            self.scene.update_with_input(dt, &self.input_manager);
+            // Update scene entities with input (deterministic runs already step a fixed dt)
+            match self.config.fixed_timestep_hz.filter(|hz| *hz > 0) {
+                Some(hz) if self.config.deterministic_seed.is_none() => self.update_fixed(dt, 1.0 / hz as f32, &coverage),
+                _ => self.update_scenes(dt, &coverage),
+            }
+            self.check_panics();
+            self.play_script_sounds();
+            self.run_plugins(|plugin, game| plugin.post_update(game, dt));
+            self.jobs.run(&mut self.scene);
+            let update_done = get_time();
+            let physics = self.scene.take_physics_time()
+                + self.layers.iter().map(|layer| layer.scene.take_physics_time()).sum::<f64>();
             
-            // Update camera separately
-            self.scene.update_camera(dt);
+            // Update cameras separately, in real time so they keep moving through hitstop
+            let camera_dt = self.time_manager.unscaled_delta_time();
+            if !coverage[0].paused {
+                self.scene.update_camera(camera_dt);
+            }
+            for (layer, covered) in self.layers.iter_mut().zip(&coverage[1..]) {
+                if !covered.paused {
+                    layer.scene.update_camera(camera_dt);
+                }
+            }
+            // Sounds are heard from every split-screen view; the ambience follows the first
+            self.audio.set_listeners(self.scene.views().map(|camera| camera.get_view_rect()));
+            let ambience_listener = self.scene.views().next().map_or(Vec2::ZERO, |camera| camera.get_final_position());
+            self.ambience.update(&self.audio, self.scene.audio(), ambience_listener, camera_dt);
+            self.audio.update(camera_dt);
+            self.sound_events.update(camera_dt);
+            let camera_done = get_time();
             
             // Clear screen
             clear_background(self.config.background_color);
+            for plugin in &self.plugins {
+                plugin.pre_draw(self);
+            }
             
-            // Apply camera and draw scene (Game handles camera operations)
-            self.scene.camera.apply();
-            self.scene.draw_entities();
-            self.scene.camera.reset();
+            // Draw scenes bottom to top, each in world space once per camera view and then
+            // in screen space, so a layer covers the UI of the scenes below (Game handles camera operations)
+            if !coverage[0].hidden {
+                draw_scene(&self.scene, &self.config.debug_theme);
+            }
+            for (layer, covered) in self.layers.iter().zip(&coverage[1..]) {
+                if !covered.hidden {
+                    draw_scene(&layer.scene, &self.config.debug_theme);
+                }
+            }
+            if self.config.show_debug_draw {
+                for camera in self.scene.views() {
+                    debug_draw::draw(camera);
+                }
+            }
+            if let Some(diff) = &self.desync {
+                for camera in self.scene.views() {
+                    diff.draw(&self.config.debug_theme, camera);
+                }
+                diff.draw_panel(&self.config.debug_theme, vec2(screen_width() * 0.25, 10.0), DESYNC_LINES);
+            }
             
-            // Show debug info if enabled
+            // Show debug info if enabled (screen space)
             if self.config.show_fps {
                 self.draw_fps_info();
             }
@@ -109,20 +952,250 @@ impl Game {
             if self.config.show_input_debug {
                 self.draw_input_debug();
             }
+            
+            if self.config.show_profiler {
+                let size = self.profiler.panel_size();
+                self.profiler.draw(&self.config.debug_theme, vec2(screen_width() - size.x - 10.0, 10.0));
+            }
+            
+            if let Some(info) = self.build_info.as_ref().filter(|_| self.config.show_build_info) {
+                info.draw_overlay(&self.config.debug_theme);
+            }
+            
+            if cfg!(debug_assertions) && self.config.show_log {
+                logging::draw_log_view(&self.config.debug_theme, self.config.log_lines);
+            }
+            
+            for plugin in &self.plugins {
+                plugin.post_draw(self);
+            }
+            
+            if self.config.enable_cheats {
+                self.cheats.draw(&self.config.debug_theme);
+            }
+            self.save_screenshots();
+            self.cursor.draw();
+            debug_draw::advance(camera_dt);
+            let draw_done = get_time();
+            
+            let ms = |seconds: f64| (seconds * 1000.0) as f32;
+            let stats = FrameStats {
+                frame_time: ms(start - frame_start),
+                input: ms(input_done - start),
+                update: ms(update_done - input_done - physics),
+                physics: ms(physics),
+                camera: ms(camera_done - update_done),
+                draw: ms(draw_done - camera_done),
+                entities: self.scene.active_entity_count(),
+            };
+            if let Some(recorder) = &mut self.frame_stats {
+                recorder.push(stats);
+            }
+            self.last_frame = Some(stats);
+            self.profiler.push(stats);
+            frame_start = start;
 
             next_frame().await;
         }
+        self.run_plugins(|plugin, game| plugin.exit(game));
+        if let Some(hook) = &mut self.exit_hook {
+            hook(&mut self.scene);
+        }
+        self.ambience.stop(&self.audio);
+    }
+    
+    /// Run a hook on every plugin, each getting the whole game
+    fn run_plugins<F: FnMut(&mut dyn Plugin, &mut Game)>(&mut self, mut hook: F) {
+        let mut plugins = std::mem::take(&mut self.plugins);
+        for plugin in plugins.iter_mut() {
+            hook(plugin.as_mut(), self);
+        }
+        // Keep plugins added by the hooks, after the existing ones
+        plugins.append(&mut self.plugins);
+        self.plugins = plugins;
+    }
+    
+    /// Play the sounds timelines asked for this frame
+    fn play_script_sounds(&mut self) {
+        let mut sounds = self.scene.take_script_sounds();
+        for layer in self.layers.iter_mut() {
+            sounds.extend(layer.scene.take_script_sounds());
+        }
+        for (sound, position) in sounds {
+            match position {
+                Some(position) => self.audio.play_at(&sound, position, 1.0),
+                None => self.audio.play(&sound, 1.0),
+            };
+        }
+    }
+
+    fn deliver_base_results(&mut self) {
+        for result in self.layers.take_base_results() {
+            self.scene.receive_result(result);
+        }
+    }
+    
+    fn update_scenes(&mut self, dt: f32, coverage: &[Coverage]) {
+        self.tick += 1;
+        let resources = &mut self.resources;
+        if !coverage[0].paused {
+            if coverage[0].input_blocked {
+                self.scene.update_with_resources(dt, &self.blocked_input, None, resources);
+            } else if self.players.is_empty() {
+                self.scene.update_with_resources(dt, &self.input_manager, None, resources);
+            } else {
+                self.players.update(dt);
+                self.scene.update_with_resources(dt, &self.input_manager, Some(&self.players), resources);
+            }
+        }
+        
+        for (layer, covered) in self.layers.iter_mut().zip(&coverage[1..]) {
+            if covered.paused {
+                continue;
+            }
+            let input = if covered.input_blocked { &self.blocked_input } else { &self.input_manager };
+            layer.scene.update_with_resources(dt, input, None, resources);
+        }
+    }
+    
+    /// Run as many fixed steps as the accumulated time allows
+    fn update_fixed(&mut self, dt: f32, step: f32, coverage: &[Coverage]) {
+        self.step_accumulator += dt;
+        let mut steps = 0;
+        while self.step_accumulator >= step && steps < MAX_FIXED_STEPS {
+            self.update_scenes(step, coverage);
+            self.step_accumulator -= step;
+            steps += 1;
+        }
+        if steps == MAX_FIXED_STEPS {
+            self.step_accumulator = self.step_accumulator.min(step);
+        }
+    }
+    
+    fn check_hitch(&mut self) {
+        let Some(hitch) = self.time_manager.hitch() else {
+            return;
+        };
+        // The stall happened during the previous frame or while waiting for the next one
+        let hitch = Hitch { blame: self.last_frame, ..hitch.clone() };
+        if self.config.log_hitches {
+            crate::log_warn!("time", "{}", hitch);
+        }
+        if self.hitches.len() >= MAX_HITCHES {
+            self.hitches.remove(0);
+        }
+        self.hitches.push(hitch);
+    }
+    
+    fn check_panics(&mut self) {
+        let mut panics = self.scene.take_panics();
+        for layer in self.layers.iter_mut() {
+            panics.extend(layer.scene.take_panics());
+        }
+        self.panic_screen = PanicScreen::new(panics, self.build_info.as_ref());
+    }
+    
+    fn check_remote_config(&mut self) {
+        #[cfg(feature = "http")]
+        self.remote_config.poll(&self.http);
+        let events = self.remote_config.take_events();
+        if let Some(hook) = &mut self.config_hook {
+            for event in &events {
+                hook(event, &mut self.scene);
+            }
+        }
+    }
+    
+    #[cfg(feature = "hot-reload")]
+    fn check_reloads(&mut self) {
+        self.reload_timer -= self.time_manager.unscaled_delta_time();
+        if self.reload_timer > 0.0 {
+            return;
+        }
+        self.reload_timer = RELOAD_INTERVAL;
+        for reload in self.assets.reload_if_changed() {
+            if let AssetReload::Failed(err) = &reload {
+                crate::log_warn!("assets", "hot reload: {}", err);
+            }
+            if let Some(hook) = &mut self.reload_hook {
+                hook(&reload, &mut self.scene);
+            }
+        }
+    }
+    
+    /// Fire the pause or resume hook if the base scene's pause state changed
+    fn check_pause(&mut self) {
+        let paused = self.scene.is_paused();
+        if paused == self.was_paused {
+            return;
+        }
+        self.was_paused = paused;
+        let hook = if paused { &mut self.pause_hook } else { &mut self.resume_hook };
+        if let Some(hook) = hook {
+            hook(&mut self.scene);
+        }
+        match self.states.current() {
+            GameState::Playing if paused => self.states.request(GameState::Paused),
+            GameState::Paused if !paused => self.states.request(GameState::Playing),
+            _ => {}
+        }
+    }
+    
+    /// Apply state changes and run the current state's update hooks, keeping the base
+    /// scene paused exactly while `Paused`
+    fn update_states(&mut self, dt: f32) {
+        let Some((from, to)) = self.states.update(&mut self.scene, dt) else {
+            return;
+        };
+        if to == GameState::Paused && !self.scene.is_paused() {
+            self.pause();
+        } else if from == GameState::Paused && self.scene.is_paused() {
+            self.resume();
+        }
+    }
+    
+    fn check_resize(&mut self) {
+        let size = Vec2::new(screen_width(), screen_height());
+        self.resized = size != self.window_size;
+        if !self.resized {
+            return;
+        }
+        self.window_size = size;
+        let (width, height) = self.clamp_window_size(size.x, size.y);
+        if (width, height) != (size.x, size.y) && !self.config.fullscreen {
+            request_new_screen_size(width, height);
+        }
+        if let Some(hook) = &mut self.resize_hook {
+            hook(size, &mut self.scene);
+        }
+    }
+    
+    fn clamp_window_size(&self, width: f32, height: f32) -> (f32, f32) {
+        (width.max(self.config.min_window_width as f32), height.max(self.config.min_window_height as f32))
+    }
+    
+    fn check_idle(&mut self) {
+        let Some(hook) = &mut self.idle_hook else {
+            return;
+        };
+        if self.input_manager.seconds_since_last_input() < hook.timeout {
+            hook.fired = false;
+        } else if !hook.fired {
+            hook.fired = true;
+            (hook.callback)(&mut self.scene);
+        }
     }
     
     fn draw_fps_info(&self) {
+        let text = self.config.debug_theme.text();
         let fps = get_fps();
-        draw_text(&format!("FPS: {}", fps), 10.0, 30.0, 20.0, WHITE);
+        draw_text(&format!("FPS: {}", fps), 10.0, 30.0, 20.0, text);
         draw_text(
             &format!("Entities: {}", self.scene.active_entity_count()),
             10.0,
             50.0,
             20.0,
-            WHITE,
+            text,
         );
         
         // Show camera info
@@ -132,22 +1205,23 @@ impl Game {
             10.0,
             70.0,
             20.0,
-            WHITE,
+            text,
         );
         draw_text(
             &format!("Zoom: {:.2}x", self.scene.camera.zoom),
             10.0,
             90.0,
             20.0,
-            WHITE,
+            text,
         );
     }
     
     fn draw_input_debug(&self) {
+        let theme = &self.config.debug_theme;
         let y_start = if self.config.show_fps { 110.0 } else { 30.0 };
         let mut y_offset = 0.0;
         
-        draw_text("=== INPUT DEBUG ===", 10.0, y_start + y_offset, 16.0, YELLOW);
+        draw_text("=== INPUT DEBUG ===", 10.0, y_start + y_offset, 16.0, theme.heading());
         y_offset += 20.0;
         
         // Show movement input
@@ -158,13 +1232,12 @@ impl Game {
                 10.0,
                 y_start + y_offset,
                 16.0,
-                GREEN,
+                theme.active(),
             );
             y_offset += 20.0;
         }
         
         // Show active actions
-        use crate::input::Action;
         let test_actions = [
             Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight,
             Action::Jump, Action::Attack, Action::Defend, Action::Interact, Action::Pause,
@@ -177,7 +1250,7 @@ impl Game {
                     10.0,
                     y_start + y_offset,
                     16.0,
-                    GREEN,
+                    theme.active(),
                 );
                 y_offset += 20.0;
             }
@@ -190,11 +1263,26 @@ impl Game {
             10.0,
             y_start + y_offset,
             16.0,
-            GRAY,
+            theme.muted(),
         );
     }
 }
 
+/// World pass once per camera view, then the screen-space UI pass
+fn draw_scene(scene: &Scene, theme: &DebugTheme) {
+    for camera in scene.views() {
+        camera.apply();
+        scene.draw_entities_in(camera);
+        if scene.physics_debug().is_any_enabled() {
+            scene.physics_debug().draw(scene, theme);
+        }
+        camera.reset();
+    }
+    set_default_camera();
+    scene.draw_entities_ui();
+    scene.draw_transition();
+}
+
 impl Default for Game {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,237 @@
+use macroquad::prelude::*;
+use std::cell::Cell;
+use super::{Resources, Rng, Scene};
+use crate::input::{InputFrame, InputManager};
+use crate::rendering::debug_draw;
+
+thread_local! {
+    // Synthetic clock and screen size while a `HeadlessRunner` is alive on this thread
+    static HEADLESS: Cell<Option<(f64, Vec2)>> = const { Cell::new(None) };
+}
+
+/// Whether a `HeadlessRunner` is driving the engine on this thread (there is no window)
+pub fn is_headless() -> bool {
+    HEADLESS.with(|headless| headless.get().is_some())
+}
+
+/// Seconds since the game started: the runner's synthetic clock when headless
+pub(crate) fn now() -> f64 {
+    HEADLESS.with(|headless| headless.get()).map_or_else(get_time, |(time, _)| time)
+}
+
+/// Window size in pixels, or the runner's pretend screen when headless
+pub(crate) fn screen_size() -> Vec2 {
+    HEADLESS.with(|headless| headless.get()).map_or_else(|| vec2(screen_width(), screen_height()), |(_, size)| size)
+}
+
+fn set_clock(time: f64, size: Vec2) {
+    HEADLESS.with(|headless| headless.set(Some((time, size))));
+}
+
+/// Steps a scene without a window, on a synthetic clock and scripted input, so tests can
+/// simulate frames and check entity state:
+///
+/// ```ignore
+/// #[test]
+/// fn player_walks_right() {
+///     let mut runner = HeadlessRunner::new();
+///     let player = runner.get_scene_mut().add_entity(Box::new(Player::new(vec2(0.0, 0.0))));
+///     runner.press(KeyCode::D);
+///     runner.run(60);
+///     assert!(runner.get_scene().entity_position(player).unwrap().x > 100.0);
+/// }
+/// ```
+///
+/// Create the runner (and the scene, if passed to `with_scene`) on the thread that steps
+/// it; engine code there reads the synthetic clock until the runner is dropped. Entities
+/// must not call macroquad's window functions from `update`.
+///
+/// Only the scene is stepped, once per frame at `with_dt`'s dt: there are no plugins, game
+/// states, layers, jobs or fixed timestep, so test those through the scene or `Game` itself.
+/// `debug_draw` shapes count down as if drawn each frame, and are cleared with the runner.
+pub struct HeadlessRunner {
+    scene: Scene,
+    input: InputManager,
+    resources: Resources,
+    // Input held from frame to frame
+    held: InputFrame,
+    // Keys released again after the next frame
+    taps: Vec<KeyCode>,
+    dt: f32,
+    frame: u64,
+    time: f64,
+    screen_size: Vec2,
+}
+
+impl Default for HeadlessRunner {
+    fn default() -> Self {
+        let screen_size = vec2(800.0, 600.0);
+        set_clock(0.0, screen_size);
+        Self {
+            scene: Scene::new(),
+            input: InputManager::new(),
+            resources: Resources::new(),
+            held: InputFrame::new(0.0),
+            taps: vec![],
+            dt: 1.0 / 60.0,
+            frame: 0,
+            time: 0.0,
+            screen_size,
+        }
+    }
+}
+
+impl HeadlessRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seconds per frame (1/60 by default)
+    pub fn with_dt(mut self, dt: f32) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Screen size cameras see, in pixels (800x600 by default)
+    pub fn with_screen_size(mut self, width: f32, height: f32) -> Self {
+        self.screen_size = vec2(width, height);
+        set_clock(self.time, self.screen_size);
+        self
+    }
+
+    /// Put a seeded `Rng` in the resources and seed macroquad's `rand`, as
+    /// `GameConfig::deterministic_seed` does
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.resources.insert(Rng::new(seed));
+        super::rng::seed_shared(seed);
+        macroquad::rand::srand(seed);
+        self
+    }
+
+    pub fn with_scene(mut self, scene: Scene) -> Self {
+        self.scene = scene;
+        self
+    }
+
+    pub fn get_scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn get_scene_mut(&mut self) -> &mut Scene {
+        &mut self.scene
+    }
+
+    /// The input manager entities read; bind actions on it as the game would
+    pub fn get_input(&self) -> &InputManager {
+        &self.input
+    }
+
+    pub fn get_input_mut(&mut self) -> &mut InputManager {
+        &mut self.input
+    }
+
+    pub fn get_resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub fn get_resources_mut(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+
+    /// Frames stepped so far
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Synthetic seconds since the runner started
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Hold a key down until `release`
+    pub fn press(&mut self, key: KeyCode) {
+        if !self.held.keys.contains(&key) {
+            self.held.keys.push(key);
+        }
+    }
+
+    pub fn release(&mut self, key: KeyCode) {
+        self.held.keys.retain(|held| *held != key);
+    }
+
+    /// Press a key for the next frame only
+    pub fn tap(&mut self, key: KeyCode) {
+        self.press(key);
+        self.taps.push(key);
+    }
+
+    pub fn press_mouse(&mut self, button: MouseButton) {
+        if !self.held.mouse_buttons.contains(&button) {
+            self.held.mouse_buttons.push(button);
+        }
+    }
+
+    pub fn release_mouse(&mut self, button: MouseButton) {
+        self.held.mouse_buttons.retain(|held| *held != button);
+    }
+
+    pub fn set_mouse_position(&mut self, position: Vec2) {
+        self.held.mouse_position = position;
+    }
+
+    /// Characters typed during the next frame (for text input)
+    pub fn type_text(&mut self, text: &str) {
+        self.held.chars.extend(text.chars());
+    }
+
+    /// Release every key and button
+    pub fn release_all(&mut self) {
+        self.held = InputFrame { mouse_position: self.held.mouse_position, ..InputFrame::new(0.0) };
+        self.taps.clear();
+    }
+
+    /// Advance one frame: input, entity updates, then cameras
+    pub fn step(&mut self) {
+        self.time += self.dt as f64;
+        set_clock(self.time, self.screen_size);
+
+        self.input.script_frame(self.held.clone());
+        self.input.update(self.dt);
+        // During input replay, advance by the recorded frame time
+        let dt = self.input.frame_dt();
+        self.held.chars.clear();
+        for key in self.taps.drain(..) {
+            self.held.keys.retain(|held| *held != key);
+        }
+
+        self.scene.update_with_resources(dt, &self.input, None, &mut self.resources);
+        self.scene.update_camera(dt);
+        debug_draw::advance(dt);
+        self.frame += 1;
+    }
+
+    pub fn run(&mut self, frames: u32) {
+        for _ in 0..frames {
+            self.step();
+        }
+    }
+
+    /// Step until `done` is true of the scene, at most `max_frames` times. Returns the
+    /// frames it took, or None if it never happened.
+    pub fn run_until<F: FnMut(&Scene) -> bool>(&mut self, max_frames: u32, mut done: F) -> Option<u32> {
+        for frames in 1..=max_frames {
+            self.step();
+            if done(&self.scene) {
+                return Some(frames);
+            }
+        }
+        None
+    }
+}
+
+impl Drop for HeadlessRunner {
+    fn drop(&mut self) {
+        HEADLESS.with(|headless| headless.set(None));
+        debug_draw::clear();
+    }
+}
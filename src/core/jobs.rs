@@ -0,0 +1,156 @@
+use macroquad::time::get_time;
+use std::collections::VecDeque;
+use super::Scene;
+
+/// Whether a job has more increments to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Continue,
+    Done,
+}
+
+/// Handle to a job in a `JobQueue`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JobId(u64);
+
+/// One increment of a job, run with the base scene
+type JobStep = Box<dyn FnMut(&mut Scene) -> JobStatus>;
+
+struct Job {
+    id: JobId,
+    name: String,
+    step: JobStep,
+    steps_run: u32,
+}
+
+/// Long tasks split into small increments and run within a per-frame time budget, so a
+/// pathfinding batch or a chunk rebuild spreads over several frames instead of causing
+/// a spike.
+///
+/// Jobs take turns, one increment at a time, until the budget is spent; each frame runs
+/// at least one increment so jobs always make progress. `Game` owns a queue and runs it
+/// after updating the scenes:
+///
+/// ```ignore
+/// let mut chunks = dirty_chunks.into_iter();
+/// game.add_job("rebuild chunks", move |scene| match chunks.next() {
+///     Some(chunk) => {
+///         rebuild_chunk(scene, chunk);
+///         JobStatus::Continue
+///     }
+///     None => JobStatus::Done,
+/// });
+/// ```
+pub struct JobQueue {
+    jobs: VecDeque<Job>,
+    next_id: u64,
+    budget_ms: f32,
+    // Time spent and increments run during the last `run`
+    last_ms: f32,
+    last_steps: u32,
+    finished: Vec<JobId>,
+}
+
+impl Default for JobQueue {
+    fn default() -> Self {
+        Self {
+            jobs: VecDeque::new(),
+            next_id: 0,
+            budget_ms: 4.0,
+            last_ms: 0.0,
+            last_steps: 0,
+            finished: vec![],
+        }
+    }
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Milliseconds of job work per frame (4 by default)
+    pub fn with_budget(mut self, ms: f32) -> Self {
+        self.set_budget(ms);
+        self
+    }
+
+    pub fn set_budget(&mut self, ms: f32) {
+        self.budget_ms = ms.max(0.0);
+    }
+
+    pub fn budget(&self) -> f32 {
+        self.budget_ms
+    }
+
+    /// Queue a job; `step` runs one increment each time it's called
+    pub fn push<F: FnMut(&mut Scene) -> JobStatus + 'static>(&mut self, name: &str, step: F) -> JobId {
+        let id = JobId(self.next_id);
+        self.next_id += 1;
+        self.jobs.push_back(Job { id, name: name.to_string(), step: Box::new(step), steps_run: 0 });
+        id
+    }
+
+    /// Drop a job before it finishes; returns false if it wasn't queued
+    pub fn cancel(&mut self, id: JobId) -> bool {
+        let before = self.jobs.len();
+        self.jobs.retain(|job| job.id != id);
+        self.jobs.len() != before
+    }
+
+    pub fn clear(&mut self) {
+        self.jobs.clear();
+    }
+
+    pub fn is_queued(&self, id: JobId) -> bool {
+        self.jobs.iter().any(|job| job.id == id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.jobs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.jobs.is_empty()
+    }
+
+    /// Queued jobs' names and how many increments each has run
+    pub fn jobs(&self) -> impl Iterator<Item = (JobId, &str, u32)> {
+        self.jobs.iter().map(|job| (job.id, job.name.as_str(), job.steps_run))
+    }
+
+    /// Jobs that finished since the last call
+    pub fn take_finished(&mut self) -> Vec<JobId> {
+        std::mem::take(&mut self.finished)
+    }
+
+    /// Milliseconds spent on jobs during the last frame
+    pub fn last_frame_ms(&self) -> f32 {
+        self.last_ms
+    }
+
+    /// Increments run during the last frame
+    pub fn last_frame_steps(&self) -> u32 {
+        self.last_steps
+    }
+
+    /// Run job increments until the budget is spent (`Game` calls this every frame)
+    pub fn run(&mut self, scene: &mut Scene) {
+        let start = get_time();
+        let budget = self.budget_ms as f64 / 1000.0;
+        self.last_steps = 0;
+        while let Some(mut job) = self.jobs.pop_front() {
+            let status = (job.step)(scene);
+            job.steps_run += 1;
+            self.last_steps += 1;
+            match status {
+                JobStatus::Continue => self.jobs.push_back(job),
+                JobStatus::Done => self.finished.push(job.id),
+            }
+            if get_time() - start >= budget {
+                break;
+            }
+        }
+        self.last_ms = ((get_time() - start) * 1000.0) as f32;
+    }
+}
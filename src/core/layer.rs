@@ -0,0 +1,171 @@
+use super::Scene;
+use std::any::Any;
+
+/// A scene drawn and updated above the game's base scene (pause menu, HUD, dialogue box).
+///
+/// Each layer decides what happens to the scenes below it while it is on the stack.
+pub struct SceneLayer {
+    pub name: String,
+    pub scene: Scene,
+    /// Scenes below stop updating (their entities and cameras freeze)
+    pub pauses_below: bool,
+    /// Scenes below update without input, so only this layer reacts to the player
+    pub blocks_input: bool,
+    /// Scenes below aren't drawn (full-screen opaque layers)
+    pub hides_below: bool,
+}
+
+impl SceneLayer {
+    /// A layer that lets the scenes below keep updating, receive input and be drawn
+    pub fn new(name: &str, scene: Scene) -> Self {
+        Self {
+            name: name.to_string(),
+            scene,
+            pauses_below: false,
+            blocks_input: false,
+            hides_below: false,
+        }
+    }
+
+    /// A layer that pauses everything below and takes all input (pause menus)
+    pub fn modal(name: &str, scene: Scene) -> Self {
+        Self::new(name, scene).with_pauses_below(true).with_blocks_input(true)
+    }
+
+    pub fn with_pauses_below(mut self, pauses: bool) -> Self {
+        self.pauses_below = pauses;
+        self
+    }
+
+    pub fn with_blocks_input(mut self, blocks: bool) -> Self {
+        self.blocks_input = blocks;
+        self
+    }
+
+    pub fn with_hides_below(mut self, hides: bool) -> Self {
+        self.hides_below = hides;
+        self
+    }
+}
+
+/// What the layers above a scene allow it to do this frame
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Coverage {
+    pub(crate) paused: bool,
+    pub(crate) input_blocked: bool,
+    pub(crate) hidden: bool,
+}
+
+/// Layers stacked above the base scene, bottom to top.
+///
+/// A layer can be pushed with a payload and leave a result with `Scene::set_result`;
+/// when it is popped or removed, the result goes to the scene right below it.
+#[derive(Default)]
+pub struct SceneLayers {
+    layers: Vec<SceneLayer>,
+    // Results of bottom layers, waiting for the Game to hand them to the base scene
+    base_results: Vec<Box<dyn Any>>,
+}
+
+impl SceneLayers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Put a layer on top (replaces an existing layer with the same name)
+    pub fn push(&mut self, layer: SceneLayer) {
+        self.remove(&layer.name);
+        self.layers.push(layer);
+    }
+
+    /// Put a layer on top with data for it to read through `Scene::payload`
+    ///
+    /// ```ignore
+    /// layers.push_with(SceneLayer::modal("shop", shop_scene()), ShopStock { items });
+    /// // later, in the scene below
+    /// if let Some(purchases) = scene.take_returned::<Purchases>() { ... }
+    /// ```
+    pub fn push_with<T: 'static>(&mut self, mut layer: SceneLayer, payload: T) {
+        layer.scene.set_payload(payload);
+        self.push(layer);
+    }
+
+    /// Remove the top layer
+    pub fn pop(&mut self) -> Option<SceneLayer> {
+        let index = self.layers.len().checked_sub(1)?;
+        Some(self.remove_at(index))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<SceneLayer> {
+        let index = self.layers.iter().position(|layer| layer.name == name)?;
+        Some(self.remove_at(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SceneLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut SceneLayer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn top(&self) -> Option<&SceneLayer> {
+        self.layers.last()
+    }
+
+    /// Layers from bottom to top
+    pub fn iter(&self) -> impl Iterator<Item = &SceneLayer> {
+        self.layers.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut SceneLayer> {
+        self.layers.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Remove every layer (results are dropped)
+    pub fn clear(&mut self) {
+        self.layers.clear();
+    }
+
+    fn remove_at(&mut self, index: usize) -> SceneLayer {
+        let mut layer = self.layers.remove(index);
+        if let Some(result) = layer.scene.take_result() {
+            match index.checked_sub(1) {
+                Some(below) => self.layers[below].scene.receive_result(result),
+                None => self.base_results.push(result),
+            }
+        }
+        layer
+    }
+
+    /// Results of removed bottom layers, for the base scene
+    pub(crate) fn take_base_results(&mut self) -> Vec<Box<dyn Any>> {
+        std::mem::take(&mut self.base_results)
+    }
+
+    /// Coverage of the base scene followed by each layer, bottom to top
+    pub(crate) fn coverage(&self) -> Vec<Coverage> {
+        let mut coverage = vec![Coverage::default(); self.layers.len() + 1];
+        for (index, layer) in self.layers.iter().enumerate().rev() {
+            let above = coverage[index + 1];
+            coverage[index] = Coverage {
+                paused: above.paused || layer.pauses_below,
+                input_blocked: above.input_blocked || layer.blocks_input,
+                hidden: above.hidden || layer.hides_below,
+            };
+        }
+        coverage
+    }
+}
@@ -0,0 +1,284 @@
+use macroquad::miniquad::date;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Mutex;
+use crate::rendering::DebugTheme;
+
+const FONT_SIZE: f32 = 16.0;
+
+/// How serious a log line is, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            other => Err(format!("unknown log level '{}'", other)),
+        }
+    }
+}
+
+impl LogLevel {
+    /// Color of the level's lines in the log view
+    pub fn color(self, theme: &DebugTheme) -> Color {
+        match self {
+            LogLevel::Trace | LogLevel::Debug => theme.muted(),
+            LogLevel::Info => theme.text(),
+            LogLevel::Warn => theme.warning(),
+            LogLevel::Error => theme.error(),
+        }
+    }
+}
+
+/// One logged line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    /// What the line is about, e.g. "audio" or "assets"
+    pub category: String,
+    pub message: String,
+    /// Seconds since the first line was logged
+    pub time: f64,
+}
+
+impl fmt::Display for LogRecord {
+    /// e.g. "[   12.345] WARN  assets: missing sound 'sfx/jump.wav'"
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:>9.3}] {:<5} {}: {}", self.time, self.level, self.category, self.message)
+    }
+}
+
+struct LogState {
+    records: VecDeque<LogRecord>,
+    capacity: usize,
+    level: LogLevel,
+    // Levels of categories that differ from `level`
+    categories: BTreeMap<String, LogLevel>,
+    echo: bool,
+    started: Option<f64>,
+}
+
+impl LogState {
+    fn enabled(&self, level: LogLevel, category: &str) -> bool {
+        level >= self.categories.get(category).copied().unwrap_or(self.level)
+    }
+}
+
+// Shared with other threads (the `log` crate's logger has to be `Sync`)
+static LOG: Mutex<LogState> = Mutex::new(LogState {
+    records: VecDeque::new(),
+    capacity: 500,
+    level: if cfg!(debug_assertions) { LogLevel::Debug } else { LogLevel::Info },
+    categories: BTreeMap::new(),
+    echo: true,
+    started: None,
+});
+
+fn with_state<R>(f: impl FnOnce(&mut LogState) -> R) -> R {
+    // A panic while logging shouldn't silence the log for good
+    let mut state = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(&mut state)
+}
+
+/// Log a line: kept in the ring buffer for the in-game log view (`GameConfig::show_log`)
+/// and printed to stderr on native builds. The `log_*!` macros format the message:
+///
+/// ```ignore
+/// lastor::log_warn!("assets", "missing sound '{}'", path);
+/// lastor::log_info!("save", "saved slot {} in {:.1} ms", slot, ms);
+/// logging::set_category_level("ai", LogLevel::Trace);
+/// ```
+///
+/// With the `log` feature, lines from the `log` crate's macros land here too, with
+/// their target as the category (see `install_log_crate`).
+pub fn log(level: LogLevel, category: &str, message: &str) {
+    with_state(|state| {
+        if !state.enabled(level, category) {
+            return;
+        }
+        let now = date::now();
+        let time = now - *state.started.get_or_insert(now);
+        let record = LogRecord { level, category: category.to_string(), message: message.to_string(), time };
+        #[cfg(not(target_arch = "wasm32"))]
+        if state.echo {
+            eprintln!("{}", record);
+        }
+        if state.records.len() >= state.capacity {
+            state.records.pop_front();
+        }
+        state.records.push_back(record);
+    });
+}
+
+/// Whether a line would be kept, to skip building expensive messages
+pub fn enabled(level: LogLevel, category: &str) -> bool {
+    with_state(|state| state.enabled(level, category))
+}
+
+/// Least serious level kept (Debug in debug builds, Info in release)
+pub fn set_level(level: LogLevel) {
+    with_state(|state| state.level = level);
+}
+
+pub fn level() -> LogLevel {
+    with_state(|state| state.level)
+}
+
+/// Keep a different level for one category, e.g. Trace for the system being debugged
+pub fn set_category_level(category: &str, level: LogLevel) {
+    with_state(|state| state.categories.insert(category.to_string(), level));
+}
+
+pub fn clear_category_level(category: &str) {
+    with_state(|state| state.categories.remove(category));
+}
+
+/// Lines kept for the log view (500 by default)
+pub fn set_capacity(lines: usize) {
+    with_state(|state| {
+        state.capacity = lines.max(1);
+        while state.records.len() > state.capacity {
+            state.records.pop_front();
+        }
+    });
+}
+
+/// Print lines to stderr as they're logged (on by default; native builds only)
+pub fn set_echo(enabled: bool) {
+    with_state(|state| state.echo = enabled);
+}
+
+/// The latest `count` lines, oldest first
+pub fn recent(count: usize) -> Vec<LogRecord> {
+    with_state(|state| {
+        let skip = state.records.len().saturating_sub(count);
+        state.records.iter().skip(skip).cloned().collect()
+    })
+}
+
+pub fn clear() {
+    with_state(|state| state.records.clear());
+}
+
+/// Draw the latest lines over the bottom-left of the screen
+pub(crate) fn draw_log_view(theme: &DebugTheme, lines: usize) {
+    let records = recent(lines);
+    let mut y = screen_height() - 8.0;
+    // Newest at the bottom; multi-line messages are shown whole
+    for record in records.iter().rev() {
+        let text = record.to_string();
+        for line in text.lines().rev() {
+            draw_text(line, 9.0, y + 1.0, FONT_SIZE, theme.shadow());
+            draw_text(line, 8.0, y, FONT_SIZE, record.level.color(theme));
+            y -= FONT_SIZE;
+            if y < FONT_SIZE {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+struct LogBridge;
+
+#[cfg(feature = "log")]
+static BRIDGE: LogBridge = LogBridge;
+
+#[cfg(feature = "log")]
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace => LogLevel::Trace,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Error => LogLevel::Error,
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl log::Log for LogBridge {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        enabled(metadata.level().into(), metadata.target())
+    }
+
+    fn log(&self, record: &log::Record) {
+        log(record.level().into(), record.target(), &record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Send the `log` crate's lines (from the game and its dependencies) to this log.
+/// `Game::run` calls this; it fails if another logger was installed first.
+#[cfg(feature = "log")]
+pub fn install_log_crate() -> Result<(), log::SetLoggerError> {
+    log::set_logger(&BRIDGE)?;
+    log::set_max_level(log::LevelFilter::Trace);
+    Ok(())
+}
+
+/// Log a formatted line at a level: `log_at!(LogLevel::Info, "net", "connected to {}", host)`
+#[macro_export]
+macro_rules! log_at {
+    ($level:expr, $category:expr, $($arg:tt)+) => {
+        if $crate::core::logging::enabled($level, $category) {
+            $crate::core::logging::log($level, $category, &format!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($category:expr, $($arg:tt)+) => { $crate::log_at!($crate::core::logging::LogLevel::Error, $category, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($category:expr, $($arg:tt)+) => { $crate::log_at!($crate::core::logging::LogLevel::Warn, $category, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($category:expr, $($arg:tt)+) => { $crate::log_at!($crate::core::logging::LogLevel::Info, $category, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($category:expr, $($arg:tt)+) => { $crate::log_at!($crate::core::logging::LogLevel::Debug, $category, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($category:expr, $($arg:tt)+) => { $crate::log_at!($crate::core::logging::LogLevel::Trace, $category, $($arg)+) };
+}
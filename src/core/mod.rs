@@ -1,9 +1,72 @@
 pub mod entity;
 pub mod scene;
 pub mod game;
+pub mod config;
 pub mod time;
+pub mod builder;
+pub mod constraints;
+pub mod frame_stats;
+pub mod layer;
+pub mod component;
+pub mod build_info;
+pub mod panic_screen;
+pub mod draw_order;
+pub mod prefab;
+pub mod portal;
+pub mod remote_config;
+pub mod timeline;
+pub mod scene_file;
+pub mod stress;
+pub mod plugin;
+pub mod rewind;
+pub mod resources;
+pub mod context;
+pub mod jobs;
+pub mod state;
+pub mod ownership;
+pub mod accessibility;
+pub mod relations;
+pub mod profiler;
+pub mod logging;
+pub mod snapshot;
+pub mod headless;
+pub mod rng;
+pub mod pool;
+pub mod arena;
+pub mod pending;
 
-pub use entity::{Entity, GameObject};
+pub use entity::{AsAny, Entity, EntityId, GameObject};
 pub use scene::Scene;
-pub use game::{Game, GameConfig};
-pub use time::TimeManager;
\ No newline at end of file
+pub use game::Game;
+pub use plugin::Plugin;
+pub use rewind::{RewindSettings, RewindState};
+pub use resources::Resources;
+pub use context::{Commands, Events, LifecycleContext, UpdateContext};
+pub use jobs::{JobId, JobQueue, JobStatus};
+pub use state::GameState;
+pub use ownership::OwnerRemoval;
+pub use relations::Relation;
+pub use accessibility::MotionSettings;
+pub use config::{GameConfig, GameConfigError};
+pub use time::{Hitch, TimeManager};
+pub use builder::{spawn, EntityBuilder, SimpleEntity};
+pub use constraints::Constraint;
+pub use component::{Added, Changed, ComponentStore, Mut, Query, QueryFilter, With, Without};
+pub use layer::{SceneLayer, SceneLayers};
+pub use build_info::BuildInfo;
+pub use panic_screen::{Crash, EntityPanic};
+pub use draw_order::{CullingStats, DrawOrder, DrawOrderEntry};
+pub use prefab::{Jitter, Prefab, PrefabDef, PrefabError, PrefabLibrary, PrefabOverrides, VariantGroup};
+pub use portal::{CameraHandoff, Portal, PortalEvent, PortalId, PortalTarget, PortalTransition};
+pub use stress::{StressCommand, StressReport, StressTest};
+pub use scene_file::{EntityData, EntityRecord, EntityRegistry, SceneFile, SceneFileError};
+pub use timeline::{ScriptAction, ScriptEvent, ScriptKey, Timeline, TimelineError};
+pub use remote_config::{ConfigEvent, ConfigValue, RemoteConfig, RemoteConfigError};
+pub use frame_stats::{FrameStats, FrameStatsLog, Percentiles, StatsError, StatsSummary};
+pub use profiler::FrameProfiler;
+pub use logging::{LogLevel, LogRecord};
+pub use headless::{is_headless, HeadlessRunner};
+pub use rng::Rng;
+pub use pool::{Pool, PoolStats, DEFAULT_POOL_CAPACITY};
+pub use arena::{Arena, ArenaKey};
+pub use snapshot::{ChecksumReport, ComponentDiff, DiffKind, EntityDiff, EntitySnapshot, SceneSnapshot, SnapshotDiff, SnapshotError, SnapshotHistory};
\ No newline at end of file
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::EntityId;
+
+/// What happens to an owned entity when its owner leaves the scene (see `Scene::set_owner`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OwnerRemoval {
+    /// Remove it along with the owner, e.g. a summon that vanishes with its caster
+    Despawn,
+    /// Keep it without an owner, e.g. a bullet still in flight when its shooter dies
+    #[default]
+    Orphan,
+}
+
+/// Owner links between a scene's entities
+#[derive(Default)]
+pub(crate) struct Ownership {
+    owners: HashMap<EntityId, (EntityId, OwnerRemoval)>,
+    owned: HashMap<EntityId, Vec<EntityId>>,
+}
+
+impl Ownership {
+    /// Link `entity` to `owner`, replacing its previous owner. Returns false, changing
+    /// nothing, if `owner` is `entity` or already owned by it.
+    pub(crate) fn set(&mut self, entity: EntityId, owner: EntityId, removal: OwnerRemoval) -> bool {
+        if self.root(owner) == entity {
+            return false;
+        }
+        self.clear(entity);
+        self.owners.insert(entity, (owner, removal));
+        self.owned.entry(owner).or_default().push(entity);
+        true
+    }
+
+    pub(crate) fn clear(&mut self, entity: EntityId) {
+        let Some((owner, _)) = self.owners.remove(&entity) else {
+            return;
+        };
+        if let Some(owned) = self.owned.get_mut(&owner) {
+            owned.retain(|id| *id != entity);
+            if owned.is_empty() {
+                self.owned.remove(&owner);
+            }
+        }
+    }
+
+    pub(crate) fn owner(&self, entity: EntityId) -> Option<EntityId> {
+        self.owners.get(&entity).map(|(owner, _)| *owner)
+    }
+
+    pub(crate) fn removal(&self, entity: EntityId) -> Option<OwnerRemoval> {
+        self.owners.get(&entity).map(|(_, removal)| *removal)
+    }
+
+    /// The top of the owner chain, or `entity` itself if it has no owner
+    pub(crate) fn root(&self, entity: EntityId) -> EntityId {
+        let mut root = entity;
+        while let Some(owner) = self.owner(root) {
+            root = owner;
+        }
+        root
+    }
+
+    pub(crate) fn owned(&self, owner: EntityId) -> &[EntityId] {
+        self.owned.get(&owner).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Drop a removed entity's links, orphaning what it owned. Returns the owned
+    /// entities that should be despawned with it.
+    pub(crate) fn forget_entity(&mut self, entity: EntityId) -> Vec<EntityId> {
+        self.clear(entity);
+        let mut despawn = vec![];
+        for child in self.owned.remove(&entity).unwrap_or_default() {
+            if let Some((_, OwnerRemoval::Despawn)) = self.owners.remove(&child) {
+                despawn.push(child);
+            }
+        }
+        despawn
+    }
+
+    pub(crate) fn clear_all(&mut self) {
+        self.owners.clear();
+        self.owned.clear();
+    }
+}
@@ -0,0 +1,281 @@
+use super::{logging, BuildInfo, EntityId};
+use crate::input::clipboard::set_clipboard;
+use crate::rendering::DebugTheme;
+use macroquad::prelude::*;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::{Context, Poll};
+
+// Recent log lines attached to reports
+const REPORT_LOG_LINES: usize = 50;
+
+// Whether scenes wrap entity updates in catch_unwind (see `Game::set_catch_panics`)
+static CATCHING: AtomicBool = AtomicBool::new(false);
+static INSTALL_HOOK: Once = Once::new();
+
+thread_local! {
+    // Details of the last panic on this thread, filled in by the hook before unwinding
+    static LAST_PANIC: RefCell<Option<(String, Option<String>, String)>> = const { RefCell::new(None) };
+}
+
+/// A panic caught while updating an entity
+#[derive(Debug, Clone)]
+pub struct EntityPanic {
+    pub entity: EntityId,
+    pub message: String,
+    /// file:line:column of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl EntityPanic {
+    /// Plain-text report for bug trackers
+    pub fn report(&self, build: Option<&BuildInfo>) -> String {
+        report(build, Some(self.entity), &self.message, self.location.as_deref(), &self.backtrace)
+    }
+}
+
+/// A panic that escaped the game loop, shown on the crash screen (see
+/// `GameConfig::crash_screen`)
+#[derive(Debug, Clone)]
+pub struct Crash {
+    pub message: String,
+    /// file:line:column of the panic
+    pub location: Option<String>,
+    pub backtrace: String,
+}
+
+impl Crash {
+    fn from_payload(payload: &(dyn Any + Send)) -> Self {
+        let (message, location, backtrace) = LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| (payload_message(payload), None, String::new()));
+        Self { message, location, backtrace }
+    }
+
+    /// Plain-text report for bug trackers, with the latest log lines
+    pub fn report(&self, build: Option<&BuildInfo>) -> String {
+        report(build, None, &self.message, self.location.as_deref(), &self.backtrace)
+    }
+
+    /// Write the report to a new file in `dir`, returning its path
+    pub fn save<P: AsRef<Path>>(&self, dir: P, build: Option<&BuildInfo>) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&dir)?;
+        let millis = (macroquad::miniquad::date::now() * 1000.0) as u64;
+        let path = dir.as_ref().join(format!("crash-{}.txt", millis));
+        std::fs::write(&path, self.report(build))?;
+        Ok(path)
+    }
+}
+
+fn report(build: Option<&BuildInfo>, entity: Option<EntityId>, message: &str, location: Option<&str>, backtrace: &str) -> String {
+    let mut report = String::new();
+    if let Some(build) = build {
+        report.push_str(&format!("build: {}\n", build));
+    }
+    report.push_str(&format!("engine: {}\n", BuildInfo::engine()));
+    if let Some(entity) = entity {
+        report.push_str(&format!("entity: {:?}\n", entity));
+    }
+    report.push_str(&format!("panicked: {}\n", message));
+    if let Some(location) = location {
+        report.push_str(&format!("at: {}\n", location));
+    }
+    report.push_str("\nbacktrace:\n");
+    report.push_str(backtrace);
+    let log = logging::recent(REPORT_LOG_LINES);
+    if !log.is_empty() {
+        report.push_str("\n\nlog:\n");
+        for record in log {
+            report.push_str(&format!("{}\n", record));
+        }
+    }
+    report
+}
+
+pub(crate) fn set_catching(enabled: bool) {
+    if enabled {
+        install_hook();
+    }
+    CATCHING.store(enabled, Ordering::Relaxed);
+}
+
+/// Records the message, location and backtrace of each panic, then runs the previous hook
+pub(crate) fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = payload_message(info.payload());
+            let location = info.location().map(ToString::to_string);
+            let backtrace = Backtrace::force_capture().to_string();
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some((message, location, backtrace)));
+            previous(info);
+        }));
+    });
+}
+
+/// Run one entity's update, returning the panic instead of unwinding when catching is on
+pub(crate) fn guard<F: FnOnce()>(entity: EntityId, update: F) -> Option<EntityPanic> {
+    if !CATCHING.load(Ordering::Relaxed) {
+        update();
+        return None;
+    }
+    let payload = panic::catch_unwind(AssertUnwindSafe(update)).err()?;
+    let Crash { message, location, backtrace } = Crash::from_payload(payload.as_ref());
+    Some(EntityPanic { entity, message, location, backtrace })
+}
+
+/// Polls the game loop, turning a panic into a `Crash` instead of unwinding out of
+/// `Game::run`. Panics abort on WASM, so there it only passes the loop through.
+pub(crate) struct CatchUnwind<F>(pub(crate) F);
+
+impl<F: Future + Unpin> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, Crash>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match panic::catch_unwind(AssertUnwindSafe(|| Pin::new(&mut self.0).poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(Crash::from_payload(payload.as_ref()))),
+        }
+    }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+/// What the player picked on the error screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PanicChoice {
+    Wait,
+    /// Keep playing with the entity disabled (debug builds only)
+    Continue,
+    Quit,
+}
+
+/// Full-window error screen shown instead of the game after an entity panic or a crash
+pub(crate) struct PanicScreen {
+    summary: String,
+    location: Option<String>,
+    backtrace: String,
+    report: String,
+    // Further panics caught in the same frame
+    others: usize,
+    // Entity panics can be skipped past; crashes can't
+    can_continue: bool,
+    // Where the crash report was written
+    saved: Option<PathBuf>,
+    scroll: usize,
+    copied: bool,
+}
+
+impl PanicScreen {
+    pub(crate) fn new(mut panics: Vec<EntityPanic>, build: Option<&BuildInfo>) -> Option<Self> {
+        if panics.is_empty() {
+            return None;
+        }
+        let others = panics.len() - 1;
+        let panic = panics.swap_remove(0);
+        Some(Self {
+            summary: format!("{:?} panicked: {}", panic.entity, panic.message),
+            report: panic.report(build),
+            location: panic.location,
+            backtrace: panic.backtrace,
+            others,
+            can_continue: cfg!(debug_assertions),
+            saved: None,
+            scroll: 0,
+            copied: false,
+        })
+    }
+
+    pub(crate) fn crash(crash: Crash, build: Option<&BuildInfo>, saved: Option<PathBuf>) -> Self {
+        Self {
+            summary: format!("panicked: {}", crash.message),
+            report: crash.report(build),
+            location: crash.location,
+            backtrace: crash.backtrace,
+            others: 0,
+            can_continue: false,
+            saved,
+            scroll: 0,
+            copied: false,
+        }
+    }
+
+    pub(crate) fn update(&mut self) -> PanicChoice {
+        if is_key_pressed(KeyCode::C) {
+            set_clipboard(&self.report);
+            self.copied = true;
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.scroll += 1;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        if self.can_continue && is_key_pressed(KeyCode::Enter) {
+            PanicChoice::Continue
+        } else if is_key_pressed(KeyCode::Escape) {
+            PanicChoice::Quit
+        } else {
+            PanicChoice::Wait
+        }
+    }
+
+    pub(crate) fn draw(&self, theme: &DebugTheme) {
+        clear_background(theme.background());
+        let margin = 24.0;
+        let mut y = margin + 24.0;
+        draw_text("The game hit an error", margin, y, 32.0, theme.error());
+        y += 36.0;
+        draw_text(&self.summary, margin, y, 20.0, theme.text());
+        y += 24.0;
+        if let Some(location) = &self.location {
+            draw_text(&format!("at {}", location), margin, y, 18.0, theme.muted());
+            y += 22.0;
+        }
+        if self.others > 0 {
+            draw_text(&format!("(+{} more in the same frame)", self.others), margin, y, 18.0, theme.muted());
+            y += 22.0;
+        }
+        if let Some(path) = &self.saved {
+            draw_text(&format!("Report saved to {}", path.display()), margin, y, 18.0, theme.muted());
+            y += 22.0;
+        }
+
+        // Backtrace, as many lines as fit above the footer
+        y += 10.0;
+        let footer_y = screen_height() - margin;
+        let line_height = 16.0;
+        for line in self.backtrace.lines().skip(self.scroll) {
+            if y > footer_y - 2.0 * line_height {
+                break;
+            }
+            draw_text(line, margin, y, 14.0, theme.muted());
+            y += line_height;
+        }
+
+        let copy = if self.copied { "[C] Copied!" } else { "[C] Copy report" };
+        let footer = if self.can_continue {
+            format!("{}    [Up/Down] Scroll    [Enter] Continue without this entity    [Esc] Quit", copy)
+        } else {
+            format!("{}    [Up/Down] Scroll    [Esc] Quit", copy)
+        };
+        draw_text(&footer, margin, footer_y, 18.0, theme.heading());
+    }
+}
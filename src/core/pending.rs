@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+use super::{Entity, EntityId};
+
+/// Entities added to a scene since its last update, in the order they were added.
+/// Removing one leaves an empty spot instead of shifting the rest, so it's O(1) like
+/// removing from the scene's arena.
+#[derive(Default)]
+pub(crate) struct PendingEntities {
+    entities: Vec<Option<(EntityId, Box<dyn Entity>)>>,
+    // Spot of each entity in `entities`
+    index: HashMap<EntityId, usize>,
+}
+
+impl PendingEntities {
+    pub(crate) fn push(&mut self, id: EntityId, entity: Box<dyn Entity>) {
+        self.index.insert(id, self.entities.len());
+        self.entities.push(Some((id, entity)));
+    }
+
+    pub(crate) fn remove(&mut self, id: EntityId) -> Option<Box<dyn Entity>> {
+        let spot = self.index.remove(&id)?;
+        self.entities[spot].take().map(|(_, entity)| entity)
+    }
+
+    pub(crate) fn contains(&self, id: EntityId) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    pub(crate) fn get(&self, id: EntityId) -> Option<&dyn Entity> {
+        let (_, entity) = self.entities[*self.index.get(&id)?].as_ref()?;
+        Some(entity.as_ref())
+    }
+
+    pub(crate) fn get_mut(&mut self, id: EntityId) -> Option<&mut (dyn Entity + 'static)> {
+        let (_, entity) = self.entities[*self.index.get(&id)?].as_mut()?;
+        Some(entity.as_mut())
+    }
+
+    /// Entities in the order they were added
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &(EntityId, Box<dyn Entity>)> {
+        self.entities.iter().flatten()
+    }
+
+    pub(crate) fn ids(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.iter().map(|(id, _)| *id)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Take every entity out, in the order they were added
+    pub(crate) fn drain(&mut self) -> Vec<(EntityId, Box<dyn Entity>)> {
+        self.index.clear();
+        self.entities.drain(..).flatten().collect()
+    }
+}
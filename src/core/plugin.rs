@@ -0,0 +1,51 @@
+use super::Game;
+
+/// A subsystem packaged to plug into `Game`, e.g. a debug console or a game's own audio mix.
+/// Every hook is optional:
+///
+/// ```ignore
+/// struct Autosave { timer: f32 }
+///
+/// impl Plugin for Autosave {
+///     fn name(&self) -> &str {
+///         "autosave"
+///     }
+///
+///     fn build(&mut self, game: &mut Game) {
+///         game.get_cheats_mut().button("Save", "Save now", |scene| save_game(scene));
+///     }
+///
+///     fn post_update(&mut self, game: &mut Game, dt: f32) {
+///         self.timer -= dt;
+///         if self.timer <= 0.0 {
+///             save_game(game.get_scene_mut());
+///             self.timer = 60.0;
+///         }
+///     }
+/// }
+///
+/// game.add_plugin(Autosave { timer: 60.0 });
+/// ```
+pub trait Plugin {
+    /// Name shown in debug output and used by `Game::has_plugin`
+    fn name(&self) -> &str;
+
+    /// Called once, from `Game::add_plugin`
+    fn build(&mut self, _game: &mut Game) {}
+
+    /// Called every frame after input, before the scenes update (once per frame with the
+    /// frame's dt, even with a fixed timestep)
+    fn pre_update(&mut self, _game: &mut Game, _dt: f32) {}
+
+    /// Called every frame after the scenes update
+    fn post_update(&mut self, _game: &mut Game, _dt: f32) {}
+
+    /// Called every frame after clearing the screen, before the scenes draw (screen space)
+    fn pre_draw(&self, _game: &Game) {}
+
+    /// Called every frame after the scenes and debug overlays draw, before the cheat menu (screen space)
+    fn post_draw(&self, _game: &Game) {}
+
+    /// Called once when `Game::run` ends, before the game's `on_exit` callback
+    fn exit(&mut self, _game: &mut Game) {}
+}
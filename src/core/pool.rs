@@ -0,0 +1,215 @@
+use std::any::{type_name, TypeId};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use super::{Entity, EntityId};
+
+/// Pool size `Scene::spawn_pooled` uses for a type without `Scene::add_pool`
+pub const DEFAULT_POOL_CAPACITY: usize = 256;
+
+/// Counts for tuning a pool's capacity: if `created` keeps growing the pool is too
+/// small for the peak, if `discarded` does the capacity is below what's in flight
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Most items the pool keeps for reuse
+    pub capacity: usize,
+    /// Items waiting in the pool
+    pub available: usize,
+    /// Items taken and not given back yet
+    pub in_use: usize,
+    pub peak_in_use: usize,
+    /// Items made because the pool was empty
+    pub created: u64,
+    /// Takes served from the pool
+    pub reused: u64,
+    /// Items given back while the pool was full, and dropped
+    pub discarded: u64,
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in use (peak {}), {}/{} free, {} created, {} reused, {} discarded",
+            self.in_use, self.peak_in_use, self.available, self.capacity, self.created, self.reused, self.discarded
+        )
+    }
+}
+
+/// Recycled values, so hot paths (bullets, particles, hit sparks) stop allocating once
+/// the pool has warmed up:
+///
+/// ```ignore
+/// let mut sparks = Pool::new(128);
+/// sparks.prewarm(64, Spark::default);
+/// let mut spark = sparks.take_or_else(Spark::default);
+/// // ...when it burns out
+/// sparks.give(spark);
+/// ```
+pub struct Pool<T> {
+    free: Vec<T>,
+    stats: PoolStats,
+}
+
+impl<T> Pool<T> {
+    /// A pool that keeps up to `capacity` items for reuse
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            free: Vec::with_capacity(capacity),
+            stats: PoolStats { capacity, ..PoolStats::default() },
+        }
+    }
+
+    /// Make items up front until `count` are waiting (at most the capacity)
+    pub fn prewarm(&mut self, count: usize, mut make: impl FnMut() -> T) {
+        let count = count.min(self.stats.capacity);
+        while self.free.len() < count {
+            self.free.push(make());
+            self.stats.created += 1;
+        }
+        self.stats.available = self.free.len();
+    }
+
+    /// An item from the pool, or None if it's empty
+    pub fn take(&mut self) -> Option<T> {
+        let value = self.free.pop()?;
+        self.stats.reused += 1;
+        self.taken();
+        Some(value)
+    }
+
+    /// An item from the pool, or a new one from `make` if it's empty
+    pub fn take_or_else(&mut self, make: impl FnOnce() -> T) -> T {
+        self.take().unwrap_or_else(|| {
+            self.stats.created += 1;
+            self.taken();
+            make()
+        })
+    }
+
+    /// Put an item back for reuse. Returns false if the pool was full and it was dropped.
+    pub fn give(&mut self, value: T) -> bool {
+        self.stats.in_use = self.stats.in_use.saturating_sub(1);
+        if self.free.len() >= self.stats.capacity {
+            self.stats.discarded += 1;
+            return false;
+        }
+        self.free.push(value);
+        self.stats.available = self.free.len();
+        true
+    }
+
+    /// A taken item won't be given back (it was dropped or kept elsewhere)
+    pub fn forget(&mut self) {
+        self.stats.in_use = self.stats.in_use.saturating_sub(1);
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.stats.capacity
+    }
+
+    /// Change how many items are kept, dropping any over the new capacity
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.stats.capacity = capacity;
+        self.free.truncate(capacity);
+        self.stats.available = self.free.len();
+    }
+
+    /// Items waiting in the pool
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Drop every waiting item (the counters are kept)
+    pub fn clear(&mut self) {
+        self.free.clear();
+        self.stats.available = 0;
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    fn taken(&mut self) {
+        self.stats.in_use += 1;
+        self.stats.peak_in_use = self.stats.peak_in_use.max(self.stats.in_use);
+        self.stats.available = self.free.len();
+    }
+}
+
+impl<T> Default for Pool<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_POOL_CAPACITY)
+    }
+}
+
+type EntityPool = Pool<Box<dyn Entity>>;
+
+/// A scene's entity pools, one per entity type
+#[derive(Default)]
+pub(crate) struct EntityPools {
+    // By entity type, with the type's name for stats
+    pools: HashMap<TypeId, (&'static str, EntityPool)>,
+    // Entities spawned through a pool, which go back to it when they're deactivated
+    pooled: HashSet<EntityId>,
+}
+
+impl EntityPools {
+    pub(crate) fn pool_mut<E: Entity + 'static>(&mut self) -> &mut EntityPool {
+        &mut self.pools.entry(TypeId::of::<E>())
+            .or_insert_with(|| (type_name::<E>(), Pool::default()))
+            .1
+    }
+
+    pub(crate) fn stats<E: Entity + 'static>(&self) -> Option<PoolStats> {
+        self.pools.get(&TypeId::of::<E>()).map(|(_, pool)| pool.stats())
+    }
+
+    /// Stats of every pool by entity type name, sorted by name
+    pub(crate) fn all_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        let mut stats: Vec<_> = self.pools.values().map(|(name, pool)| (*name, pool.stats())).collect();
+        stats.sort_by_key(|(name, _)| *name);
+        stats
+    }
+
+    /// A boxed `entity`, reusing a pooled box of its type when there is one
+    pub(crate) fn spawn<E: Entity + 'static>(&mut self, id: EntityId, entity: E) -> Box<dyn Entity> {
+        self.pooled.insert(id);
+        let pool = self.pool_mut::<E>();
+        match pool.take() {
+            Some(mut reused) => {
+                match (*reused).as_any_mut().downcast_mut::<E>() {
+                    Some(slot) => *slot = entity,
+                    None => reused = Box::new(entity),
+                }
+                reused
+            }
+            None => pool.take_or_else(|| Box::new(entity)),
+        }
+    }
+
+    /// Entities spawned through a pool that haven't gone back yet
+    pub(crate) fn pooled(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.pooled.iter().copied()
+    }
+
+    /// Give a removed entity back to its pool if it came from one, else drop it
+    pub(crate) fn recycle(&mut self, id: EntityId, entity: Box<dyn Entity>) {
+        if !self.pooled.remove(&id) {
+            return;
+        }
+        let type_id = (*entity).as_any().type_id();
+        if let Some((_, pool)) = self.pools.get_mut(&type_id) {
+            pool.give(entity);
+        }
+    }
+
+    /// A pooled entity left the scene without coming back (`Scene::remove_entity`)
+    pub(crate) fn forget(&mut self, id: EntityId, entity: &dyn Entity) {
+        if !self.pooled.remove(&id) {
+            return;
+        }
+        if let Some((_, pool)) = self.pools.get_mut(&entity.as_any().type_id()) {
+            pool.forget();
+        }
+    }
+}
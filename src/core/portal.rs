@@ -0,0 +1,306 @@
+use macroquad::prelude::*;
+use std::collections::HashSet;
+use super::{EntityId, MotionSettings, Scene};
+use crate::math::Easing;
+use crate::rendering::{Camera, CameraSequence};
+
+/// Handle to a portal in a `Scene`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PortalId(pub(crate) u32);
+
+/// Where a portal sends entities
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalTarget {
+    /// Out of another portal in the same scene
+    Portal(PortalId),
+    /// To a point in the same scene
+    Position(Vec2),
+    /// Into another scene or level. The portal only reports `PortalEvent::SceneRequested`;
+    /// the game loads the scene and places the entity at the named spawn point.
+    Scene { scene: String, spawn: String },
+}
+
+/// What the cameras following a teleported entity do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraHandoff {
+    /// Jump straight to the new position
+    Snap,
+    /// Pan over to the new position, then resume following
+    Pan { duration: f32, easing: Easing },
+    /// Leave it to the camera's usual follow smoothing
+    Follow,
+}
+
+/// How a teleport looks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PortalTransition {
+    Instant,
+    /// Fade the screen to `color` and back; the entity moves at the darkest point
+    Fade { duration: f32, color: Color },
+}
+
+/// A door or portal: entities that walk into `area` are sent to `target`.
+///
+/// ```ignore
+/// let door = scene.add_portal(Portal::new(Rect::new(100.0, 200.0, 32.0, 48.0)).with_tag("player"));
+/// let cellar = scene.add_portal(Portal::new(Rect::new(900.0, 40.0, 32.0, 48.0)).with_exit_offset(vec2(0.0, 40.0)));
+/// scene.link_portals(door, cellar);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Portal {
+    /// World-space trigger area
+    pub area: Rect,
+    /// None for exit-only portals
+    pub target: Option<PortalTarget>,
+    /// Where arriving entities are placed, relative to the center of `area`
+    pub exit_offset: Vec2,
+    /// Rotation given to arriving entities (None keeps theirs)
+    pub exit_rotation: Option<f32>,
+    /// Only entities with this tag use the portal (None = any entity with a transform)
+    pub tag: Option<String>,
+    pub camera: CameraHandoff,
+    pub transition: PortalTransition,
+    pub enabled: bool,
+}
+
+impl Portal {
+    pub fn new(area: Rect) -> Self {
+        Self {
+            area,
+            target: None,
+            exit_offset: Vec2::ZERO,
+            exit_rotation: None,
+            tag: None,
+            camera: CameraHandoff::Snap,
+            transition: PortalTransition::Instant,
+            enabled: true,
+        }
+    }
+
+    pub fn with_target(mut self, target: PortalTarget) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    pub fn with_exit_offset(mut self, offset: Vec2) -> Self {
+        self.exit_offset = offset;
+        self
+    }
+
+    pub fn with_exit_rotation(mut self, rotation: f32) -> Self {
+        self.exit_rotation = Some(rotation);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    pub fn with_camera(mut self, camera: CameraHandoff) -> Self {
+        self.camera = camera;
+        self
+    }
+
+    pub fn with_transition(mut self, transition: PortalTransition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    /// Where entities arriving through this portal end up
+    pub fn exit_point(&self) -> Vec2 {
+        self.area.center() + self.exit_offset
+    }
+}
+
+/// Portal activity, read with `Scene::take_portal_events`
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalEvent {
+    Entered { entity: EntityId, portal: PortalId },
+    Teleported { entity: EntityId, from: PortalId, position: Vec2 },
+    /// The entity walked into a portal leading to another scene
+    SceneRequested { entity: EntityId, portal: PortalId, scene: String, spawn: String },
+}
+
+/// A teleport waiting for its fade to reach the midpoint
+struct PendingTeleport {
+    entity: EntityId,
+    from: PortalId,
+    destination: (Vec2, Option<PortalId>),
+    elapsed: f32,
+    duration: f32,
+    color: Color,
+    done: bool,
+}
+
+/// Portals of a scene and the state needed to use them
+#[derive(Default)]
+pub(crate) struct Portals {
+    portals: Vec<(PortalId, Portal)>,
+    next_id: u32,
+    // Entities standing in each portal; they have to leave before it triggers again
+    inside: HashSet<(PortalId, EntityId)>,
+    pending: Vec<PendingTeleport>,
+    events: Vec<PortalEvent>,
+    motion: MotionSettings,
+}
+
+impl Portals {
+    pub(crate) fn add(&mut self, portal: Portal) -> PortalId {
+        let id = PortalId(self.next_id);
+        self.next_id += 1;
+        self.portals.push((id, portal));
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: PortalId) -> Option<Portal> {
+        let index = self.portals.iter().position(|(portal_id, _)| *portal_id == id)?;
+        self.inside.retain(|(portal, _)| *portal != id);
+        Some(self.portals.remove(index).1)
+    }
+
+    pub(crate) fn get(&self, id: PortalId) -> Option<&Portal> {
+        self.portals.iter().find(|(portal_id, _)| *portal_id == id).map(|(_, portal)| portal)
+    }
+
+    pub(crate) fn get_mut(&mut self, id: PortalId) -> Option<&mut Portal> {
+        self.portals.iter_mut().find(|(portal_id, _)| *portal_id == id).map(|(_, portal)| portal)
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (PortalId, &Portal)> {
+        self.portals.iter().map(|(id, portal)| (*id, portal))
+    }
+
+    pub(crate) fn set_motion(&mut self, motion: MotionSettings) {
+        self.motion = motion;
+    }
+
+    pub(crate) fn take_events(&mut self) -> Vec<PortalEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub(crate) fn forget_entity(&mut self, entity: EntityId) {
+        self.inside.retain(|(_, inside)| *inside != entity);
+        self.pending.retain(|pending| pending.entity != entity);
+    }
+
+    pub(crate) fn update(&mut self, dt: f32, scene: &mut Scene) {
+        // Fades in progress: move the entity at the midpoint
+        for index in 0..self.pending.len() {
+            let pending = &mut self.pending[index];
+            pending.elapsed += dt;
+            if !pending.done && pending.elapsed >= pending.duration * 0.5 {
+                pending.done = true;
+                let (entity, from, destination) = (pending.entity, pending.from, pending.destination);
+                self.teleport(scene, entity, from, destination);
+            }
+        }
+        self.pending.retain(|pending| pending.elapsed < pending.duration);
+
+        let entities: Vec<_> = scene.entity_ids().collect();
+        for index in 0..self.portals.len() {
+            let (portal_id, portal) = self.portals[index].clone();
+            for &entity in &entities {
+                let Some(position) = scene.entity_position(entity) else {
+                    continue;
+                };
+                let key = (portal_id, entity);
+                if !portal.area.contains(position) {
+                    self.inside.remove(&key);
+                    continue;
+                }
+                let allowed = portal.tag.as_ref()
+                    .is_none_or(|tag| scene.get_entity(entity).is_some_and(|e| e.has_tag(tag)));
+                if !portal.enabled || !allowed || !self.inside.insert(key) {
+                    continue;
+                }
+                self.events.push(PortalEvent::Entered { entity, portal: portal_id });
+                if self.pending.iter().any(|pending| pending.entity == entity) {
+                    continue;
+                }
+
+                let destination = match &portal.target {
+                    None => continue,
+                    Some(PortalTarget::Position(position)) => (*position, None),
+                    Some(PortalTarget::Portal(target)) => match self.get(*target) {
+                        Some(exit) => (exit.exit_point(), Some(*target)),
+                        None => continue,
+                    },
+                    Some(PortalTarget::Scene { scene: name, spawn }) => {
+                        self.events.push(PortalEvent::SceneRequested {
+                            entity,
+                            portal: portal_id,
+                            scene: name.clone(),
+                            spawn: spawn.clone(),
+                        });
+                        continue;
+                    }
+                };
+                match portal.transition {
+                    PortalTransition::Instant => self.teleport(scene, entity, portal_id, destination),
+                    PortalTransition::Fade { duration, color } => self.pending.push(PendingTeleport {
+                        entity,
+                        from: portal_id,
+                        destination,
+                        elapsed: 0.0,
+                        duration: self.motion.fade_duration(duration),
+                        color,
+                        done: false,
+                    }),
+                }
+            }
+        }
+    }
+
+    fn teleport(&mut self, scene: &mut Scene, entity: EntityId, from: PortalId, (position, exit): (Vec2, Option<PortalId>)) {
+        let exit_rotation = exit.and_then(|exit| self.get(exit)).and_then(|exit| exit.exit_rotation);
+        let Some(transform) = scene.get_entity_mut(entity).and_then(|e| e.get_transform_mut()) else {
+            return;
+        };
+        transform.position = position;
+        if let Some(rotation) = exit_rotation {
+            transform.rotation = rotation;
+        }
+        // Arriving inside the exit portal shouldn't send the entity straight back
+        if let Some(exit) = exit {
+            self.inside.insert((exit, entity));
+        }
+
+        let handoff = self.get(from).map_or(CameraHandoff::Snap, |portal| portal.camera);
+        if scene.camera.followed_entity() == Some(entity) {
+            hand_off(&mut scene.camera, handoff, position);
+        }
+        for camera in scene.cameras_mut() {
+            if camera.followed_entity() == Some(entity) {
+                hand_off(camera, handoff, position);
+            }
+        }
+        self.events.push(PortalEvent::Teleported { entity, from, position });
+    }
+
+    /// Full-screen fade of the strongest transition in progress
+    pub(crate) fn draw_transition(&self) {
+        let fade = self.pending.iter()
+            .map(|pending| {
+                let t = (pending.elapsed / pending.duration.max(f32::EPSILON)).clamp(0.0, 1.0);
+                (1.0 - (t * 2.0 - 1.0).abs(), pending.color)
+            })
+            .max_by(|a, b| a.0.total_cmp(&b.0));
+        if let Some((alpha, color)) = fade {
+            let color = Color::new(color.r, color.g, color.b, color.a * alpha);
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), color);
+        }
+    }
+}
+
+fn hand_off(camera: &mut Camera, handoff: CameraHandoff, position: Vec2) {
+    match handoff {
+        CameraHandoff::Snap => camera.set_position(position),
+        CameraHandoff::Pan { duration, easing } => camera.play_sequence(
+            CameraSequence::new("portal")
+                .pan_to(position, duration, easing)
+                .return_to_follow(0.0, easing),
+        ),
+        CameraHandoff::Follow => {}
+    }
+}
@@ -0,0 +1,454 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use super::{rng, EntityBuilder, EntityId, Scene, SimpleEntity, Timeline};
+use crate::assets::AssetManager;
+use crate::movement::MovementPattern;
+use crate::physics::{Body, Collider};
+
+/// Error returned when loading prefab definitions
+#[derive(Debug)]
+pub enum PrefabError {
+    Io(std::io::Error),
+    Format(String),
+    /// A definition uses a texture the `AssetManager` hasn't loaded
+    MissingTexture { prefab: String, path: String },
+}
+
+impl fmt::Display for PrefabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefabError::Io(err) => write!(f, "failed to read prefab file: {}", err),
+            PrefabError::Format(msg) => write!(f, "invalid prefab data: {}", msg),
+            PrefabError::MissingTexture { prefab, path } => {
+                write!(f, "prefab '{}' uses texture '{}', which isn't loaded", prefab, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PrefabError {}
+
+impl From<std::io::Error> for PrefabError {
+    fn from(err: std::io::Error) -> Self {
+        PrefabError::Io(err)
+    }
+}
+
+/// The parts of an entity, as written in a prefab file. Every field is optional:
+///
+/// ```ron
+/// (
+///     sprite: Some("enemies/bat.png"),
+///     collider: Some(Circle(radius: 6.0)),
+///     tags: ["enemy"],
+///     movement: Some(SineStrafe(velocity: (0.0, 40.0), amplitude: 24.0, frequency: 1.5)),
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefabDef {
+    /// Texture path, already loaded through the `AssetManager`
+    pub sprite: Option<String>,
+    /// RGBA
+    pub color: Option<[f32; 4]>,
+    pub scale: Option<Vec2>,
+    pub rotation: f32,
+    pub collider: Option<Collider>,
+    pub tags: Vec<String>,
+    pub layer: i32,
+    pub sort_key: i64,
+    pub body: Option<Body>,
+    pub movement: Option<MovementPattern>,
+    pub timeline: Option<Timeline>,
+}
+
+impl PrefabDef {
+    /// Turn the definition into a prefab, looking up its sprite in `assets`
+    pub fn to_prefab(&self, name: &str, assets: &AssetManager) -> Result<Prefab, PrefabError> {
+        let sprite = match &self.sprite {
+            Some(path) => Some(assets.texture(path).cloned().ok_or_else(|| PrefabError::MissingTexture {
+                prefab: name.to_string(),
+                path: path.clone(),
+            })?),
+            None => None,
+        };
+        let def = self.clone();
+        let prefab = Prefab::new(move || def.builder(sprite.clone()));
+        Ok(match &self.timeline {
+            Some(timeline) => prefab.with_timeline(timeline.clone()),
+            None => prefab,
+        })
+    }
+
+    fn builder(&self, sprite: Option<Texture2D>) -> EntityBuilder {
+        let mut builder = EntityBuilder::new().rotation(self.rotation).layer(self.layer).sort_key(self.sort_key);
+        if let Some(texture) = sprite {
+            builder = builder.sprite(texture);
+        }
+        if let Some(color) = self.color {
+            builder = builder.color(Color::from(color));
+        }
+        if let Some(scale) = self.scale {
+            builder = builder.scale(scale);
+        }
+        if let Some(collider) = self.collider {
+            builder = builder.collider(collider);
+        }
+        for tag in &self.tags {
+            builder = builder.tag(tag);
+        }
+        if let Some(body) = self.body {
+            builder = builder.with_body(body);
+        }
+        if let Some(pattern) = &self.movement {
+            builder = builder.movement(pattern.clone());
+        }
+        builder
+    }
+}
+
+/// Per-spawn changes to a prefab, on top of its position, e.g. from a level file:
+///
+/// ```ignore
+/// let overrides = PrefabOverrides::new().with_velocity(vec2(-80.0, 0.0)).with_tag("boss");
+/// library.spawn_with("bat", vec2(400.0, 120.0), &overrides, &mut scene);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefabOverrides {
+    pub rotation: Option<f32>,
+    pub scale: Option<Vec2>,
+    /// RGBA
+    pub color: Option<[f32; 4]>,
+    pub velocity: Option<Vec2>,
+    /// Added to the prefab's tags
+    pub tags: Vec<String>,
+}
+
+impl PrefabOverrides {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = Some(rotation);
+        self
+    }
+
+    pub fn with_scale(mut self, scale: Vec2) -> Self {
+        self.scale = Some(scale);
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some([color.r, color.g, color.b, color.a]);
+        self
+    }
+
+    /// Starting velocity (adds a weightless body if the prefab has none)
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = Some(velocity);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    pub fn apply(&self, entity: &mut SimpleEntity) {
+        if let Some(rotation) = self.rotation {
+            entity.transform.rotation = rotation;
+        }
+        if let Some(scale) = self.scale {
+            entity.transform.scale = scale;
+        }
+        if let Some(color) = self.color {
+            entity.color = Color::from(color);
+        }
+        if let Some(velocity) = self.velocity {
+            entity.body.get_or_insert_with(|| Body::new(0.0)).velocity = velocity;
+        }
+        for tag in &self.tags {
+            entity.add_tag(tag);
+        }
+    }
+}
+
+/// A reusable entity template: a function that sets up an `EntityBuilder`
+pub struct Prefab {
+    build: Box<dyn Fn() -> EntityBuilder>,
+    timeline: Option<Timeline>,
+}
+
+impl Prefab {
+    /// ```ignore
+    /// let bat = Prefab::new(move || spawn().sprite(bat_texture.clone()).collider_circle(6.0).tag("enemy"));
+    /// ```
+    pub fn new<F: Fn() -> EntityBuilder + 'static>(build: F) -> Self {
+        Self { build: Box::new(build), timeline: None }
+    }
+
+    /// Run this timeline on every entity spawned from the prefab
+    pub fn with_timeline(mut self, timeline: Timeline) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
+    pub fn timeline(&self) -> Option<&Timeline> {
+        self.timeline.as_ref()
+    }
+
+    /// A fresh builder, for adding per-spawn parts before finishing
+    pub fn builder(&self) -> EntityBuilder {
+        (self.build)()
+    }
+
+    pub fn instantiate(&self, position: Vec2) -> SimpleEntity {
+        self.builder().at(position).finish()
+    }
+
+    /// Build the entity, add it to the scene and start its timeline
+    pub fn spawn(&self, position: Vec2, scene: &mut Scene) -> EntityId {
+        add_to_scene(self.instantiate(position), self.timeline(), scene)
+    }
+
+    /// Like `spawn`, with per-spawn changes applied
+    pub fn spawn_with(&self, position: Vec2, overrides: &PrefabOverrides, scene: &mut Scene) -> EntityId {
+        let mut entity = self.instantiate(position);
+        overrides.apply(&mut entity);
+        add_to_scene(entity, self.timeline(), scene)
+    }
+}
+
+/// Random variation applied to each spawned variant
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Jitter {
+    /// Range the scale is multiplied by
+    pub scale: (f32, f32),
+    /// Range added to the rotation, in radians
+    pub rotation: (f32, f32),
+    /// Largest change to each tint channel, as a fraction (0.1 = +/-10%)
+    pub tint: f32,
+}
+
+impl Default for Jitter {
+    fn default() -> Self {
+        Self { scale: (1.0, 1.0), rotation: (0.0, 0.0), tint: 0.0 }
+    }
+}
+
+impl Jitter {
+    fn apply(&self, entity: &mut SimpleEntity) {
+        entity.transform.scale *= random_in(self.scale);
+        entity.transform.rotation += random_in(self.rotation);
+        if self.tint > 0.0 {
+            let channel = |value: f32| (value * (1.0 + random_in((-self.tint, self.tint)))).clamp(0.0, 1.0);
+            entity.color = Color::new(channel(entity.color.r), channel(entity.color.g), channel(entity.color.b), entity.color.a);
+        }
+    }
+}
+
+/// Prefabs spawned in place of one another with weighted probability, e.g. five trees
+/// that scatter as a varied forest:
+///
+/// ```ignore
+/// let trees = VariantGroup::new()
+///     .with_variant(3.0, oak)
+///     .with_variant(1.0, birch)
+///     .with_scale(0.8, 1.2)
+///     .with_tint(0.1);
+/// library.register_variants("tree", trees);
+/// ```
+#[derive(Default)]
+pub struct VariantGroup {
+    variants: Vec<(f32, Prefab)>,
+    pub jitter: Jitter,
+}
+
+impl VariantGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a variant; `weight` is relative to the other variants' weights
+    pub fn with_variant(mut self, weight: f32, prefab: Prefab) -> Self {
+        self.variants.push((weight.max(0.0), prefab));
+        self
+    }
+
+    pub fn with_scale(mut self, min: f32, max: f32) -> Self {
+        self.jitter.scale = (min, max);
+        self
+    }
+
+    pub fn with_rotation(mut self, min: f32, max: f32) -> Self {
+        self.jitter.rotation = (min, max);
+        self
+    }
+
+    pub fn with_tint(mut self, amount: f32) -> Self {
+        self.jitter.tint = amount.max(0.0);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    /// Pick a variant by weight (None if the group is empty or all weights are zero)
+    pub fn pick(&self) -> Option<&Prefab> {
+        let total: f32 = self.variants.iter().map(|(weight, _)| weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = random_in((0.0, total));
+        for (weight, prefab) in &self.variants {
+            if roll < *weight {
+                return Some(prefab);
+            }
+            roll -= weight;
+        }
+        // Float rounding can leave the roll just past the last weight
+        self.variants.iter().rev().find(|(weight, _)| *weight > 0.0).map(|(_, prefab)| prefab)
+    }
+
+    /// Pick a variant and apply the jitter
+    pub fn instantiate(&self, position: Vec2) -> Option<SimpleEntity> {
+        self.instantiate_with_timeline(position).map(|(entity, _)| entity)
+    }
+
+    /// Pick a variant, apply the jitter, add it to the scene and start its timeline
+    pub fn spawn(&self, position: Vec2, scene: &mut Scene) -> Option<EntityId> {
+        self.spawn_with(position, &PrefabOverrides::default(), scene)
+    }
+
+    /// Like `spawn`, with per-spawn changes applied after the jitter
+    pub fn spawn_with(&self, position: Vec2, overrides: &PrefabOverrides, scene: &mut Scene) -> Option<EntityId> {
+        let (mut entity, timeline) = self.instantiate_with_timeline(position)?;
+        overrides.apply(&mut entity);
+        Some(add_to_scene(entity, timeline, scene))
+    }
+
+    fn instantiate_with_timeline(&self, position: Vec2) -> Option<(SimpleEntity, Option<&Timeline>)> {
+        let prefab = self.pick()?;
+        let mut entity = prefab.instantiate(position);
+        self.jitter.apply(&mut entity);
+        Some((entity, prefab.timeline()))
+    }
+}
+
+enum PrefabEntry {
+    Single(Prefab),
+    Variants(VariantGroup),
+}
+
+/// Prefabs and variant groups by name, registered in code or loaded from RON files of
+/// definitions by name:
+///
+/// ```ron
+/// {
+///     "bat": (sprite: Some("enemies/bat.png"), collider: Some(Circle(radius: 6.0)), tags: ["enemy"]),
+///     "crate": (color: Some([0.6, 0.4, 0.2, 1.0]), collider: Some(Rect(size: (16.0, 16.0))), layer: 1),
+/// }
+/// ```
+///
+/// ```ignore
+/// assets.load_texture("enemies/bat.png").await?;
+/// library.load_defs("data/prefabs.ron", &assets)?;
+/// for x in 0..50 {
+///     library.spawn("bat", vec2(x as f32 * 20.0, 64.0), &mut scene);
+/// }
+/// ```
+#[derive(Default)]
+pub struct PrefabLibrary {
+    entries: HashMap<String, PrefabEntry>,
+}
+
+impl PrefabLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, name: &str, prefab: Prefab) {
+        self.entries.insert(name.to_string(), PrefabEntry::Single(prefab));
+    }
+
+    pub fn register_variants(&mut self, name: &str, group: VariantGroup) {
+        self.entries.insert(name.to_string(), PrefabEntry::Variants(group));
+    }
+
+    /// Register every definition in a prefab file, replacing prefabs with the same name.
+    /// Returns the names loaded.
+    pub fn load_defs<P: AsRef<Path>>(&mut self, path: P, assets: &AssetManager) -> Result<Vec<String>, PrefabError> {
+        let data = std::fs::read_to_string(path)?;
+        self.load_defs_from_str(&data, assets)
+    }
+
+    /// Register definitions from a RON string. Nothing is registered if any definition fails.
+    pub fn load_defs_from_str(&mut self, data: &str, assets: &AssetManager) -> Result<Vec<String>, PrefabError> {
+        let defs: BTreeMap<String, PrefabDef> = ron::from_str(data).map_err(|err| PrefabError::Format(err.to_string()))?;
+        let prefabs = defs.iter()
+            .map(|(name, def)| Ok((name.clone(), def.to_prefab(name, assets)?)))
+            .collect::<Result<Vec<_>, PrefabError>>()?;
+        Ok(prefabs.into_iter().map(|(name, prefab)| {
+            self.register(&name, prefab);
+            name
+        }).collect())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> bool {
+        self.entries.remove(name).is_some()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Build the named prefab (a variant picked at random for groups) without adding it to a scene
+    pub fn instantiate(&self, name: &str, position: Vec2) -> Option<SimpleEntity> {
+        match self.entries.get(name)? {
+            PrefabEntry::Single(prefab) => Some(prefab.instantiate(position)),
+            PrefabEntry::Variants(group) => group.instantiate(position),
+        }
+    }
+
+    /// Build the named prefab, add it to the scene and start its timeline
+    pub fn spawn(&self, name: &str, position: Vec2, scene: &mut Scene) -> Option<EntityId> {
+        match self.entries.get(name)? {
+            PrefabEntry::Single(prefab) => Some(prefab.spawn(position, scene)),
+            PrefabEntry::Variants(group) => group.spawn(position, scene),
+        }
+    }
+
+    /// Like `spawn`, with per-spawn changes applied
+    pub fn spawn_with(&self, name: &str, position: Vec2, overrides: &PrefabOverrides, scene: &mut Scene) -> Option<EntityId> {
+        match self.entries.get(name)? {
+            PrefabEntry::Single(prefab) => Some(prefab.spawn_with(position, overrides, scene)),
+            PrefabEntry::Variants(group) => group.spawn_with(position, overrides, scene),
+        }
+    }
+}
+
+fn add_to_scene(entity: SimpleEntity, timeline: Option<&Timeline>, scene: &mut Scene) -> EntityId {
+    let id = scene.add_entity(Box::new(entity));
+    if let Some(timeline) = timeline {
+        scene.run_timeline(id, timeline.clone());
+    }
+    id
+}
+
+fn random_in((min, max): (f32, f32)) -> f32 {
+    rng::with_shared(|rng| rng.range(min, max))
+}
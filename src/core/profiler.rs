@@ -0,0 +1,161 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+use super::FrameStats;
+use crate::rendering::DebugTheme;
+
+// Width of one frame's bar in pixels
+const BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 80.0;
+const PADDING: f32 = 8.0;
+const LINE_HEIGHT: f32 = 16.0;
+
+/// Rolling history of where each frame's time went, drawn as a bar graph by the debug
+/// overlay (`GameConfig::show_profiler`). `Game` feeds it every frame; read it yourself
+/// to react to slow sections:
+///
+/// ```ignore
+/// let profiler = game.get_profiler();
+/// if profiler.average().draw > 8.0 {
+///     game.get_scene_mut().set_culling(true);
+/// }
+/// ```
+pub struct FrameProfiler {
+    history: VecDeque<FrameStats>,
+    capacity: usize,
+    budget: f32,
+}
+
+impl Default for FrameProfiler {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::new(),
+            capacity: 120,
+            budget: 1000.0 / 60.0,
+        }
+    }
+}
+
+impl FrameProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames kept and drawn (120 by default)
+    pub fn with_history(mut self, frames: usize) -> Self {
+        self.set_history(frames);
+        self
+    }
+
+    /// Frame time in milliseconds marked on the graph (one 60 Hz frame by default)
+    pub fn with_budget(mut self, ms: f32) -> Self {
+        self.budget = ms;
+        self
+    }
+
+    pub fn set_history(&mut self, frames: usize) {
+        self.capacity = frames.max(1);
+        while self.history.len() > self.capacity {
+            self.history.pop_front();
+        }
+    }
+
+    pub fn set_budget(&mut self, ms: f32) {
+        self.budget = ms;
+    }
+
+    pub fn budget(&self) -> f32 {
+        self.budget
+    }
+
+    pub fn push(&mut self, stats: FrameStats) {
+        if self.history.len() >= self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(stats);
+    }
+
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Recorded frames, oldest first
+    pub fn history(&self) -> impl Iterator<Item = &FrameStats> {
+        self.history.iter()
+    }
+
+    pub fn latest(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    /// Mean of each section over the history
+    pub fn average(&self) -> FrameStats {
+        let count = self.history.len().max(1) as f32;
+        let mean = |section: fn(&FrameStats) -> f32| self.history.iter().map(section).sum::<f32>() / count;
+        FrameStats {
+            frame_time: mean(|stats| stats.frame_time),
+            input: mean(|stats| stats.input),
+            update: mean(|stats| stats.update),
+            physics: mean(|stats| stats.physics),
+            camera: mean(|stats| stats.camera),
+            draw: mean(|stats| stats.draw),
+            entities: self.latest().map_or(0, |stats| stats.entities),
+        }
+    }
+
+    /// Longest section over the history: "input", "update", "physics", "camera" or "draw"
+    pub fn slowest(&self) -> &'static str {
+        self.average().sections().into_iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or("update", |(name, _)| name)
+    }
+
+    /// Size of the panel `draw` draws
+    pub fn panel_size(&self) -> Vec2 {
+        vec2(
+            self.capacity as f32 * BAR_WIDTH + PADDING * 2.0,
+            GRAPH_HEIGHT + LINE_HEIGHT * 6.0 + PADDING * 3.0,
+        )
+    }
+
+    /// Draw the graph and a legend of average times in screen space, with the top-left
+    /// corner at `position`. The graph's top is twice the budget; slower frames are cut off.
+    pub fn draw(&self, theme: &DebugTheme, position: Vec2) {
+        let size = self.panel_size();
+        draw_rectangle(position.x, position.y, size.x, size.y, theme.background());
+
+        let average = self.average();
+        let x = position.x + PADDING;
+        let mut y = position.y + PADDING + LINE_HEIGHT * 0.75;
+        let fps = if average.frame_time > 0.0 { 1000.0 / average.frame_time } else { 0.0 };
+        draw_text(&format!("Frame {:.2} ms ({:.0} FPS)", average.frame_time, fps), x, y, LINE_HEIGHT, theme.heading());
+        y += LINE_HEIGHT * 0.25 + PADDING;
+
+        // Stacked bars, newest on the right
+        let bottom = y + GRAPH_HEIGHT;
+        let scale = GRAPH_HEIGHT / (self.budget * 2.0).max(f32::EPSILON);
+        let first = x + (self.capacity - self.history.len()) as f32 * BAR_WIDTH;
+        for (index, stats) in self.history.iter().enumerate() {
+            let bar_x = first + index as f32 * BAR_WIDTH;
+            let mut top = bottom;
+            for (section, (_, ms)) in stats.sections().into_iter().enumerate() {
+                let height = (ms * scale).min(top - y);
+                if height <= 0.0 {
+                    continue;
+                }
+                top -= height;
+                draw_rectangle(bar_x, top, BAR_WIDTH, height, theme.profiler(section));
+            }
+        }
+        let budget_y = bottom - self.budget * scale;
+        draw_line(x, budget_y, x + self.capacity as f32 * BAR_WIDTH, budget_y, 1.0, theme.warning());
+        y = bottom + LINE_HEIGHT;
+
+        let slowest = self.slowest();
+        for (section, (name, ms)) in average.sections().into_iter().enumerate() {
+            draw_rectangle(x, y - LINE_HEIGHT * 0.6, LINE_HEIGHT * 0.6, LINE_HEIGHT * 0.6, theme.profiler(section));
+            let color = if name == slowest { theme.warning() } else { theme.text() };
+            draw_text(&format!("{:<8}{:>7.2} ms", name, ms), x + LINE_HEIGHT, y, LINE_HEIGHT, color);
+            y += LINE_HEIGHT;
+        }
+    }
+}
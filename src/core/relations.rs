@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::EntityId;
+
+/// Kind of link from one entity to another (see `Scene::relate`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Relation {
+    /// Aiming at or attacking
+    Targeting,
+    /// Moving after, e.g. a pet or a party member
+    Following,
+    /// Guarding, e.g. a bodyguard or a convoy escort
+    Escorting,
+    Custom(String),
+}
+
+impl Relation {
+    pub fn custom(name: &str) -> Self {
+        Relation::Custom(name.to_string())
+    }
+}
+
+/// Links between a scene's entities, one per entity and relation, indexed both ways by
+/// entity so forgetting one only touches its own links
+#[derive(Default)]
+pub(crate) struct Relations {
+    // Where each entity links to
+    forward: HashMap<EntityId, HashMap<Relation, EntityId>>,
+    // What links to each entity
+    reverse: HashMap<EntityId, HashMap<Relation, Vec<EntityId>>>,
+}
+
+impl Relations {
+    /// Link `from` to `to`, replacing its previous link of this kind
+    pub(crate) fn set(&mut self, from: EntityId, relation: Relation, to: EntityId) {
+        self.clear(from, &relation);
+        self.reverse.entry(to).or_default().entry(relation.clone()).or_default().push(from);
+        self.forward.entry(from).or_default().insert(relation, to);
+    }
+
+    pub(crate) fn clear(&mut self, from: EntityId, relation: &Relation) {
+        let Some(links) = self.forward.get_mut(&from) else {
+            return;
+        };
+        let Some(to) = links.remove(relation) else {
+            return;
+        };
+        if links.is_empty() {
+            self.forward.remove(&from);
+        }
+        self.unlink_source(to, relation, from);
+    }
+
+    /// Drop `from` from the entities linking to `to`
+    fn unlink_source(&mut self, to: EntityId, relation: &Relation, from: EntityId) {
+        let Some(incoming) = self.reverse.get_mut(&to) else {
+            return;
+        };
+        if let Some(sources) = incoming.get_mut(relation) {
+            sources.retain(|id| *id != from);
+            if sources.is_empty() {
+                incoming.remove(relation);
+            }
+        }
+        if incoming.is_empty() {
+            self.reverse.remove(&to);
+        }
+    }
+
+    pub(crate) fn get(&self, from: EntityId, relation: &Relation) -> Option<EntityId> {
+        self.forward.get(&from)?.get(relation).copied()
+    }
+
+    pub(crate) fn sources(&self, to: EntityId, relation: &Relation) -> &[EntityId] {
+        self.reverse.get(&to)
+            .and_then(|incoming| incoming.get(relation))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Every link from an entity
+    pub(crate) fn of(&self, from: EntityId) -> impl Iterator<Item = (&Relation, EntityId)> {
+        self.forward.get(&from)
+            .into_iter()
+            .flatten()
+            .map(|(relation, to)| (relation, *to))
+    }
+
+    /// Drop every link from and to a removed entity
+    pub(crate) fn forget_entity(&mut self, entity: EntityId) {
+        for (relation, to) in self.forward.remove(&entity).unwrap_or_default() {
+            self.unlink_source(to, &relation, entity);
+        }
+        for (relation, sources) in self.reverse.remove(&entity).unwrap_or_default() {
+            for from in sources {
+                if let Some(links) = self.forward.get_mut(&from) {
+                    links.remove(&relation);
+                    if links.is_empty() {
+                        self.forward.remove(&from);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn clear_all(&mut self) {
+        self.forward.clear();
+        self.reverse.clear();
+    }
+}
@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "http")]
+use crate::net::{HttpClient, HttpRequest, RequestId};
+
+/// Error returned when loading or saving remote config values
+#[derive(Debug)]
+pub enum RemoteConfigError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for RemoteConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RemoteConfigError::Io(err) => write!(f, "failed to access config file: {}", err),
+            RemoteConfigError::Format(msg) => write!(f, "invalid config data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RemoteConfigError {}
+
+impl From<std::io::Error> for RemoteConfigError {
+    fn from(err: std::io::Error) -> Self {
+        RemoteConfigError::Io(err)
+    }
+}
+
+/// One tunable value or feature flag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ConfigValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl ConfigValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ConfigValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            ConfigValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Ints convert too, so `speed: 120` reads as 120.0
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ConfigValue::Float(value) => Some(*value),
+            ConfigValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            ConfigValue::Text(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::Bool(value) => write!(f, "{}", value),
+            ConfigValue::Int(value) => write!(f, "{}", value),
+            ConfigValue::Float(value) => write!(f, "{}", value),
+            ConfigValue::Text(value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+/// What happened to the config, read with `RemoteConfig::take_events`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigEvent {
+    /// A value differs from before (`old` is None for keys that weren't set)
+    Changed { key: String, old: Option<ConfigValue>, new: ConfigValue },
+    /// A fetch finished and its values were applied
+    Fetched,
+    /// A fetch failed; the cached or bundled values stay in use
+    FetchFailed(String),
+}
+
+/// Tunable gameplay values and feature flags that can change without a patch.
+///
+/// Values come from three places, each overriding the one before: the defaults bundled
+/// with the game, the cache written by the last successful fetch, and the latest fetch.
+/// Config files are a RON map, which also accepts flat JSON objects:
+///
+/// ```ignore
+/// let mut config = RemoteConfig::from_defaults_str(include_str!("tuning.ron"))?.with_cache("remote.ron");
+/// config.fetch(&game.get_http(), "https://example.com/tuning.json");
+/// let speed = config.get_f32("enemy_speed", 120.0);
+/// if config.is_enabled("new_shop") { ... }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RemoteConfig {
+    defaults: BTreeMap<String, ConfigValue>,
+    values: BTreeMap<String, ConfigValue>,
+    cache_path: Option<PathBuf>,
+    events: Vec<ConfigEvent>,
+    #[cfg(feature = "http")]
+    pending: Option<RequestId>,
+}
+
+impl RemoteConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from bundled defaults
+    pub fn with_defaults(defaults: BTreeMap<String, ConfigValue>) -> Self {
+        Self { values: defaults.clone(), defaults, ..Self::default() }
+    }
+
+    /// Start from bundled defaults in a RON or JSON string
+    pub fn from_defaults_str(data: &str) -> Result<Self, RemoteConfigError> {
+        Ok(Self::with_defaults(parse(data)?))
+    }
+
+    /// Keep fetched values in `path`, and apply the ones cached there now (a missing or
+    /// unreadable cache leaves the defaults in place)
+    pub fn with_cache<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        if let Ok(cached) = std::fs::read_to_string(&path).map_err(RemoteConfigError::from).and_then(|data| parse(&data)) {
+            self.apply(cached);
+        }
+        self.cache_path = Some(path);
+        self
+    }
+
+    /// Apply values from a local file, e.g. a tuning file for playtests
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), RemoteConfigError> {
+        let data = std::fs::read_to_string(path)?;
+        self.load_from_str(&data)
+    }
+
+    /// Apply values from a RON or JSON string
+    pub fn load_from_str(&mut self, data: &str) -> Result<(), RemoteConfigError> {
+        let values = parse(data)?;
+        self.apply(values);
+        Ok(())
+    }
+
+    /// Current values written as RON
+    pub fn to_ron_string(&self) -> Result<String, RemoteConfigError> {
+        ron::ser::to_string_pretty(&self.values, ron::ser::PrettyConfig::new())
+            .map_err(|err| RemoteConfigError::Format(err.to_string()))
+    }
+
+    /// Write current values to the cache path, if there is one
+    pub fn save_cache(&self) -> Result<(), RemoteConfigError> {
+        if let Some(path) = &self.cache_path {
+            std::fs::write(path, self.to_ron_string()?)?;
+        }
+        Ok(())
+    }
+
+    /// Go back to the bundled defaults (the cache file is left alone)
+    pub fn reset(&mut self) {
+        self.apply(self.defaults.clone());
+        let defaults = &self.defaults;
+        self.values.retain(|key, _| defaults.contains_key(key));
+    }
+
+    /// Set one value, e.g. from a debug console
+    pub fn set(&mut self, key: &str, value: ConfigValue) {
+        self.apply(BTreeMap::from([(key.to_string(), value)]));
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ConfigValue> {
+        self.values.get(key)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// `fallback` when the key is missing or not a bool
+    pub fn get_bool(&self, key: &str, fallback: bool) -> bool {
+        self.get(key).and_then(ConfigValue::as_bool).unwrap_or(fallback)
+    }
+
+    pub fn get_i64(&self, key: &str, fallback: i64) -> i64 {
+        self.get(key).and_then(ConfigValue::as_i64).unwrap_or(fallback)
+    }
+
+    pub fn get_f32(&self, key: &str, fallback: f32) -> f32 {
+        self.get(key).and_then(ConfigValue::as_f64).map_or(fallback, |value| value as f32)
+    }
+
+    pub fn get_str<'a>(&'a self, key: &str, fallback: &'a str) -> &'a str {
+        self.get(key).and_then(ConfigValue::as_str).unwrap_or(fallback)
+    }
+
+    /// Feature flag check; unknown flags are off
+    pub fn is_enabled(&self, flag: &str) -> bool {
+        self.get_bool(flag, false)
+    }
+
+    /// Changes and fetch results since the last call
+    pub fn take_events(&mut self) -> Vec<ConfigEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Request values from `url`; they're applied by `poll` once they arrive
+    #[cfg(feature = "http")]
+    pub fn fetch(&mut self, http: &HttpClient, url: &str) {
+        if let Some(previous) = self.pending.take() {
+            http.cancel(previous);
+        }
+        let request = HttpRequest::get(url).with_retries(2, 1.0).with_tag("remote_config");
+        self.pending = Some(http.send(request));
+    }
+
+    #[cfg(feature = "http")]
+    pub fn is_fetching(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Apply a finished fetch and cache its values (`Game` calls this every frame for
+    /// the config set with `Game::set_remote_config`)
+    #[cfg(feature = "http")]
+    pub fn poll(&mut self, http: &HttpClient) {
+        let Some(result) = self.pending.and_then(|id| http.take_result(id)) else {
+            return;
+        };
+        self.pending = None;
+        match result.map_err(|err| err.to_string()).and_then(|body| parse(&body).map_err(|err| err.to_string())) {
+            Ok(values) => {
+                self.apply(values);
+                if let Err(err) = self.save_cache() {
+                    crate::log_warn!("config", "failed to cache remote config: {}", err);
+                }
+                self.events.push(ConfigEvent::Fetched);
+            }
+            Err(err) => self.events.push(ConfigEvent::FetchFailed(err)),
+        }
+    }
+
+    fn apply(&mut self, values: BTreeMap<String, ConfigValue>) {
+        for (key, value) in values {
+            if self.values.get(&key) == Some(&value) {
+                continue;
+            }
+            let old = self.values.insert(key.clone(), value.clone());
+            self.events.push(ConfigEvent::Changed { key, old, new: value });
+        }
+    }
+}
+
+fn parse(data: &str) -> Result<BTreeMap<String, ConfigValue>, RemoteConfigError> {
+    ron::from_str(data).map_err(|err| RemoteConfigError::Format(err.to_string()))
+}
@@ -0,0 +1,76 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+/// Global game state stored by type, one value per type (score, settings, a shared RNG).
+///
+/// `Game` owns one and hands it to entities through `UpdateContext`:
+///
+/// ```ignore
+/// struct Score(u32);
+/// game.insert_resource(Score(0));
+///
+/// impl Entity for Coin {
+///     fn update(&mut self, ctx: &mut UpdateContext) {
+///         if self.collected {
+///             if let Some(score) = ctx.resources.get_mut::<Score>() {
+///                 score.0 += 1;
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[derive(Default)]
+pub struct Resources {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Resources {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a resource, returning the one of the same type it replaced
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values.insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(|old| old.downcast().ok())
+            .map(|old| *old)
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast().ok())
+            .map(|value| *value)
+    }
+
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>())?.downcast_mut()
+    }
+
+    /// The resource of type `T`, inserting `T::default()` first if there is none
+    pub fn get_or_default<T: Default + 'static>(&mut self) -> &mut T {
+        self.values.entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(T::default()))
+            .downcast_mut()
+            .expect("resource stored under the wrong type")
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
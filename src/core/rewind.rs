@@ -0,0 +1,218 @@
+use macroquad::prelude::*;
+use std::collections::{HashSet, VecDeque};
+use super::{Entity, EntityId};
+
+/// What rewinding records and restores for each entity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RewindState {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub scale: Vec2,
+    /// From `Entity::animation_frame`
+    pub animation_frame: Option<u32>,
+}
+
+impl RewindState {
+    fn capture(entity: &dyn Entity) -> Option<Self> {
+        let transform = entity.get_transform()?;
+        Some(Self {
+            position: transform.position,
+            rotation: transform.rotation,
+            scale: transform.scale,
+            animation_frame: entity.animation_frame(),
+        })
+    }
+
+    pub(crate) fn restore(&self, entity: &mut dyn Entity) {
+        if let Some(transform) = entity.get_transform_mut() {
+            transform.position = self.position;
+            transform.rotation = self.rotation;
+            transform.scale = self.scale;
+        }
+        if let Some(frame) = self.animation_frame {
+            entity.set_animation_frame(frame);
+        }
+    }
+}
+
+/// How much history a scene keeps for rewinding
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RewindSettings {
+    /// Seconds of history kept
+    pub seconds: f32,
+    /// Most bytes of history kept; the oldest frames are dropped first
+    pub memory_budget: usize,
+    /// Playback speed (2.0 = rewind twice as fast as the game ran)
+    pub speed: f32,
+}
+
+impl Default for RewindSettings {
+    fn default() -> Self {
+        Self { seconds: 5.0, memory_budget: 1024 * 1024, speed: 1.0 }
+    }
+}
+
+impl RewindSettings {
+    pub fn new(seconds: f32) -> Self {
+        Self { seconds, ..Default::default() }
+    }
+
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed.max(0.0);
+        self
+    }
+}
+
+/// The tracked entities' states after one update
+struct RewindFrame {
+    dt: f32,
+    states: Vec<(EntityId, RewindState)>,
+}
+
+impl RewindFrame {
+    fn bytes(&self) -> usize {
+        std::mem::size_of::<Self>() + self.states.capacity() * std::mem::size_of::<(EntityId, RewindState)>()
+    }
+}
+
+/// A scene's rewind history: a ring buffer of frames for the entities that opted in
+#[derive(Default)]
+pub(crate) struct Rewind {
+    settings: Option<RewindSettings>,
+    tracked: HashSet<EntityId>,
+    frames: VecDeque<RewindFrame>,
+    // Seconds and bytes held in `frames`
+    duration: f32,
+    bytes: usize,
+    rewinding: bool,
+    // Playback time not yet spent on a whole frame
+    pending: f32,
+}
+
+impl Rewind {
+    pub(crate) fn set_settings(&mut self, settings: Option<RewindSettings>) {
+        if settings.is_none() {
+            self.clear();
+            self.rewinding = false;
+        }
+        self.settings = settings;
+        self.trim();
+    }
+
+    pub(crate) fn settings(&self) -> Option<&RewindSettings> {
+        self.settings.as_ref()
+    }
+
+    pub(crate) fn set_tracked(&mut self, id: EntityId, tracked: bool) {
+        if tracked {
+            self.tracked.insert(id);
+        } else {
+            self.forget_entity(id);
+        }
+    }
+
+    pub(crate) fn is_tracked(&self, id: EntityId) -> bool {
+        self.tracked.contains(&id)
+    }
+
+    pub(crate) fn forget_entity(&mut self, id: EntityId) {
+        self.tracked.remove(&id);
+    }
+
+    pub(crate) fn start(&mut self) {
+        self.rewinding = self.settings.is_some();
+        self.pending = 0.0;
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.rewinding = false;
+    }
+
+    pub(crate) fn is_rewinding(&self) -> bool {
+        self.rewinding
+    }
+
+    pub(crate) fn available(&self) -> f32 {
+        self.duration
+    }
+
+    pub(crate) fn memory_used(&self) -> usize {
+        self.bytes
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.frames.clear();
+        self.duration = 0.0;
+        self.bytes = 0;
+    }
+
+    /// Forget every entity and the history, keeping the settings
+    pub(crate) fn reset(&mut self) {
+        self.tracked.clear();
+        self.clear();
+        self.rewinding = false;
+    }
+
+    /// Record the tracked entities after an update
+    pub(crate) fn record<'a>(&mut self, dt: f32, entities: impl Iterator<Item = (&'a EntityId, &'a Box<dyn Entity>)>) {
+        if self.settings.is_none() || self.tracked.is_empty() || dt <= 0.0 {
+            return;
+        }
+        let states = entities
+            .filter(|(id, _)| self.tracked.contains(id))
+            .filter_map(|(id, entity)| RewindState::capture(entity.as_ref()).map(|state| (*id, state)))
+            .collect();
+        let frame = RewindFrame { dt, states };
+        self.duration += frame.dt;
+        self.bytes += frame.bytes();
+        self.frames.push_back(frame);
+        self.trim();
+    }
+
+    /// Play history backwards by `dt`, returning the states to restore (None when no whole
+    /// frame has passed yet). Stops rewinding once the history runs out.
+    pub(crate) fn step_back(&mut self, dt: f32) -> Option<Vec<(EntityId, RewindState)>> {
+        let speed = self.settings.map_or(1.0, |settings| settings.speed);
+        self.pending += dt * speed;
+        let mut restored = None;
+        while let Some(frame) = self.frames.back() {
+            if frame.dt > self.pending {
+                break;
+            }
+            self.pending -= frame.dt;
+            restored = self.pop_back().map(|frame| frame.states);
+        }
+        if self.frames.is_empty() {
+            self.rewinding = false;
+        }
+        restored
+    }
+
+    fn pop_back(&mut self) -> Option<RewindFrame> {
+        let frame = self.frames.pop_back()?;
+        self.duration -= frame.dt;
+        self.bytes -= frame.bytes();
+        Some(frame)
+    }
+
+    fn trim(&mut self) {
+        let Some(settings) = self.settings else {
+            return;
+        };
+        while self.duration > settings.seconds || self.bytes > settings.memory_budget {
+            let Some(frame) = self.frames.pop_front() else {
+                break;
+            };
+            self.duration -= frame.dt;
+            self.bytes -= frame.bytes();
+        }
+        if self.frames.is_empty() {
+            self.duration = 0.0;
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use macroquad::prelude::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::f32::consts::TAU;
+
+thread_local! {
+    // Engine systems without access to resources (particles, sound events, prefab jitter)
+    // draw from this one instead of macroquad's global `rand`. It starts from the clock, so
+    // runs differ, until deterministic mode seeds it.
+    static SHARED: RefCell<Rng> = RefCell::new(Rng::new(clock_seed()));
+}
+
+/// A seed that differs from run to run
+fn clock_seed() -> u64 {
+    (macroquad::miniquad::date::now() * 1_000_000.0) as u64
+}
+
+/// Run `f` with the engine's shared `Rng`
+pub fn with_shared<R>(f: impl FnOnce(&mut Rng) -> R) -> R {
+    SHARED.with(|shared| f(&mut shared.borrow_mut()))
+}
+
+/// Restart the engine's shared `Rng` from `seed` (done for `GameConfig::deterministic_seed`)
+pub fn seed_shared(seed: u64) {
+    with_shared(|rng| rng.reseed(seed));
+}
+
+/// Seeded random numbers that come out the same on every platform and run, so gameplay
+/// using them replays identically. With `GameConfig::deterministic_seed` the game puts
+/// one in its resources:
+///
+/// ```ignore
+/// let rng = ctx.resources.get_mut::<Rng>().unwrap();
+/// if rng.chance(0.25) {
+///     self.fire(rng.unit_vec2());
+/// }
+/// ```
+///
+/// It serializes with its position in the sequence, so a saved game continues the same
+/// rolls after loading.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { seed, state: seed }
+    }
+
+    /// The seed it started from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Start the sequence over from `seed`
+    pub fn reseed(&mut self, seed: u64) {
+        *self = Self::new(seed);
+    }
+
+    // SplitMix64: one word of state, so it's cheap to copy into snapshots
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    /// In [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// In [min, max), or `min` if the range is empty
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        if max > min { min + self.next_f32() * (max - min) } else { min }
+    }
+
+    /// In [min, max), or `min` if the range is empty
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        if max > min { (min as i64 + (self.next_u64() % (max as i64 - min as i64) as u64) as i64) as i32 } else { min }
+    }
+
+    /// An index into a collection of `len` items (0 if it's empty)
+    pub fn index(&mut self, len: usize) -> usize {
+        if len > 0 { (self.next_u64() % len as u64) as usize } else { 0 }
+    }
+
+    /// True with probability `p` (0 never, 1 always)
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    /// A random item (None if `items` is empty)
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() { None } else { items.get(self.index(items.len())) }
+    }
+
+    /// An angle in radians, in [0, TAU)
+    pub fn angle(&mut self) -> f32 {
+        self.next_f32() * TAU
+    }
+
+    /// A direction of length 1
+    pub fn unit_vec2(&mut self) -> Vec2 {
+        Vec2::from_angle(self.angle())
+    }
+
+    /// A point spread evenly over a circle of `radius` around the origin
+    pub fn point_in_circle(&mut self, radius: f32) -> Vec2 {
+        self.unit_vec2() * radius * self.next_f32().sqrt()
+    }
+
+    /// A point in the rectangle from `min` to `max`
+    pub fn point_in_rect(&mut self, min: Vec2, max: Vec2) -> Vec2 {
+        vec2(self.range(min.x, max.x), self.range(min.y, max.y))
+    }
+}
@@ -1,101 +1,1263 @@
 // src/scene.rs
-use super::Entity;
-use crate::input::InputManager;
-use crate::rendering::Camera;
-use macroquad::prelude::Vec2;
+use super::{Constraint, Entity, EntityId, MotionSettings};
+use super::component::{ComponentStore, Mut, Query, QueryFilter};
+use super::draw_order::{CullingStats, DrawOrder, DrawOrderEntry};
+use super::arena::{Arena, ArenaKey};
+use super::pending::PendingEntities;
+use super::panic_screen::{self, EntityPanic};
+use super::pool::{EntityPools, PoolStats};
+use super::ownership::{OwnerRemoval, Ownership};
+use super::relations::{Relation, Relations};
+use super::portal::{Portal, PortalEvent, PortalId, PortalTarget, Portals};
+use super::rewind::{Rewind, RewindSettings};
+use super::resources::Resources;
+use super::context::{Command, Commands, Events, LifecycleContext, UpdateContext};
+use super::headless;
+use super::time::TimeScales;
+use super::timeline::{ActiveTimeline, ScriptAction, ScriptEvent, Timeline};
+use super::scene_file::{EntityRegistry, SceneFile, SceneFileError};
+use crate::audio::SceneAudio;
+use crate::input::{InputManager, PlayerInputs};
+use crate::physics::{PhysicsDebug, Quadtree};
+use crate::rendering::{split_screen_viewports, Camera, CameraTarget, Lod, LodLevel, LodSettings, SpriteBatch, SpriteBatchStats};
+use macroquad::prelude::{Rect, Vec2};
+use std::any::Any;
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::{HashMap, HashSet};
+
+/// `Entity::on_added` or `on_removed`
+type LifecycleHook = fn(&mut (dyn Entity + 'static), &mut LifecycleContext);
+
 /// A scene is a collection of entities with lifecycle management
 pub struct Scene {
-    entities: Vec<Box<dyn Entity>>,
-    entities_to_add: Vec<Box<dyn Entity>>,
+    // Slots reused after removals; ids never are, so `entity_index` maps them to slots
+    entities: Arena<(EntityId, Box<dyn Entity>)>,
+    entity_index: HashMap<EntityId, ArenaKey>,
+    entities_to_add: PendingEntities,
+    next_entity_id: u64,
+    // Boxes of deactivated pooled entities, reused by `spawn_pooled`
+    pools: EntityPools,
+    constraints: Vec<(EntityId, Constraint)>,
+    portals: Portals,
+    timelines: Vec<ActiveTimeline>,
+    rewind: Rewind,
+    ownership: Ownership,
+    relations: Relations,
+    events: Events,
+    // Seconds of unpaused updates
+    elapsed: f32,
+    script_events: Vec<ScriptEvent>,
+    // Sounds timelines asked for, played by `Game` through its `AudioManager`
+    script_sounds: Vec<(String, Option<Vec2>)>,
     should_clear_inactive: bool,
+    components: ComponentStore,
+    // Entities skipped by update and draw (e.g. after their update panicked)
+    disabled: HashSet<EntityId>,
+    // Disabled because their update panicked; only `enable_entity` brings them back
+    panicked: HashSet<EntityId>,
+    panics: Vec<EntityPanic>,
+    time_scales: TimeScales,
+    // Only entities with `updates_while_paused` update
+    paused: bool,
+    // Data handed over when the scene was pushed, its own result, and results of scenes popped above it
+    payload: Option<Box<dyn Any>>,
+    result: Option<Box<dyn Any>>,
+    returned: Vec<Box<dyn Any>>,
     pub camera: Camera,
+    // Split-screen views; when there are any, they draw the scene instead of `camera`
+    cameras: Vec<Camera>,
+    lod: Option<LodSettings>,
+    // Skip entities whose bounds are outside the view being drawn
+    culling: bool,
+    // Bounds of active entities as of the last update, built while culling is on, so
+    // drawing a view only visits what's in it; entities without bounds are listed apart
+    spatial: Quadtree<ArenaKey>,
+    unbounded: Vec<ArenaKey>,
+    culling_stats: Cell<CullingStats>,
+    // Sprites queued by entities while drawing, submitted grouped by texture
+    sprite_batching: bool,
+    sprite_batch: RefCell<SpriteBatch>,
+    physics_debug: PhysicsDebug,
+    // Last applied to the cameras and portals, and to views added since
+    motion: MotionSettings,
+    audio: SceneAudio,
+    // Seconds spent on physics since the game last read it
+    physics_time: Cell<f64>,
 }
 
 impl Scene {
     pub fn new() -> Self {
         Self {
-            entities: vec![],
-            entities_to_add: vec![],
+            entities: Arena::new(),
+            entity_index: HashMap::new(),
+            entities_to_add: PendingEntities::default(),
+            next_entity_id: 0,
+            pools: EntityPools::default(),
+            constraints: vec![],
+            portals: Portals::default(),
+            timelines: vec![],
+            rewind: Rewind::default(),
+            ownership: Ownership::default(),
+            relations: Relations::default(),
+            events: Events::new(),
+            elapsed: 0.0,
+            script_events: vec![],
+            script_sounds: vec![],
             should_clear_inactive: false,
+            components: ComponentStore::new(),
+            disabled: HashSet::new(),
+            panicked: HashSet::new(),
+            panics: vec![],
+            time_scales: TimeScales::default(),
+            paused: false,
+            payload: None,
+            result: None,
+            returned: vec![],
             camera: Camera::new(),
+            cameras: vec![],
+            lod: None,
+            culling: false,
+            spatial: Quadtree::new(Rect::default()),
+            unbounded: vec![],
+            culling_stats: Cell::new(CullingStats::default()),
+            sprite_batching: false,
+            sprite_batch: RefCell::new(SpriteBatch::new()),
+            physics_debug: PhysicsDebug::new(),
+            motion: MotionSettings::default(),
+            physics_time: Cell::new(0.0),
+            audio: SceneAudio::default(),
         }
     }
 
     /// Add an entity to the scene (will be added on next update)
-    pub fn add_entity(&mut self, entity: Box<dyn Entity>) {
-        self.entities_to_add.push(entity);
+    pub fn add_entity(&mut self, entity: Box<dyn Entity>) -> EntityId {
+        let id = EntityId(self.next_entity_id);
+        self.next_entity_id += 1;
+        self.entities_to_add.push(id, entity);
+        id
     }
 
-    /// Update all active entities
-    pub fn update(&mut self, dt: f32) {
-        // Add new entities
-        self.entities.extend(self.entities_to_add.drain(..));
-        
-        // Update active entities
-        for entity in self.entities.iter_mut() {
-            if entity.is_active() {
-                entity.update(dt);
+    /// Add an entity through its type's pool: it reuses the box of a deactivated one when
+    /// there is one, and goes back to the pool (instead of being freed) at the end of the
+    /// update it's found inactive in, without waiting for `clear_inactive`.
+    /// For bullets and other short-lived entities spawned many times a second:
+    ///
+    /// ```ignore
+    /// scene.add_pool::<Bullet>(1024);
+    /// scene.spawn_pooled(Bullet::new(position, velocity));
+    /// ```
+    pub fn spawn_pooled<E: Entity>(&mut self, entity: E) -> EntityId {
+        let id = EntityId(self.next_entity_id);
+        self.next_entity_id += 1;
+        let entity = self.pools.spawn(id, entity);
+        self.entities_to_add.push(id, entity);
+        id
+    }
+
+    /// Keep up to `capacity` deactivated entities of type `E` for `spawn_pooled`
+    /// (`DEFAULT_POOL_CAPACITY` if never set)
+    pub fn add_pool<E: Entity>(&mut self, capacity: usize) {
+        self.pools.pool_mut::<E>().set_capacity(capacity);
+    }
+
+    /// Fill `E`'s pool with `count` entities up front, so the first waves don't allocate
+    pub fn prewarm_pool<E: Entity>(&mut self, count: usize, mut make: impl FnMut() -> E) {
+        self.pools.pool_mut::<E>().prewarm(count, || Box::new(make()));
+    }
+
+    /// Stats of `E`'s pool, if it has one
+    pub fn pool_stats<E: Entity>(&self) -> Option<PoolStats> {
+        self.pools.stats::<E>()
+    }
+
+    /// Stats of every entity pool by type name, for tuning their capacities
+    pub fn all_pool_stats(&self) -> Vec<(&'static str, PoolStats)> {
+        self.pools.all_stats()
+    }
+
+    /// Remove an entity right away, returning it. This doesn't depend on how many entities
+    /// there are; constraints and timelines of removed entities are dropped on the next
+    /// update, and ignored until then.
+    pub fn remove_entity(&mut self, id: EntityId) -> Option<Box<dyn Entity>> {
+        self.remove_entity_with(id, None)
+    }
+
+    /// `remove_entity`, giving `on_removed` the update's resources when there are some
+    fn remove_entity_with(&mut self, id: EntityId, mut resources: Option<&mut Resources>) -> Option<Box<dyn Entity>> {
+        self.components.remove_entity(id);
+        self.disabled.remove(&id);
+        self.panicked.remove(&id);
+        self.time_scales.clear_entity(id);
+        self.portals.forget_entity(id);
+        self.rewind.forget_entity(id);
+        self.relations.forget_entity(id);
+        let owned = self.ownership.forget_entity(id);
+        if let Some(entity) = self.entities_to_add.remove(id) {
+            self.pools.forget(id, entity.as_ref());
+            self.despawn_owned(owned, resources);
+            return Some(entity);
+        }
+
+        let key = self.entity_index.remove(&id)?;
+        let (_, mut entity) = self.entities.remove(key)?;
+        let mut commands = Commands::new(self.next_entity_id);
+        self.run_lifecycle_hook(id, entity.as_mut(), resources.as_deref_mut(), &mut commands, Entity::on_removed);
+        self.apply_commands(commands, resources.as_deref_mut());
+        self.despawn_owned(owned, resources);
+        self.pools.forget(id, entity.as_ref());
+        Some(entity)
+    }
+
+    /// Remove entities that go with their removed owner
+    fn despawn_owned(&mut self, owned: Vec<EntityId>, mut resources: Option<&mut Resources>) {
+        for id in owned {
+            self.remove_entity_with(id, resources.as_deref_mut());
+        }
+    }
+
+    /// Get an entity by id (including ones added this frame)
+    pub fn get_entity(&self, id: EntityId) -> Option<&dyn Entity> {
+        match self.entity_index.get(&id).and_then(|key| self.entities.get(*key)) {
+            Some((_, entity)) => Some(entity.as_ref()),
+            None => self.entities_to_add.get(id),
+        }
+    }
+
+    /// Get mutable access to an entity by id (including ones added this frame)
+    pub fn get_entity_mut(&mut self, id: EntityId) -> Option<&mut (dyn Entity + 'static)> {
+        match self.entity_index.get(&id).and_then(|key| self.entities.get_mut(*key)) {
+            Some((_, entity)) => Some(entity.as_mut()),
+            None => self.entities_to_add.get_mut(id),
+        }
+    }
+
+    /// Check if an entity is still in the scene
+    pub fn contains_entity(&self, id: EntityId) -> bool {
+        self.get_entity(id).is_some()
+    }
+
+    /// Position of an entity with a transform
+    pub fn entity_position(&self, id: EntityId) -> Option<Vec2> {
+        self.get_entity(id)?.get_transform().map(|transform| transform.position)
+    }
+
+    /// Ids of all entities, in update order
+    pub fn entity_ids(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.entities.values().map(|(id, _)| *id)
+    }
+
+    /// Skip an entity in update and draw without removing it
+    pub fn disable_entity(&mut self, id: EntityId) {
+        self.disabled.insert(id);
+    }
+
+    /// Update and draw an entity again, including one disabled after its update panicked
+    pub fn enable_entity(&mut self, id: EntityId) {
+        self.disabled.remove(&id);
+        self.panicked.remove(&id);
+    }
+
+    pub fn is_entity_disabled(&self, id: EntityId) -> bool {
+        self.disabled.contains(&id)
+    }
+
+    /// Panics caught in entity updates since the last call (see `Game::set_catch_panics`).
+    /// Each offending entity has already been disabled.
+    pub fn take_panics(&mut self) -> Vec<EntityPanic> {
+        std::mem::take(&mut self.panics)
+    }
+
+    fn record_panics(&mut self, panics: Vec<EntityPanic>) {
+        for panic in panics {
+            self.disabled.insert(panic.entity);
+            self.panicked.insert(panic.entity);
+            self.panics.push(panic);
+        }
+    }
+
+    // === Components ===
+
+    /// Attach a component to an entity, returning the one of the same type it replaced
+    pub fn insert_component<T: 'static>(&mut self, id: EntityId, component: T) -> Option<T> {
+        self.components.insert(id, component)
+    }
+
+    pub fn remove_component<T: 'static>(&mut self, id: EntityId) -> Option<T> {
+        self.components.remove(id)
+    }
+
+    pub fn get_component<T: 'static>(&self, id: EntityId) -> Option<&T> {
+        self.components.get(id)
+    }
+
+    /// Mutable access that marks the component changed once it is written to
+    pub fn get_component_mut<T: 'static>(&mut self, id: EntityId) -> Option<Mut<'_, T>> {
+        self.components.get_mut(id)
+    }
+
+    pub fn has_component<T: 'static>(&self, id: EntityId) -> bool {
+        self.components.contains::<T>(id)
+    }
+
+    /// Check if an entity's `T` was added since the start of this frame's update
+    pub fn is_component_added<T: 'static>(&self, id: EntityId) -> bool {
+        self.components.is_added::<T>(id)
+    }
+
+    /// Check if an entity's `T` was added or written to since the start of this frame's update
+    pub fn is_component_changed<T: 'static>(&self, id: EntityId) -> bool {
+        self.components.is_changed::<T>(id)
+    }
+
+    /// Entities that lost their `T` this frame, including entities that were removed
+    pub fn removed_components<T: 'static>(&self) -> &[EntityId] {
+        self.components.removed::<T>()
+    }
+
+    /// Iterate over entities that have every component in `Q` and pass the filter `F`,
+    /// in update order. Use `()` as the filter to visit every match.
+    ///
+    /// ```ignore
+    /// for (id, (transform, mut velocity)) in scene.query::<(&Transform, &mut Velocity), With<EnemyTag>>() {
+    ///     velocity.0 = (player - transform.position).normalize() * 80.0;
+    /// }
+    /// // Only redraw health bars whose Health was written to this frame
+    /// for (id, health) in scene.query::<&Health, Changed<Health>>() { ... }
+    /// ```
+    ///
+    /// Panics if `Q` names the same component type twice.
+    pub fn query<Q: Query, F: QueryFilter>(&mut self) -> impl Iterator<Item = (EntityId, Q::Item<'_>)> {
+        let ids = self.entity_ids()
+            .chain(self.entities_to_add.ids())
+            .collect();
+        self.components.query::<Q, F>(ids)
+    }
+
+    // === Rewind ===
+
+    /// Record history for rewinding (None stops recording and drops the history)
+    pub fn set_rewind(&mut self, settings: Option<RewindSettings>) {
+        self.rewind.set_settings(settings);
+    }
+
+    pub fn rewind_settings(&self) -> Option<&RewindSettings> {
+        self.rewind.settings()
+    }
+
+    /// Opt an entity in or out of rewinding; only entities with a transform are recorded
+    pub fn set_rewindable(&mut self, id: EntityId, rewindable: bool) {
+        self.rewind.set_tracked(id, rewindable);
+    }
+
+    pub fn is_rewindable(&self, id: EntityId) -> bool {
+        self.rewind.is_tracked(id)
+    }
+
+    /// Play the recorded history backwards instead of updating entities, until
+    /// `stop_rewind` or the history runs out (Braid-style):
+    ///
+    /// ```ignore
+    /// scene.set_rewind(Some(RewindSettings::new(10.0).with_speed(2.0)));
+    /// scene.set_rewindable(player, true);
+    /// // each frame
+    /// if input.is_key_down(KeyCode::R) { scene.start_rewind() } else { scene.stop_rewind() }
+    /// ```
+    ///
+    /// Does nothing unless rewind settings are set.
+    pub fn start_rewind(&mut self) {
+        if !self.rewind.is_rewinding() {
+            self.rewind.start();
+        }
+    }
+
+    /// Resume normal updates from the rewound state
+    pub fn stop_rewind(&mut self) {
+        self.rewind.stop();
+    }
+
+    pub fn is_rewinding(&self) -> bool {
+        self.rewind.is_rewinding()
+    }
+
+    /// Seconds of history that can be rewound
+    pub fn rewind_available(&self) -> f32 {
+        self.rewind.available()
+    }
+
+    /// Approximate bytes the rewind history takes
+    pub fn rewind_memory_used(&self) -> usize {
+        self.rewind.memory_used()
+    }
+
+    /// Drop the recorded history (e.g. after a checkpoint)
+    pub fn clear_rewind_history(&mut self) {
+        self.rewind.clear();
+    }
+
+    // === Scene Payloads and Results ===
+
+    /// Hand data to the scene (usually through `SceneLayers::push_with`)
+    pub fn set_payload<T: 'static>(&mut self, payload: T) {
+        self.payload = Some(Box::new(payload));
+    }
+
+    /// The data the scene was pushed with, if it is a `T`
+    pub fn payload<T: 'static>(&self) -> Option<&T> {
+        self.payload.as_ref()?.downcast_ref()
+    }
+
+    pub fn take_payload<T: 'static>(&mut self) -> Option<T> {
+        let payload = self.payload.take()?;
+        match payload.downcast::<T>() {
+            Ok(payload) => Some(*payload),
+            Err(payload) => {
+                self.payload = Some(payload);
+                None
             }
         }
-        
-        // Remove inactive entities if needed
-        if self.should_clear_inactive {
-            self.entities.retain(|entity| entity.is_active());
-            self.should_clear_inactive = false;
+    }
+
+    /// Set the result handed to the scene below when this one is popped (e.g. a shop's purchases)
+    pub fn set_result<T: 'static>(&mut self, result: T) {
+        self.result = Some(Box::new(result));
+    }
+
+    /// Take a `T` returned by a scene that was popped off above this one
+    pub fn take_returned<T: 'static>(&mut self) -> Option<T> {
+        let index = self.returned.iter().position(|result| result.is::<T>())?;
+        self.returned.remove(index).downcast().ok().map(|result| *result)
+    }
+
+    pub(crate) fn take_result(&mut self) -> Option<Box<dyn Any>> {
+        self.result.take()
+    }
+
+    pub(crate) fn receive_result(&mut self, result: Box<dyn Any>) {
+        self.returned.push(result);
+    }
+
+    // === Constraints ===
+
+    /// Attach a constraint to an entity. Constraints run after every entity has updated,
+    /// in the order they were added, so chains (a moon orbiting an orbiting planet) work.
+    pub fn add_constraint(&mut self, entity: EntityId, constraint: Constraint) {
+        self.constraints.push((entity, constraint));
+    }
+
+    /// Make `entity` circle around `center`
+    pub fn orbit(&mut self, entity: EntityId, center: EntityId, radius: f32, angular_speed: f32) {
+        self.add_constraint(entity, Constraint::orbit(center, radius, angular_speed));
+    }
+
+    /// Make `entity` rotate to face `target`
+    pub fn look_at(&mut self, entity: EntityId, target: EntityId) {
+        self.add_constraint(entity, Constraint::look_at(target));
+    }
+
+    /// Keep `entity` at `target`'s position plus `offset`
+    pub fn copy_position(&mut self, entity: EntityId, target: EntityId, offset: Vec2) {
+        self.add_constraint(entity, Constraint::copy_position(target, offset));
+    }
+
+    /// Remove every constraint on an entity
+    pub fn remove_constraints(&mut self, entity: EntityId) {
+        self.constraints.retain(|(owner, _)| *owner != entity);
+    }
+
+    /// Constraints attached to an entity that's still in the scene
+    pub fn constraints_of(&self, entity: EntityId) -> impl Iterator<Item = &Constraint> {
+        let present = self.contains_entity(entity);
+        self.constraints.iter()
+            .filter(move |(owner, _)| present && *owner == entity)
+            .map(|(_, constraint)| constraint)
+    }
+
+    // === Ownership ===
+
+    /// Make `owner` own `entity`, e.g. a shooter and its projectile, replacing any previous
+    /// owner. `removal` says what happens to `entity` when the owner is removed. Returns
+    /// false if either entity isn't in the scene or `owner` is already owned by `entity`.
+    ///
+    /// ```ignore
+    /// let bullet = ctx.commands.spawn(Box::new(Bullet::new(position, aim)));
+    /// ctx.commands.set_owner(bullet, ctx.id, OwnerRemoval::Orphan);
+    /// // on hit, credit whoever is ultimately responsible
+    /// let scorer = scene.root_owner(bullet);
+    /// ```
+    pub fn set_owner(&mut self, entity: EntityId, owner: EntityId, removal: OwnerRemoval) -> bool {
+        if !self.contains_entity(entity) || !self.contains_entity(owner) {
+            return false;
         }
+        self.ownership.set(entity, owner, removal)
+    }
+
+    /// Unlink an entity from its owner
+    pub fn clear_owner(&mut self, entity: EntityId) {
+        self.ownership.clear(entity);
+    }
+
+    pub fn owner_of(&self, entity: EntityId) -> Option<EntityId> {
+        self.ownership.owner(entity)
+    }
+
+    /// What happens to `entity` when its owner is removed, if it has one
+    pub fn owner_removal(&self, entity: EntityId) -> Option<OwnerRemoval> {
+        self.ownership.removal(entity)
+    }
+
+    /// The top of the owner chain (the caster of a summon's projectile), or `entity` itself
+    /// if it has no owner. Use it to attribute damage and score.
+    pub fn root_owner(&self, entity: EntityId) -> EntityId {
+        self.ownership.root(entity)
+    }
+
+    /// Entities `owner` owns directly
+    pub fn owned_by(&self, owner: EntityId) -> &[EntityId] {
+        self.ownership.owned(owner)
+    }
+
+    // === Relations ===
+
+    /// Link `from` to `to`, e.g. an enemy targeting the player, replacing any link of the
+    /// same kind `from` had. Links go away when either entity is removed. Returns false
+    /// if either entity isn't in the scene, or they're the same entity.
+    ///
+    /// ```ignore
+    /// ctx.commands.relate(ctx.id, Relation::Targeting, player);
+    /// // threat indicators, and keeping attackers in the shot
+    /// let threats = scene.related_to(player, &Relation::Targeting).to_vec();
+    /// let framing = threats.iter().map(|id| CameraTarget::from(*id)).chain([player.into()]).collect();
+    /// scene.get_camera_mut().follow_targets(framing);
+    /// ```
+    pub fn relate(&mut self, from: EntityId, relation: Relation, to: EntityId) -> bool {
+        if from == to || !self.contains_entity(from) || !self.contains_entity(to) {
+            return false;
+        }
+        self.relations.set(from, relation, to);
+        true
+    }
+
+    pub fn unrelate(&mut self, from: EntityId, relation: &Relation) {
+        self.relations.clear(from, relation);
+    }
+
+    /// The entity `from` is linked to, e.g. what it's targeting
+    pub fn related(&self, from: EntityId, relation: &Relation) -> Option<EntityId> {
+        self.relations.get(from, relation)
+    }
+
+    /// Entities linked to `to`, e.g. everything targeting it, in the order they were linked
+    pub fn related_to(&self, to: EntityId, relation: &Relation) -> &[EntityId] {
+        self.relations.sources(to, relation)
+    }
+
+    /// Every link from an entity
+    pub fn relations_of(&self, from: EntityId) -> impl Iterator<Item = (&Relation, EntityId)> {
+        self.relations.of(from)
+    }
+
+    // Portals
+    
+    /// Add a door or portal (see `Portal`)
+    pub fn add_portal(&mut self, portal: Portal) -> PortalId {
+        self.portals.add(portal)
+    }
+    
+    /// Make two portals lead to each other
+    pub fn link_portals(&mut self, a: PortalId, b: PortalId) {
+        if let Some(portal) = self.portals.get_mut(a) {
+            portal.target = Some(PortalTarget::Portal(b));
+        }
+        if let Some(portal) = self.portals.get_mut(b) {
+            portal.target = Some(PortalTarget::Portal(a));
+        }
+    }
+    
+    pub fn remove_portal(&mut self, id: PortalId) -> Option<Portal> {
+        self.portals.remove(id)
     }
     
-    /// Update all active entities with input access
-    pub fn update_with_input(&mut self, dt: f32, input: &InputManager) {
+    pub fn get_portal(&self, id: PortalId) -> Option<&Portal> {
+        self.portals.get(id)
+    }
+    
+    pub fn get_portal_mut(&mut self, id: PortalId) -> Option<&mut Portal> {
+        self.portals.get_mut(id)
+    }
+    
+    pub fn portals(&self) -> impl Iterator<Item = (PortalId, &Portal)> {
+        self.portals.iter()
+    }
+    
+    /// Entries, teleports and scene change requests since the last call
+    pub fn take_portal_events(&mut self) -> Vec<PortalEvent> {
+        self.portals.take_events()
+    }
+    
+    /// Screen fade of portal transitions in progress (drawn by Game after the UI pass)
+    pub fn draw_transition(&self) {
+        self.portals.draw_transition();
+    }
+    
+    /// Run a timeline on an entity, replacing any it was already running
+    pub fn run_timeline(&mut self, entity: EntityId, timeline: Timeline) {
+        self.stop_timeline(entity);
+        self.timelines.push(ActiveTimeline::new(entity, timeline));
+    }
+
+    pub fn stop_timeline(&mut self, entity: EntityId) {
+        self.timelines.retain(|timeline| timeline.entity != entity);
+    }
+
+    pub fn has_timeline(&self, entity: EntityId) -> bool {
+        self.contains_entity(entity) && self.timelines.iter().any(|timeline| timeline.entity == entity)
+    }
+
+    /// Actions timelines ran since the last call
+    pub fn take_script_events(&mut self) -> Vec<ScriptEvent> {
+        std::mem::take(&mut self.script_events)
+    }
+
+    pub(crate) fn take_script_sounds(&mut self) -> Vec<(String, Option<Vec2>)> {
+        std::mem::take(&mut self.script_sounds)
+    }
+
+    /// Stop updating entities, constraints, portals and timelines; cameras, drawing and entities
+    /// with `updates_while_paused` carry on (see `Game::pause`)
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Scale the dt of one entity, e.g. 0.0 to freeze it or 2.0 to hurry it. Stacks with
+    /// the scales of its groups and the game's time scale.
+    pub fn set_entity_time_scale(&mut self, id: EntityId, scale: f32) {
+        self.time_scales.set_entity(id, scale);
+    }
+
+    pub fn clear_entity_time_scale(&mut self, id: EntityId) {
+        self.time_scales.clear_entity(id);
+    }
+
+    /// The entity's own time scale (1.0 if none was set)
+    pub fn entity_time_scale(&self, id: EntityId) -> f32 {
+        self.time_scales.entity(id)
+    }
+
+    /// Scale the dt of every entity with `tag`, e.g. bullet time that slows "enemy" and
+    /// "bullet" but not the player
+    pub fn set_group_time_scale(&mut self, tag: &str, scale: f32) {
+        self.time_scales.set_group(tag, scale);
+    }
+
+    pub fn clear_group_time_scale(&mut self, tag: &str) {
+        self.time_scales.clear_group(tag);
+    }
+
+    pub fn group_time_scale(&self, tag: &str) -> f32 {
+        self.time_scales.group(tag)
+    }
+
+    /// The scale applied to an entity's dt: its own times that of each of its groups
+    pub fn effective_time_scale(&self, id: EntityId) -> f32 {
+        self.get_entity(id).map_or(1.0, |entity| self.time_scales.scale_of(id, entity))
+    }
+
+    fn run_timelines(&mut self, dt: f32, resources: &mut Resources) {
+        let mut despawned = vec![];
+        let mut timelines = std::mem::take(&mut self.timelines);
+        timelines.retain_mut(|timeline| {
+            let id = timeline.entity;
+            let Some((_, entity)) = self.entity_index.get(&id).and_then(|key| self.entities.get_mut(*key)) else {
+                // Keep timelines of entities that are still waiting to be added
+                return self.entities_to_add.contains(id);
+            };
+            if self.disabled.contains(&id) {
+                return true;
+            }
+            let entity = entity.as_mut();
+            let Some(actions) = timeline.advance(dt * self.time_scales.scale_of(id, entity)) else {
+                return false;
+            };
+            let position = entity.get_transform().map(|transform| transform.position);
+            for action in actions {
+                entity.on_script_action(&action);
+                match &action {
+                    ScriptAction::PlaySound(sound) => self.script_sounds.push((sound.clone(), position)),
+                    ScriptAction::Despawn => despawned.push(id),
+                    _ => {}
+                }
+                self.script_events.push(ScriptEvent { entity: id, action, position });
+            }
+            true
+        });
+        self.timelines = timelines;
+        for id in despawned {
+            self.remove_entity_with(id, Some(&mut *resources));
+        }
+    }
+
+    fn step_rewind(&mut self, dt: f32) {
+        let Some(states) = self.rewind.step_back(dt) else {
+            return;
+        };
+        for (id, state) in states {
+            // Entities removed since the frame was recorded stay gone
+            if let Some((_, entity)) = self.entity_index.get(&id).and_then(|key| self.entities.get_mut(*key)) {
+                state.restore(entity.as_mut());
+            }
+        }
+    }
+
+    fn update_portals(&mut self, dt: f32) {
+        let mut portals = std::mem::take(&mut self.portals);
+        portals.update(dt, self);
+        self.portals = portals;
+    }
+
+    fn apply_constraints(&mut self, dt: f32) {
+        // Constraints whose entities are gone are dropped
+        let entity_index = &self.entity_index;
+        self.constraints.retain(|(owner, constraint)| {
+            entity_index.contains_key(owner) && entity_index.contains_key(&constraint.target())
+        });
+
+        for (owner, constraint) in self.constraints.iter_mut() {
+            let target_key = self.entity_index[&constraint.target()];
+            let owner_key = self.entity_index[owner];
+            let Some(target) = self.entities.get(target_key).and_then(|(_, target)| target.get_transform()).map(|t| t.position) else {
+                continue;
+            };
+            let Some((_, entity)) = self.entities.get_mut(owner_key) else {
+                continue;
+            };
+            // Attachments move on their owner's clock
+            let dt = dt * self.time_scales.scale_of(*owner, entity.as_ref());
+            if let Some(transform) = entity.get_transform_mut() {
+                let (position, rotation) = constraint.solve(dt, transform.position, transform.rotation, target);
+                transform.position = position;
+                transform.rotation = rotation;
+            }
+        }
+    }
+
+    /// Add pending entities, running their `on_added` hooks. Entities spawned by the hooks
+    /// are added on the next update.
+    fn flush_pending_entities(&mut self, resources: &mut Resources) {
+        let pending = self.entities_to_add.drain();
+        let mut commands = Commands::new(self.next_entity_id);
+        for (id, mut entity) in pending {
+            self.run_lifecycle_hook(id, entity.as_mut(), Some(&mut *resources), &mut commands, Entity::on_added);
+            let key = self.entities.insert((id, entity));
+            self.entity_index.insert(id, key);
+        }
+        self.apply_commands(commands, Some(resources));
+    }
+
+    /// Remove inactive entities, or only the pooled ones (which go back to their pools)
+    fn remove_inactive_entities(&mut self, resources: &mut Resources, pooled_only: bool) {
+        let mut inactive: Vec<_> = if pooled_only {
+            self.pools.pooled()
+                .filter_map(|id| self.entity_index.get(&id).copied())
+                .filter(|key| self.entities.get(*key).is_some_and(|(_, entity)| !entity.is_active()))
+                .collect()
+        } else {
+            self.entities.iter()
+                .filter(|(_, (_, entity))| !entity.is_active())
+                .map(|(key, _)| key)
+                .collect()
+        };
+        // Slot order, so freed slots are reused the same way every run
+        inactive.sort();
+        let mut commands = Commands::new(self.next_entity_id);
+        let mut owned = vec![];
+        for key in inactive {
+            // Removing frees the slot without moving the other entities
+            if let Some((id, mut entity)) = self.entities.remove(key) {
+                self.entity_index.remove(&id);
+                self.components.remove_entity(id);
+                self.disabled.remove(&id);
+                self.panicked.remove(&id);
+                self.time_scales.clear_entity(id);
+                self.portals.forget_entity(id);
+                self.rewind.forget_entity(id);
+                self.relations.forget_entity(id);
+                owned.extend(self.ownership.forget_entity(id));
+                self.run_lifecycle_hook(id, entity.as_mut(), Some(&mut *resources), &mut commands, Entity::on_removed);
+                self.pools.recycle(id, entity);
+            }
+        }
+        self.apply_commands(commands, Some(&mut *resources));
+        self.despawn_owned(owned, Some(resources));
+    }
+
+    /// Run `on_added` or `on_removed` on an entity that's out of the entity list,
+    /// catching panics like updates do
+    fn run_lifecycle_hook(
+        &mut self,
+        id: EntityId,
+        entity: &mut (dyn Entity + 'static),
+        resources: Option<&mut Resources>,
+        commands: &mut Commands,
+        hook: LifecycleHook,
+    ) {
+        let mut ctx = LifecycleContext {
+            id,
+            elapsed: self.elapsed,
+            resources,
+            events: &mut self.events,
+            commands,
+        };
+        let panic = panic_screen::guard(id, || hook(entity, &mut ctx));
+        self.record_panics(panic.into_iter().collect());
+    }
+
+    /// Update all active entities
+    pub fn update(&mut self, dt: f32, input: &InputManager) {
+        self.update_with_resources(dt, input, None, &mut Resources::new());
+    }
+
+    /// Update all active entities, giving each the input of its player slot
+    /// (entities without a slot, or with an unknown one, get `input`)
+    pub fn update_with_players(&mut self, dt: f32, input: &InputManager, players: &PlayerInputs) {
+        self.update_with_resources(dt, input, Some(players), &mut Resources::new());
+    }
+
+    /// Update all active entities with input and shared resources (see `UpdateContext`).
+    /// With `players`, each entity gets the input of its player slot.
+    ///
+    /// Entities update (and `query` visits them) in slot order. Removed entities' slots are
+    /// reused by later ones, so the order only depends on the adds and removes before it,
+    /// and a deterministic game stays deterministic.
+    pub fn update_with_resources(&mut self, dt: f32, input: &InputManager, players: Option<&PlayerInputs>, resources: &mut Resources) {
+        // New frame for component change detection
+        self.components.begin_frame();
+        self.physics_debug.begin_frame();
+        self.culling_stats.take();
+        self.sprite_batch.get_mut().reset_stats();
+        self.events.swap();
+        
         // Add new entities
-        self.entities.extend(self.entities_to_add.drain(..));
+        self.flush_pending_entities(resources);
+        
+        // Rewinding replaces the frame's updates
+        if self.rewind.is_rewinding() {
+            self.step_rewind(dt);
+            self.rebuild_spatial_index();
+            return;
+        }
+        if !self.paused {
+            self.elapsed += dt;
+        }
         
-        // Update active entities with input
-        for entity in self.entities.iter_mut() {
-            if entity.is_active() {
-                entity.update_with_input(dt, input);
+        let mut panics = vec![];
+        let mut commands = Commands::new(self.next_entity_id);
+        for (id, entity) in self.entities.values_mut() {
+            if entity.is_active() && !self.disabled.contains(id) && (!self.paused || entity.updates_while_paused()) {
+                let entity_input = players
+                    .and_then(|players| entity.player_slot().and_then(|slot| players.input(slot)))
+                    .unwrap_or(input);
+                let mut ctx = UpdateContext {
+                    id: *id,
+                    dt: dt * self.time_scales.scale_of(*id, entity.as_ref()),
+                    elapsed: self.elapsed,
+                    input: entity_input,
+                    camera: &self.camera,
+                    resources: &mut *resources,
+                    events: &mut self.events,
+                    commands: &mut commands,
+                    physics_time: &self.physics_time,
+                };
+                panics.extend(panic_screen::guard(*id, || entity.update(&mut ctx)));
             }
         }
+        self.record_panics(panics);
+        self.apply_commands(commands, Some(&mut *resources));
         
-        // Remove inactive entities if needed
-        if self.should_clear_inactive {
-            self.entities.retain(|entity| entity.is_active());
-            self.should_clear_inactive = false;
+        // Attachments follow their targets' new positions
+        if !self.paused {
+            let physics_start = headless::now();
+            self.apply_constraints(dt);
+            self.update_portals(dt);
+            self.physics_time.set(self.physics_time.get() + headless::now() - physics_start);
+            self.run_timelines(dt, resources);
+            self.rewind.record(dt, self.entities.values().map(|(id, entity)| (id, entity)));
+        }
+        
+        // Remove inactive entities if asked to; inactive pooled ones go back every update
+        self.remove_inactive_entities(resources, !self.should_clear_inactive);
+        self.should_clear_inactive = false;
+        self.rebuild_spatial_index();
+    }
+
+    /// Index where active entities are for culling (nothing while culling is off)
+    fn rebuild_spatial_index(&mut self) {
+        self.unbounded.clear();
+        if !self.culling {
+            self.spatial.clear();
+            return;
+        }
+        let mut bounded = vec![];
+        for (key, (id, entity)) in self.entities.iter() {
+            if !entity.is_active() || self.disabled.contains(id) {
+                continue;
+            }
+            match entity.get_bounds() {
+                Some((position, size)) => bounded.push((Rect::new(position.x, position.y, size.x, size.y), key)),
+                None => self.unbounded.push(key),
+            }
+        }
+        self.spatial = Quadtree::from_items(&bounded);
+        if self.physics_debug.cells {
+            self.physics_debug.record_cells(self.spatial.cells());
         }
     }
 
-    /// Update only the camera (called by Game before drawing)
+    /// Apply what entities queued during the update, in order
+    fn apply_commands(&mut self, commands: Commands, mut resources: Option<&mut Resources>) {
+        let (queue, next_id) = commands.into_parts();
+        self.next_entity_id = next_id;
+        for command in queue {
+            match command {
+                Command::Spawn(id, entity) => self.entities_to_add.push(id, entity),
+                Command::Despawn(id) => {
+                    self.remove_entity_with(id, resources.as_deref_mut());
+                }
+                Command::SetEnabled(id, true) => {
+                    if !self.panicked.contains(&id) {
+                        self.enable_entity(id);
+                    }
+                }
+                Command::SetEnabled(id, false) => self.disable_entity(id),
+                Command::SetOwner(id, owner, removal) => {
+                    self.set_owner(id, owner, removal);
+                }
+                Command::Relate(from, relation, to) => {
+                    self.relate(from, relation, to);
+                }
+                Command::Unrelate(from, relation) => self.unrelate(from, &relation),
+            }
+        }
+    }
+
+    /// Gameplay events entities sent during the last update (see `Events`)
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    /// Send events from game code; entities read them during the next update
+    pub fn events_mut(&mut self) -> &mut Events {
+        &mut self.events
+    }
+
+    /// Update only the cameras (called by Game before drawing)
     pub fn update_camera(&mut self, dt: f32) {
-        self.camera.update(dt);
+        let mut camera = std::mem::take(&mut self.camera);
+        self.update_view(&mut camera, dt);
+        self.camera = camera;
+        
+        let mut cameras = std::mem::take(&mut self.cameras);
+        for camera in cameras.iter_mut() {
+            self.update_view(camera, dt);
+        }
+        self.cameras = cameras;
+    }
+    
+    /// Resolve a camera's entity targets, then update it
+    fn update_view(&self, camera: &mut Camera, dt: f32) {
+        if let Some(id) = camera.followed_entity() {
+            camera.set_follow_entity_position(self.entity_position(id));
+        }
+        if !camera.get_follow_targets().is_empty() {
+            let positions = camera.get_follow_targets().iter()
+                .filter_map(|target| match target {
+                    CameraTarget::Entity(id) => self.entity_position(*id),
+                    CameraTarget::Point(point) => Some(*point),
+                })
+                .collect();
+            camera.set_follow_target_positions(positions);
+        }
+        camera.update(dt);
+    }
+    
+    // Split-screen
+    
+    /// Add a split-screen view (give it a viewport with `Camera::with_viewport`).
+    /// Once the scene has views, it is drawn once per view instead of through `camera`.
+    pub fn add_camera(&mut self, mut camera: Camera) -> usize {
+        camera.set_motion_shake_scale(self.motion.effective_shake_scale());
+        self.cameras.push(camera);
+        self.cameras.len() - 1
+    }
+    
+    /// One view per entity, laid out with `split_screen_viewports` (2-4 players)
+    ///
+    /// ```ignore
+    /// scene.set_split_screen(&[player_one, player_two]);
+    /// ```
+    pub fn set_split_screen(&mut self, players: &[EntityId]) {
+        let shake_scale = self.motion.effective_shake_scale();
+        self.cameras = players.iter()
+            .zip(split_screen_viewports(players.len()))
+            .map(|(id, viewport)| {
+                let mut camera = Camera::new().with_viewport(viewport);
+                camera.set_motion_shake_scale(shake_scale);
+                camera.follow_entity(*id);
+                camera
+            })
+            .collect();
+    }
+    
+    pub fn cameras(&self) -> &[Camera] {
+        &self.cameras
+    }
+    
+    pub fn cameras_mut(&mut self) -> &mut [Camera] {
+        &mut self.cameras
+    }
+    
+    /// Back to drawing through `camera` alone
+    pub fn clear_cameras(&mut self) {
+        self.cameras.clear();
+    }
+    
+    /// Cameras the scene is drawn through, in order
+    pub fn views(&self) -> impl Iterator<Item = &Camera> {
+        let main = self.cameras.is_empty().then_some(&self.camera);
+        main.into_iter().chain(&self.cameras)
+    }
+
+    /// Order active entities are drawn in: by `Entity::draw_layer`, then `Entity::sort_key`,
+    /// then the order they were added in. Print it to see why an entity draws above or
+    /// below another.
+    pub fn draw_order(&self) -> DrawOrder {
+        DrawOrder::new(self.draw_list().into_iter().map(|(entry, _)| entry).collect())
+    }
+
+    /// Active entities sorted like `draw_order`
+    fn draw_list(&self) -> Vec<(DrawOrderEntry, &dyn Entity)> {
+        self.sorted_for_draw(self.entities.values())
+    }
+
+    /// Active entities in `camera`'s view, sorted like `draw_order`. With culling on, only
+    /// the ones the spatial index finds in the view are visited.
+    fn visible_list(&self, camera: &Camera) -> Vec<(DrawOrderEntry, &dyn Entity)> {
+        let visible = |entity: &dyn Entity| entity.get_bounds().is_none_or(|(position, size)| camera.is_rect_visible(position, size));
+        if !self.culling {
+            let all = self.sorted_for_draw(self.entities.values());
+            let total = all.len();
+            let list: Vec<_> = all.into_iter().filter(|(_, entity)| visible(*entity)).collect();
+            self.count_drawn(list.len(), total - list.len());
+            return list;
+        }
+        let (min, max) = camera.get_view_rect();
+        let mut keys = self.spatial.query(Rect::new(min.x, min.y, max.x - min.x, max.y - min.y));
+        keys.extend(&self.unbounded);
+        let list = self.sorted_for_draw(keys.into_iter().filter_map(|key| self.entities.get(key)));
+        let indexed = self.spatial.len() + self.unbounded.len();
+        self.count_drawn(list.len(), indexed.saturating_sub(list.len()));
+        list
+    }
+
+    fn sorted_for_draw<'a>(&self, entities: impl Iterator<Item = &'a (EntityId, Box<dyn Entity>)>) -> Vec<(DrawOrderEntry, &'a dyn Entity)> {
+        let mut list: Vec<_> = entities
+            .filter(|(id, entity)| entity.is_active() && !self.disabled.contains(id))
+            .map(|(id, entity)| {
+                let entry = DrawOrderEntry {
+                    entity: *id,
+                    layer: entity.draw_layer(),
+                    sort_key: entity.sort_key(),
+                    insertion: id.0 as usize,
+                };
+                (entry, entity.as_ref())
+            })
+            .collect();
+        list.sort_by_key(|(entry, _)| entry.order());
+        list
+    }
+
+    fn count_drawn(&self, drawn: usize, culled: usize) {
+        let stats = self.culling_stats.get();
+        self.culling_stats.set(CullingStats { drawn: stats.drawn + drawn, culled: stats.culled + culled });
     }
 
-    /// Draw all active entities (without camera operations - Game handles camera.apply/reset)
+    /// Entities drawn and culled since the last update, across every view
+    pub fn culling_stats(&self) -> CullingStats {
+        self.culling_stats.get()
+    }
+
+    /// Draw all active entities in draw order (without camera operations - Game handles camera.apply/reset)
     pub fn draw_entities(&self) {
-        for entity in &self.entities {
-            if entity.is_active() {
-                entity.draw();
-            }
+        let list = self.draw_list();
+        self.count_drawn(list.len(), 0);
+        let mut batch = self.batch();
+        for (entry, entity) in list {
+            Self::draw_or_queue(batch.as_deref_mut(), entry.layer, entity, || entity.draw());
         }
+        Self::submit(batch.as_deref_mut());
     }
 
-    /// Draw entities with frustum culling optimization
-    pub fn draw_entities_optimized(&self) {
-        for entity in &self.entities {
-            if !entity.is_active() {
-                continue;
+    /// The sprite batch, while batching is on
+    fn batch(&self) -> Option<RefMut<'_, SpriteBatch>> {
+        self.sprite_batching.then(|| self.sprite_batch.borrow_mut())
+    }
+
+    /// Queue `entity`'s sprites when batching; otherwise submit what's queued first, so
+    /// the draw order holds, and `draw` it
+    fn draw_or_queue(batch: Option<&mut SpriteBatch>, layer: i32, entity: &dyn Entity, draw: impl FnOnce()) {
+        if let Some(batch) = batch {
+            batch.set_layer(layer);
+            if entity.queue_sprites(batch) {
+                return;
             }
-            
-            // Frustum culling - only draw if visible
-            if let Some((pos, size)) = entity.get_bounds() {
-                if !self.camera.is_rect_visible(pos, size) {
-                    continue;
+            batch.draw();
+        }
+        draw();
+    }
+
+    fn submit(batch: Option<&mut SpriteBatch>) {
+        if let Some(batch) = batch {
+            batch.draw();
+        }
+    }
+
+    /// Draw all active entities in draw order as seen through `camera`, at the detail
+    /// the scene's `LodSettings` pick and skipping off-view ones when culling is on
+    /// (Game calls this once per view)
+    pub fn draw_entities_in(&self, camera: &Camera) {
+        if self.lod.is_none() && !self.culling {
+            self.draw_entities();
+            return;
+        }
+        let pixel_size = 1.0 / camera.zoom.max(f32::EPSILON);
+        let list = if self.culling {
+            self.visible_list(camera)
+        } else {
+            let list = self.draw_list();
+            self.count_drawn(list.len(), 0);
+            list
+        };
+        let mut batch = self.batch();
+        for (entry, entity) in list {
+            match &self.lod {
+                Some(settings) => {
+                    let position = entity.get_transform().map(|transform| transform.position);
+                    let lod = Lod { level: settings.level(camera, position), pixel_size };
+                    // Reduced detail is drawn directly
+                    let batch = batch.as_deref_mut().filter(|_| lod.level == LodLevel::Full);
+                    Self::draw_or_queue(batch, entry.layer, entity, || entity.draw_lod(lod));
                 }
+                None => Self::draw_or_queue(batch.as_deref_mut(), entry.layer, entity, || entity.draw()),
             }
-            
-            entity.draw();
+        }
+        Self::submit(batch.as_deref_mut());
+    }
+
+    /// Batch entity sprites by texture (see `Entity::queue_sprites`): entities that queue
+    /// sprites are submitted together, one draw call per texture or atlas page in each draw
+    /// layer, instead of one each. Sprites on the same layer may then draw in a different
+    /// order, grouped by texture.
+    pub fn set_sprite_batching(&mut self, enabled: bool) {
+        self.sprite_batching = enabled;
+    }
+
+    pub fn is_sprite_batching(&self) -> bool {
+        self.sprite_batching
+    }
+
+    /// Sprites and draw calls batched since the last update, across every view
+    pub fn sprite_batch_stats(&self) -> SpriteBatchStats {
+        self.sprite_batch.borrow().stats()
+    }
+
+    /// Seconds of physics since the last call, for frame stats: constraints, portal
+    /// crossings and what entities wrap in `UpdateContext::physics`
+    pub(crate) fn take_physics_time(&self) -> f64 {
+        self.physics_time.take()
+    }
+
+    /// Physics gizmos drawn over the entities
+    pub fn physics_debug(&self) -> &PhysicsDebug {
+        &self.physics_debug
+    }
+
+    /// Switch physics gizmos on and off, and report raycasts (see `PhysicsDebug`)
+    pub fn physics_debug_mut(&mut self) -> &mut PhysicsDebug {
+        &mut self.physics_debug
+    }
+
+    /// Ambient beds and reverb zones, played by `Game` for the base scene (see `SceneAudio`)
+    pub fn set_audio(&mut self, audio: SceneAudio) {
+        self.audio = audio;
+    }
+
+    pub fn audio(&self) -> &SceneAudio {
+        &self.audio
+    }
+
+    /// Apply motion and flashing settings to the cameras' shake and portal fades (`Game`
+    /// does this from `GameConfig::motion` when it changes). The shake multiplies with
+    /// each camera's own `Camera::set_shake_scale`.
+    pub fn set_motion_settings(&mut self, settings: MotionSettings) {
+        if settings == self.motion {
+            return;
+        }
+        self.motion = settings;
+        let shake_scale = settings.effective_shake_scale();
+        self.camera.set_motion_shake_scale(shake_scale);
+        for camera in &mut self.cameras {
+            camera.set_motion_shake_scale(shake_scale);
+        }
+        self.portals.set_motion(settings);
+    }
+
+    pub fn motion_settings(&self) -> MotionSettings {
+        self.motion
+    }
+
+    /// Skip drawing entities whose bounds are outside the view (entities without bounds always
+    /// draw). Each update then indexes where entities are in a quadtree, so drawing a view
+    /// only visits the ones in it; `culling_stats` counts what was drawn and skipped.
+    pub fn set_culling(&mut self, enabled: bool) {
+        self.culling = enabled;
+        self.rebuild_spatial_index();
+    }
+
+    pub fn is_culling(&self) -> bool {
+        self.culling
+    }
+
+    /// Draw entities with less detail when zoomed out or far from the camera
+    /// (None draws everything at full detail)
+    pub fn set_lod(&mut self, settings: Option<LodSettings>) {
+        self.lod = settings;
+    }
+
+    pub fn lod(&self) -> Option<&LodSettings> {
+        self.lod.as_ref()
+    }
+
+    /// Screen-space pass: `Entity::draw_ui` for active entities in draw order
+    /// (call without a camera applied - Game does this after the world pass)
+    pub fn draw_entities_ui(&self) {
+        for (_, entity) in self.draw_list() {
+            entity.draw_ui();
         }
     }
 
+    /// Draw entities in draw order with frustum culling optimization (through the
+    /// spatial index when culling is on)
+    pub fn draw_entities_optimized(&self) {
+        let mut batch = self.batch();
+        for (entry, entity) in self.visible_list(&self.camera) {
+            Self::draw_or_queue(batch.as_deref_mut(), entry.layer, entity, || entity.draw());
+        }
+        Self::submit(batch.as_deref_mut());
+    }
+
     /// Get immutable reference to camera
     pub fn get_camera(&self) -> &Camera {
         &self.camera
@@ -113,8 +1275,27 @@ impl Scene {
 
     /// Remove all entities immediately
     pub fn clear_all_entities(&mut self) {
-        self.entities.clear();
-        self.entities_to_add.clear();
+        let entities = self.entities.drain();
+        // Entities spawned by the hooks are cleared along with the rest
+        let mut commands = Commands::new(self.next_entity_id);
+        for (id, mut entity) in entities {
+            self.run_lifecycle_hook(id, entity.as_mut(), None, &mut commands, Entity::on_removed);
+            self.pools.recycle(id, entity);
+        }
+        self.next_entity_id = commands.into_parts().1;
+        self.entity_index.clear();
+        for (id, entity) in self.entities_to_add.drain() {
+            self.pools.recycle(id, entity);
+        }
+        self.constraints.clear();
+        self.components.clear();
+        self.disabled.clear();
+        self.panicked.clear();
+        self.time_scales.clear();
+        self.portals = Portals::default();
+        self.rewind.reset();
+        self.ownership.clear_all();
+        self.relations.clear_all();
         self.should_clear_inactive = false;
     }
 
@@ -125,18 +1306,18 @@ impl Scene {
     
     /// Get number of active entities
     pub fn active_entity_count(&self) -> usize {
-        self.entities.iter().filter(|e| e.is_active()).count() + 
-        self.entities_to_add.iter().filter(|e| e.is_active()).count()
+        self.entities.values().filter(|(_, e)| e.is_active()).count() + 
+        self.entities_to_add.iter().filter(|(_, e)| e.is_active()).count()
     }
 
-    /// Get reference to all entities (for iteration)
-    pub fn get_entities(&self) -> &Vec<Box<dyn Entity>> {
-        &self.entities
+    /// All entities, in update order
+    pub fn get_entities(&self) -> impl Iterator<Item = &Box<dyn Entity>> {
+        self.entities.values().map(|(_, entity)| entity)
     }
 
-    /// Get mutable reference to all entities
-    pub fn get_entities_mut(&mut self) -> &mut Vec<Box<dyn Entity>> {
-        &mut self.entities
+    /// Mutable access to all entities (use add/remove_entity to change the set)
+    pub fn get_entities_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn Entity>> {
+        self.entities.values_mut().map(|(_, entity)| entity)
     }
 
     /// Find entities by type (simple filtering)
@@ -144,7 +1325,7 @@ impl Scene {
     where 
         F: Fn(&Box<dyn Entity>) -> bool,
     {
-        self.entities.iter()
+        self.get_entities()
             .filter(|e| e.is_active() && predicate(e))
             .collect()
     }
@@ -154,10 +1335,48 @@ impl Scene {
     where 
         F: Fn(&Box<dyn Entity>) -> bool,
     {
-        self.entities.iter()
+        self.get_entities()
             .find(|e| e.is_active() && predicate(e))
     }
 
+    /// Find all active entities with a tag
+    pub fn find_by_tag(&self, tag: &str) -> Vec<&dyn Entity> {
+        self.get_entities()
+            .filter(|e| e.is_active() && e.has_tag(tag))
+            .map(|e| e.as_ref())
+            .collect()
+    }
+
+    /// The entities that support saving (see `Entity::save`), in update order, the camera
+    /// and the scene's audio
+    pub fn to_file(&self) -> SceneFile {
+        let entities = self.get_entities()
+            .filter(|entity| entity.is_active())
+            .filter_map(|entity| entity.save())
+            .collect();
+        SceneFile {
+            camera: Some((self.camera.position, self.camera.zoom)),
+            entities,
+            audio: (!self.audio.is_empty()).then(|| self.audio.clone()),
+        }
+    }
+
+    /// Spawn every entity of a scene file and apply its camera and audio. Nothing is added
+    /// if any record fails.
+    pub fn load_file(&mut self, file: &SceneFile, registry: &EntityRegistry) -> Result<Vec<EntityId>, SceneFileError> {
+        let entities = file.entities.iter()
+            .map(|record| registry.spawn(record))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some((position, zoom)) = file.camera {
+            self.camera.position = position;
+            self.camera.zoom = zoom;
+        }
+        if let Some(audio) = &file.audio {
+            self.audio = audio.clone();
+        }
+        Ok(entities.into_iter().map(|entity| self.add_entity(entity)).collect())
+    }
+
     /// Set up camera for a platformer game
     pub fn setup_platformer_camera(&mut self, player_position: Vec2, level_size: Vec2) {
         self.camera.set_position(player_position);
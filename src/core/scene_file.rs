@@ -0,0 +1,268 @@
+use macroquad::prelude::Vec2;
+use ron::value::RawValue;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use super::Entity;
+use crate::audio::SceneAudio;
+
+/// Error returned when saving or loading a scene file
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(std::io::Error),
+    Format(String),
+    /// No factory is registered for this entity kind
+    UnknownKind(String),
+    /// A factory couldn't read an entity's data
+    Data { kind: String, message: String },
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(err) => write!(f, "failed to read scene file: {}", err),
+            SceneFileError::Format(msg) => write!(f, "invalid scene file: {}", msg),
+            SceneFileError::UnknownKind(kind) => write!(f, "unknown entity kind '{}'", kind),
+            SceneFileError::Data { kind, message } => write!(f, "invalid data for entity '{}': {}", kind, message),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl From<std::io::Error> for SceneFileError {
+    fn from(err: std::io::Error) -> Self {
+        SceneFileError::Io(err)
+    }
+}
+
+/// An entity's data in a scene file, nested in the file as written. RON data is kept as
+/// its raw value rather than a `ron::Value`, which loses enum variant names; `.json` files
+/// hold plain JSON values, whose enums are tagged already. RON data saved to a `.json`
+/// file (e.g. from `EntityRecord::with_data`) is written as its RON text.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityData {
+    Ron(Box<RawValue>),
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+}
+
+impl EntityData {
+    pub fn from_rust<T: Serialize>(value: &T) -> Result<Self, String> {
+        RawValue::from_rust(value).map(EntityData::Ron).map_err(|err| err.to_string())
+    }
+
+    /// Read the data as a `T`
+    pub fn to_rust<T: DeserializeOwned>(&self) -> Result<T, String> {
+        match self {
+            EntityData::Ron(raw) => raw.into_rust().map_err(|err| err.to_string()),
+            #[cfg(feature = "json")]
+            EntityData::Json(value) => serde_json::from_value(value.clone()).or_else(|err| match value {
+                // RON data saved to a JSON file is written as its text
+                serde_json::Value::String(text) => ron::from_str(text).map_err(|_| err.to_string()),
+                _ => Err(err.to_string()),
+            }),
+        }
+    }
+
+    /// The data as text, e.g. for snapshots
+    pub fn text(&self) -> String {
+        match self {
+            EntityData::Ron(raw) => raw.get_ron().to_string(),
+            #[cfg(feature = "json")]
+            EntityData::Json(value) => value.to_string(),
+        }
+    }
+}
+
+impl Serialize for EntityData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            EntityData::Ron(raw) => raw.serialize(serializer),
+            #[cfg(feature = "json")]
+            EntityData::Json(value) => value.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityData {
+    /// Only RON is read this way; `SceneFile::load_from_json` fills in JSON data itself
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Box::<RawValue>::deserialize(deserializer).map(EntityData::Ron)
+    }
+}
+
+/// One entity in a scene file: its kind, used to find the factory that spawns it, and
+/// whatever data that factory needs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityRecord {
+    pub kind: String,
+    #[serde(default)]
+    pub data: Option<EntityData>,
+}
+
+impl EntityRecord {
+    /// A record without data (the factory gets `()`)
+    pub fn new(kind: &str) -> Self {
+        Self { kind: kind.to_string(), data: None }
+    }
+
+    /// A record holding `data`, returned from `Entity::save`:
+    ///
+    /// ```ignore
+    /// fn save(&self) -> Option<EntityRecord> {
+    ///     EntityRecord::with_data("coin", &CoinData { position: self.transform.position, value: self.value }).ok()
+    /// }
+    /// ```
+    pub fn with_data<T: Serialize>(kind: &str, data: &T) -> Result<Self, SceneFileError> {
+        let data = EntityData::from_rust(data)
+            .map_err(|message| SceneFileError::Data { kind: kind.to_string(), message })?;
+        Ok(Self { kind: kind.to_string(), data: Some(data) })
+    }
+}
+
+/// The saveable contents of a scene, as written to a level file.
+///
+/// ```ron
+/// (
+///     camera: Some(((400.0, 300.0), 1.0)),
+///     entities: [
+///         (kind: "player", data: Some((position: (64.0, 128.0)))),
+///         (kind: "coin", data: Some((position: (200.0, 96.0), value: 5, kind: Gold))),
+///         (kind: "exit"),
+///     ],
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneFile {
+    /// Camera position and zoom
+    pub camera: Option<(Vec2, f32)>,
+    pub entities: Vec<EntityRecord>,
+    /// Ambient beds and reverb zones (see `SceneAudio`)
+    pub audio: Option<SceneAudio>,
+}
+
+impl SceneFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a scene file; `.json` files are read as JSON (with the `json` feature), others as RON
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SceneFileError> {
+        let data = std::fs::read_to_string(&path)?;
+        #[cfg(feature = "json")]
+        if is_json(path.as_ref()) {
+            return Self::load_from_json(&data);
+        }
+        Self::load_from_str(&data)
+    }
+
+    /// Parse a scene from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, SceneFileError> {
+        ron::from_str(data).map_err(|err| SceneFileError::Format(err.to_string()))
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, SceneFileError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|err| SceneFileError::Format(err.to_string()))
+    }
+
+    #[cfg(feature = "json")]
+    pub fn load_from_json(data: &str) -> Result<Self, SceneFileError> {
+        let value = serde_json::from_str(data).map_err(|err| SceneFileError::Format(err.to_string()))?;
+        Self::from_json_value(value)
+    }
+
+    /// Read a parsed JSON scene, keeping each entity's data as its JSON value
+    #[cfg(feature = "json")]
+    pub fn from_json_value(mut value: serde_json::Value) -> Result<Self, SceneFileError> {
+        let records = value.get_mut("entities").and_then(serde_json::Value::as_array_mut);
+        let data: Vec<_> = records.into_iter()
+            .flatten()
+            .map(|record| record.get_mut("data").map(serde_json::Value::take))
+            .collect();
+        let mut scene: Self = serde_json::from_value(value).map_err(|err| SceneFileError::Format(err.to_string()))?;
+        for (record, data) in scene.entities.iter_mut().zip(data) {
+            record.data = data.filter(|data| !data.is_null()).map(EntityData::Json);
+        }
+        Ok(scene)
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json_string(&self) -> Result<String, SceneFileError> {
+        serde_json::to_string_pretty(self).map_err(|err| SceneFileError::Format(err.to_string()))
+    }
+
+    /// Write the scene; `.json` paths are written as JSON (with the `json` feature), others as RON
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SceneFileError> {
+        #[cfg(feature = "json")]
+        if is_json(path.as_ref()) {
+            std::fs::write(path, self.to_json_string()?)?;
+            return Ok(());
+        }
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+/// Spawns an entity from its record's data
+type EntityFactory = Box<dyn Fn(Option<&EntityData>) -> Result<Box<dyn Entity>, String>>;
+
+/// Factories that turn scene file records back into entities, by kind:
+///
+/// ```ignore
+/// let mut registry = EntityRegistry::new();
+/// registry.register("coin", |data: CoinData| Box::new(Coin::new(data.position, data.value)));
+/// registry.register("exit", |_: ()| Box::new(Exit::new()));
+///
+/// let level = SceneFile::load("levels/1-1.ron")?;
+/// scene.load_file(&level, &registry)?;
+/// ```
+#[derive(Default)]
+pub struct EntityRegistry {
+    factories: HashMap<String, EntityFactory>,
+}
+
+impl EntityRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn entities of `kind` from their data, deserialized as `T`
+    pub fn register<T, F>(&mut self, kind: &str, spawn: F)
+    where
+        T: DeserializeOwned,
+        F: Fn(T) -> Box<dyn Entity> + 'static,
+    {
+        self.factories.insert(kind.to_string(), Box::new(move |data| {
+            let data = match data {
+                Some(data) => data.to_rust()?,
+                None => ron::from_str("()").map_err(|err| err.to_string())?,
+            };
+            Ok(spawn(data))
+        }));
+    }
+
+    pub fn contains(&self, kind: &str) -> bool {
+        self.factories.contains_key(kind)
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    /// Build the entity a record describes without adding it to a scene
+    pub fn spawn(&self, record: &EntityRecord) -> Result<Box<dyn Entity>, SceneFileError> {
+        let factory = self.factories.get(&record.kind)
+            .ok_or_else(|| SceneFileError::UnknownKind(record.kind.clone()))?;
+        factory(record.data.as_ref()).map_err(|message| SceneFileError::Data { kind: record.kind.clone(), message })
+    }
+}
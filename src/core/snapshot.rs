@@ -0,0 +1,440 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::path::Path;
+use super::{Entity, EntityId, Scene};
+use crate::rendering::{with_clip_rect, Camera, DebugTheme};
+
+const FONT_SIZE: f32 = 16.0;
+const PADDING: f32 = 8.0;
+// Screen-space radius of the marker around a diverged entity
+const MARKER_RADIUS: f32 = 12.0;
+
+/// Error returned when saving or loading snapshots and diff reports
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "failed to read snapshot: {}", err),
+            SnapshotError::Format(msg) => write!(f, "invalid snapshot: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+/// The state of one entity as compared between snapshots. Components are kept as text
+/// (floats print exactly), keyed by name: "position", "rotation", "scale", "velocity",
+/// "animation_frame", "active" and "data" (the entity's `save` record).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub id: EntityId,
+    /// The `save` record's kind, if the entity has one
+    pub kind: Option<String>,
+    /// Where to mark the entity in the diff overlay
+    pub position: Option<Vec2>,
+    pub components: BTreeMap<String, String>,
+}
+
+impl EntitySnapshot {
+    fn capture(id: EntityId, entity: &dyn Entity) -> Self {
+        let mut components = BTreeMap::new();
+        let transform = entity.get_transform();
+        if let Some(transform) = transform {
+            components.insert("position".to_string(), format!("{:?}", transform.position));
+            components.insert("rotation".to_string(), format!("{:?}", transform.rotation));
+            components.insert("scale".to_string(), format!("{:?}", transform.scale));
+        }
+        if let Some(velocity) = entity.get_velocity() {
+            components.insert("velocity".to_string(), format!("{:?}", velocity));
+        }
+        if let Some(frame) = entity.animation_frame() {
+            components.insert("animation_frame".to_string(), frame.to_string());
+        }
+        components.insert("active".to_string(), entity.is_active().to_string());
+        let record = entity.save();
+        // The saved data's text, so enum fields show by variant name in diffs
+        if let Some(data) = record.as_ref().and_then(|record| record.data.as_ref()) {
+            components.insert("data".to_string(), data.text());
+        }
+        Self {
+            id,
+            kind: record.map(|record| record.kind),
+            position: transform.map(|transform| transform.position),
+            components,
+        }
+    }
+
+    /// Hash of the components, stable across platforms and runs
+    pub fn checksum(&self) -> u64 {
+        let mut hash = Fnv::new();
+        hash.write(&self.id.0.to_le_bytes());
+        for (name, value) in &self.components {
+            hash.write(name.as_bytes());
+            hash.write(&[0]);
+            hash.write(value.as_bytes());
+            hash.write(&[0]);
+        }
+        hash.finish()
+    }
+}
+
+/// Every entity's state on one frame, for finding where two runs of a deterministic or
+/// networked game drifted apart:
+///
+/// ```ignore
+/// // each simulation step
+/// history.record(game.get_scene().snapshot(tick));
+/// net.send(history.latest().unwrap().checksums());
+///
+/// // when the peer's checksums for a tick arrive
+/// if let Some(diff) = history.compare(&remote) {
+///     diff.save("desync.txt")?;
+///     game.show_desync(diff);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct SceneSnapshot {
+    /// Simulation step the snapshot was taken on
+    pub frame: u64,
+    /// In update order
+    pub entities: Vec<EntitySnapshot>,
+}
+
+impl SceneSnapshot {
+    /// Hash of the whole scene; equal checksums mean the runs still agree
+    pub fn checksum(&self) -> u64 {
+        let mut hash = Fnv::new();
+        hash.write(&self.frame.to_le_bytes());
+        for entity in &self.entities {
+            hash.write(&entity.checksum().to_le_bytes());
+        }
+        hash.finish()
+    }
+
+    /// The compact form sent to peers instead of the whole snapshot
+    pub fn checksums(&self) -> ChecksumReport {
+        ChecksumReport {
+            frame: self.frame,
+            checksum: self.checksum(),
+            entities: self.entities.iter().map(|entity| (entity.id, entity.checksum())).collect(),
+        }
+    }
+
+    pub fn get(&self, id: EntityId) -> Option<&EntitySnapshot> {
+        self.entities.iter().find(|entity| entity.id == id)
+    }
+
+    /// Compare against another run's snapshot of the same frame, component by component
+    pub fn diff(&self, remote: &SceneSnapshot) -> SnapshotDiff {
+        let mut entities = Vec::new();
+        for local in &self.entities {
+            let Some(other) = remote.get(local.id) else {
+                entities.push(EntityDiff::new(local, DiffKind::OnlyLocal));
+                continue;
+            };
+            let names = local.components.keys().chain(other.components.keys().filter(|name| !local.components.contains_key(*name)));
+            let components: Vec<_> = names
+                .filter_map(|name| {
+                    let (mine, theirs) = (local.components.get(name), other.components.get(name));
+                    (mine != theirs).then(|| ComponentDiff { name: name.clone(), local: mine.cloned(), remote: theirs.cloned() })
+                })
+                .collect();
+            if !components.is_empty() {
+                entities.push(EntityDiff::new(local, DiffKind::Changed(components)));
+            }
+        }
+        for other in remote.entities.iter().filter(|other| self.get(other.id).is_none()) {
+            entities.push(EntityDiff::new(other, DiffKind::OnlyRemote));
+        }
+        entities.sort_by_key(|entity| entity.id);
+        SnapshotDiff { frame: self.frame, local_checksum: self.checksum(), remote_checksum: remote.checksum(), entities }
+    }
+
+    /// Compare against a peer's checksums. Only which entities differ is known, not how.
+    pub fn diff_checksums(&self, remote: &ChecksumReport) -> SnapshotDiff {
+        let mut entities = Vec::new();
+        for local in &self.entities {
+            match remote.entities.iter().find(|(id, _)| *id == local.id) {
+                None => entities.push(EntityDiff::new(local, DiffKind::OnlyLocal)),
+                Some(&(_, checksum)) if checksum != local.checksum() => {
+                    entities.push(EntityDiff::new(local, DiffKind::Checksum { local: local.checksum(), remote: checksum }));
+                }
+                Some(_) => {}
+            }
+        }
+        for &(id, _) in remote.entities.iter().filter(|(id, _)| self.get(*id).is_none()) {
+            entities.push(EntityDiff { id, kind: None, position: None, change: DiffKind::OnlyRemote });
+        }
+        entities.sort_by_key(|entity| entity.id);
+        SnapshotDiff { frame: self.frame, local_checksum: self.checksum(), remote_checksum: remote.checksum, entities }
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, SnapshotError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    pub fn load_from_str(data: &str) -> Result<Self, SnapshotError> {
+        ron::from_str(data).map_err(|err| SnapshotError::Format(err.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|err| SnapshotError::Format(err.to_string()))?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+impl Scene {
+    /// Capture every entity's state (including ones added this frame) as `frame`
+    pub fn snapshot(&self, frame: u64) -> SceneSnapshot {
+        let mut entities: Vec<_> = self.entity_ids()
+            .filter_map(|id| self.get_entity(id).map(|entity| EntitySnapshot::capture(id, entity)))
+            .collect();
+        // By id rather than slot, so checksums don't depend on which slots got reused
+        entities.sort_by_key(|entity| entity.id);
+        SceneSnapshot { frame, entities }
+    }
+}
+
+/// Per-entity checksums of one frame, small enough to send every step
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct ChecksumReport {
+    pub frame: u64,
+    pub checksum: u64,
+    pub entities: Vec<(EntityId, u64)>,
+}
+
+/// Recent snapshots, so a peer's checksums can be checked when they arrive a few frames late
+pub struct SnapshotHistory {
+    snapshots: VecDeque<SceneSnapshot>,
+    capacity: usize,
+}
+
+impl Default for SnapshotHistory {
+    fn default() -> Self {
+        Self { snapshots: VecDeque::new(), capacity: 120 }
+    }
+}
+
+impl SnapshotHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frames kept (120 by default)
+    pub fn with_capacity(mut self, frames: usize) -> Self {
+        self.capacity = frames.max(1);
+        self
+    }
+
+    pub fn record(&mut self, snapshot: SceneSnapshot) {
+        if self.snapshots.len() >= self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    pub fn get(&self, frame: u64) -> Option<&SceneSnapshot> {
+        self.snapshots.iter().find(|snapshot| snapshot.frame == frame)
+    }
+
+    pub fn latest(&self) -> Option<&SceneSnapshot> {
+        self.snapshots.back()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+
+    /// Check a peer's checksums against the snapshot of the same frame. None if they agree
+    /// or that frame is no longer (or not yet) kept.
+    pub fn compare(&self, remote: &ChecksumReport) -> Option<SnapshotDiff> {
+        let local = self.get(remote.frame)?;
+        if local.checksum() == remote.checksum {
+            return None;
+        }
+        Some(local.diff_checksums(remote))
+    }
+
+    /// The earliest frame both histories have where they disagree, e.g. between two
+    /// recorded runs of the same replay
+    pub fn first_divergence(&self, remote: &SnapshotHistory) -> Option<SnapshotDiff> {
+        self.snapshots.iter()
+            .filter_map(|local| remote.get(local.frame).map(|other| (local, other)))
+            .find(|(local, other)| local.checksum() != other.checksum())
+            .map(|(local, other)| local.diff(other))
+    }
+}
+
+/// How an entity differs between the two runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffKind {
+    /// The entity exists only in the local run
+    OnlyLocal,
+    /// The entity exists only in the remote run
+    OnlyRemote,
+    /// Components whose values differ
+    Changed(Vec<ComponentDiff>),
+    /// Only checksums were compared
+    Checksum { local: u64, remote: u64 },
+}
+
+/// One component's value in each run; None where the entity doesn't have it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDiff {
+    pub name: String,
+    pub local: Option<String>,
+    pub remote: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityDiff {
+    pub id: EntityId,
+    pub kind: Option<String>,
+    pub position: Option<Vec2>,
+    pub change: DiffKind,
+}
+
+impl EntityDiff {
+    fn new(entity: &EntitySnapshot, change: DiffKind) -> Self {
+        Self { id: entity.id, kind: entity.kind.clone(), position: entity.position, change }
+    }
+
+    fn label(&self) -> String {
+        match &self.kind {
+            Some(kind) => format!("{:?} ({})", self.id, kind),
+            None => format!("{:?}", self.id),
+        }
+    }
+
+    /// One line per difference, e.g. "position: Vec2(1.0, 2.0) vs Vec2(1.0, 2.5)"
+    fn details(&self) -> Vec<String> {
+        match &self.change {
+            DiffKind::OnlyLocal => vec!["only in local".to_string()],
+            DiffKind::OnlyRemote => vec!["only in remote".to_string()],
+            DiffKind::Changed(components) => components.iter()
+                .map(|component| {
+                    let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+                    format!("{}: {} vs {}", component.name, value(&component.local), value(&component.remote))
+                })
+                .collect(),
+            DiffKind::Checksum { local, remote } => vec![format!("checksum {:016x} vs {:016x}", local, remote)],
+        }
+    }
+}
+
+/// Where two runs disagree on one frame. Entities are in spawn order, so the first one is
+/// usually where the desync started and the rest followed from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotDiff {
+    pub frame: u64,
+    pub local_checksum: u64,
+    pub remote_checksum: u64,
+    pub entities: Vec<EntityDiff>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entities.is_empty() && self.local_checksum == self.remote_checksum
+    }
+
+    pub fn first(&self) -> Option<&EntityDiff> {
+        self.entities.first()
+    }
+
+    /// Write the report as text, for attaching to bug reports
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), SnapshotError> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Mark diverged entities seen through a camera, the first one in the error color
+    pub fn draw(&self, theme: &DebugTheme, camera: &Camera) {
+        with_clip_rect(camera.screen_rect(), || {
+            for (index, entity) in self.entities.iter().enumerate().rev() {
+                let Some(position) = entity.position else { continue };
+                let at = camera.world_to_screen(position);
+                let color = if index == 0 { theme.error() } else { theme.warning() };
+                draw_circle_lines(at.x, at.y, MARKER_RADIUS, 2.0, color);
+                draw_text(&entity.label(), at.x + MARKER_RADIUS, at.y - MARKER_RADIUS, FONT_SIZE, color);
+            }
+        });
+    }
+
+    /// List the differences in screen space, with the top-left corner at `position`
+    pub fn draw_panel(&self, theme: &DebugTheme, position: Vec2, lines: usize) {
+        let mut text = vec![(format!("DESYNC at frame {}: {} entities differ", self.frame, self.entities.len()), theme.error())];
+        for (index, entity) in self.entities.iter().enumerate() {
+            let color = if index == 0 { theme.error() } else { theme.warning() };
+            text.push((entity.label(), color));
+            text.extend(entity.details().into_iter().map(|line| (format!("  {}", line), theme.text())));
+        }
+        if text.len() > lines {
+            let hidden = text.len() - lines + 1;
+            text.truncate(lines - 1);
+            text.push((format!("... {} more lines", hidden), theme.muted()));
+        }
+        let width = text.iter()
+            .map(|(line, _)| measure_text(line, None, FONT_SIZE as u16, 1.0).width)
+            .fold(0.0, f32::max);
+        draw_rectangle(position.x, position.y, width + PADDING * 2.0, text.len() as f32 * FONT_SIZE + PADDING * 2.0, theme.background());
+        let mut y = position.y + PADDING + FONT_SIZE * 0.75;
+        for (line, color) in text {
+            draw_text(&line, position.x + PADDING, y, FONT_SIZE, color);
+            y += FONT_SIZE;
+        }
+    }
+}
+
+impl fmt::Display for SnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "desync at frame {}", self.frame)?;
+        writeln!(f, "checksum local {:016x} remote {:016x}", self.local_checksum, self.remote_checksum)?;
+        writeln!(f, "{} entities differ", self.entities.len())?;
+        for entity in &self.entities {
+            match entity.position {
+                Some(position) => writeln!(f, "{} at ({:.1}, {:.1})", entity.label(), position.x, position.y)?,
+                None => writeln!(f, "{}", entity.label())?,
+            }
+            for line in entity.details() {
+                writeln!(f, "    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// FNV-1a: simple, and the same on every platform and Rust version unlike `DefaultHasher`
+struct Fnv(u64);
+
+impl Fnv {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
@@ -0,0 +1,154 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use super::{EntityId, Scene};
+
+/// Where the game is in its flow. `Game` runs enter, exit and update hooks as it moves
+/// between states (see `Game::set_state`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum GameState {
+    /// Loading and setup, before the first screen
+    #[default]
+    Boot,
+    Menu,
+    Playing,
+    /// On top of `Playing`: pausing doesn't exit `Playing`, so its entities stay
+    Paused,
+    GameOver,
+}
+
+impl GameState {
+    /// The state this one sits on top of, which stays entered while it's active
+    pub fn parent(&self) -> Option<GameState> {
+        match self {
+            GameState::Paused => Some(GameState::Playing),
+            _ => None,
+        }
+    }
+
+    /// This state followed by its parents
+    fn chain(self) -> Vec<GameState> {
+        std::iter::successors(Some(self), GameState::parent).collect()
+    }
+}
+
+/// Callback run with the base scene when a state is entered or exited
+type StateHook = Box<dyn FnMut(&mut Scene)>;
+
+/// Callback run every frame in a state; returning a state moves there
+type StateUpdateHook = Box<dyn FnMut(&mut Scene, f32) -> Option<GameState>>;
+
+#[derive(Default)]
+struct StateHooks {
+    enter: Vec<StateHook>,
+    exit: Vec<StateHook>,
+    update: Vec<StateUpdateHook>,
+}
+
+/// The game's current state, its hooks and the entities that live only in a state
+#[derive(Default)]
+pub(crate) struct StateMachine {
+    current: GameState,
+    previous: Option<GameState>,
+    // Requested with `Game::set_state`, applied at the start of the next frame
+    pending: Option<GameState>,
+    time_in_state: f32,
+    hooks: HashMap<GameState, StateHooks>,
+    scoped: HashMap<GameState, Vec<EntityId>>,
+    // The first state's enter hooks run on the first frame
+    started: bool,
+}
+
+impl StateMachine {
+    pub(crate) fn current(&self) -> GameState {
+        self.current
+    }
+
+    pub(crate) fn previous(&self) -> Option<GameState> {
+        self.previous
+    }
+
+    pub(crate) fn time_in_state(&self) -> f32 {
+        self.time_in_state
+    }
+
+    /// Whether `state` is the current state or one it sits on top of
+    pub(crate) fn is_in(&self, state: GameState) -> bool {
+        self.current.chain().contains(&state)
+    }
+
+    pub(crate) fn request(&mut self, state: GameState) {
+        self.pending = Some(state);
+    }
+
+    pub(crate) fn on_enter(&mut self, state: GameState, hook: StateHook) {
+        self.hooks.entry(state).or_default().enter.push(hook);
+    }
+
+    pub(crate) fn on_exit(&mut self, state: GameState, hook: StateHook) {
+        self.hooks.entry(state).or_default().exit.push(hook);
+    }
+
+    pub(crate) fn on_update(&mut self, state: GameState, hook: StateUpdateHook) {
+        self.hooks.entry(state).or_default().update.push(hook);
+    }
+
+    pub(crate) fn scope(&mut self, state: GameState, id: EntityId) {
+        self.scoped.entry(state).or_default().push(id);
+    }
+
+    /// Apply a requested state, then run the current state's update hooks. Returns the
+    /// states moved from and to, if the state changed.
+    pub(crate) fn update(&mut self, scene: &mut Scene, dt: f32) -> Option<(GameState, GameState)> {
+        if !self.started {
+            self.started = true;
+            for state in self.current.chain().into_iter().rev() {
+                self.run_hooks(state, scene, |hooks| &mut hooks.enter);
+            }
+        }
+        let mut changed = self.pending.take().and_then(|next| self.transition(scene, next));
+
+        self.time_in_state += dt;
+        let mut next = None;
+        if let Some(hooks) = self.hooks.get_mut(&self.current) {
+            for hook in &mut hooks.update {
+                next = next.or(hook(scene, dt));
+            }
+        }
+        if let Some((from, to)) = next.and_then(|next| self.transition(scene, next)) {
+            // Report the whole frame's move when both the request and a hook changed state
+            changed = Some((changed.map_or(from, |(first, _)| first), to));
+        }
+        changed
+    }
+
+    /// Exit the states `next` doesn't share with the current one, innermost first, then
+    /// enter the new ones, outermost first
+    fn transition(&mut self, scene: &mut Scene, next: GameState) -> Option<(GameState, GameState)> {
+        let from = self.current;
+        if next == from {
+            return None;
+        }
+        let (old, new) = (from.chain(), next.chain());
+        for state in old.iter().filter(|state| !new.contains(state)) {
+            self.run_hooks(*state, scene, |hooks| &mut hooks.exit);
+            for id in self.scoped.remove(state).unwrap_or_default() {
+                scene.remove_entity(id);
+            }
+        }
+        self.previous = Some(from);
+        self.current = next;
+        self.time_in_state = 0.0;
+        for state in new.iter().rev().filter(|state| !old.contains(state)) {
+            self.run_hooks(*state, scene, |hooks| &mut hooks.enter);
+        }
+        Some((from, next))
+    }
+
+    fn run_hooks<F: Fn(&mut StateHooks) -> &mut Vec<StateHook>>(&mut self, state: GameState, scene: &mut Scene, pick: F) {
+        if let Some(hooks) = self.hooks.get_mut(&state) {
+            for hook in pick(hooks) {
+                hook(scene);
+            }
+        }
+    }
+}
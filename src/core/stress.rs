@@ -0,0 +1,268 @@
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+use std::rc::Rc;
+use super::frame_stats::FrameStats;
+use super::{rng, EntityId, PrefabLibrary, Scene};
+use crate::rendering::LodSettings;
+use crate::ui::CheatMenu;
+
+// Frames averaged before and after a change
+const MEASURE_FRAMES: usize = 60;
+
+/// A change to make to the scene, then measure
+#[derive(Debug, Clone, PartialEq)]
+pub enum StressCommand {
+    /// Spawn `count` of a prefab at random spots in the camera view
+    Spawn { prefab: String, count: usize },
+    /// Remove everything the stress test spawned
+    Clear,
+    SetCulling(bool),
+    SetLod(bool),
+    SetBatching(bool),
+    /// Measure without changing anything
+    Report,
+}
+
+impl fmt::Display for StressCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let on_off = |on: &bool| if *on { "on" } else { "off" };
+        match self {
+            StressCommand::Spawn { prefab, count } => write!(f, "spawn {} x {}", count, prefab),
+            StressCommand::Clear => write!(f, "clear stress entities"),
+            StressCommand::SetCulling(on) => write!(f, "culling {}", on_off(on)),
+            StressCommand::SetLod(on) => write!(f, "LOD {}", on_off(on)),
+            StressCommand::SetBatching(on) => write!(f, "sprite batching {}", on_off(on)),
+            StressCommand::Report => write!(f, "report"),
+        }
+    }
+}
+
+/// Average frame timings before and after a stress command
+#[derive(Debug, Clone, PartialEq)]
+pub struct StressReport {
+    pub command: StressCommand,
+    pub before: FrameStats,
+    pub after: FrameStats,
+}
+
+impl StressReport {
+    /// The section whose time grew the most: "input", "update", "physics", "camera" or "draw"
+    pub fn bottleneck(&self) -> &'static str {
+        self.after.sections().into_iter()
+            .zip(self.before.sections())
+            .map(|((name, after), (_, before))| (name, after - before))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or("update", |(name, _)| name)
+    }
+}
+
+impl fmt::Display for StressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before, after) = (&self.before, &self.after);
+        writeln!(f, "stress: {} ({} -> {} entities)", self.command, before.entities, after.entities)?;
+        let sections = before.sections().into_iter()
+            .zip(after.sections())
+            .map(|((name, before), (_, after))| (name, before, after));
+        for (name, before, after) in std::iter::once(("frame", before.frame_time, after.frame_time)).chain(sections) {
+            writeln!(f, "  {:<7} {:>7.2} -> {:>7.2} ms", name, before, after)?;
+        }
+        write!(f, "  most growth: {}", self.bottleneck())
+    }
+}
+
+struct StressState {
+    prefabs: Rc<PrefabLibrary>,
+    commands: Vec<StressCommand>,
+    spawned: Vec<EntityId>,
+    samples: VecDeque<FrameStats>,
+    // Command being measured, the average before it, and frames left to sample
+    measuring: Option<(StressCommand, FrameStats, usize)>,
+    reports: Vec<StressReport>,
+    // LOD settings put aside while LOD is toggled off
+    stashed_lod: Option<LodSettings>,
+}
+
+/// Runtime tools for finding out whether a slowdown is in update, draw or collision:
+/// spawn bursts of a prefab, toggle culling, LOD and sprite batching, and compare frame
+/// timings before and after each change.
+///
+/// Every command is measured over the next second of frames and reported with
+/// the log (category "stress") and `take_reports`. Commands can come from code or from the cheat menu:
+///
+/// ```ignore
+/// let stress = game.enable_stress_test(Rc::new(prefabs));
+/// stress.spawn("bat", 1000);
+/// // or press F1 and use the "Stress" category
+/// ```
+#[derive(Clone)]
+pub struct StressTest {
+    state: Rc<RefCell<StressState>>,
+}
+
+impl StressTest {
+    pub fn new(prefabs: Rc<PrefabLibrary>) -> Self {
+        Self {
+            state: Rc::new(RefCell::new(StressState {
+                prefabs,
+                commands: vec![],
+                spawned: vec![],
+                samples: VecDeque::with_capacity(MEASURE_FRAMES),
+                measuring: None,
+                reports: vec![],
+                stashed_lod: None,
+            })),
+        }
+    }
+
+    pub fn run(&self, command: StressCommand) {
+        self.state.borrow_mut().commands.push(command);
+    }
+
+    pub fn spawn(&self, prefab: &str, count: usize) {
+        self.run(StressCommand::Spawn { prefab: prefab.to_string(), count });
+    }
+
+    pub fn clear(&self) {
+        self.run(StressCommand::Clear);
+    }
+
+    pub fn set_culling(&self, enabled: bool) {
+        self.run(StressCommand::SetCulling(enabled));
+    }
+
+    pub fn set_lod(&self, enabled: bool) {
+        self.run(StressCommand::SetLod(enabled));
+    }
+
+    pub fn set_batching(&self, enabled: bool) {
+        self.run(StressCommand::SetBatching(enabled));
+    }
+
+    pub fn report(&self) {
+        self.run(StressCommand::Report);
+    }
+
+    /// Entities spawned by the stress test that are still around
+    pub fn spawned_count(&self) -> usize {
+        self.state.borrow().spawned.len()
+    }
+
+    pub fn take_reports(&self) -> Vec<StressReport> {
+        std::mem::take(&mut self.state.borrow_mut().reports)
+    }
+
+    /// Add a "Stress" category to the cheat menu: spawn buttons for every prefab and each
+    /// of `counts`, clear, culling, LOD and batching toggles (starting as `scene` has
+    /// them), and a report button
+    pub fn add_cheats(&self, cheats: &mut CheatMenu, scene: &Scene, counts: &[usize]) {
+        let mut prefabs: Vec<String> = self.state.borrow().prefabs.names().map(str::to_string).collect();
+        prefabs.sort();
+        for prefab in prefabs {
+            for &count in counts {
+                let stress = self.clone();
+                let prefab_name = prefab.clone();
+                cheats.button("Stress", &format!("Spawn {} x {}", count, prefab), move |_| stress.spawn(&prefab_name, count));
+            }
+        }
+        let stress = self.clone();
+        cheats.button("Stress", "Clear spawned", move |_| stress.clear());
+        let stress = self.clone();
+        cheats.toggle("Stress", "Culling", scene.is_culling(), move |_, on| stress.set_culling(on));
+        let stress = self.clone();
+        cheats.toggle("Stress", "LOD", scene.lod().is_some(), move |_, on| stress.set_lod(on));
+        let stress = self.clone();
+        cheats.toggle("Stress", "Sprite batching", scene.is_sprite_batching(), move |_, on| stress.set_batching(on));
+        let stress = self.clone();
+        cheats.button("Stress", "Report frame stats", move |_| stress.report());
+    }
+
+    /// Run queued commands and sample the last frame (`Game` calls this every frame)
+    pub(crate) fn update(&self, scene: &mut Scene, last_frame: Option<FrameStats>) {
+        let mut state = self.state.borrow_mut();
+        if let Some(stats) = last_frame {
+            if state.samples.len() == MEASURE_FRAMES {
+                state.samples.pop_front();
+            }
+            state.samples.push_back(stats);
+        }
+
+        if let Some((command, before, frames_left)) = state.measuring.take() {
+            if frames_left > 1 {
+                state.measuring = Some((command, before, frames_left - 1));
+            } else {
+                let report = StressReport { command, before, after: average(&state.samples) };
+                crate::log_info!("stress", "{}", report);
+                state.reports.push(report);
+            }
+        }
+
+        let commands = std::mem::take(&mut state.commands);
+        for command in commands {
+            state.apply(&command, scene);
+            // Commands in quick succession are measured together against the frames before the first
+            let before = match state.measuring.take() {
+                Some((_, before, _)) => before,
+                None => average(&state.samples),
+            };
+            state.samples.clear();
+            state.measuring = Some((command, before, MEASURE_FRAMES));
+        }
+    }
+}
+
+impl StressState {
+    fn apply(&mut self, command: &StressCommand, scene: &mut Scene) {
+        match command {
+            StressCommand::Spawn { prefab, count } => {
+                let (min, max) = scene.camera.get_view_rect();
+                for _ in 0..*count {
+                    let position = rng::with_shared(|rng| rng.point_in_rect(min, max));
+                    match self.prefabs.spawn(prefab, position, scene) {
+                        Some(id) => self.spawned.push(id),
+                        None => {
+                            crate::log_warn!("stress", "no prefab named '{}'", prefab);
+                            break;
+                        }
+                    }
+                }
+            }
+            StressCommand::Clear => {
+                for id in self.spawned.drain(..) {
+                    scene.remove_entity(id);
+                }
+            }
+            StressCommand::SetCulling(on) => scene.set_culling(*on),
+            StressCommand::SetLod(true) => {
+                let settings = self.stashed_lod.take().or_else(|| scene.lod().copied()).unwrap_or_default();
+                scene.set_lod(Some(settings));
+            }
+            StressCommand::SetLod(false) => {
+                self.stashed_lod = scene.lod().copied().or(self.stashed_lod);
+                scene.set_lod(None);
+            }
+            StressCommand::SetBatching(on) => scene.set_sprite_batching(*on),
+            StressCommand::Report => {}
+        }
+        self.spawned.retain(|id| scene.contains_entity(*id));
+    }
+}
+
+/// Mean of each section over the sampled frames
+fn average(samples: &VecDeque<FrameStats>) -> FrameStats {
+    let Some(last) = samples.back() else {
+        return FrameStats::default();
+    };
+    let count = samples.len() as f32;
+    let mean = |section: fn(&FrameStats) -> f32| samples.iter().map(section).sum::<f32>() / count;
+    FrameStats {
+        frame_time: mean(|stats| stats.frame_time),
+        input: mean(|stats| stats.input),
+        update: mean(|stats| stats.update),
+        physics: mean(|stats| stats.physics),
+        camera: mean(|stats| stats.camera),
+        draw: mean(|stats| stats.draw),
+        entities: last.entities,
+    }
+}
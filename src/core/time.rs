@@ -1,30 +1,131 @@
 use macroquad::prelude::*;
+use super::{Entity, EntityId, FrameStats};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A frame that took far longer than the target frame time (a GC pause, an OS stall, a
+/// shader compile), reported by `TimeManager::hitch`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hitch {
+    /// Game time when the hitch was detected, in seconds
+    pub time: f32,
+    /// Real length of the frame, in seconds
+    pub raw_dt: f32,
+    /// dt handed to the game (the smoothed dt when hitch smoothing is on), in seconds
+    pub dt: f32,
+    pub target_dt: f32,
+    /// Section timings of the frame that ran long, filled in by `Game`
+    pub blame: Option<FrameStats>,
+}
+
+impl fmt::Display for Hitch {
+    /// e.g. "hitch at 12.30s: 96.0 ms frame (target 16.7 ms, used 16.9 ms) - update 80.2 ms, draw 3.1 ms, ..."
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "hitch at {:.2}s: {:.1} ms frame (target {:.1} ms, used {:.1} ms)",
+            self.time,
+            self.raw_dt * 1000.0,
+            self.target_dt * 1000.0,
+            self.dt * 1000.0
+        )?;
+        let Some(stats) = &self.blame else {
+            return Ok(());
+        };
+        // Biggest section first
+        let mut sections = stats.sections();
+        sections.sort_by(|a, b| b.1.total_cmp(&a.1));
+        write!(f, " -")?;
+        for (name, ms) in sections {
+            write!(f, " {} {:.1} ms,", name, ms)?;
+        }
+        let outside = self.raw_dt * 1000.0 - sections.iter().map(|(_, ms)| ms).sum::<f32>();
+        write!(f, " {:.1} ms outside the game loop", outside.max(0.0))
+    }
+}
 
 /// Manages game time and provides utilities
 pub struct TimeManager {
     delta_time: f32,
+    raw_delta_time: f32,
     time_scale: f32,
     total_time: f32,
     last_frame_time: f64,
     fps_counter: FPSCounter,
+    // Hitch detection: frames longer than target * threshold are hitches
+    target_frame_time: f32,
+    hitch_threshold: f32,
+    smooth_hitches: bool,
+    // Running average of recent normal frames, used in place of a hitch's dt
+    average_frame_time: f32,
+    hitch: Option<Hitch>,
+    // Frame time before time scale and hitstop
+    unscaled_delta_time: f32,
+    // Hitstop: real seconds left and the scale used meanwhile
+    hitstop_remaining: f32,
+    hitstop_scale: f32,
+    // Slow-motion ramp: from, to, elapsed and duration in real seconds
+    scale_ramp: Option<(f32, f32, f32, f32)>,
+    // Used as every frame's dt instead of the measured one (deterministic mode)
+    fixed_delta: Option<f32>,
 }
 
 impl TimeManager {
     pub fn new() -> Self {
         Self {
             delta_time: 0.0,
+            raw_delta_time: 0.0,
             time_scale: 1.0,
             total_time: 0.0,
             last_frame_time: get_time(),
             fps_counter: FPSCounter::new(),
+            target_frame_time: 1.0 / 60.0,
+            hitch_threshold: 3.0,
+            smooth_hitches: false,
+            average_frame_time: 1.0 / 60.0,
+            hitch: None,
+            unscaled_delta_time: 0.0,
+            hitstop_remaining: 0.0,
+            hitstop_scale: 0.0,
+            scale_ramp: None,
+            fixed_delta: None,
         }
     }
     
     pub fn update(&mut self) {
         let current_time = get_time();
-        self.delta_time = ((current_time - self.last_frame_time) as f32) * self.time_scale;
+        let raw = (current_time - self.last_frame_time) as f32;
         self.last_frame_time = current_time;
+        self.raw_delta_time = raw;
+        
+        let is_hitch = raw > self.target_frame_time * self.hitch_threshold;
+        let dt = match self.fixed_delta {
+            Some(step) => step,
+            None if is_hitch && self.smooth_hitches => self.average_frame_time,
+            None => raw,
+        };
+        if !is_hitch {
+            self.average_frame_time += (raw - self.average_frame_time) * 0.1;
+        }
+        
+        self.update_scale_ramp(dt);
+        let scale = if self.hitstop_remaining > 0.0 {
+            self.hitstop_remaining -= dt;
+            self.hitstop_scale
+        } else {
+            self.time_scale
+        };
+        
+        self.unscaled_delta_time = dt;
+        self.delta_time = dt * scale;
         self.total_time += self.delta_time;
+        self.hitch = is_hitch.then_some(Hitch {
+            time: self.total_time,
+            raw_dt: raw,
+            dt,
+            target_dt: self.target_frame_time,
+            blame: None,
+        });
         self.fps_counter.update();
     }
     
@@ -32,6 +133,96 @@ impl TimeManager {
         self.delta_time
     }
     
+    /// Real time since the previous frame, before smoothing and time scale
+    pub fn raw_delta_time(&self) -> f32 {
+        self.raw_delta_time
+    }
+    
+    /// Frame time ignoring time scale, slow motion and hitstop (for UI and cameras)
+    pub fn unscaled_delta_time(&self) -> f32 {
+        self.unscaled_delta_time
+    }
+    
+    // Hitstop and slow motion
+    
+    /// Freeze gameplay time for `seconds` of real time (UI and cameras keep running).
+    /// A longer hitstop already running isn't cut short.
+    pub fn hitstop(&mut self, seconds: f32) {
+        self.hitstop_with_scale(seconds, 0.0);
+    }
+    
+    /// Slow gameplay time to `scale` for `seconds` of real time instead of freezing it
+    pub fn hitstop_with_scale(&mut self, seconds: f32, scale: f32) {
+        if seconds >= self.hitstop_remaining {
+            self.hitstop_remaining = seconds;
+            self.hitstop_scale = scale.max(0.0);
+        }
+    }
+    
+    pub fn is_in_hitstop(&self) -> bool {
+        self.hitstop_remaining > 0.0
+    }
+    
+    /// Ease the time scale to `scale` over `seconds` of real time (slow-motion ramps)
+    pub fn set_time_scale_over(&mut self, scale: f32, seconds: f32) {
+        if seconds <= 0.0 {
+            self.set_time_scale(scale);
+            return;
+        }
+        self.scale_ramp = Some((self.time_scale, scale.max(0.0), 0.0, seconds));
+    }
+    
+    fn update_scale_ramp(&mut self, dt: f32) {
+        let Some((from, to, elapsed, duration)) = &mut self.scale_ramp else {
+            return;
+        };
+        *elapsed += dt;
+        let t = (*elapsed / *duration).min(1.0);
+        self.time_scale = *from + (*to - *from) * t * t * (3.0 - 2.0 * t);
+        if t >= 1.0 {
+            self.scale_ramp = None;
+        }
+    }
+    
+    // Hitch handling
+    
+    /// Frame rate the game aims for; hitches are measured against it
+    pub fn set_target_fps(&mut self, fps: u32) {
+        self.target_frame_time = 1.0 / fps.max(1) as f32;
+        self.average_frame_time = self.target_frame_time;
+    }
+    
+    /// Frames longer than `multiplier` times the target frame time count as hitches (default 3)
+    pub fn set_hitch_threshold(&mut self, multiplier: f32) {
+        self.hitch_threshold = multiplier.max(1.0);
+    }
+    
+    /// Hand the game the average of recent frames instead of a hitch's real dt,
+    /// so a single stall doesn't teleport entities across the screen
+    pub fn set_hitch_smoothing(&mut self, enabled: bool) {
+        self.smooth_hitches = enabled;
+    }
+    
+    pub fn is_hitch_smoothing(&self) -> bool {
+        self.smooth_hitches
+    }
+    
+    /// Advance every frame by exactly `step` seconds, however long it really took, so the
+    /// same inputs always give the same result (None = measured frame time). Hitstop and
+    /// slow-motion ramps then count these steps instead of real time.
+    pub fn set_fixed_delta(&mut self, step: Option<f32>) {
+        self.fixed_delta = step.filter(|step| *step > 0.0);
+    }
+    
+    pub fn fixed_delta(&self) -> Option<f32> {
+        self.fixed_delta
+    }
+    
+    /// The hitch detected this frame, if the frame ran long
+    pub fn hitch(&self) -> Option<&Hitch> {
+        self.hitch.as_ref()
+    }
+    
     pub fn total_time(&self) -> f32 {
         self.total_time
     }
@@ -40,8 +231,10 @@ impl TimeManager {
         self.time_scale
     }
     
+    /// Set the time scale right away (cancels a ramp in progress)
     pub fn set_time_scale(&mut self, scale: f32) {
         self.time_scale = scale.max(0.0);
+        self.scale_ramp = None;
     }
     
     pub fn fps(&self) -> f32 {
@@ -78,4 +271,52 @@ impl FPSCounter {
     fn fps(&self) -> f32 {
         self.current_fps
     }
-}
\ No newline at end of file
+}
+/// Time scales of single entities and of tag groups within a scene (see
+/// `Scene::set_entity_time_scale`). They stack with each other and with the game's
+/// time scale.
+#[derive(Default)]
+pub(crate) struct TimeScales {
+    entities: HashMap<EntityId, f32>,
+    groups: HashMap<String, f32>,
+}
+
+impl TimeScales {
+    pub(crate) fn set_entity(&mut self, id: EntityId, scale: f32) {
+        self.entities.insert(id, scale.max(0.0));
+    }
+
+    pub(crate) fn clear_entity(&mut self, id: EntityId) {
+        self.entities.remove(&id);
+    }
+
+    pub(crate) fn entity(&self, id: EntityId) -> f32 {
+        self.entities.get(&id).copied().unwrap_or(1.0)
+    }
+
+    pub(crate) fn set_group(&mut self, tag: &str, scale: f32) {
+        self.groups.insert(tag.to_string(), scale.max(0.0));
+    }
+
+    pub(crate) fn clear_group(&mut self, tag: &str) {
+        self.groups.remove(tag);
+    }
+
+    pub(crate) fn group(&self, tag: &str) -> f32 {
+        self.groups.get(tag).copied().unwrap_or(1.0)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entities.clear();
+        self.groups.clear();
+    }
+
+    /// Combined scale of an entity: its own times that of every group it's tagged with
+    pub(crate) fn scale_of(&self, id: EntityId, entity: &dyn Entity) -> f32 {
+        let groups: f32 = self.groups.iter()
+            .filter(|(tag, _)| entity.has_tag(tag))
+            .map(|(_, scale)| scale)
+            .product();
+        self.entity(id) * groups
+    }
+}
@@ -0,0 +1,154 @@
+use macroquad::prelude::Vec2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use super::EntityId;
+
+/// Something a timeline does to its entity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScriptAction {
+    /// Play a sound registered with the game's `AudioManager`, at the entity's position
+    PlaySound(String),
+    EnableCollider,
+    DisableCollider,
+    Show,
+    Hide,
+    /// Remove the entity from the scene
+    Despawn,
+    /// Game-specific action, passed to `Entity::on_script_action` and reported by
+    /// `Scene::take_script_events`
+    Event(String),
+}
+
+/// An action and when it happens, in seconds after the timeline starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptKey {
+    pub at: f32,
+    pub action: ScriptAction,
+}
+
+/// Error returned when loading a timeline
+#[derive(Debug)]
+pub enum TimelineError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for TimelineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimelineError::Io(err) => write!(f, "failed to read timeline file: {}", err),
+            TimelineError::Format(msg) => write!(f, "invalid timeline data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for TimelineError {}
+
+impl From<std::io::Error> for TimelineError {
+    fn from(err: std::io::Error) -> Self {
+        TimelineError::Io(err)
+    }
+}
+
+/// Timed actions a scene runs on an entity, for scripted hazards and traps without code:
+///
+/// ```ignore
+/// let spikes = Timeline::new()
+///     .at(0.5, ScriptAction::PlaySound("spikes_out".into()))
+///     .at(0.5, ScriptAction::EnableCollider)
+///     .at(1.5, ScriptAction::DisableCollider)
+///     .looping(3.0);
+/// library.register("spike_trap", Prefab::new(build_spikes).with_timeline(spikes));
+/// ```
+///
+/// ```ron
+/// (keys: [(at: 0.5, action: PlaySound("spikes_out")), (at: 2.0, action: Despawn)], repeat: None)
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timeline {
+    pub keys: Vec<ScriptKey>,
+    /// Start over after this many seconds (None = run once)
+    pub repeat: Option<f32>,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn at(mut self, time: f32, action: ScriptAction) -> Self {
+        self.keys.push(ScriptKey { at: time.max(0.0), action });
+        self
+    }
+
+    pub fn looping(mut self, period: f32) -> Self {
+        self.repeat = (period > 0.0).then_some(period);
+        self
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TimelineError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    /// Parse a timeline from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, TimelineError> {
+        ron::from_str(data).map_err(|err| TimelineError::Format(err.to_string()))
+    }
+
+    /// Seconds until the last action
+    pub fn duration(&self) -> f32 {
+        self.keys.iter().map(|key| key.at).fold(0.0, f32::max)
+    }
+}
+
+/// An action a timeline ran, reported by `Scene::take_script_events`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptEvent {
+    pub entity: EntityId,
+    pub action: ScriptAction,
+    pub position: Option<Vec2>,
+}
+
+/// A timeline running on one entity
+pub(crate) struct ActiveTimeline {
+    pub entity: EntityId,
+    timeline: Timeline,
+    elapsed: f32,
+    // Index of the next key, with keys sorted by time
+    next: usize,
+}
+
+impl ActiveTimeline {
+    pub fn new(entity: EntityId, mut timeline: Timeline) -> Self {
+        timeline.keys.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self { entity, timeline, elapsed: 0.0, next: 0 }
+    }
+
+    /// Advance by `dt` and return the actions now due. Returns None once finished.
+    pub fn advance(&mut self, dt: f32) -> Option<Vec<ScriptAction>> {
+        self.elapsed += dt;
+        let mut due = vec![];
+        loop {
+            while let Some(key) = self.timeline.keys.get(self.next).filter(|key| key.at <= self.elapsed) {
+                due.push(key.action.clone());
+                self.next += 1;
+            }
+            match self.timeline.repeat {
+                Some(period) if self.elapsed >= period => {
+                    // Keys past the period never fire
+                    self.elapsed -= period;
+                    self.next = 0;
+                }
+                Some(_) => break,
+                None if self.next >= self.timeline.keys.len() => {
+                    return if due.is_empty() { None } else { Some(due) };
+                }
+                None => break,
+            }
+        }
+        Some(due)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod pacing;
+
+pub use pacing::{DirectorConfig, DirectorDecision, DirectorError, DirectorLogEntry, PacingDirector, PacingPhase};
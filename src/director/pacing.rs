@@ -0,0 +1,437 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use crate::core::rng;
+use crate::math::Easing;
+
+/// Error returned when loading a director config or saving its log
+#[derive(Debug)]
+pub enum DirectorError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for DirectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectorError::Io(err) => write!(f, "failed to access director file: {}", err),
+            DirectorError::Format(msg) => write!(f, "invalid director config: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DirectorError {}
+
+impl From<std::io::Error> for DirectorError {
+    fn from(err: std::io::Error) -> Self {
+        DirectorError::Io(err)
+    }
+}
+
+/// Stage of the tension and relief cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PacingPhase {
+    /// Spawns ramp up along `DirectorConfig::build_up_curve`
+    BuildUp,
+    /// The player is under pressure; spawns stay at their highest
+    Peak,
+    /// Few or no spawns and more drops, until the player calms down
+    Relax,
+}
+
+impl fmt::Display for PacingPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PacingPhase::BuildUp => "build-up",
+            PacingPhase::Peak => "peak",
+            PacingPhase::Relax => "relax",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Tuning of a `PacingDirector`, usually loaded from a RON file so designers can change
+/// it without a rebuild. Missing fields use the defaults:
+///
+/// ```ron
+/// (build_up_time: 90.0, max_spawn_rate: 4.0, build_up_curve: CubicIn, relax_drops: 3.0)
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DirectorConfig {
+    // Share of each proxy in the stress level
+    pub health_weight: f32,
+    pub damage_weight: f32,
+    pub kill_weight: f32,
+    /// Damage per second that counts as fully stressed
+    pub max_damage_rate: f32,
+    /// Kills per second that counts as fully stressed
+    pub max_kill_rate: f32,
+    /// Seconds over which recent damage and kills fade out
+    pub memory: f32,
+    /// Spawn points per second at the start and end of a build-up
+    pub min_spawn_rate: f32,
+    pub max_spawn_rate: f32,
+    pub relax_spawn_rate: f32,
+    /// How the spawn rate rises from min to max over `build_up_time`
+    pub build_up_curve: Easing,
+    pub build_up_time: f32,
+    /// Stress that turns a build-up into a peak
+    pub peak_stress: f32,
+    /// Seconds a peak lasts
+    pub peak_time: f32,
+    /// Shortest relax, in seconds
+    pub relax_time: f32,
+    /// Stress the player has to calm down to before the next build-up
+    pub calm_stress: f32,
+    // Drop chance multipliers of each phase
+    pub build_up_drops: f32,
+    pub peak_drops: f32,
+    pub relax_drops: f32,
+    /// Extra drop chance at full stress (0.5 = 50% more)
+    pub stress_drop_bonus: f32,
+    /// Most spawn points that can be saved up while nothing spawns
+    pub max_banked: f32,
+    /// Seconds between stress samples in the log (0 for none)
+    pub sample_interval: f32,
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            health_weight: 0.4,
+            damage_weight: 0.35,
+            kill_weight: 0.25,
+            max_damage_rate: 20.0,
+            max_kill_rate: 1.0,
+            memory: 5.0,
+            min_spawn_rate: 0.5,
+            max_spawn_rate: 3.0,
+            relax_spawn_rate: 0.0,
+            build_up_curve: Easing::QuadIn,
+            build_up_time: 60.0,
+            peak_stress: 0.75,
+            peak_time: 10.0,
+            relax_time: 15.0,
+            calm_stress: 0.3,
+            build_up_drops: 1.0,
+            peak_drops: 1.5,
+            relax_drops: 2.0,
+            stress_drop_bonus: 0.5,
+            max_banked: 10.0,
+            sample_interval: 1.0,
+        }
+    }
+}
+
+impl DirectorConfig {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DirectorError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    pub fn load_from_str(data: &str) -> Result<Self, DirectorError> {
+        ron::from_str(data).map_err(|err| DirectorError::Format(err.to_string()))
+    }
+}
+
+/// Something the director decided
+#[derive(Debug, Clone, PartialEq)]
+pub enum DirectorDecision {
+    PhaseChange { from: PacingPhase, to: PacingPhase, reason: &'static str },
+    /// Budget spent on a spawn (refused spawns aren't logged; the spawner asks every frame)
+    Spawn { cost: f32, banked: f32 },
+    Drop { chance: f32, dropped: bool },
+    /// Periodic reading of the player's stress
+    Sample { health: f32, damage_rate: f32, kill_rate: f32, spawn_rate: f32 },
+}
+
+/// One line of the director's log
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectorLogEntry {
+    /// Seconds since the director started
+    pub time: f32,
+    pub phase: PacingPhase,
+    pub stress: f32,
+    pub decision: DirectorDecision,
+}
+
+impl fmt::Display for DirectorLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{:>8.2}s] {:<8} stress {:.2}: ", self.time, self.phase, self.stress)?;
+        match &self.decision {
+            DirectorDecision::PhaseChange { from, to, reason } => write!(f, "{} -> {} ({})", from, to, reason),
+            DirectorDecision::Spawn { cost, banked } => write!(f, "spawn for {:.1} ({:.1} left)", cost, banked),
+            DirectorDecision::Drop { chance, dropped } => {
+                write!(f, "drop at {:.0}%: {}", chance * 100.0, if *dropped { "dropped" } else { "none" })
+            }
+            DirectorDecision::Sample { health, damage_rate, kill_rate, spawn_rate } => write!(
+                f,
+                "health {:.0}%, {:.1} dmg/s, {:.2} kills/s, spawning {:.2}/s",
+                health * 100.0, damage_rate, kill_rate, spawn_rate
+            ),
+        }
+    }
+}
+
+/// Pacing director: reads how stressed the player is from their health, recent damage
+/// and kill rate, and cycles between build-ups, peaks and relief by handing out spawn
+/// budget and scaling item drops. The game's spawners and loot code ask it before acting:
+///
+/// ```ignore
+/// let mut director = PacingDirector::new(DirectorConfig::load("data/director.ron")?);
+/// // each frame
+/// director.report_health(player.health / player.max_health);
+/// director.update(dt);
+/// if director.try_spawn(ZOMBIE_COST) {
+///     scene.add_entity(Box::new(Zombie::new(spawn_point)));
+/// }
+/// // when the player is hit or kills something
+/// director.report_damage(amount);
+/// director.report_kill();
+/// if director.roll_drop(0.1) {
+///     scene.add_entity(Box::new(HealthPack::new(position)));
+/// }
+/// ```
+///
+/// Every decision goes to a log (see `decisions` and `save_log`) so designers can see
+/// why a fight went the way it did.
+pub struct PacingDirector {
+    config: DirectorConfig,
+    phase: PacingPhase,
+    phase_time: f32,
+    time: f32,
+    health: f32,
+    // Damage and kills, fading out over `memory` seconds
+    recent_damage: f32,
+    recent_kills: f32,
+    stress: f32,
+    banked: f32,
+    since_sample: f32,
+    log: Vec<DirectorLogEntry>,
+    log_limit: Option<usize>,
+    echo: bool,
+}
+
+impl Default for PacingDirector {
+    fn default() -> Self {
+        Self::new(DirectorConfig::default())
+    }
+}
+
+impl PacingDirector {
+    pub fn new(config: DirectorConfig) -> Self {
+        Self {
+            config,
+            phase: PacingPhase::BuildUp,
+            phase_time: 0.0,
+            time: 0.0,
+            health: 1.0,
+            recent_damage: 0.0,
+            recent_kills: 0.0,
+            stress: 0.0,
+            banked: 0.0,
+            since_sample: 0.0,
+            log: vec![],
+            log_limit: None,
+            echo: false,
+        }
+    }
+
+    /// Keep only the latest entries of the log (all by default)
+    pub fn with_log_limit(mut self, entries: usize) -> Self {
+        self.log_limit = Some(entries);
+        self
+    }
+
+    /// Also send each decision to the game log (category "director") as it's made
+    pub fn with_logging(mut self, enabled: bool) -> Self {
+        self.echo = enabled;
+        self
+    }
+
+    pub fn config(&self) -> &DirectorConfig {
+        &self.config
+    }
+
+    /// Retune while running, e.g. after reloading the config file
+    pub fn set_config(&mut self, config: DirectorConfig) {
+        self.config = config;
+    }
+
+    pub fn phase(&self) -> PacingPhase {
+        self.phase
+    }
+
+    /// Seconds since the current phase started
+    pub fn phase_time(&self) -> f32 {
+        self.phase_time
+    }
+
+    /// How stressed the player is, from 0 (calm) to 1
+    pub fn stress(&self) -> f32 {
+        self.stress
+    }
+
+    /// Spawn points saved up
+    pub fn banked(&self) -> f32 {
+        self.banked
+    }
+
+    /// Player health as a fraction of the maximum
+    pub fn report_health(&mut self, fraction: f32) {
+        self.health = fraction.clamp(0.0, 1.0);
+    }
+
+    pub fn report_damage(&mut self, amount: f32) {
+        self.recent_damage += amount.max(0.0);
+    }
+
+    pub fn report_kill(&mut self) {
+        self.recent_kills += 1.0;
+    }
+
+    /// Recent damage per second
+    pub fn damage_rate(&self) -> f32 {
+        self.recent_damage / self.config.memory.max(f32::EPSILON)
+    }
+
+    /// Recent kills per second
+    pub fn kill_rate(&self) -> f32 {
+        self.recent_kills / self.config.memory.max(f32::EPSILON)
+    }
+
+    /// Spawn points earned per second in the current phase
+    pub fn spawn_rate(&self) -> f32 {
+        let config = &self.config;
+        match self.phase {
+            PacingPhase::BuildUp => {
+                let progress = config.build_up_curve.apply(self.phase_time / config.build_up_time.max(f32::EPSILON));
+                config.min_spawn_rate + (config.max_spawn_rate - config.min_spawn_rate) * progress
+            }
+            PacingPhase::Peak => config.max_spawn_rate,
+            PacingPhase::Relax => config.relax_spawn_rate,
+        }
+    }
+
+    /// Advance the cycle and earn spawn budget
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+        self.phase_time += dt;
+        let fade = (-dt / self.config.memory.max(f32::EPSILON)).exp();
+        self.recent_damage *= fade;
+        self.recent_kills *= fade;
+        self.stress = self.measure_stress();
+
+        let config = &self.config;
+        let next = match self.phase {
+            PacingPhase::BuildUp if self.stress >= config.peak_stress => Some((PacingPhase::Peak, "stress reached the peak")),
+            PacingPhase::Peak if self.phase_time >= config.peak_time => Some((PacingPhase::Relax, "peak time is up")),
+            PacingPhase::Relax if self.phase_time >= config.relax_time && self.stress <= config.calm_stress => {
+                Some((PacingPhase::BuildUp, "player calmed down"))
+            }
+            _ => None,
+        };
+        if let Some((phase, reason)) = next {
+            self.change_phase(phase, reason);
+        }
+
+        self.banked = (self.banked + self.spawn_rate() * dt).min(self.config.max_banked);
+
+        if self.config.sample_interval > 0.0 {
+            self.since_sample += dt;
+            if self.since_sample >= self.config.sample_interval {
+                self.since_sample = 0.0;
+                self.record(DirectorDecision::Sample {
+                    health: self.health,
+                    damage_rate: self.damage_rate(),
+                    kill_rate: self.kill_rate(),
+                    spawn_rate: self.spawn_rate(),
+                });
+            }
+        }
+    }
+
+    /// Jump to a phase, e.g. for a scripted ambush or a safe room
+    pub fn force_phase(&mut self, phase: PacingPhase) {
+        if phase != self.phase {
+            self.change_phase(phase, "forced");
+        }
+    }
+
+    /// Spend budget on a spawn costing `cost` points, if there's enough saved up
+    pub fn try_spawn(&mut self, cost: f32) -> bool {
+        if self.banked < cost {
+            return false;
+        }
+        self.banked -= cost;
+        self.record(DirectorDecision::Spawn { cost, banked: self.banked });
+        true
+    }
+
+    /// A drop chance scaled for the current phase and stress
+    pub fn drop_chance(&self, base: f32) -> f32 {
+        let phase = match self.phase {
+            PacingPhase::BuildUp => self.config.build_up_drops,
+            PacingPhase::Peak => self.config.peak_drops,
+            PacingPhase::Relax => self.config.relax_drops,
+        };
+        (base * phase * (1.0 + self.stress * self.config.stress_drop_bonus)).clamp(0.0, 1.0)
+    }
+
+    /// Decide whether an item with a `base` chance drops
+    pub fn roll_drop(&mut self, base: f32) -> bool {
+        let chance = self.drop_chance(base);
+        let dropped = rng::with_shared(|rng| rng.chance(chance));
+        self.record(DirectorDecision::Drop { chance, dropped });
+        dropped
+    }
+
+    /// Decisions so far, oldest first
+    pub fn decisions(&self) -> &[DirectorLogEntry] {
+        &self.log
+    }
+
+    pub fn clear_log(&mut self) {
+        self.log.clear();
+    }
+
+    /// Write the log as text, one decision per line
+    pub fn save_log<P: AsRef<Path>>(&self, path: P) -> Result<(), DirectorError> {
+        let text: String = self.log.iter().map(|entry| format!("{}\n", entry)).collect();
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    fn measure_stress(&self) -> f32 {
+        let config = &self.config;
+        let damage = (self.damage_rate() / config.max_damage_rate.max(f32::EPSILON)).min(1.0);
+        let kills = (self.kill_rate() / config.max_kill_rate.max(f32::EPSILON)).min(1.0);
+        let total = config.health_weight + config.damage_weight + config.kill_weight;
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let weighted = config.health_weight * (1.0 - self.health) + config.damage_weight * damage + config.kill_weight * kills;
+        (weighted / total).clamp(0.0, 1.0)
+    }
+
+    fn change_phase(&mut self, phase: PacingPhase, reason: &'static str) {
+        let from = self.phase;
+        self.phase = phase;
+        self.phase_time = 0.0;
+        self.record(DirectorDecision::PhaseChange { from, to: phase, reason });
+    }
+
+    fn record(&mut self, decision: DirectorDecision) {
+        let entry = DirectorLogEntry { time: self.time, phase: self.phase, stress: self.stress, decision };
+        if self.echo {
+            crate::log_info!("director", "{}", entry);
+        }
+        self.log.push(entry);
+        let limit = self.log_limit.unwrap_or(usize::MAX);
+        if self.log.len() > limit {
+            let excess = self.log.len() - limit;
+            self.log.drain(..excess);
+        }
+    }
+}
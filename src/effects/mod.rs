@@ -0,0 +1,5 @@
+pub mod particles;
+pub mod presets;
+
+pub use particles::{ParticleEffect, EmitterConfig, ParticleShape};
+pub use presets::EffectLibrary;
@@ -0,0 +1,252 @@
+use macroquad::prelude::*;
+use crate::core::{rng, Entity, UpdateContext};
+use crate::math::Easing;
+
+/// How each particle is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParticleShape {
+    Circle,
+    /// Square rotated by the particle's spin
+    Square,
+    /// Hollow circle - good for shockwaves
+    Ring { thickness: f32 },
+    /// Flat rectangle that flips as it spins, like a paper scrap
+    Confetti,
+}
+
+/// Settings for one particle emitter
+#[derive(Debug, Clone)]
+pub struct EmitterConfig {
+    pub shape: ParticleShape,
+    /// Particles emitted immediately when the effect starts
+    pub burst_count: u32,
+    /// Particles per second while `duration` lasts (0 = burst only)
+    pub rate: f32,
+    /// How long continuous emission runs, in seconds
+    pub duration: f32,
+    /// Random lifetime range in seconds
+    pub lifetime: (f32, f32),
+    /// Random launch speed range
+    pub speed: (f32, f32),
+    /// Launch direction in radians (0 = right, -PI/2 = up)
+    pub direction: f32,
+    /// Total angle of the launch cone in radians (TAU = all directions)
+    pub spread: f32,
+    /// Particles spawn at a random point within this radius
+    pub spawn_radius: f32,
+    pub gravity: Vec2,
+    /// Velocity damping per second
+    pub drag: f32,
+    /// Random starting size range (radius for round shapes, half-size for others)
+    pub size: (f32, f32),
+    /// Size multiplier reached at the end of a particle's life
+    pub end_size_scale: f32,
+    pub size_easing: Easing,
+    /// Random angular velocity range in radians per second
+    pub spin: (f32, f32),
+    /// Each particle picks one of these colors
+    pub colors: Vec<Color>,
+    /// Fade alpha to zero over the particle's life
+    pub fade_out: bool,
+}
+
+impl Default for EmitterConfig {
+    fn default() -> Self {
+        Self {
+            shape: ParticleShape::Circle,
+            burst_count: 10,
+            rate: 0.0,
+            duration: 0.0,
+            lifetime: (0.5, 1.0),
+            speed: (50.0, 100.0),
+            direction: 0.0,
+            spread: std::f32::consts::TAU,
+            spawn_radius: 0.0,
+            gravity: Vec2::ZERO,
+            drag: 0.0,
+            size: (2.0, 4.0),
+            end_size_scale: 1.0,
+            size_easing: Easing::Linear,
+            spin: (0.0, 0.0),
+            colors: vec![WHITE],
+            fade_out: true,
+        }
+    }
+}
+
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    rotation: f32,
+    spin: f32,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    color: Color,
+    emitter: usize,
+}
+
+struct Emitter {
+    config: EmitterConfig,
+    elapsed: f32,
+    pending: f32,
+    started: bool,
+}
+
+impl Emitter {
+    fn is_finished(&self) -> bool {
+        self.started && (self.config.rate <= 0.0 || self.elapsed >= self.config.duration)
+    }
+}
+
+/// A self-contained particle effect entity.
+/// It deactivates itself once every emitter is done and all particles have died,
+/// so `Scene::clear_inactive` cleans it up.
+pub struct ParticleEffect {
+    position: Vec2,
+    emitters: Vec<Emitter>,
+    particles: Vec<Particle>,
+    active: bool,
+}
+
+impl ParticleEffect {
+    pub fn new(position: Vec2, emitters: Vec<EmitterConfig>) -> Self {
+        Self {
+            position,
+            emitters: emitters
+                .into_iter()
+                .map(|config| Emitter { config, elapsed: 0.0, pending: 0.0, started: false })
+                .collect(),
+            particles: vec![],
+            active: true,
+        }
+    }
+
+    /// Move the emission point (already spawned particles stay where they are)
+    pub fn set_position(&mut self, position: Vec2) {
+        self.position = position;
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn particle_count(&self) -> usize {
+        self.particles.len()
+    }
+
+    /// Check if all emitters are done and every particle has died
+    pub fn is_finished(&self) -> bool {
+        self.particles.is_empty() && self.emitters.iter().all(Emitter::is_finished)
+    }
+
+    /// Stop emitting new particles; the effect ends once the live ones die
+    pub fn stop_emitting(&mut self) {
+        for emitter in &mut self.emitters {
+            emitter.started = true;
+            emitter.elapsed = emitter.config.duration;
+        }
+    }
+
+    fn emit(&mut self, emitter_index: usize, count: u32) {
+        let config = &self.emitters[emitter_index].config;
+        rng::with_shared(|rng| {
+            for _ in 0..count {
+                let angle = config.direction + rng.range(-0.5, 0.5) * config.spread;
+                let speed = rng.range(config.speed.0, config.speed.1);
+                let offset = if config.spawn_radius > 0.0 { rng.point_in_circle(config.spawn_radius) } else { Vec2::ZERO };
+                let color = rng.pick(&config.colors).copied().unwrap_or(WHITE);
+
+                self.particles.push(Particle {
+                    position: self.position + offset,
+                    velocity: Vec2::from_angle(angle) * speed,
+                    rotation: rng.angle(),
+                    spin: rng.range(config.spin.0, config.spin.1),
+                    age: 0.0,
+                    lifetime: rng.range(config.lifetime.0, config.lifetime.1).max(0.01),
+                    size: rng.range(config.size.0, config.size.1),
+                    color,
+                    emitter: emitter_index,
+                });
+            }
+        });
+    }
+}
+
+impl Entity for ParticleEffect {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        let dt = ctx.dt;
+        // Emission
+        for i in 0..self.emitters.len() {
+            let emitter = &mut self.emitters[i];
+            let mut to_emit = 0;
+
+            if !emitter.started {
+                emitter.started = true;
+                to_emit += emitter.config.burst_count;
+            } else if emitter.config.rate > 0.0 && emitter.elapsed < emitter.config.duration {
+                emitter.pending += emitter.config.rate * dt;
+                to_emit += emitter.pending as u32;
+                emitter.pending = emitter.pending.fract();
+                emitter.elapsed += dt;
+            }
+
+            if to_emit > 0 {
+                self.emit(i, to_emit);
+            }
+        }
+
+        // Simulation
+        let emitters = &self.emitters;
+        for particle in &mut self.particles {
+            let config = &emitters[particle.emitter].config;
+            particle.age += dt;
+            particle.velocity += config.gravity * dt;
+            particle.velocity *= 1.0 / (1.0 + config.drag * dt);
+            particle.position += particle.velocity * dt;
+            particle.rotation += particle.spin * dt;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+
+        if self.is_finished() {
+            self.active = false;
+        }
+    }
+
+    fn draw(&self) {
+        for particle in &self.particles {
+            let config = &self.emitters[particle.emitter].config;
+            let t = particle.age / particle.lifetime;
+            let size = particle.size * config.size_easing.interpolate(1.0, config.end_size_scale, t);
+            let mut color = particle.color;
+            if config.fade_out {
+                color.a *= 1.0 - t;
+            }
+
+            let (x, y) = (particle.position.x, particle.position.y);
+            match config.shape {
+                ParticleShape::Circle => draw_circle(x, y, size, color),
+                ParticleShape::Square => draw_poly(x, y, 4, size, particle.rotation.to_degrees(), color),
+                ParticleShape::Ring { thickness } => draw_circle_lines(x, y, size, thickness, color),
+                ParticleShape::Confetti => {
+                    // Squash one axis with the spin to fake a 3D flip
+                    let half = Vec2::new(size, size * 0.5 * particle.rotation.sin().abs().max(0.15));
+                    let rotation = Vec2::from_angle(particle.rotation * 0.5);
+                    let corners = [
+                        Vec2::new(-half.x, -half.y),
+                        Vec2::new(half.x, -half.y),
+                        Vec2::new(half.x, half.y),
+                        Vec2::new(-half.x, half.y),
+                    ]
+                    .map(|corner| particle.position + rotation.rotate(corner));
+                    draw_triangle(corners[0], corners[1], corners[2], color);
+                    draw_triangle(corners[0], corners[2], corners[3], color);
+                }
+            }
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.active
+    }
+}
@@ -0,0 +1,197 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+use crate::math::Easing;
+use super::particles::{EmitterConfig, ParticleEffect, ParticleShape};
+
+/// Named, ready-made juice effects.
+///
+/// ```ignore
+/// let effects = EffectLibrary::with_defaults();
+/// if let Some(effect) = effects.spawn("confetti", player_pos) {
+///     scene.add_entity(Box::new(effect));
+/// }
+/// ```
+pub struct EffectLibrary {
+    presets: HashMap<String, Vec<EmitterConfig>>,
+}
+
+impl EffectLibrary {
+    /// Create an empty library
+    pub fn new() -> Self {
+        Self {
+            presets: HashMap::new(),
+        }
+    }
+
+    /// Create a library with the built-in presets:
+    /// "confetti", "coin_sparkle", "dust_landing", "level_up", "death_poof"
+    pub fn with_defaults() -> Self {
+        let mut library = Self::new();
+        library.register("confetti", confetti_burst());
+        library.register("coin_sparkle", coin_sparkle());
+        library.register("dust_landing", dust_landing());
+        library.register("level_up", level_up_ring());
+        library.register("death_poof", death_poof());
+        library
+    }
+
+    /// Add or replace a preset
+    pub fn register(&mut self, name: &str, emitters: Vec<EmitterConfig>) {
+        self.presets.insert(name.to_string(), emitters);
+    }
+
+    /// Get a preset's emitters (clone and tweak them to make variants)
+    pub fn get(&self, name: &str) -> Option<&Vec<EmitterConfig>> {
+        self.presets.get(name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.presets.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.presets.keys().map(|name| name.as_str())
+    }
+
+    /// Create an effect entity for a preset at the given position
+    pub fn spawn(&self, name: &str, position: Vec2) -> Option<ParticleEffect> {
+        self.presets
+            .get(name)
+            .map(|emitters| ParticleEffect::new(position, emitters.clone()))
+    }
+}
+
+impl Default for EffectLibrary {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+// === Built-in presets ===
+
+/// Colorful paper scraps shot upward that flutter down
+pub fn confetti_burst() -> Vec<EmitterConfig> {
+    vec![EmitterConfig {
+        shape: ParticleShape::Confetti,
+        burst_count: 80,
+        lifetime: (1.5, 2.5),
+        speed: (200.0, 450.0),
+        direction: -FRAC_PI_2,
+        spread: PI * 0.6,
+        gravity: Vec2::new(0.0, 500.0),
+        drag: 2.5,
+        size: (4.0, 7.0),
+        spin: (-12.0, 12.0),
+        colors: vec![RED, GOLD, GREEN, SKYBLUE, PINK, VIOLET],
+        ..Default::default()
+    }]
+}
+
+/// Quick golden glints for pickups
+pub fn coin_sparkle() -> Vec<EmitterConfig> {
+    vec![EmitterConfig {
+        shape: ParticleShape::Square,
+        burst_count: 12,
+        lifetime: (0.3, 0.6),
+        speed: (40.0, 120.0),
+        spread: TAU,
+        drag: 4.0,
+        size: (2.0, 4.0),
+        end_size_scale: 0.0,
+        size_easing: Easing::QuadIn,
+        spin: (-6.0, 6.0),
+        colors: vec![GOLD, YELLOW, WHITE],
+        ..Default::default()
+    }]
+}
+
+/// Soft puffs kicked sideways when landing
+pub fn dust_landing() -> Vec<EmitterConfig> {
+    let puff = EmitterConfig {
+        shape: ParticleShape::Circle,
+        burst_count: 5,
+        lifetime: (0.35, 0.6),
+        speed: (40.0, 90.0),
+        spread: PI * 0.25,
+        gravity: Vec2::new(0.0, -30.0),
+        drag: 5.0,
+        size: (3.0, 5.0),
+        end_size_scale: 2.2,
+        size_easing: Easing::QuadOut,
+        colors: vec![Color::new(0.8, 0.75, 0.65, 0.6), Color::new(0.6, 0.58, 0.55, 0.6)],
+        ..Default::default()
+    };
+
+    vec![
+        EmitterConfig { direction: PI + 0.2, ..puff.clone() },
+        EmitterConfig { direction: -0.2, ..puff },
+    ]
+}
+
+/// Expanding shockwave ring with rising sparkles
+pub fn level_up_ring() -> Vec<EmitterConfig> {
+    vec![
+        EmitterConfig {
+            shape: ParticleShape::Ring { thickness: 4.0 },
+            burst_count: 1,
+            lifetime: (0.7, 0.7),
+            speed: (0.0, 0.0),
+            size: (10.0, 10.0),
+            end_size_scale: 8.0,
+            size_easing: Easing::CubicOut,
+            colors: vec![GOLD],
+            ..Default::default()
+        },
+        EmitterConfig {
+            shape: ParticleShape::Square,
+            burst_count: 20,
+            rate: 30.0,
+            duration: 0.4,
+            lifetime: (0.6, 1.0),
+            speed: (60.0, 140.0),
+            direction: -FRAC_PI_2,
+            spread: PI * 0.5,
+            spawn_radius: 20.0,
+            drag: 1.5,
+            size: (2.0, 3.5),
+            end_size_scale: 0.2,
+            spin: (-4.0, 4.0),
+            colors: vec![GOLD, WHITE, YELLOW],
+            ..Default::default()
+        },
+    ]
+}
+
+/// Cartoon smoke puff with a few bits of debris
+pub fn death_poof() -> Vec<EmitterConfig> {
+    vec![
+        EmitterConfig {
+            shape: ParticleShape::Circle,
+            burst_count: 18,
+            lifetime: (0.4, 0.8),
+            speed: (60.0, 160.0),
+            spread: TAU,
+            spawn_radius: 6.0,
+            drag: 4.0,
+            size: (6.0, 11.0),
+            end_size_scale: 1.6,
+            size_easing: Easing::QuadOut,
+            colors: vec![WHITE, LIGHTGRAY, Color::new(0.85, 0.85, 0.9, 1.0)],
+            ..Default::default()
+        },
+        EmitterConfig {
+            shape: ParticleShape::Square,
+            burst_count: 8,
+            lifetime: (0.5, 0.9),
+            speed: (120.0, 240.0),
+            direction: -FRAC_PI_2,
+            spread: PI,
+            gravity: Vec2::new(0.0, 600.0),
+            size: (2.0, 3.0),
+            spin: (-10.0, 10.0),
+            colors: vec![DARKGRAY, GRAY],
+            ..Default::default()
+        },
+    ]
+}
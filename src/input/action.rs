@@ -1,7 +1,12 @@
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use super::gestures::Gesture;
+use super::keys::{key_from_name, key_name, mouse_button_from_name, mouse_button_name};
 
 /// Represents a game action that can be triggered by various inputs
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     // Movement actions
     MoveUp,
@@ -15,6 +20,8 @@ pub enum Action {
     Defend,
     Interact,
     Pause,
+    /// Save a screenshot (see `GameConfig::screenshot_with_action`)
+    Screenshot,
 
     //camera actions
      CameraZoomIn,
@@ -35,14 +42,29 @@ impl Action {
     }
 }
 
+/// String prefix marking a physical key binding
+const PHYSICAL_PREFIX: &str = "Physical:";
+
+/// String prefix marking a touch gesture binding
+const TOUCH_PREFIX: &str = "Touch:";
+
 /// Different types of input bindings
-#[derive(Debug, Clone)]
+///
+/// Serialized as a readable string such as `"W"`, `"LeftControl+S"`, `"Physical:W"`, `"MouseLeft"`
+/// or `"Touch:SwipeLeft"`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
 pub enum InputBinding {
     Key(KeyBinding),
+    /// A key by physical position, named after the key at that spot on US QWERTY.
+    /// Resolved through the input manager's `KeyboardLayout`, so WASD stays WASD-shaped on AZERTY.
+    Physical(KeyBinding),
     Mouse(MouseBinding),
+    /// A touch gesture (see `Gesture`)
+    Gesture(Gesture),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct KeyBinding {
     pub key: KeyCode,
     pub modifiers: Vec<KeyCode>, // For Ctrl+S, Alt+F4, etc.
@@ -62,7 +84,7 @@ impl KeyBinding {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MouseBinding {
     pub button: MouseButton,
 }
@@ -83,7 +105,85 @@ impl InputBinding {
         InputBinding::Key(KeyBinding::new(key).with_modifier(modifier))
     }
     
+    /// Bind the key at a physical position (given as the US QWERTY key there)
+    pub fn physical(position: KeyCode) -> Self {
+        InputBinding::Physical(KeyBinding::new(position))
+    }
+    
     pub fn mouse(button: MouseButton) -> Self {
         InputBinding::Mouse(MouseBinding::new(button))
     }
-}
\ No newline at end of file
+    
+    pub fn gesture(gesture: Gesture) -> Self {
+        InputBinding::Gesture(gesture)
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for modifier in &self.modifiers {
+            write!(f, "{}+", key_name(*modifier))?;
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key_part = parts.pop().unwrap_or_default();
+        let key = key_from_name(key_part).ok_or_else(|| format!("unknown key '{}'", key_part))?;
+
+        let mut binding = KeyBinding::new(key);
+        for part in parts {
+            let modifier = key_from_name(part).ok_or_else(|| format!("unknown modifier '{}'", part))?;
+            binding = binding.with_modifier(modifier);
+        }
+        Ok(binding)
+    }
+}
+
+impl fmt::Display for InputBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputBinding::Key(binding) => write!(f, "{}", binding),
+            InputBinding::Physical(binding) => write!(f, "{}{}", PHYSICAL_PREFIX, binding),
+            InputBinding::Mouse(binding) => write!(f, "{}", mouse_button_name(binding.button)),
+            InputBinding::Gesture(gesture) => write!(f, "{}{}", TOUCH_PREFIX, gesture),
+        }
+    }
+}
+
+impl FromStr for InputBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(button) = mouse_button_from_name(s) {
+            return Ok(InputBinding::mouse(button));
+        }
+        if let Some(gesture) = s.strip_prefix(TOUCH_PREFIX) {
+            return Ok(InputBinding::Gesture(gesture.parse()?));
+        }
+        if let Some(position) = s.strip_prefix(PHYSICAL_PREFIX) {
+            return Ok(InputBinding::Physical(position.parse()?));
+        }
+        Ok(InputBinding::Key(s.parse()?))
+    }
+}
+
+impl From<InputBinding> for String {
+    fn from(binding: InputBinding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for InputBinding {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
@@ -0,0 +1,87 @@
+use super::{Action, InputManager};
+
+/// What happened to an action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionEventKind {
+    /// Activated this frame (also fired by completed sequences)
+    Pressed,
+    /// Deactivated this frame
+    Released,
+    /// Active this frame, fired every frame while held
+    Held,
+    DoubleTapped,
+    LongPressed,
+}
+
+/// Passed to action callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionEvent {
+    pub action: Action,
+    pub kind: ActionEventKind,
+    /// How long the action has been held (for `Released`, how long it was held)
+    pub hold_duration: f32,
+}
+
+/// Handle to a registered callback, used to remove it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CallbackId(u64);
+
+struct ActionCallback {
+    id: CallbackId,
+    action: Action,
+    kind: ActionEventKind,
+    callback: Box<dyn FnMut(&ActionEvent)>,
+}
+
+/// Closures registered on an `InputManager`
+#[derive(Default)]
+pub(crate) struct ActionCallbacks {
+    callbacks: Vec<ActionCallback>,
+    next_id: u64,
+}
+
+impl ActionCallbacks {
+    pub(crate) fn add(&mut self, action: Action, kind: ActionEventKind, callback: Box<dyn FnMut(&ActionEvent)>) -> CallbackId {
+        let id = CallbackId(self.next_id);
+        self.next_id += 1;
+        self.callbacks.push(ActionCallback { id, action, kind, callback });
+        id
+    }
+
+    pub(crate) fn remove(&mut self, id: CallbackId) -> bool {
+        let count = self.callbacks.len();
+        self.callbacks.retain(|callback| callback.id != id);
+        self.callbacks.len() != count
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.callbacks.clear();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    /// Run every callback whose event happened this frame, in registration order
+    pub(crate) fn fire(&mut self, input: &InputManager) {
+        for entry in &mut self.callbacks {
+            let action = &entry.action;
+            let happened = match entry.kind {
+                ActionEventKind::Pressed => input.is_action_just_activated(action),
+                ActionEventKind::Released => input.is_action_just_deactivated(action),
+                ActionEventKind::Held => input.is_action_active(action),
+                ActionEventKind::DoubleTapped => input.is_action_double_tapped(action),
+                ActionEventKind::LongPressed => input.is_action_long_pressed(action),
+            };
+            if !happened {
+                continue;
+            }
+
+            let hold_duration = match entry.kind {
+                ActionEventKind::Released => input.action_released_after(action).unwrap_or(0.0),
+                _ => input.action_hold_duration(action),
+            };
+            (entry.callback)(&ActionEvent { action: action.clone(), kind: entry.kind, hold_duration });
+        }
+    }
+}
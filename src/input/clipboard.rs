@@ -0,0 +1,22 @@
+use macroquad::input::KeyCode;
+use macroquad::miniquad::window::{clipboard_get, clipboard_set};
+
+/// Text on the system clipboard.
+///
+/// Browsers only hand the clipboard to a page inside a paste event, so on the web
+/// this returns the text of the last paste (None until the player has pasted).
+pub fn get_clipboard() -> Option<String> {
+    clipboard_get()
+}
+
+/// Put text on the system clipboard (seeds, run codes, console output)
+pub fn set_clipboard(text: &str) {
+    clipboard_set(text);
+}
+
+/// Control on Windows/Linux/web, Command (Super) on macOS
+pub(crate) fn is_shortcut_modifier_down(is_down: impl Fn(KeyCode) -> bool) -> bool {
+    [KeyCode::LeftControl, KeyCode::RightControl, KeyCode::LeftSuper, KeyCode::RightSuper]
+        .into_iter()
+        .any(is_down)
+}
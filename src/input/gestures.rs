@@ -0,0 +1,269 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// A finger on the screen during one frame
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TouchPoint {
+    pub id: u64,
+    pub position: Vec2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SwipeDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl SwipeDirection {
+    /// Direction closest to a screen-space movement
+    pub fn from_vector(vector: Vec2) -> Self {
+        if vector.x.abs() >= vector.y.abs() {
+            if vector.x >= 0.0 { SwipeDirection::Right } else { SwipeDirection::Left }
+        } else if vector.y >= 0.0 {
+            SwipeDirection::Down
+        } else {
+            SwipeDirection::Up
+        }
+    }
+}
+
+/// A recognized touch gesture, in screen pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GestureEvent {
+    /// A quick touch that barely moved
+    Tap { position: Vec2 },
+    /// A finger held still past the long-press time (sent once, while still held)
+    LongPress { position: Vec2 },
+    /// A quick flick; `velocity` is in pixels per second
+    Swipe { direction: SwipeDirection, start: Vec2, end: Vec2, velocity: Vec2 },
+    /// Two fingers moved apart (`scale` above 1) or together this frame
+    Pinch { center: Vec2, scale: f32 },
+    /// Two fingers moved together across the screen this frame
+    Pan { center: Vec2, delta: Vec2 },
+}
+
+/// Gestures that can be bound to actions, written as `"Touch:SwipeLeft"` in bindings.
+/// Taps and swipes are active for one frame; the others while they happen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gesture {
+    Tap,
+    LongPress,
+    Swipe(SwipeDirection),
+    PinchIn,
+    PinchOut,
+    TwoFingerPan,
+}
+
+impl fmt::Display for Gesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Gesture::Tap => "Tap",
+            Gesture::LongPress => "LongPress",
+            Gesture::Swipe(SwipeDirection::Up) => "SwipeUp",
+            Gesture::Swipe(SwipeDirection::Down) => "SwipeDown",
+            Gesture::Swipe(SwipeDirection::Left) => "SwipeLeft",
+            Gesture::Swipe(SwipeDirection::Right) => "SwipeRight",
+            Gesture::PinchIn => "PinchIn",
+            Gesture::PinchOut => "PinchOut",
+            Gesture::TwoFingerPan => "TwoFingerPan",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Gesture {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "Tap" => Ok(Gesture::Tap),
+            "LongPress" => Ok(Gesture::LongPress),
+            "SwipeUp" => Ok(Gesture::Swipe(SwipeDirection::Up)),
+            "SwipeDown" => Ok(Gesture::Swipe(SwipeDirection::Down)),
+            "SwipeLeft" => Ok(Gesture::Swipe(SwipeDirection::Left)),
+            "SwipeRight" => Ok(Gesture::Swipe(SwipeDirection::Right)),
+            "PinchIn" => Ok(Gesture::PinchIn),
+            "PinchOut" => Ok(Gesture::PinchOut),
+            "TwoFingerPan" => Ok(Gesture::TwoFingerPan),
+            other => Err(format!("unknown gesture '{}'", other)),
+        }
+    }
+}
+
+/// A finger being tracked from when it touched down
+struct TrackedTouch {
+    start: Vec2,
+    position: Vec2,
+    held: f32,
+    // Moved too far to be a tap or long press
+    moved: bool,
+    // Was down with other fingers, so it's part of a pinch or pan
+    multi: bool,
+    long_pressed: bool,
+}
+
+/// Turns raw touches into taps, swipes, long presses, pinches and two-finger pans.
+/// `InputManager` owns one and feeds it every frame (see `InputManager::gesture_events`).
+pub struct GestureRecognizer {
+    touches: HashMap<u64, TrackedTouch>,
+    // Finger spread and center of the last frame with exactly two fingers down
+    two_fingers: Option<(f32, Vec2)>,
+    events: Vec<GestureEvent>,
+    active: HashSet<Gesture>,
+    /// Pixels a finger can drift and still tap or long-press
+    pub tap_slop: f32,
+    /// Longest touch that counts as a tap, in seconds
+    pub tap_time: f32,
+    pub long_press_time: f32,
+    /// Shortest swipe, in pixels
+    pub swipe_distance: f32,
+    /// Longest swipe, in seconds
+    pub swipe_time: f32,
+}
+
+impl Default for GestureRecognizer {
+    fn default() -> Self {
+        Self {
+            touches: HashMap::new(),
+            two_fingers: None,
+            events: vec![],
+            active: HashSet::new(),
+            tap_slop: 12.0,
+            tap_time: 0.3,
+            long_press_time: 0.5,
+            swipe_distance: 60.0,
+            swipe_time: 0.5,
+        }
+    }
+}
+
+impl GestureRecognizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Gestures recognized this frame
+    pub fn events(&self) -> &[GestureEvent] {
+        &self.events
+    }
+
+    pub fn is_active(&self, gesture: Gesture) -> bool {
+        self.active.contains(&gesture)
+    }
+
+    /// Number of fingers down
+    pub fn touch_count(&self) -> usize {
+        self.touches.len()
+    }
+
+    /// Pinch scale this frame (1 when not pinching)
+    pub fn pinch_scale(&self) -> f32 {
+        self.events.iter()
+            .filter_map(|event| match event {
+                GestureEvent::Pinch { scale, .. } => Some(*scale),
+                _ => None,
+            })
+            .product()
+    }
+
+    /// Two-finger pan movement this frame
+    pub fn pan_delta(&self) -> Vec2 {
+        self.events.iter()
+            .filter_map(|event| match event {
+                GestureEvent::Pan { delta, .. } => Some(*delta),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Advance with the fingers down this frame; fingers that are gone were lifted
+    pub fn update(&mut self, dt: f32, touches: &[TouchPoint]) {
+        self.events.clear();
+        self.active.clear();
+
+        // Lifted fingers end taps and swipes
+        let down: HashSet<u64> = touches.iter().map(|touch| touch.id).collect();
+        let lifted: Vec<u64> = self.touches.keys().copied().filter(|id| !down.contains(id)).collect();
+        for id in lifted {
+            let Some(touch) = self.touches.remove(&id) else {
+                continue;
+            };
+            // Fingers of a pinch or pan don't also tap or swipe
+            if touch.multi || touch.long_pressed {
+                continue;
+            }
+            let offset = touch.position - touch.start;
+            if !touch.moved && touch.held <= self.tap_time {
+                self.push(Gesture::Tap, GestureEvent::Tap { position: touch.position });
+            } else if offset.length() >= self.swipe_distance && touch.held <= self.swipe_time {
+                let direction = SwipeDirection::from_vector(offset);
+                let velocity = offset / touch.held.max(f32::EPSILON);
+                self.push(Gesture::Swipe(direction), GestureEvent::Swipe { direction, start: touch.start, end: touch.position, velocity });
+            }
+        }
+
+        for point in touches {
+            let touch = self.touches.entry(point.id).or_insert(TrackedTouch {
+                start: point.position,
+                position: point.position,
+                held: 0.0,
+                moved: false,
+                multi: false,
+                long_pressed: false,
+            });
+            touch.position = point.position;
+            touch.held += dt;
+            touch.moved |= point.position.distance(touch.start) > self.tap_slop;
+            touch.multi |= touches.len() > 1;
+        }
+
+        // Long press: one finger held still
+        if self.touches.len() == 1 {
+            let (long_press_time, mut pressed) = (self.long_press_time, None);
+            for touch in self.touches.values_mut() {
+                if !touch.moved && !touch.multi && touch.held >= long_press_time {
+                    if !touch.long_pressed {
+                        touch.long_pressed = true;
+                        pressed = Some(touch.position);
+                    }
+                    self.active.insert(Gesture::LongPress);
+                }
+            }
+            if let Some(position) = pressed {
+                self.events.push(GestureEvent::LongPress { position });
+            }
+        }
+
+        self.update_two_fingers(touches);
+    }
+
+    fn update_two_fingers(&mut self, touches: &[TouchPoint]) {
+        let [a, b] = touches else {
+            self.two_fingers = None;
+            return;
+        };
+        let spread = a.position.distance(b.position);
+        let center = (a.position + b.position) * 0.5;
+        if let Some((last_spread, last_center)) = self.two_fingers.replace((spread, center)) {
+            if last_spread > 0.0 && spread != last_spread {
+                let scale = spread / last_spread;
+                let gesture = if scale > 1.0 { Gesture::PinchOut } else { Gesture::PinchIn };
+                self.push(gesture, GestureEvent::Pinch { center, scale });
+            }
+            let delta = center - last_center;
+            if delta != Vec2::ZERO {
+                self.push(Gesture::TwoFingerPan, GestureEvent::Pan { center, delta });
+            }
+        }
+    }
+
+    fn push(&mut self, gesture: Gesture, event: GestureEvent) {
+        self.active.insert(gesture);
+        self.events.push(event);
+    }
+}
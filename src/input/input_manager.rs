@@ -1,6 +1,63 @@
-use super::{Action, InputBinding};
+use super::{Action, InputBinding, KeyBinding};
+use super::clipboard::{get_clipboard, is_shortcut_modifier_down, set_clipboard};
+use super::callbacks::{ActionCallbacks, ActionEvent, ActionEventKind, CallbackId};
+use super::keys::{all_keys, is_modifier_key};
+use super::gestures::{GestureEvent, GestureRecognizer, TouchPoint};
+use super::layout::KeyboardLayout;
+use super::recording::{InputFrame, InputRecording, Playback};
+use super::rumble::{RumbleBackend, RumbleState};
+use super::sequence::{InputSequence, SequenceProgress, SequenceTracker};
+use crate::core::headless::is_headless;
+use crate::ui::{to_physical, to_ui};
 use macroquad::prelude::*;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+const TRACKED_MOUSE_BUTTONS: &[MouseButton] = &[MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+// Backspace auto-repeat while typing
+const BACKSPACE_REPEAT_DELAY: f32 = 0.4;
+const BACKSPACE_REPEAT_INTERVAL: f32 = 0.05;
+
+/// Error returned when saving or loading input bindings
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingsError::Io(err) => write!(f, "failed to access bindings file: {}", err),
+            BindingsError::Format(msg) => write!(f, "invalid bindings data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
+
+impl From<std::io::Error> for BindingsError {
+    fn from(err: std::io::Error) -> Self {
+        BindingsError::Io(err)
+    }
+}
+
+/// An in-progress "press a key to bind" request
+#[derive(Debug, Clone)]
+struct Rebinding {
+    action: Action,
+    slot: Option<usize>,
+}
+
+/// An active text entry session
+#[derive(Debug, Clone)]
+struct TextInput {
+    text: String,
+    max_length: Option<usize>,
+    backspace_repeat: f32,
+}
 
 /// Manages all input state and action bindings
 pub struct InputManager {
@@ -15,6 +72,8 @@ pub struct InputManager {
     mouse_pressed: HashSet<MouseButton>,
     mouse_just_pressed: HashSet<MouseButton>,
     mouse_just_released: HashSet<MouseButton>,
+    // An overlay took the mouse this frame (see `consume_mouse_buttons`)
+    mouse_consumed: bool,
     mouse_position: Vec2,
     mouse_delta: Vec2,
     scroll_delta: Vec2,
@@ -27,11 +86,66 @@ pub struct InputManager {
     // Input buffering (for fighting games, precise timing)
     buffer_time: f32,
     buffered_actions: HashMap<Action, f32>,
+    
+    // Hold, double-tap and long-press tracking
+    hold_durations: HashMap<Action, f32>,
+    released_hold_durations: HashMap<Action, f32>,
+    time_since_tap: HashMap<Action, f32>,
+    double_tap_window: f32,
+    long_press_time: f32,
+    actions_double_tapped: HashSet<Action>,
+    actions_long_pressed: HashSet<Action>,
+    
+    // Ordered input sequences (motion inputs) that resolve into actions
+    sequences: HashMap<Action, Vec<SequenceTracker>>,
+    
+    // Runtime rebinding (controls screens)
+    rebinding: Option<Rebinding>,
+    completed_rebinding: Option<(Action, InputBinding)>,
+    unsupported_rebind_key: bool,
+    rebind_cancel_key: Option<KeyCode>,
+    rebind_physical: bool,
+    
+    // Layout used to resolve physical key bindings
+    keyboard_layout: KeyboardLayout,
+    
+    // Text entry (name entry, dev console)
+    text_input: Option<TextInput>,
+    submitted_text: Option<String>,
+    
+    // Input recording and replay
+    recording: Option<InputRecording>,
+    playback: Option<Playback>,
+    frame_dt: f32,
+    // Raw input for the next update instead of polling (headless runs)
+    scripted: Option<InputFrame>,
+    
+    // Gamepad vibration
+    rumble: RumbleState,
+    
+    // Touch gestures
+    gestures: GestureRecognizer,
+    
+    // Closures fired on action events during update
+    callbacks: ActionCallbacks,
+    
+    // Idle detection (attract mode)
+    idle_time: f32,
+    last_live_mouse: Option<Vec2>,
 }
 
 impl InputManager {
     pub fn new() -> Self {
-        let mut input_manager = Self {
+        let mut input_manager = Self::empty();
+        
+        // Set up default bindings
+        input_manager.setup_default_bindings();
+        input_manager
+    }
+    
+    /// Create an input manager with no bindings
+    pub fn empty() -> Self {
+        Self {
             bindings: HashMap::new(),
             keys_pressed: HashSet::new(),
             keys_just_pressed: HashSet::new(),
@@ -39,6 +153,7 @@ impl InputManager {
             mouse_pressed: HashSet::new(),
             mouse_just_pressed: HashSet::new(),
             mouse_just_released: HashSet::new(),
+            mouse_consumed: false,
             mouse_position: Vec2::ZERO,
             mouse_delta: Vec2::ZERO,
             scroll_delta: Vec2::ZERO,
@@ -47,33 +162,54 @@ impl InputManager {
             actions_just_deactivated: HashSet::new(),
             buffer_time: 0.1, // 100ms buffer by default
             buffered_actions: HashMap::new(),
-        };
-        
-        // Set up default bindings
-        input_manager.setup_default_bindings();
-        input_manager
+            hold_durations: HashMap::new(),
+            released_hold_durations: HashMap::new(),
+            time_since_tap: HashMap::new(),
+            double_tap_window: 0.25, // 250ms between taps by default
+            long_press_time: 0.5,
+            actions_double_tapped: HashSet::new(),
+            actions_long_pressed: HashSet::new(),
+            sequences: HashMap::new(),
+            rebinding: None,
+            completed_rebinding: None,
+            unsupported_rebind_key: false,
+            rebind_cancel_key: Some(KeyCode::Escape),
+            rebind_physical: false,
+            keyboard_layout: KeyboardLayout::Qwerty,
+            text_input: None,
+            submitted_text: None,
+            recording: None,
+            playback: None,
+            frame_dt: 0.0,
+            scripted: None,
+            rumble: RumbleState::new(),
+            gestures: GestureRecognizer::new(),
+            callbacks: ActionCallbacks::default(),
+            idle_time: 0.0,
+            last_live_mouse: None,
+        }
     }
     
     /// Set up common default bindings
     fn setup_default_bindings(&mut self) {
-        // Movement (WASD + Arrow keys)
+        // Movement (WASD by physical position + Arrow keys)
         self.bind_action(Action::MoveUp, vec![
-            InputBinding::key(KeyCode::W),
+            InputBinding::physical(KeyCode::W),
             InputBinding::key(KeyCode::Up),
         ]);
         
         self.bind_action(Action::MoveDown, vec![
-            InputBinding::key(KeyCode::S),
+            InputBinding::physical(KeyCode::S),
             InputBinding::key(KeyCode::Down),
         ]);
         
         self.bind_action(Action::MoveLeft, vec![
-            InputBinding::key(KeyCode::A),
+            InputBinding::physical(KeyCode::A),
             InputBinding::key(KeyCode::Left),
         ]);
         
         self.bind_action(Action::MoveRight, vec![
-            InputBinding::key(KeyCode::D),
+            InputBinding::physical(KeyCode::D),
             InputBinding::key(KeyCode::Right),
         ]);
         
@@ -89,6 +225,7 @@ impl InputManager {
         ]);
         self.bind_action(Action::Interact, vec![InputBinding::key(KeyCode::E)]);
         self.bind_action(Action::Pause, vec![InputBinding::key(KeyCode::Escape)]);
+        self.bind_action(Action::Screenshot, vec![InputBinding::key(KeyCode::F12)]);
     }
     
     /// Update input state - call this once per frame
@@ -98,39 +235,127 @@ impl InputManager {
         self.keys_just_released.clear();
         self.mouse_just_pressed.clear();
         self.mouse_just_released.clear();
+        self.mouse_consumed = false;
         self.actions_just_activated.clear();
         self.actions_just_deactivated.clear();
+        self.actions_double_tapped.clear();
+        self.actions_long_pressed.clear();
+        self.released_hold_durations.clear();
+        self.unsupported_rebind_key = false;
+        
+        // Keys macroquad has no code for never reach the frame, so a rebind prompt
+        // has to be told about them separately
+        let unknown_key_pressed = self.scripted.is_none() && self.playback.is_none()
+            && get_last_key_pressed() == Some(KeyCode::Unknown);
+        
+        // Read this frame's raw input (live, or from a replay). Only the player's own
+        // input counts for idling, so an attract-mode replay can be interrupted.
+        let live = match self.scripted.take() {
+            Some(frame) => InputFrame { dt, ..frame },
+            None => self.poll_frame(dt),
+        };
+        let frame = self.next_playback_frame().unwrap_or_else(|| live.clone());
+        if let Some(recording) = &mut self.recording {
+            recording.push(frame.clone());
+        }
+        let dt = frame.dt;
+        self.frame_dt = dt;
+        self.update_idle_time(&live);
         
         // Update key state
-        self.update_key_state();
+        self.update_key_state(&frame);
         
         // Update mouse state
-        self.update_mouse_state();
+        self.update_mouse_state(&frame);
+        self.gestures.update(dt, &frame.touches);
         
-        // Update action state
-        self.update_action_state();
+        // Update action state (actions are suppressed while waiting for a rebind or typing)
+        if self.rebinding.is_some() {
+            self.unsupported_rebind_key = unknown_key_pressed;
+            self.update_rebinding();
+        } else if self.text_input.is_some() {
+            self.update_text_input(dt, &frame.chars);
+        } else {
+            self.update_action_state();
+            self.update_hold_state(dt);
+            self.update_sequences(dt);
+        }
         
         // Update input buffer
         self.update_input_buffer(dt);
+        
+        // Run action callbacks
+        if !self.callbacks.is_empty() {
+            let mut callbacks = std::mem::take(&mut self.callbacks);
+            callbacks.fire(self);
+            self.callbacks = callbacks;
+        }
+        
+        // Fade out rumble effects
+        self.rumble.update(dt);
     }
     
-    fn update_key_state(&mut self) {
-        // Check all possible keys (this is a simplified approach)
-        let all_keys = [
-            KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F,
-            KeyCode::G, KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L,
-            KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
-            KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X,
-            KeyCode::Y, KeyCode::Z, KeyCode::Key0, KeyCode::Key1, KeyCode::Key2,
-            KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6, KeyCode::Key7,
-            KeyCode::Key8, KeyCode::Key9, KeyCode::Space, KeyCode::Enter, KeyCode::Escape,
-            KeyCode::Backspace, KeyCode::Tab, KeyCode::LeftShift, KeyCode::RightShift,
-            KeyCode::LeftControl, KeyCode::RightControl, KeyCode::LeftAlt, KeyCode::RightAlt,
-            KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
-        ];
-        
-        for &key in &all_keys {
-            let is_down = is_key_down(key);
+    fn update_idle_time(&mut self, live: &InputFrame) {
+        let mouse_moved = self.last_live_mouse.is_some_and(|last| last != live.mouse_position);
+        self.last_live_mouse = Some(live.mouse_position);
+        
+        let active = !live.keys.is_empty()
+            || !live.mouse_buttons.is_empty()
+            || live.scroll != Vec2::ZERO
+            || !live.chars.is_empty()
+            || !live.touches.is_empty()
+            || mouse_moved;
+        if active {
+            self.idle_time = 0.0;
+        } else {
+            // Real time, so pausing or slowing the game doesn't stretch the timeout
+            self.idle_time += if is_headless() { live.dt } else { get_frame_time() };
+        }
+    }
+    
+    fn next_playback_frame(&mut self) -> Option<InputFrame> {
+        let frame = self.playback.as_mut()?.next_frame();
+        if frame.is_none() {
+            self.playback = None;
+        }
+        frame
+    }
+    
+    fn poll_frame(&self, dt: f32) -> InputFrame {
+        // Only drain typed characters while text input wants them
+        let mut chars = vec![];
+        if self.text_input.is_some() {
+            while let Some(character) = get_char_pressed() {
+                chars.push(character);
+            }
+            // macroquad's queue pops the newest character first
+            chars.reverse();
+            
+            // Pasted text is fed in as typed characters so recordings replay it
+            if is_key_pressed(KeyCode::V) && is_shortcut_modifier_down(is_key_down) {
+                chars.extend(get_clipboard().unwrap_or_default().chars());
+            }
+        }
+        
+        let wheel = mouse_wheel();
+        InputFrame {
+            dt,
+            keys: all_keys().filter(|key| is_key_down(*key)).collect(),
+            mouse_buttons: TRACKED_MOUSE_BUTTONS.iter().copied().filter(|b| is_mouse_button_down(*b)).collect(),
+            mouse_position: mouse_position().into(),
+            scroll: Vec2::new(wheel.0, wheel.1),
+            chars,
+            // Lifted fingers are left out; the gesture recognizer notices they're gone
+            touches: touches().iter()
+                .filter(|touch| !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled))
+                .map(|touch| TouchPoint { id: touch.id, position: touch.position })
+                .collect(),
+        }
+    }
+    
+    fn update_key_state(&mut self, frame: &InputFrame) {
+        for key in all_keys() {
+            let is_down = frame.keys.contains(&key);
             let was_pressed = self.keys_pressed.contains(&key);
             
             if is_down && !was_pressed {
@@ -143,18 +368,15 @@ impl InputManager {
         }
     }
     
-    fn update_mouse_state(&mut self) {
-        let current_mouse_pos = mouse_position().into();
+    fn update_mouse_state(&mut self, frame: &InputFrame) {
+        let current_mouse_pos = frame.mouse_position;
         self.mouse_delta = current_mouse_pos - self.mouse_position;
         self.mouse_position = current_mouse_pos;
         
-        let mouse_wheel = mouse_wheel();
-        self.scroll_delta = Vec2::new(mouse_wheel.0, mouse_wheel.1);
+        self.scroll_delta = frame.scroll;
         
-        let buttons = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
-        
-        for &button in &buttons {
-            let is_down = is_mouse_button_down(button);
+        for &button in TRACKED_MOUSE_BUTTONS {
+            let is_down = frame.mouse_buttons.contains(&button);
             let was_pressed = self.mouse_pressed.contains(&button);
             
             if is_down && !was_pressed {
@@ -189,29 +411,207 @@ impl InputManager {
         self.actions_active = new_active_actions;
     }
     
+    fn update_hold_state(&mut self, dt: f32) {
+        // Hold timers and long presses
+        for action in &self.actions_active {
+            let held = self.hold_durations.entry(action.clone()).or_insert(0.0);
+            let was_held = *held;
+            *held += dt;
+            if was_held < self.long_press_time && *held >= self.long_press_time {
+                self.actions_long_pressed.insert(action.clone());
+            }
+        }
+        for action in &self.actions_just_deactivated {
+            if let Some(held) = self.hold_durations.remove(action) {
+                self.released_hold_durations.insert(action.clone(), held);
+            }
+        }
+        
+        // Double taps: a second activation within the window of the first
+        self.time_since_tap.retain(|_, elapsed| {
+            *elapsed += dt;
+            *elapsed <= self.double_tap_window
+        });
+        for action in &self.actions_just_activated {
+            if self.time_since_tap.remove(action).is_some() {
+                // Consumed, so a triple tap doesn't report two double taps
+                self.actions_double_tapped.insert(action.clone());
+            } else {
+                self.time_since_tap.insert(action.clone(), 0.0);
+            }
+        }
+    }
+    
+    fn update_sequences(&mut self, dt: f32) {
+        if self.sequences.is_empty() {
+            return;
+        }
+        
+        // Reconstruct last frame's action state from this frame's transitions
+        let previous: HashSet<Action> = self.actions_active.iter()
+            .filter(|action| !self.actions_just_activated.contains(*action))
+            .chain(self.actions_just_deactivated.iter())
+            .cloned()
+            .collect();
+        
+        let mut completed = vec![];
+        for (action, trackers) in self.sequences.iter_mut() {
+            for tracker in trackers.iter_mut() {
+                if tracker.update(dt, &self.actions_active, &previous) {
+                    completed.push(action.clone());
+                }
+            }
+        }
+        
+        // A completed sequence behaves like a tap of its action
+        for action in completed {
+            self.actions_just_activated.insert(action.clone());
+            self.buffered_actions.insert(action, self.buffer_time);
+        }
+    }
+    
+    fn update_rebinding(&mut self) {
+        self.release_all_actions();
+        
+        let Some(rebinding) = self.rebinding.clone() else {
+            return;
+        };
+        
+        if self.rebind_cancel_key.is_some_and(|key| self.keys_just_pressed.contains(&key)) {
+            self.rebinding = None;
+            self.sync_action_state();
+            return;
+        }
+        
+        if let Some(binding) = self.capture_binding() {
+            let bindings = self.bindings.entry(rebinding.action.clone()).or_default();
+            match rebinding.slot {
+                Some(slot) if slot < bindings.len() => bindings[slot] = binding.clone(),
+                Some(_) => bindings.push(binding.clone()),
+                None => *bindings = vec![binding.clone()],
+            }
+            
+            self.completed_rebinding = Some((rebinding.action, binding));
+            self.rebinding = None;
+            self.sync_action_state();
+        }
+    }
+    
+    fn update_text_input(&mut self, dt: f32, typed: &[char]) {
+        self.release_all_actions();
+        
+        let Some(input) = &mut self.text_input else {
+            return;
+        };
+        
+        if self.keys_just_pressed.contains(&KeyCode::Escape) {
+            self.text_input = None;
+            self.sync_action_state();
+            return;
+        }
+        
+        if self.keys_just_pressed.contains(&KeyCode::Enter) {
+            self.submitted_text = self.text_input.take().map(|input| input.text);
+            self.sync_action_state();
+            return;
+        }
+        
+        if is_shortcut_modifier_down(|key| self.keys_pressed.contains(&key)) {
+            if self.keys_just_pressed.contains(&KeyCode::C) {
+                set_clipboard(&input.text);
+            } else if self.keys_just_pressed.contains(&KeyCode::X) {
+                set_clipboard(&input.text);
+                input.text.clear();
+            }
+        }
+        
+        for &character in typed.iter().filter(|c| !c.is_control()) {
+            if input.max_length.is_some_and(|max| input.text.chars().count() >= max) {
+                break;
+            }
+            input.text.push(character);
+        }
+        
+        if self.keys_just_pressed.contains(&KeyCode::Backspace) {
+            input.text.pop();
+            input.backspace_repeat = BACKSPACE_REPEAT_DELAY;
+        } else if self.keys_pressed.contains(&KeyCode::Backspace) {
+            input.backspace_repeat -= dt;
+            while input.backspace_repeat <= 0.0 {
+                input.text.pop();
+                input.backspace_repeat += BACKSPACE_REPEAT_INTERVAL;
+            }
+        }
+    }
+    
+    /// Find the binding the player just performed, if any
+    fn capture_binding(&self) -> Option<InputBinding> {
+        if let Some(&button) = TRACKED_MOUSE_BUTTONS.iter().find(|b| self.mouse_just_pressed.contains(b)) {
+            return Some(InputBinding::mouse(button));
+        }
+        
+        if let Some(key) = all_keys()
+            .find(|k| !is_modifier_key(*k) && self.keys_just_pressed.contains(k))
+        {
+            let modifiers = all_keys()
+                .filter(|k| is_modifier_key(*k) && self.keys_pressed.contains(k))
+                .collect();
+            if self.rebind_physical {
+                let position = self.keyboard_layout.position_of(key);
+                return Some(InputBinding::Physical(KeyBinding { key: position, modifiers }));
+            }
+            return Some(InputBinding::Key(KeyBinding { key, modifiers }));
+        }
+        
+        // A modifier pressed and released on its own binds the modifier itself
+        all_keys()
+            .find(|k| is_modifier_key(*k) && self.keys_just_released.contains(k))
+            .map(InputBinding::key)
+    }
+    
+    fn release_all_actions(&mut self) {
+        for action in self.actions_active.drain() {
+            self.actions_just_deactivated.insert(action);
+        }
+        self.hold_durations.clear();
+        self.time_since_tap.clear();
+    }
+    
+    /// Mark currently held bindings as active without reporting them as just activated,
+    /// so the key that finished a rebind doesn't also trigger its action
+    fn sync_action_state(&mut self) {
+        self.actions_active = self.bindings.iter()
+            .filter(|(_, bindings)| bindings.iter().any(|binding| self.is_binding_active(binding)))
+            .map(|(action, _)| action.clone())
+            .collect();
+    }
+    
     fn is_binding_active(&self, binding: &InputBinding) -> bool {
         match binding {
             InputBinding::Key(key_binding) => {
-                // Check if main key is pressed
-                if !self.keys_pressed.contains(&key_binding.key) {
-                    return false;
-                }
-                
-                // Check if all modifiers are pressed
-                for modifier in &key_binding.modifiers {
-                    if !self.keys_pressed.contains(modifier) {
-                        return false;
-                    }
-                }
-                
-                true
+                self.is_key_binding_active(key_binding.key, &key_binding.modifiers)
+            }
+            InputBinding::Physical(key_binding) => {
+                let key = self.keyboard_layout.key_at(key_binding.key);
+                self.is_key_binding_active(key, &key_binding.modifiers)
             }
             InputBinding::Mouse(mouse_binding) => {
-                self.mouse_pressed.contains(&mouse_binding.button)
+                !self.mouse_consumed && self.mouse_pressed.contains(&mouse_binding.button)
             }
+            InputBinding::Gesture(gesture) => self.gestures.is_active(*gesture),
         }
     }
     
+    fn is_key_binding_active(&self, key: KeyCode, modifiers: &[KeyCode]) -> bool {
+        // Check if main key is pressed
+        if !self.keys_pressed.contains(&key) {
+            return false;
+        }
+        
+        // Check if all modifiers are pressed
+        modifiers.iter().all(|modifier| self.keys_pressed.contains(modifier))
+    }
+    
     fn update_input_buffer(&mut self, dt: f32) {
         // Decay buffered actions
         self.buffered_actions.retain(|_, time_left| {
@@ -247,6 +647,31 @@ impl InputManager {
         self.buffered_actions.remove(action).is_some()
     }
     
+    /// How long an action has been held, in seconds (0 if not active)
+    pub fn action_hold_duration(&self, action: &Action) -> f32 {
+        self.hold_durations.get(action).copied().unwrap_or(0.0)
+    }
+    
+    /// Check if an action has been held for at least `seconds`
+    pub fn is_action_held_for(&self, action: &Action, seconds: f32) -> bool {
+        self.is_action_active(action) && self.action_hold_duration(action) >= seconds
+    }
+    
+    /// How long an action was held before being released this frame (for charge attacks)
+    pub fn action_released_after(&self, action: &Action) -> Option<f32> {
+        self.released_hold_durations.get(action).copied()
+    }
+    
+    /// Check if an action was activated twice within the double-tap window this frame
+    pub fn is_action_double_tapped(&self, action: &Action) -> bool {
+        self.actions_double_tapped.contains(action)
+    }
+    
+    /// Check if an action crossed the long-press threshold this frame (fires once per hold)
+    pub fn is_action_long_pressed(&self, action: &Action) -> bool {
+        self.actions_long_pressed.contains(action)
+    }
+    
     /// Get movement input as a Vec2 (normalized)
     pub fn get_movement_input(&self) -> Vec2 {
         let mut movement = Vec2::ZERO;
@@ -286,17 +711,43 @@ impl InputManager {
     }
     
     pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
-        self.mouse_pressed.contains(&button)
+        !self.mouse_consumed && self.mouse_pressed.contains(&button)
     }
     
     pub fn is_mouse_button_just_pressed(&self, button: MouseButton) -> bool {
-        self.mouse_just_pressed.contains(&button)
+        !self.mouse_consumed && self.mouse_just_pressed.contains(&button)
     }
     
     pub fn is_mouse_button_just_released(&self, button: MouseButton) -> bool {
-        self.mouse_just_released.contains(&button)
+        !self.mouse_consumed && self.mouse_just_released.contains(&button)
     }
     
+    /// Hide the mouse buttons and wheel from everything that reads input after this, until
+    /// the next `update`. For overlays that handled the mouse themselves (e.g. `CheatMenu`);
+    /// actions held only by a mouse binding are withdrawn for the frame.
+    pub fn consume_mouse_buttons(&mut self) {
+        if self.mouse_consumed {
+            return;
+        }
+        self.mouse_consumed = true;
+        self.scroll_delta = Vec2::ZERO;
+        let withdrawn: Vec<Action> = self.actions_active.iter()
+            .filter(|action| self.bindings.get(*action).is_some_and(|bindings| {
+                bindings.iter().any(|binding| matches!(binding, InputBinding::Mouse(_)))
+                    && !bindings.iter().any(|binding| self.is_binding_active(binding))
+            }))
+            .cloned()
+            .collect();
+        for action in withdrawn {
+            self.actions_active.remove(&action);
+            self.actions_just_activated.remove(&action);
+            self.buffered_actions.remove(&action);
+            self.hold_durations.remove(&action);
+            self.time_since_tap.remove(&action);
+        }
+    }
+    
+    /// Mouse position in logical pixels, like screen sizes and `Menu` layouts
     pub fn mouse_position(&self) -> Vec2 {
         self.mouse_position
     }
@@ -305,10 +756,44 @@ impl InputManager {
         self.mouse_delta
     }
     
+    /// Mouse position in physical pixels, e.g. to read the framebuffer under the cursor
+    /// (see `ui::display_scale`)
+    pub fn mouse_position_physical(&self) -> Vec2 {
+        to_physical(self.mouse_position)
+    }
+    
+    pub fn mouse_delta_physical(&self) -> Vec2 {
+        to_physical(self.mouse_delta)
+    }
+    
+    /// Mouse position in UI pixels, unscaled by `ui::ui_scale`
+    pub fn mouse_position_ui(&self) -> Vec2 {
+        to_ui(self.mouse_position)
+    }
+    
+    pub fn mouse_delta_ui(&self) -> Vec2 {
+        to_ui(self.mouse_delta)
+    }
+    
     pub fn scroll_delta(&self) -> Vec2 {
         self.scroll_delta
     }
     
+    /// Real seconds since the player last pressed, moved or typed anything
+    pub fn seconds_since_last_input(&self) -> f32 {
+        self.idle_time
+    }
+    
+    /// Check if there has been no input for at least `seconds`
+    pub fn is_idle_for(&self, seconds: f32) -> bool {
+        self.idle_time >= seconds
+    }
+    
+    /// Restart the idle timer (e.g. when leaving attract mode)
+    pub fn reset_idle_time(&mut self) {
+        self.idle_time = 0.0;
+    }
+    
     // Binding management
     
     /// Bind an action to multiple input bindings
@@ -336,14 +821,376 @@ impl InputManager {
         self.buffer_time = time;
     }
     
+    /// Set the max time between taps for a double tap (in seconds)
+    pub fn set_double_tap_window(&mut self, time: f32) {
+        self.double_tap_window = time;
+    }
+    
+    /// Set how long an action must be held to count as a long press (in seconds)
+    pub fn set_long_press_time(&mut self, time: f32) {
+        self.long_press_time = time;
+    }
+    
     /// Get current bindings for an action
     pub fn get_bindings(&self, action: &Action) -> Option<&Vec<InputBinding>> {
         self.bindings.get(action)
     }
+    
+    /// Get all actions that use the given binding (useful for conflict warnings)
+    pub fn actions_bound_to(&self, binding: &InputBinding) -> Vec<Action> {
+        self.bindings.iter()
+            .filter(|(_, bindings)| bindings.contains(binding))
+            .map(|(action, _)| action.clone())
+            .collect()
+    }
+    
+    /// Restore the default bindings, dropping any custom ones
+    pub fn reset_bindings(&mut self) {
+        self.bindings.clear();
+        self.setup_default_bindings();
+    }
+    
+    // Input sequences
+    
+    /// Make an ordered input sequence trigger `action`. Completing it reports the action
+    /// as just activated and buffers it. An action can have several sequences.
+    pub fn bind_sequence(&mut self, action: Action, sequence: InputSequence) {
+        self.sequences.entry(action).or_default().push(SequenceTracker::new(sequence));
+    }
+    
+    /// Remove all sequences bound to an action
+    pub fn unbind_sequences(&mut self, action: &Action) {
+        self.sequences.remove(action);
+    }
+    
+    /// Progress of the furthest-along sequence bound to an action
+    pub fn sequence_progress(&self, action: &Action) -> Option<SequenceProgress> {
+        self.sequences.get(action)?
+            .iter()
+            .map(SequenceTracker::progress)
+            .max_by_key(|progress| progress.matched_steps)
+    }
+    
+    /// Forget any partially entered sequences
+    pub fn reset_sequences(&mut self) {
+        for tracker in self.sequences.values_mut().flatten() {
+            tracker.reset();
+        }
+    }
+    
+    // Binding persistence
+    
+    /// Serialize all bindings to a RON string
+    pub fn bindings_to_string(&self) -> Result<String, BindingsError> {
+        ron::ser::to_string_pretty(&self.bindings, ron::ser::PrettyConfig::new())
+            .map_err(|err| BindingsError::Format(err.to_string()))
+    }
+    
+    /// Load bindings from a RON string. Actions present in the data replace their
+    /// current bindings; actions missing from it keep theirs.
+    pub fn load_bindings_from_str(&mut self, data: &str) -> Result<(), BindingsError> {
+        let loaded: HashMap<Action, Vec<InputBinding>> = ron::from_str(data)
+            .map_err(|err| BindingsError::Format(err.to_string()))?;
+        
+        for (action, bindings) in loaded {
+            self.bind_action(action, bindings);
+        }
+        Ok(())
+    }
+    
+    /// Save all bindings to a file
+    pub fn save_bindings<P: AsRef<Path>>(&self, path: P) -> Result<(), BindingsError> {
+        let data = self.bindings_to_string()?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+    
+    /// Load bindings from a file (see `load_bindings_from_str`)
+    pub fn load_bindings<P: AsRef<Path>>(&mut self, path: P) -> Result<(), BindingsError> {
+        let data = std::fs::read_to_string(path)?;
+        self.load_bindings_from_str(&data)
+    }
+    
+    // Action callbacks
+    
+    /// Run `callback` during `update` on the frame `action` is pressed
+    ///
+    /// ```ignore
+    /// let jumped = Rc::new(Cell::new(false));
+    /// let flag = jumped.clone();
+    /// input.on_action_pressed(Action::Jump, move |_| flag.set(true));
+    /// ```
+    pub fn on_action_pressed<F: FnMut(&ActionEvent) + 'static>(&mut self, action: Action, callback: F) -> CallbackId {
+        self.on_action(action, ActionEventKind::Pressed, callback)
+    }
+    
+    /// Run `callback` on the frame `action` is released
+    pub fn on_action_released<F: FnMut(&ActionEvent) + 'static>(&mut self, action: Action, callback: F) -> CallbackId {
+        self.on_action(action, ActionEventKind::Released, callback)
+    }
+    
+    /// Run `callback` every frame `action` is held
+    pub fn on_action_held<F: FnMut(&ActionEvent) + 'static>(&mut self, action: Action, callback: F) -> CallbackId {
+        self.on_action(action, ActionEventKind::Held, callback)
+    }
+    
+    /// Run `callback` whenever `kind` happens to `action`
+    pub fn on_action<F: FnMut(&ActionEvent) + 'static>(&mut self, action: Action, kind: ActionEventKind, callback: F) -> CallbackId {
+        self.callbacks.add(action, kind, Box::new(callback))
+    }
+    
+    /// Unregister a callback. Returns false if it was already removed.
+    pub fn remove_callback(&mut self, id: CallbackId) -> bool {
+        self.callbacks.remove(id)
+    }
+    
+    pub fn clear_callbacks(&mut self) {
+        self.callbacks.clear();
+    }
+    
+    // Runtime rebinding
+    
+    /// Wait for the next key/mouse press and make it the only binding of `action`.
+    /// Action processing is suppressed until the rebind completes or is cancelled.
+    pub fn start_rebinding(&mut self, action: Action) {
+        self.rebinding = Some(Rebinding { action, slot: None });
+        self.completed_rebinding = None;
+    }
+    
+    /// Like `start_rebinding`, but only replaces the binding at `slot`
+    /// (appends if the action has fewer bindings)
+    pub fn start_rebinding_slot(&mut self, action: Action, slot: usize) {
+        self.rebinding = Some(Rebinding { action, slot: Some(slot) });
+        self.completed_rebinding = None;
+    }
+    
+    /// Abort the current rebind, keeping the old bindings
+    pub fn cancel_rebinding(&mut self) {
+        self.rebinding = None;
+    }
+    
+    /// Check if we're waiting for the player to press a new binding
+    pub fn is_rebinding(&self) -> bool {
+        self.rebinding.is_some()
+    }
+    
+    /// The action currently being rebound
+    pub fn rebinding_action(&self) -> Option<&Action> {
+        self.rebinding.as_ref().map(|rebinding| &rebinding.action)
+    }
+    
+    /// Take the result of the last finished rebind (returns it only once)
+    pub fn take_completed_rebinding(&mut self) -> Option<(Action, InputBinding)> {
+        self.completed_rebinding.take()
+    }
+    
+    /// True on the frame the player pressed a key that can't be bound during a rebind,
+    /// so the controls screen can say so instead of ignoring it
+    pub fn rebind_key_unsupported(&self) -> bool {
+        self.unsupported_rebind_key
+    }
+    
+    /// Set the key that cancels a rebind (None = every key can be bound)
+    pub fn set_rebind_cancel_key(&mut self, key: Option<KeyCode>) {
+        self.rebind_cancel_key = key;
+    }
+    
+    /// Capture rebound keys by physical position instead of key code
+    pub fn set_rebind_physical(&mut self, physical: bool) {
+        self.rebind_physical = physical;
+    }
+    
+    // Keyboard layout
+    
+    /// Set the player's keyboard layout, used to resolve physical key bindings
+    pub fn set_keyboard_layout(&mut self, layout: KeyboardLayout) {
+        self.keyboard_layout = layout;
+    }
+    
+    pub fn keyboard_layout(&self) -> KeyboardLayout {
+        self.keyboard_layout
+    }
+    
+    /// Name of a binding as labelled on the player's keyboard (e.g. "Z" for `Physical:W` on AZERTY)
+    pub fn binding_display_name(&self, binding: &InputBinding) -> String {
+        match binding {
+            InputBinding::Physical(key_binding) => KeyBinding {
+                key: self.keyboard_layout.key_at(key_binding.key),
+                modifiers: key_binding.modifiers.clone(),
+            }.to_string(),
+            other => other.to_string(),
+        }
+    }
+    
+    // Text input
+    
+    /// Start collecting typed characters. Enter submits, Escape cancels, and
+    /// action processing is suppressed until then. Ctrl/Cmd+V pastes from the
+    /// clipboard, and Ctrl/Cmd+C or X copies or cuts the whole text.
+    pub fn start_text_input(&mut self, max_length: Option<usize>) {
+        self.start_text_input_with("", max_length);
+    }
+    
+    /// Start text input with some text already entered (e.g. the previous name)
+    pub fn start_text_input_with(&mut self, initial: &str, max_length: Option<usize>) {
+        // Drop characters typed before input started (like the key that opened it)
+        clear_input_queue();
+        
+        let text = match max_length {
+            Some(max) => initial.chars().take(max).collect(),
+            None => initial.to_string(),
+        };
+        self.text_input = Some(TextInput { text, max_length, backspace_repeat: 0.0 });
+        self.submitted_text = None;
+        self.rebinding = None;
+    }
+    
+    /// Stop text input without submitting
+    pub fn cancel_text_input(&mut self) {
+        self.text_input = None;
+    }
+    
+    /// Check if typed characters are being collected
+    pub fn is_text_input_active(&self) -> bool {
+        self.text_input.is_some()
+    }
+    
+    /// The text entered so far
+    pub fn text_input(&self) -> Option<&str> {
+        self.text_input.as_ref().map(|input| input.text.as_str())
+    }
+    
+    /// Replace the text being edited (e.g. for console history or autocomplete)
+    pub fn set_text_input(&mut self, text: &str) {
+        if let Some(input) = &mut self.text_input {
+            input.text = match input.max_length {
+                Some(max) => text.chars().take(max).collect(),
+                None => text.to_string(),
+            };
+        }
+    }
+    
+    /// Take the text submitted with Enter (returns it only once)
+    pub fn take_submitted_text(&mut self) -> Option<String> {
+        self.submitted_text.take()
+    }
+    
+    // Recording and replay
+    
+    /// Start capturing raw input every frame (replaces any unfinished recording)
+    pub fn start_recording(&mut self) {
+        self.recording = Some(InputRecording::new());
+    }
+    
+    /// Stop capturing and return what was recorded
+    pub fn stop_recording(&mut self) -> Option<InputRecording> {
+        self.recording.take()
+    }
+    
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+    
+    /// Drive input from a recording instead of the keyboard and mouse.
+    /// Live input resumes when the recording ends (unless `looping`).
+    pub fn start_playback(&mut self, recording: InputRecording, looping: bool) {
+        self.playback = Some(Playback::new(recording, looping));
+        self.reset_sequences();
+        self.time_since_tap.clear();
+    }
+    
+    /// Use `frame` as the raw input of the next update instead of the keyboard and mouse
+    /// (its `dt` is replaced by the update's). Headless runs feed every frame this way.
+    pub fn script_frame(&mut self, frame: InputFrame) {
+        self.scripted = Some(frame);
+    }
+    
+    /// Return to live input
+    pub fn stop_playback(&mut self) {
+        self.playback = None;
+    }
+    
+    pub fn is_playing_back(&self) -> bool {
+        self.playback.is_some()
+    }
+    
+    /// Current replay frame and total frames
+    pub fn playback_position(&self) -> Option<(usize, usize)> {
+        self.playback.as_ref().map(Playback::position)
+    }
+    
+    /// Frame time used by the last update: the recorded one during replay, otherwise
+    /// the one passed in. Advance the game with this so replays play out identically.
+    pub fn frame_dt(&self) -> f32 {
+        self.frame_dt
+    }
+    
+    // Touch gestures
+    
+    /// Taps, swipes, long presses, pinches and two-finger pans recognized this frame
+    pub fn gesture_events(&self) -> &[GestureEvent] {
+        self.gestures.events()
+    }
+    
+    /// Pinch zoom factor this frame (1 when not pinching), e.g. to multiply a camera's zoom
+    pub fn pinch_scale(&self) -> f32 {
+        self.gestures.pinch_scale()
+    }
+    
+    /// How far two fingers moved together this frame, in screen pixels
+    pub fn two_finger_pan(&self) -> Vec2 {
+        self.gestures.pan_delta()
+    }
+    
+    pub fn touch_count(&self) -> usize {
+        self.gestures.touch_count()
+    }
+    
+    /// Recognition thresholds (tap slop, long-press time, swipe distance...)
+    pub fn get_gestures_mut(&mut self) -> &mut GestureRecognizer {
+        &mut self.gestures
+    }
+    
+    // Gamepad rumble
+    
+    /// Connect the gamepad library that drives vibration motors
+    pub fn set_rumble_backend(&mut self, backend: Box<dyn RumbleBackend>) {
+        self.rumble.set_backend(Some(backend));
+    }
+    
+    /// Vibrate a gamepad at `intensity` (0..1), fading to nothing over `duration` seconds.
+    /// Overlapping rumbles on one gamepad use the strongest.
+    pub fn rumble(&mut self, gamepad: usize, intensity: f32, duration: f32) {
+        self.rumble.rumble(gamepad, intensity, duration);
+    }
+    
+    /// Stop all rumble on a gamepad
+    pub fn stop_rumble(&mut self, gamepad: usize) {
+        self.rumble.stop(gamepad);
+    }
+    
+    /// Turn rumble on or off globally (accessibility setting)
+    pub fn set_rumble_enabled(&mut self, enabled: bool) {
+        self.rumble.set_enabled(enabled);
+    }
+    
+    pub fn is_rumble_enabled(&self) -> bool {
+        self.rumble.is_enabled()
+    }
+    
+    /// Current vibration strength of a gamepad (0..1)
+    pub fn rumble_strength(&self, gamepad: usize) -> f32 {
+        self.rumble.strength(gamepad)
+    }
+    
+    /// Check if the rumble backend reports a gamepad as connected
+    pub fn is_gamepad_connected(&self, gamepad: usize) -> bool {
+        self.rumble.is_connected(gamepad)
+    }
 }
 
 impl Default for InputManager {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,81 @@
+use macroquad::prelude::*;
+
+macro_rules! key_table {
+    ($($key:ident),* $(,)?) => {
+        /// Every key code paired with its stable name (used in binding files)
+        const KEY_NAMES: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+    };
+}
+
+key_table!(
+    Space, Apostrophe, Comma, Minus, Period, Slash,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    Semicolon, Equal,
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    LeftBracket, Backslash, RightBracket, GraveAccent, World1, World2,
+    Escape, Enter, Tab, Backspace, Insert, Delete,
+    Right, Left, Down, Up, PageUp, PageDown, Home, End,
+    CapsLock, ScrollLock, NumLock, PrintScreen, Pause,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    F13, F14, F15, F16, F17, F18, F19, F20, F21, F22, F23, F24, F25,
+    Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9,
+    KpDecimal, KpDivide, KpMultiply, KpSubtract, KpAdd, KpEnter, KpEqual,
+    LeftShift, LeftControl, LeftAlt, LeftSuper,
+    RightShift, RightControl, RightAlt, RightSuper, Menu,
+);
+
+const MOUSE_NAMES: &[(MouseButton, &str)] = &[
+    (MouseButton::Left, "MouseLeft"),
+    (MouseButton::Right, "MouseRight"),
+    (MouseButton::Middle, "MouseMiddle"),
+];
+
+/// Stable name of a key code (matches the `KeyCode` variant name)
+pub fn key_name(key: KeyCode) -> &'static str {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map(|(_, name)| *name)
+        .unwrap_or("Unknown")
+}
+
+/// Every key macroquad can report, in table order
+pub(crate) fn all_keys() -> impl Iterator<Item = KeyCode> {
+    KEY_NAMES.iter().map(|(code, _)| *code)
+}
+
+/// Look up a key code by its name (case-insensitive)
+pub fn key_from_name(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+        .map(|(code, _)| *code)
+}
+
+/// Stable name of a mouse button
+pub fn mouse_button_name(button: MouseButton) -> &'static str {
+    MOUSE_NAMES
+        .iter()
+        .find(|(code, _)| *code == button)
+        .map(|(_, name)| *name)
+        .unwrap_or("MouseUnknown")
+}
+
+/// Look up a mouse button by its name (case-insensitive)
+pub fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+    MOUSE_NAMES
+        .iter()
+        .find(|(_, button_name)| button_name.eq_ignore_ascii_case(name))
+        .map(|(code, _)| *code)
+}
+
+/// Check if a key is a modifier (Shift, Ctrl, Alt, Super)
+pub fn is_modifier_key(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::LeftShift | KeyCode::RightShift |
+        KeyCode::LeftControl | KeyCode::RightControl |
+        KeyCode::LeftAlt | KeyCode::RightAlt |
+        KeyCode::LeftSuper | KeyCode::RightSuper
+    )
+}
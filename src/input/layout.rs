@@ -0,0 +1,86 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Keyboard layout used to resolve physical key bindings.
+///
+/// macroquad reports layout-dependent key codes and gives no access to scancodes,
+/// so the game has to tell the input manager which layout the player uses
+/// (typically from a settings screen).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum KeyboardLayout {
+    /// US QWERTY. Physical positions are named after the keys on this layout.
+    #[default]
+    Qwerty,
+    /// French AZERTY
+    Azerty,
+    /// German QWERTZ
+    Qwertz,
+    Dvorak,
+    Colemak,
+}
+
+/// (QWERTY position, key code reported on the layout) pairs that differ from QWERTY
+const AZERTY: &[(KeyCode, KeyCode)] = &[
+    (KeyCode::Q, KeyCode::A), (KeyCode::A, KeyCode::Q),
+    (KeyCode::W, KeyCode::Z), (KeyCode::Z, KeyCode::W),
+    (KeyCode::Semicolon, KeyCode::M), (KeyCode::M, KeyCode::Comma),
+    (KeyCode::Comma, KeyCode::Semicolon),
+];
+
+const QWERTZ: &[(KeyCode, KeyCode)] = &[
+    (KeyCode::Y, KeyCode::Z), (KeyCode::Z, KeyCode::Y),
+];
+
+const DVORAK: &[(KeyCode, KeyCode)] = &[
+    (KeyCode::Minus, KeyCode::LeftBracket), (KeyCode::Equal, KeyCode::RightBracket),
+    (KeyCode::Q, KeyCode::Apostrophe), (KeyCode::W, KeyCode::Comma), (KeyCode::E, KeyCode::Period),
+    (KeyCode::R, KeyCode::P), (KeyCode::T, KeyCode::Y), (KeyCode::Y, KeyCode::F),
+    (KeyCode::U, KeyCode::G), (KeyCode::I, KeyCode::C), (KeyCode::O, KeyCode::R),
+    (KeyCode::P, KeyCode::L), (KeyCode::LeftBracket, KeyCode::Slash), (KeyCode::RightBracket, KeyCode::Equal),
+    (KeyCode::S, KeyCode::O), (KeyCode::D, KeyCode::E), (KeyCode::F, KeyCode::U),
+    (KeyCode::G, KeyCode::I), (KeyCode::H, KeyCode::D), (KeyCode::J, KeyCode::H),
+    (KeyCode::K, KeyCode::T), (KeyCode::L, KeyCode::N), (KeyCode::Semicolon, KeyCode::S),
+    (KeyCode::Apostrophe, KeyCode::Minus),
+    (KeyCode::Z, KeyCode::Semicolon), (KeyCode::X, KeyCode::Q), (KeyCode::C, KeyCode::J),
+    (KeyCode::V, KeyCode::K), (KeyCode::B, KeyCode::X), (KeyCode::N, KeyCode::B),
+    (KeyCode::Comma, KeyCode::W), (KeyCode::Period, KeyCode::V), (KeyCode::Slash, KeyCode::Z),
+];
+
+const COLEMAK: &[(KeyCode, KeyCode)] = &[
+    (KeyCode::E, KeyCode::F), (KeyCode::R, KeyCode::P), (KeyCode::T, KeyCode::G),
+    (KeyCode::Y, KeyCode::J), (KeyCode::U, KeyCode::L), (KeyCode::I, KeyCode::U),
+    (KeyCode::O, KeyCode::Y), (KeyCode::P, KeyCode::Semicolon),
+    (KeyCode::S, KeyCode::R), (KeyCode::D, KeyCode::S), (KeyCode::F, KeyCode::T),
+    (KeyCode::G, KeyCode::D), (KeyCode::J, KeyCode::N), (KeyCode::K, KeyCode::E),
+    (KeyCode::L, KeyCode::I), (KeyCode::Semicolon, KeyCode::O), (KeyCode::N, KeyCode::K),
+];
+
+impl KeyboardLayout {
+    fn remaps(&self) -> &'static [(KeyCode, KeyCode)] {
+        match self {
+            KeyboardLayout::Qwerty => &[],
+            KeyboardLayout::Azerty => AZERTY,
+            KeyboardLayout::Qwertz => QWERTZ,
+            KeyboardLayout::Dvorak => DVORAK,
+            KeyboardLayout::Colemak => COLEMAK,
+        }
+    }
+
+    /// The key code this layout reports for the key at a QWERTY position
+    pub fn key_at(&self, position: KeyCode) -> KeyCode {
+        self.remaps()
+            .iter()
+            .find(|(from, _)| *from == position)
+            .map(|(_, to)| *to)
+            .unwrap_or(position)
+    }
+
+    /// The QWERTY position of a key code reported by this layout
+    pub fn position_of(&self, key: KeyCode) -> KeyCode {
+        self.remaps()
+            .iter()
+            .find(|(_, to)| *to == key)
+            .map(|(from, _)| *from)
+            .unwrap_or(key)
+    }
+}
@@ -1,5 +1,23 @@
 pub mod input_manager;
 pub mod action;
+pub mod keys;
+pub mod sequence;
+pub mod recording;
+pub mod players;
+pub mod rumble;
+pub mod layout;
+pub mod callbacks;
+pub mod clipboard;
+pub mod gestures;
 
-pub use input_manager::InputManager;
+pub use input_manager::{InputManager, BindingsError};
 pub use action::{Action, InputBinding, KeyBinding, MouseBinding};
+pub use keys::{key_name, key_from_name};
+pub use sequence::{InputSequence, SequenceProgress};
+pub use recording::{InputFrame, InputRecording, RecordingError};
+pub use players::{InputDevice, PlayerInput, PlayerInputs};
+pub use rumble::RumbleBackend;
+pub use layout::KeyboardLayout;
+pub use callbacks::{ActionEvent, ActionEventKind, CallbackId};
+pub use clipboard::{get_clipboard, set_clipboard};
+pub use gestures::{Gesture, GestureEvent, GestureRecognizer, SwipeDirection, TouchPoint};
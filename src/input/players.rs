@@ -0,0 +1,188 @@
+use macroquad::prelude::*;
+use super::{Action, InputBinding, InputManager};
+
+/// An input device (or part of one) a local player can own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InputDevice {
+    /// The whole keyboard with the usual bindings (WASD/arrows, Space, X, Z, E, Escape)
+    Keyboard,
+    /// WASD side: move with WASD, F jump, G attack, H defend, E interact, Escape pause
+    KeyboardLeft,
+    /// Arrow side: move with arrows, Right Shift jump, Right Ctrl attack, Right Alt defend, Enter interact
+    KeyboardRight,
+    /// Left click attack, right click defend
+    Mouse,
+    /// A gamepad by index. Contributes no bindings until a gamepad backend is wired in.
+    Gamepad(usize),
+}
+
+impl InputDevice {
+    /// Bindings this device provides by default
+    pub fn default_bindings(&self) -> Vec<(Action, InputBinding)> {
+        use KeyCode::*;
+        // Keys bind by physical position so each half keeps its shape on AZERTY etc.
+        let keys = |pairs: &[(Action, KeyCode)]| -> Vec<(Action, InputBinding)> {
+            pairs.iter().map(|(action, key)| (action.clone(), InputBinding::physical(*key))).collect()
+        };
+
+        match self {
+            InputDevice::Keyboard => keys(&[
+                (Action::MoveUp, W),
+                (Action::MoveUp, Up),
+                (Action::MoveDown, S),
+                (Action::MoveDown, Down),
+                (Action::MoveLeft, A),
+                (Action::MoveLeft, Left),
+                (Action::MoveRight, D),
+                (Action::MoveRight, Right),
+                (Action::Jump, Space),
+                (Action::Attack, X),
+                (Action::Defend, Z),
+                (Action::Interact, E),
+                (Action::Pause, Escape),
+            ]),
+            InputDevice::KeyboardLeft => keys(&[
+                (Action::MoveUp, W),
+                (Action::MoveDown, S),
+                (Action::MoveLeft, A),
+                (Action::MoveRight, D),
+                (Action::Jump, F),
+                (Action::Attack, G),
+                (Action::Defend, H),
+                (Action::Interact, E),
+                (Action::Pause, Escape),
+            ]),
+            InputDevice::KeyboardRight => keys(&[
+                (Action::MoveUp, Up),
+                (Action::MoveDown, Down),
+                (Action::MoveLeft, Left),
+                (Action::MoveRight, Right),
+                (Action::Jump, RightShift),
+                (Action::Attack, RightControl),
+                (Action::Defend, RightAlt),
+                (Action::Interact, Enter),
+            ]),
+            InputDevice::Mouse => vec![
+                (Action::Attack, InputBinding::mouse(MouseButton::Left)),
+                (Action::Defend, InputBinding::mouse(MouseButton::Right)),
+            ],
+            InputDevice::Gamepad(_) => vec![],
+        }
+    }
+}
+
+/// One local player's devices and input state
+pub struct PlayerInput {
+    devices: Vec<InputDevice>,
+    input: InputManager,
+}
+
+impl PlayerInput {
+    fn new(devices: Vec<InputDevice>) -> Self {
+        let mut player = Self { devices, input: InputManager::empty() };
+        player.reset_bindings();
+        player
+    }
+
+    /// Replace the bindings with the defaults of this player's devices
+    pub fn reset_bindings(&mut self) {
+        self.input.clear_bindings();
+        for device in &self.devices {
+            for (action, binding) in device.default_bindings() {
+                self.input.add_binding(action, binding);
+            }
+        }
+    }
+
+    pub fn devices(&self) -> &[InputDevice] {
+        &self.devices
+    }
+
+    pub fn input(&self) -> &InputManager {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut InputManager {
+        &mut self.input
+    }
+}
+
+/// Input for local multiplayer: each player slot owns a set of devices and
+/// has its own `InputManager` with its own bindings and action state.
+///
+/// ```ignore
+/// let mut players = PlayerInputs::new();
+/// let p1 = players.add_player(vec![InputDevice::KeyboardLeft]);
+/// let p2 = players.add_player(vec![InputDevice::KeyboardRight]);
+/// game.set_players(players);
+/// // entities report their slot through `Entity::player_slot`
+/// ```
+///
+/// Raw key queries on a player's manager still see the whole keyboard;
+/// only actions are split by device.
+pub struct PlayerInputs {
+    players: Vec<PlayerInput>,
+}
+
+impl PlayerInputs {
+    pub fn new() -> Self {
+        Self { players: vec![] }
+    }
+
+    /// Add a player owning the given devices; returns their slot
+    pub fn add_player(&mut self, devices: Vec<InputDevice>) -> usize {
+        self.players.push(PlayerInput::new(devices));
+        self.players.len() - 1
+    }
+
+    /// Give a player a new device set, resetting their bindings to its defaults
+    pub fn set_devices(&mut self, slot: usize, devices: Vec<InputDevice>) {
+        if let Some(player) = self.players.get_mut(slot) {
+            player.devices = devices;
+            player.reset_bindings();
+        }
+    }
+
+    /// Slot of the player owning a device
+    pub fn player_with_device(&self, device: InputDevice) -> Option<usize> {
+        self.players.iter().position(|player| player.devices.contains(&device))
+    }
+
+    pub fn player(&self, slot: usize) -> Option<&PlayerInput> {
+        self.players.get(slot)
+    }
+
+    pub fn player_mut(&mut self, slot: usize) -> Option<&mut PlayerInput> {
+        self.players.get_mut(slot)
+    }
+
+    /// The input manager for a player slot
+    pub fn input(&self, slot: usize) -> Option<&InputManager> {
+        self.players.get(slot).map(PlayerInput::input)
+    }
+
+    pub fn input_mut(&mut self, slot: usize) -> Option<&mut InputManager> {
+        self.players.get_mut(slot).map(PlayerInput::input_mut)
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    /// Update every player's input - call this once per frame
+    pub fn update(&mut self, dt: f32) {
+        for player in &mut self.players {
+            player.input.update(dt);
+        }
+    }
+}
+
+impl Default for PlayerInputs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
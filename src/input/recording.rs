@@ -0,0 +1,202 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use super::gestures::TouchPoint;
+use super::keys::{key_from_name, key_name, mouse_button_from_name, mouse_button_name};
+
+/// Raw input state captured for one frame
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "FrameData", try_from = "FrameData")]
+pub struct InputFrame {
+    /// Frame time, so replays advance the game by the same steps
+    pub dt: f32,
+    pub keys: Vec<KeyCode>,
+    pub mouse_buttons: Vec<MouseButton>,
+    pub mouse_position: Vec2,
+    pub scroll: Vec2,
+    /// Characters typed during text input, in order
+    pub chars: Vec<char>,
+    /// Fingers on the screen
+    pub touches: Vec<TouchPoint>,
+}
+
+impl InputFrame {
+    /// A frame with nothing pressed and the mouse at the origin
+    pub fn new(dt: f32) -> Self {
+        Self {
+            dt,
+            keys: vec![],
+            mouse_buttons: vec![],
+            mouse_position: Vec2::ZERO,
+            scroll: Vec2::ZERO,
+            chars: vec![],
+            touches: vec![],
+        }
+    }
+}
+
+/// Serialized form of a frame, with keys stored by name
+#[derive(Serialize, Deserialize)]
+struct FrameData {
+    dt: f32,
+    #[serde(default)]
+    keys: Vec<String>,
+    #[serde(default)]
+    mouse_buttons: Vec<String>,
+    mouse_position: Vec2,
+    #[serde(default)]
+    scroll: Vec2,
+    #[serde(default)]
+    chars: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    touches: Vec<TouchPoint>,
+}
+
+impl From<InputFrame> for FrameData {
+    fn from(frame: InputFrame) -> Self {
+        Self {
+            dt: frame.dt,
+            keys: frame.keys.iter().map(|key| key_name(*key).to_string()).collect(),
+            mouse_buttons: frame.mouse_buttons.iter().map(|b| mouse_button_name(*b).to_string()).collect(),
+            mouse_position: frame.mouse_position,
+            scroll: frame.scroll,
+            chars: frame.chars.into_iter().collect(),
+            touches: frame.touches,
+        }
+    }
+}
+
+impl TryFrom<FrameData> for InputFrame {
+    type Error = String;
+
+    fn try_from(data: FrameData) -> Result<Self, Self::Error> {
+        let keys = data.keys.iter()
+            .map(|name| key_from_name(name).ok_or_else(|| format!("unknown key '{}'", name)))
+            .collect::<Result<_, _>>()?;
+        let mouse_buttons = data.mouse_buttons.iter()
+            .map(|name| mouse_button_from_name(name).ok_or_else(|| format!("unknown mouse button '{}'", name)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            dt: data.dt,
+            keys,
+            mouse_buttons,
+            mouse_position: data.mouse_position,
+            scroll: data.scroll,
+            chars: data.chars.chars().collect(),
+            touches: data.touches,
+        })
+    }
+}
+
+/// Error returned when saving or loading input recordings
+#[derive(Debug)]
+pub enum RecordingError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordingError::Io(err) => write!(f, "failed to access recording file: {}", err),
+            RecordingError::Format(msg) => write!(f, "invalid recording data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(err: std::io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+/// A frame-by-frame capture of player input.
+///
+/// Replaying it drives `InputManager` exactly as the player did. The game must also be
+/// deterministic (fixed random seeds, no wall-clock logic) for the result to match.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    frames: Vec<InputFrame>,
+}
+
+impl InputRecording {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, frame: InputFrame) {
+        self.frames.push(frame);
+    }
+
+    pub fn frames(&self) -> &[InputFrame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Total recorded time in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.dt).sum()
+    }
+
+    /// Serialize to a RON string
+    pub fn to_ron_string(&self) -> Result<String, RecordingError> {
+        ron::ser::to_string(self).map_err(|err| RecordingError::Format(err.to_string()))
+    }
+
+    /// Parse a recording from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, RecordingError> {
+        ron::from_str(data).map_err(|err| RecordingError::Format(err.to_string()))
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RecordingError> {
+        std::fs::write(path, self.to_ron_string()?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RecordingError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+}
+
+/// Replay position within a recording
+#[derive(Debug, Clone)]
+pub(crate) struct Playback {
+    recording: InputRecording,
+    cursor: usize,
+    looping: bool,
+}
+
+impl Playback {
+    pub(crate) fn new(recording: InputRecording, looping: bool) -> Self {
+        Self { recording, cursor: 0, looping }
+    }
+
+    /// The next recorded frame, or None once the recording has ended
+    pub(crate) fn next_frame(&mut self) -> Option<InputFrame> {
+        if self.cursor >= self.recording.len() {
+            if !self.looping || self.recording.is_empty() {
+                return None;
+            }
+            self.cursor = 0;
+        }
+        let frame = self.recording.frames[self.cursor].clone();
+        self.cursor += 1;
+        Some(frame)
+    }
+
+    pub(crate) fn position(&self) -> (usize, usize) {
+        (self.cursor, self.recording.len())
+    }
+}
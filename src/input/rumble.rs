@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+/// Platform hook that drives gamepad motors.
+/// macroquad has no gamepad support, so plug in whichever gamepad library the game uses.
+pub trait RumbleBackend {
+    /// Check if a gamepad is connected
+    fn is_connected(&self, gamepad: usize) -> bool;
+
+    /// Set a gamepad's motor strength (0..1). Called only when the strength changes.
+    fn set_vibration(&mut self, gamepad: usize, strength: f32);
+}
+
+/// A single rumble that fades out over its duration
+#[derive(Debug, Clone, Copy)]
+struct RumbleEffect {
+    intensity: f32,
+    duration: f32,
+    elapsed: f32,
+}
+
+impl RumbleEffect {
+    fn strength(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 0.0;
+        }
+        self.intensity * (1.0 - self.elapsed / self.duration).max(0.0)
+    }
+}
+
+/// Rumble state for every gamepad, combined from overlapping effects
+pub(crate) struct RumbleState {
+    effects: HashMap<usize, Vec<RumbleEffect>>,
+    strengths: HashMap<usize, f32>,
+    enabled: bool,
+    backend: Option<Box<dyn RumbleBackend>>,
+}
+
+impl RumbleState {
+    pub(crate) fn new() -> Self {
+        Self {
+            effects: HashMap::new(),
+            strengths: HashMap::new(),
+            enabled: true,
+            backend: None,
+        }
+    }
+
+    pub(crate) fn set_backend(&mut self, backend: Option<Box<dyn RumbleBackend>>) {
+        self.stop_all();
+        self.backend = backend;
+    }
+
+    pub(crate) fn is_connected(&self, gamepad: usize) -> bool {
+        self.backend.as_ref().is_some_and(|backend| backend.is_connected(gamepad))
+    }
+
+    pub(crate) fn rumble(&mut self, gamepad: usize, intensity: f32, duration: f32) {
+        if !self.enabled || !self.is_connected(gamepad) || duration <= 0.0 {
+            return;
+        }
+        self.effects.entry(gamepad).or_default().push(RumbleEffect {
+            intensity: intensity.clamp(0.0, 1.0),
+            duration,
+            elapsed: 0.0,
+        });
+        self.sync(gamepad);
+    }
+
+    pub(crate) fn stop(&mut self, gamepad: usize) {
+        self.effects.remove(&gamepad);
+        self.sync(gamepad);
+    }
+
+    pub(crate) fn stop_all(&mut self) {
+        let gamepads: Vec<usize> = self.strengths.keys().copied().collect();
+        self.effects.clear();
+        for gamepad in gamepads {
+            self.sync(gamepad);
+        }
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.stop_all();
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn strength(&self, gamepad: usize) -> f32 {
+        self.strengths.get(&gamepad).copied().unwrap_or(0.0)
+    }
+
+    /// Decay effects and push changed strengths to the backend
+    pub(crate) fn update(&mut self, dt: f32) {
+        for effects in self.effects.values_mut() {
+            for effect in effects.iter_mut() {
+                effect.elapsed += dt;
+            }
+            effects.retain(|effect| effect.elapsed < effect.duration);
+        }
+        self.effects.retain(|_, effects| !effects.is_empty());
+
+        let gamepads: Vec<usize> = self.effects.keys().chain(self.strengths.keys()).copied().collect();
+        for gamepad in gamepads {
+            self.sync(gamepad);
+        }
+    }
+
+    fn sync(&mut self, gamepad: usize) {
+        let strength = self.effects
+            .get(&gamepad)
+            .map(|effects| effects.iter().map(RumbleEffect::strength).fold(0.0, f32::max))
+            .unwrap_or(0.0);
+
+        if self.strength(gamepad) == strength {
+            return;
+        }
+        if strength > 0.0 {
+            self.strengths.insert(gamepad, strength);
+        } else {
+            self.strengths.remove(&gamepad);
+        }
+        if let Some(backend) = &mut self.backend {
+            backend.set_vibration(gamepad, strength);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use super::Action;
+use std::collections::HashSet;
+
+/// An ordered list of action steps that must be entered within a time window,
+/// e.g. a quarter-circle-forward + punch motion:
+///
+/// ```ignore
+/// let hadouken = InputSequence::new()
+///     .then(Action::MoveDown)
+///     .then_chord(vec![Action::MoveDown, Action::MoveRight])
+///     .then(Action::MoveRight)
+///     .then(Action::Attack)
+///     .within(0.4);
+/// input.bind_sequence(Action::custom("hadouken"), hadouken);
+/// ```
+///
+/// Directions are absolute, so bind a mirrored sequence for the other facing.
+#[derive(Debug, Clone)]
+pub struct InputSequence {
+    steps: Vec<Vec<Action>>,
+    time_window: f32,
+}
+
+impl InputSequence {
+    pub fn new() -> Self {
+        Self {
+            steps: vec![],
+            time_window: 0.4, // 400ms by default
+        }
+    }
+
+    /// Add a step made of a single action
+    pub fn then(mut self, action: Action) -> Self {
+        self.steps.push(vec![action]);
+        self
+    }
+
+    /// Add a step where all actions must be held together (e.g. Down-Forward)
+    pub fn then_chord(mut self, actions: Vec<Action>) -> Self {
+        self.steps.push(actions);
+        self
+    }
+
+    /// Set the time allowed from the first step to the last (in seconds)
+    pub fn within(mut self, seconds: f32) -> Self {
+        self.time_window = seconds;
+        self
+    }
+
+    pub fn steps(&self) -> &[Vec<Action>] {
+        &self.steps
+    }
+
+    pub fn time_window(&self) -> f32 {
+        self.time_window
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Default for InputSequence {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// How far a sequence has been entered (for training-mode displays)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SequenceProgress {
+    pub matched_steps: usize,
+    pub total_steps: usize,
+    /// Seconds since the first step was entered
+    pub elapsed: f32,
+    pub time_window: f32,
+}
+
+impl SequenceProgress {
+    /// Fraction of steps entered (0..1)
+    pub fn fraction(&self) -> f32 {
+        if self.total_steps == 0 {
+            0.0
+        } else {
+            self.matched_steps as f32 / self.total_steps as f32
+        }
+    }
+
+    /// Seconds left before the partial input expires
+    pub fn time_remaining(&self) -> f32 {
+        (self.time_window - self.elapsed).max(0.0)
+    }
+}
+
+/// Matching state for one bound sequence
+#[derive(Debug, Clone)]
+pub(crate) struct SequenceTracker {
+    sequence: InputSequence,
+    matched: usize,
+    elapsed: f32,
+}
+
+impl SequenceTracker {
+    pub(crate) fn new(sequence: InputSequence) -> Self {
+        Self {
+            sequence,
+            matched: 0,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advance with this frame's action state. Returns true when the sequence completes.
+    pub(crate) fn update(&mut self, dt: f32, active: &HashSet<Action>, previous: &HashSet<Action>) -> bool {
+        if self.sequence.is_empty() {
+            return false;
+        }
+
+        if self.matched > 0 {
+            self.elapsed += dt;
+            if self.elapsed > self.sequence.time_window {
+                self.reset();
+            }
+        }
+
+        if self.step_entered(self.matched, active, previous) {
+            if self.matched == 0 {
+                self.elapsed = 0.0;
+            }
+            self.matched += 1;
+        } else if self.matched > 0 && self.step_entered(0, active, previous) {
+            // Started over: treat this as a fresh first step
+            self.matched = 1;
+            self.elapsed = 0.0;
+        }
+
+        if self.matched == self.sequence.len() {
+            self.reset();
+            return true;
+        }
+        false
+    }
+
+    pub(crate) fn progress(&self) -> SequenceProgress {
+        SequenceProgress {
+            matched_steps: self.matched,
+            total_steps: self.sequence.len(),
+            elapsed: self.elapsed,
+            time_window: self.sequence.time_window,
+        }
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.matched = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// A step is entered on the frame its condition becomes true
+    fn step_entered(&self, index: usize, active: &HashSet<Action>, previous: &HashSet<Action>) -> bool {
+        let step = &self.sequence.steps[index];
+
+        // Steps reached by releasing part of a chord (Down-Forward -> Forward)
+        // also require the released actions to be up
+        let excluded: Vec<&Action> = match index.checked_sub(1).map(|i| &self.sequence.steps[i]) {
+            Some(prev_step) if step.iter().all(|action| prev_step.contains(action)) => {
+                prev_step.iter().filter(|action| !step.contains(action)).collect()
+            }
+            _ => vec![],
+        };
+
+        let satisfied = |state: &HashSet<Action>| {
+            step.iter().all(|action| state.contains(action))
+                && !excluded.iter().any(|action| state.contains(*action))
+        };
+
+        satisfied(active) && !satisfied(previous)
+    }
+}
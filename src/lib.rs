@@ -3,22 +3,58 @@ pub mod core;
 pub mod math;
 pub mod input;
 pub mod rendering;  
+pub mod effects;
+pub mod physics;
+pub mod movement;
+pub mod bullets;
+pub mod targeting;
+pub mod modes;
+pub mod director;
+pub mod ui;
+pub mod assets;
+pub mod audio;
+pub mod save;
+pub mod procgen;
+#[cfg(feature = "http")]
+pub mod net;
 
 
 // Re-export commonly used types for convenience
-pub use core::{Entity, Scene, Game, GameConfig, GameObject, TimeManager};
-pub use math::{Transform, Vec2Utils};
+pub use core::{BuildInfo, Entity, EntityId, Scene, SceneLayer, Game, GameConfig, GameObject, GameState, Plugin, TimeManager};
+pub use math::{Transform, Vec2Utils, Easing, Noise};
 pub use input::{InputManager, Action, InputBinding};
-pub use rendering::{Camera, CameraBounds};
+pub use rendering::{Camera, CameraBounds, CameraTarget, ShapeBatch, Sprite, SpriteBatch, StrokeStyle};
+pub use effects::{ParticleEffect, EffectLibrary};
+pub use physics::{Collider, Body};
+pub use movement::{MovementPattern, PatternMover};
+pub use bullets::{BulletSystem, BulletPattern, BulletPatternLibrary};
+pub use ui::{Menu, UiLibrary, Theme};
+pub use assets::AssetManager;
+pub use audio::AudioManager;
+pub use save::{SaveData, SaveManager};
 
 // Re-export macroquad types that users will commonly need
 pub use macroquad::prelude::{Vec2, Color, KeyCode, MouseButton};
 
 // Convenience prelude for users of the framework
 pub mod prelude {
-    pub use crate::core::{Entity, Scene, Game, GameConfig, GameObject, TimeManager};
-    pub use crate::math::{Transform, Vec2Utils};
-    pub use crate::input::{InputManager, Action, InputBinding};
-    pub use crate::rendering::{Camera, CameraBounds}; 
+    pub use crate::core::{BuildInfo, Entity, EntityId, Scene, SceneLayer, SceneLayers, Game, GameConfig, GameObject, GameState, Plugin, TimeManager, RewindSettings, Resources, UpdateContext, LifecycleContext, Commands, Events, JobQueue, JobStatus, MotionSettings, spawn, EntityBuilder, SimpleEntity, Constraint, OwnerRemoval, Relation, With, Without, Added, Changed, Prefab, PrefabLibrary, PrefabOverrides, VariantGroup, Timeline, ScriptAction, ScriptEvent, SceneFile, EntityRecord, EntityRegistry, StressTest, FrameProfiler, LogLevel, SceneSnapshot, SnapshotHistory, SnapshotDiff, HeadlessRunner, Rng, Pool, Portal, PortalEvent, PortalTarget, RemoteConfig, ConfigEvent};
+    pub use crate::math::{Transform, Vec2Utils, Easing, Noise};
+    pub use crate::input::{InputManager, Action, InputBinding, InputSequence, InputDevice, PlayerInputs, KeyboardLayout, ActionEvent, ActionEventKind, Gesture, GestureEvent, SwipeDirection};
+    pub use crate::rendering::{Camera, CameraBounds, CameraTarget, CameraZone, CameraSequence, CameraEvent, Shake, AmbientShake, Framing, split_screen_viewports, debug_draw, DebugTheme, Lod, LodLevel, LodSettings, ShapeBatch, Sprite, SpriteBatch, StrokeStyle, DashPattern, LineJoin, LineCap, with_clip_rect};
+    pub use crate::effects::{ParticleEffect, EffectLibrary, EmitterConfig, ParticleShape};
+    pub use crate::physics::{Collider, Body, PhysicsDebug, TileCollision};
+    pub use crate::movement::{MovementPattern, PatternMover};
+    pub use crate::bullets::{BulletSystem, BulletPattern, BulletPatternLibrary, EmitterDef, EmitterShape};
+    pub use crate::targeting::{Target, TargetShape, TargetValidity, TargetingEvent, TargetingPreview};
+    pub use crate::modes::{TimeTrial, MedalThresholds, Medal, GhostEntity, GhostRun};
+    pub use crate::director::{PacingDirector, DirectorConfig, PacingPhase};
+    #[cfg(feature = "http")]
+    pub use crate::net::{HttpClient, HttpEvent, HttpRequest};
+    pub use crate::ui::{Menu, UiLibrary, Theme, Dialog, DialogButton, RadialMenu, RadialItem, CheatMenu, Cursor, CursorSprite, SystemCursor, Anchor, Style, ButtonAction, WidgetDef, WidgetKind, ScreenDef};
+    pub use crate::assets::{AssetManager, ContentValidator, LoadProgress};
+    pub use crate::audio::{AudioManager, PlayResult, SoundDef, SoundEvents, SceneAudio, AmbientBed, AudioZone, Reverb, Stinger};
+    pub use crate::procgen::{ContentLibrary, NameGenerator, PaletteGenerator, Harmony, DescriptorGenerator, seeded_rng};
+    pub use crate::save::{SaveData, SaveManager, CloudSync, LocalFolderStorage, Resolution, SaveStorage};
     pub use macroquad::prelude::*;
 }
\ No newline at end of file
@@ -1,5 +1,9 @@
 pub mod transform;
 pub mod vec2_utils;
+pub mod easing;
+pub mod noise;
 
 pub use transform::Transform;
-pub use vec2_utils::Vec2Utils;
\ No newline at end of file
+pub use vec2_utils::Vec2Utils;
+pub use easing::Easing;
+pub use noise::Noise;
@@ -0,0 +1,107 @@
+use macroquad::prelude::*;
+
+/// Seeded gradient noise (Perlin-style). Smooth, repeatable, output roughly in -1..1.
+///
+/// ```ignore
+/// let noise = Noise::new(42);
+/// let wobble = noise.sample_1d(time * 2.0) * 10.0;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Noise {
+    seed: u32,
+}
+
+impl Noise {
+    pub fn new(seed: u32) -> Self {
+        Self { seed }
+    }
+
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Sample 1D noise
+    pub fn sample_1d(&self, x: f32) -> f32 {
+        let x0 = x.floor();
+        let t = x - x0;
+        let i = x0 as i32;
+
+        let g0 = self.gradient_1d(i);
+        let g1 = self.gradient_1d(i.wrapping_add(1));
+        let value = lerp(g0 * t, g1 * (t - 1.0), fade(t));
+        (value * 2.0).clamp(-1.0, 1.0)
+    }
+
+    /// Sample 2D noise
+    pub fn sample_2d(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i32, y0 as i32);
+
+        let corner = |dx: i32, dy: i32| {
+            let gradient = self.gradient_2d(ix.wrapping_add(dx), iy.wrapping_add(dy));
+            gradient.dot(Vec2::new(tx - dx as f32, ty - dy as f32))
+        };
+
+        let (u, v) = (fade(tx), fade(ty));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+        (lerp(top, bottom, v) * std::f32::consts::SQRT_2).clamp(-1.0, 1.0)
+    }
+
+    /// Layered 1D noise with finer detail per octave (fractal Brownian motion)
+    pub fn fbm_1d(&self, x: f32, octaves: u32) -> f32 {
+        self.fbm(octaves, |noise, frequency| noise.sample_1d(x * frequency))
+    }
+
+    /// Layered 2D noise with finer detail per octave (fractal Brownian motion)
+    pub fn fbm_2d(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        self.fbm(octaves, |noise, frequency| noise.sample_2d(x * frequency, y * frequency))
+    }
+
+    fn fbm<F: Fn(&Noise, f32) -> f32>(&self, octaves: u32, sample: F) -> f32 {
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+        let mut max_amplitude = 0.0;
+
+        for octave in 0..octaves.max(1) {
+            // Offset the seed per octave so layers don't line up
+            let layer = Noise::new(self.seed.wrapping_add(octave.wrapping_mul(0x9E37_79B9)));
+            total += sample(&layer, frequency) * amplitude;
+            max_amplitude += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+
+        total / max_amplitude
+    }
+
+    fn gradient_1d(&self, i: i32) -> f32 {
+        (hash(self.seed, i, 0) as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+
+    fn gradient_2d(&self, x: i32, y: i32) -> Vec2 {
+        let angle = (hash(self.seed, x, y) as f32 / u32::MAX as f32) * std::f32::consts::TAU;
+        Vec2::from_angle(angle)
+    }
+}
+
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+fn hash(seed: u32, x: i32, y: i32) -> u32 {
+    let mut h = seed
+        ^ (x as u32).wrapping_mul(0x27D4_EB2D)
+        ^ (y as u32).wrapping_mul(0x1656_67B1);
+    h ^= h >> 15;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
@@ -0,0 +1,3 @@
+pub mod time_trial;
+
+pub use time_trial::{GhostEntity, GhostFrame, GhostRun, Medal, MedalThresholds, SplitResult, TimeTrial, TrialRecord, TrialResult};
@@ -0,0 +1,350 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::core::{Entity, EntityId, Scene, UpdateContext};
+use crate::save::{SaveData, SaveError, SaveManager};
+
+/// Award for finishing a level fast enough
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+/// Slowest finishing times, in seconds, that still earn each medal
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MedalThresholds {
+    pub gold: f32,
+    pub silver: f32,
+    pub bronze: f32,
+}
+
+impl MedalThresholds {
+    pub fn new(gold: f32, silver: f32, bronze: f32) -> Self {
+        Self { gold, silver, bronze }
+    }
+
+    /// Best medal a finishing time earns
+    pub fn medal_for(&self, time: f32) -> Option<Medal> {
+        if time <= self.gold {
+            Some(Medal::Gold)
+        } else if time <= self.silver {
+            Some(Medal::Silver)
+        } else if time <= self.bronze {
+            Some(Medal::Bronze)
+        } else {
+            None
+        }
+    }
+
+    /// Time to beat for a medal
+    pub fn time_for(&self, medal: Medal) -> f32 {
+        match medal {
+            Medal::Gold => self.gold,
+            Medal::Silver => self.silver,
+            Medal::Bronze => self.bronze,
+        }
+    }
+}
+
+/// Where the player was at one moment of a run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GhostFrame {
+    pub time: f32,
+    pub position: Vec2,
+}
+
+/// A recorded run, replayed as a ghost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GhostRun {
+    pub frames: Vec<GhostFrame>,
+}
+
+impl GhostRun {
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Length of the run in seconds
+    pub fn duration(&self) -> f32 {
+        self.frames.last().map_or(0.0, |frame| frame.time)
+    }
+
+    /// Position at `time`, between the recorded frames (None before the first recorded frame
+    /// or for an empty run; the last frame after the end)
+    pub fn position_at(&self, time: f32) -> Option<Vec2> {
+        let next = self.frames.partition_point(|frame| frame.time <= time);
+        if next == 0 {
+            return self.frames.first().filter(|frame| frame.time <= time).map(|frame| frame.position);
+        }
+        let before = self.frames[next - 1];
+        let Some(after) = self.frames.get(next) else {
+            return Some(before.position);
+        };
+        let t = (time - before.time) / (after.time - before.time).max(f32::EPSILON);
+        Some(before.position.lerp(after.position, t))
+    }
+}
+
+/// Best run of one level, saved in its own slot (see `TimeTrial::load`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrialRecord {
+    pub best_time: Option<f32>,
+    /// Split times of the best run, from the start
+    pub best_splits: Vec<f32>,
+    pub best_medal: Option<Medal>,
+    pub ghost: GhostRun,
+    pub attempts: u32,
+}
+
+impl SaveData for TrialRecord {}
+
+/// A split reached during a run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitResult {
+    pub index: usize,
+    /// Seconds since the start
+    pub time: f32,
+    /// Ahead (negative) or behind (positive) the best run at this split
+    pub delta: Option<f32>,
+}
+
+/// How a finished run went
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrialResult {
+    pub time: f32,
+    pub splits: Vec<f32>,
+    pub medal: Option<Medal>,
+    /// Best time before this run
+    pub previous_best: Option<f32>,
+    pub new_best: bool,
+}
+
+/// Time trial of one level: a timer with splits, the player's best run replayed as a
+/// ghost, and medals for finishing under set times. The best run is kept per level in
+/// a save slot.
+///
+/// ```ignore
+/// let mut trial = TimeTrial::new("forest-1", MedalThresholds::new(45.0, 60.0, 90.0));
+/// trial.load(&saves)?;
+/// trial.start(player_position);
+/// trial.spawn_ghost(scene, Color::new(0.6, 0.8, 1.0, 0.4));
+/// // each frame
+/// trial.update(dt, player_position);
+/// // at checkpoints and the goal
+/// let split = trial.split();
+/// if let Some(result) = trial.finish(player_position) {
+///     trial.save(&saves)?;
+/// }
+/// ```
+pub struct TimeTrial {
+    level: String,
+    medals: MedalThresholds,
+    record: TrialRecord,
+    elapsed: f32,
+    splits: Vec<f32>,
+    running: bool,
+    recording: GhostRun,
+    // Seconds between recorded ghost frames
+    sample_interval: f32,
+    since_sample: f32,
+}
+
+impl TimeTrial {
+    pub fn new(level: &str, medals: MedalThresholds) -> Self {
+        Self {
+            level: level.to_string(),
+            medals,
+            record: TrialRecord::default(),
+            elapsed: 0.0,
+            splits: vec![],
+            running: false,
+            recording: GhostRun::default(),
+            sample_interval: 1.0 / 30.0,
+            since_sample: 0.0,
+        }
+    }
+
+    /// Ghost frames recorded per second (30 by default)
+    pub fn with_sample_rate(mut self, per_second: f32) -> Self {
+        self.sample_interval = 1.0 / per_second.max(1.0);
+        self
+    }
+
+    pub fn with_record(mut self, record: TrialRecord) -> Self {
+        self.record = record;
+        self
+    }
+
+    pub fn level(&self) -> &str {
+        &self.level
+    }
+
+    pub fn medals(&self) -> &MedalThresholds {
+        &self.medals
+    }
+
+    pub fn record(&self) -> &TrialRecord {
+        &self.record
+    }
+
+    /// Save slot of a level's record
+    pub fn slot(level: &str) -> String {
+        format!("trial_{}", level)
+    }
+
+    /// Load this level's best run, if it was ever saved
+    pub fn load(&mut self, saves: &SaveManager) -> Result<(), SaveError> {
+        self.record = saves.load_or_default(&Self::slot(&self.level))?;
+        Ok(())
+    }
+
+    pub fn save(&self, saves: &SaveManager) -> Result<(), SaveError> {
+        saves.save(&Self::slot(&self.level), &self.record)
+    }
+
+    /// Start (or restart) the timer and ghost recording from the player's position
+    pub fn start(&mut self, position: Vec2) {
+        self.elapsed = 0.0;
+        self.splits.clear();
+        self.running = true;
+        self.recording = GhostRun { frames: vec![GhostFrame { time: 0.0, position }] };
+        self.since_sample = 0.0;
+        self.record.attempts += 1;
+    }
+
+    /// Stop without finishing; the run isn't recorded
+    pub fn abort(&mut self) {
+        self.running = false;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Seconds since the start
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    /// Split times of the current run
+    pub fn splits(&self) -> &[f32] {
+        &self.splits
+    }
+
+    /// Advance the timer and record the player's position for the ghost
+    pub fn update(&mut self, dt: f32, position: Vec2) {
+        if !self.running {
+            return;
+        }
+        self.elapsed += dt;
+        self.since_sample += dt;
+        if self.since_sample >= self.sample_interval {
+            self.since_sample = 0.0;
+            self.recording.frames.push(GhostFrame { time: self.elapsed, position });
+        }
+    }
+
+    /// Record a split (e.g. at a checkpoint), compared with the best run's
+    pub fn split(&mut self) -> Option<SplitResult> {
+        if !self.running {
+            return None;
+        }
+        let index = self.splits.len();
+        self.splits.push(self.elapsed);
+        let delta = self.record.best_splits.get(index).map(|best| self.elapsed - best);
+        Some(SplitResult { index, time: self.elapsed, delta })
+    }
+
+    /// Stop the timer at the goal. A new best run replaces the record and its ghost;
+    /// call `save` to keep it.
+    pub fn finish(&mut self, position: Vec2) -> Option<TrialResult> {
+        if !self.running {
+            return None;
+        }
+        self.running = false;
+        self.recording.frames.push(GhostFrame { time: self.elapsed, position });
+
+        let medal = self.medals.medal_for(self.elapsed);
+        let previous_best = self.record.best_time;
+        let new_best = previous_best.is_none_or(|best| self.elapsed < best);
+        if new_best {
+            self.record.best_time = Some(self.elapsed);
+            self.record.best_splits = self.splits.clone();
+            self.record.ghost = std::mem::take(&mut self.recording);
+        }
+        self.record.best_medal = self.record.best_medal.max(medal);
+        Some(TrialResult { time: self.elapsed, splits: self.splits.clone(), medal, previous_best, new_best })
+    }
+
+    /// Where the best run was at this point of the current run
+    pub fn ghost_position(&self) -> Option<Vec2> {
+        self.record.ghost.position_at(self.elapsed)
+    }
+
+    /// Add an entity replaying the best run from now on, drawn as a translucent circle
+    /// (returns None if there's no best run yet)
+    pub fn spawn_ghost(&self, scene: &mut Scene, color: Color) -> Option<EntityId> {
+        if self.record.ghost.is_empty() {
+            return None;
+        }
+        Some(scene.add_entity(Box::new(GhostEntity::new(self.record.ghost.clone(), color))))
+    }
+}
+
+/// Replays a `GhostRun` from when it's added to the scene
+pub struct GhostEntity {
+    run: GhostRun,
+    time: f32,
+    position: Vec2,
+    color: Color,
+    radius: f32,
+    texture: Option<Texture2D>,
+}
+
+impl GhostEntity {
+    pub fn new(run: GhostRun, color: Color) -> Self {
+        let position = run.frames.first().map_or(Vec2::ZERO, |frame| frame.position);
+        Self { run, time: 0.0, position, color, radius: 12.0, texture: None }
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    /// Draw a texture, centered and tinted with the ghost's color, instead of a circle
+    pub fn with_texture(mut self, texture: Texture2D) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    /// Whether the replay reached the end of the run
+    pub fn is_finished(&self) -> bool {
+        self.time >= self.run.duration()
+    }
+}
+
+impl Entity for GhostEntity {
+    fn update(&mut self, ctx: &mut UpdateContext) {
+        self.time += ctx.dt;
+        if let Some(position) = self.run.position_at(self.time) {
+            self.position = position;
+        }
+    }
+
+    fn draw(&self) {
+        match &self.texture {
+            Some(texture) => {
+                let corner = self.position - texture.size() * 0.5;
+                draw_texture(texture, corner.x, corner.y, self.color);
+            }
+            None => draw_circle(self.position.x, self.position.y, self.radius, self.color),
+        }
+    }
+
+    fn has_tag(&self, tag: &str) -> bool {
+        tag == "ghost"
+    }
+}
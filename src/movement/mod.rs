@@ -0,0 +1,3 @@
+pub mod patterns;
+
+pub use patterns::{MovementPattern, PatternMover};
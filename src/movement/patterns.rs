@@ -0,0 +1,215 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use crate::math::{Easing, Noise};
+
+/// A parameterized movement path, described as an offset from the starting point over time.
+/// Patterns are plain data, so enemy variety can live in RON files:
+///
+/// ```ignore
+/// let weave_dive = MovementPattern::Combine(vec![
+///     MovementPattern::ZigZag { velocity: vec2(0.0, 60.0), amplitude: 40.0, period: 0.8 },
+///     MovementPattern::Dive { delay: 1.5, direction: vec2(0.0, 1.0), acceleration: 600.0 },
+/// ]);
+/// ```
+///
+/// Every pattern starts at offset zero, so patterns can be added together or chained.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum MovementPattern {
+    /// Stay put
+    #[default]
+    Still,
+    /// Constant velocity
+    Linear { velocity: Vec2 },
+    /// Travel along `velocity` while weaving side to side
+    SineStrafe { velocity: Vec2, amplitude: f32, frequency: f32 },
+    /// Travel along `velocity` in sharp side-to-side zig-zags (`period` = one full zig and zag)
+    ZigZag { velocity: Vec2, amplitude: f32, period: f32 },
+    /// Circle around a center `radius` away, starting on the circle at `start_angle`
+    Circle { radius: f32, angular_speed: f32, start_angle: f32 },
+    /// Hover for `delay` seconds, then plunge along `direction` with increasing speed
+    Dive { delay: f32, direction: Vec2, acceleration: f32 },
+    /// Circle for `orbit_time` seconds, then charge along the mover's aim direction
+    OrbitThenCharge { radius: f32, angular_speed: f32, orbit_time: f32, charge_speed: f32 },
+    /// Spiral outward from the start point
+    Spiral { angular_speed: f32, radial_speed: f32, start_angle: f32 },
+    /// Drift along `velocity` with smooth random wandering
+    Wander { velocity: Vec2, amplitude: f32, frequency: f32, seed: u32 },
+    /// Move to `to` over `duration` seconds following an easing curve, then stop
+    Ease { to: Vec2, duration: f32, easing: Easing },
+    /// Several patterns added together
+    Combine(Vec<MovementPattern>),
+    /// Patterns run one after another, each for its duration in seconds.
+    /// The last pattern keeps running once its duration is over.
+    Sequence(Vec<(MovementPattern, f32)>),
+}
+
+impl MovementPattern {
+    /// Offset from the start point after `time` seconds.
+    /// `aim` is the unit direction used by patterns that charge at something.
+    pub fn offset(&self, time: f32, aim: Vec2) -> Vec2 {
+        let t = time.max(0.0);
+        match self {
+            MovementPattern::Still => Vec2::ZERO,
+            MovementPattern::Linear { velocity } => *velocity * t,
+            MovementPattern::SineStrafe { velocity, amplitude, frequency } => {
+                *velocity * t + side_axis(*velocity) * (t * frequency * TAU).sin() * *amplitude
+            }
+            MovementPattern::ZigZag { velocity, amplitude, period } => {
+                let phase = if *period > 0.0 { t / period } else { 0.0 };
+                *velocity * t + side_axis(*velocity) * triangle_wave(phase) * *amplitude
+            }
+            MovementPattern::Circle { radius, angular_speed, start_angle } => {
+                orbit_offset(*radius, *start_angle, *angular_speed * t)
+            }
+            MovementPattern::Dive { delay, direction, acceleration } => {
+                let dive_time = (t - delay).max(0.0);
+                direction.normalize_or_zero() * 0.5 * *acceleration * dive_time * dive_time
+            }
+            MovementPattern::OrbitThenCharge { radius, angular_speed, orbit_time, charge_speed } => {
+                let orbit_t = t.min(*orbit_time);
+                let charge_t = t - orbit_t;
+                orbit_offset(*radius, 0.0, *angular_speed * orbit_t) + aim * *charge_speed * charge_t
+            }
+            MovementPattern::Spiral { angular_speed, radial_speed, start_angle } => {
+                Vec2::from_angle(start_angle + angular_speed * t) * (radial_speed * t)
+            }
+            MovementPattern::Wander { velocity, amplitude, frequency, seed } => {
+                let noise = Noise::new(*seed);
+                let sample = t * frequency;
+                // Subtract the value at t=0 so wandering starts at the origin
+                let wobble = Vec2::new(
+                    noise.sample_1d(sample) - noise.sample_1d(0.0),
+                    noise.sample_2d(sample, 17.3) - noise.sample_2d(0.0, 17.3),
+                );
+                *velocity * t + wobble * *amplitude
+            }
+            MovementPattern::Ease { to, duration, easing } => {
+                let progress = if *duration > 0.0 { t / duration } else { 1.0 };
+                *to * easing.apply(progress)
+            }
+            MovementPattern::Combine(patterns) => {
+                patterns.iter().map(|pattern| pattern.offset(t, aim)).sum()
+            }
+            MovementPattern::Sequence(steps) => {
+                let mut start_offset = Vec2::ZERO;
+                let mut remaining = t;
+                for (i, (pattern, duration)) in steps.iter().enumerate() {
+                    if remaining <= *duration || i == steps.len() - 1 {
+                        return start_offset + pattern.offset(remaining, aim);
+                    }
+                    start_offset += pattern.offset(*duration, aim);
+                    remaining -= duration;
+                }
+                start_offset
+            }
+        }
+    }
+}
+
+/// Perpendicular to the travel direction (sideways for downward-moving enemies)
+fn side_axis(velocity: Vec2) -> Vec2 {
+    let forward = velocity.normalize_or_zero();
+    if forward == Vec2::ZERO {
+        Vec2::X
+    } else {
+        forward.perp()
+    }
+}
+
+/// Ranges -1..1, starting at 0 and rising
+fn triangle_wave(phase: f32) -> f32 {
+    let p = (phase + 0.25).rem_euclid(1.0);
+    1.0 - 4.0 * (p - 0.5).abs()
+}
+
+/// Offset along a circle whose center is `radius` away from the start point
+fn orbit_offset(radius: f32, start_angle: f32, swept: f32) -> Vec2 {
+    (Vec2::from_angle(start_angle + swept) - Vec2::from_angle(start_angle)) * radius
+}
+
+/// Drives a position along a `MovementPattern`.
+///
+/// ```ignore
+/// let mut mover = PatternMover::new(pattern, spawn_point);
+/// mover.aim_at(player_pos);
+/// enemy.position = mover.update(dt);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PatternMover {
+    pattern: MovementPattern,
+    origin: Vec2,
+    time: f32,
+    aim: Vec2,
+    position: Vec2,
+    velocity: Vec2,
+}
+
+impl PatternMover {
+    pub fn new(pattern: MovementPattern, origin: Vec2) -> Self {
+        Self {
+            pattern,
+            origin,
+            time: 0.0,
+            aim: Vec2::Y, // Down the screen, shmup-style
+            position: origin,
+            velocity: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_aim(mut self, direction: Vec2) -> Self {
+        self.set_aim(direction);
+        self
+    }
+
+    /// Set the direction used by charging patterns
+    pub fn set_aim(&mut self, direction: Vec2) {
+        if direction != Vec2::ZERO {
+            self.aim = direction.normalize();
+        }
+    }
+
+    /// Aim charging patterns at a point from the current position
+    pub fn aim_at(&mut self, target: Vec2) {
+        self.set_aim(target - self.position);
+    }
+
+    /// Advance the pattern and return the new position
+    pub fn update(&mut self, dt: f32) -> Vec2 {
+        self.time += dt;
+        let new_position = self.origin + self.pattern.offset(self.time, self.aim);
+        if dt > 0.0 {
+            self.velocity = (new_position - self.position) / dt;
+        }
+        self.position = new_position;
+        new_position
+    }
+
+    /// Restart the pattern from a new origin
+    pub fn restart(&mut self, origin: Vec2) {
+        self.origin = origin;
+        self.time = 0.0;
+        self.position = origin;
+        self.velocity = Vec2::ZERO;
+    }
+
+    pub fn pattern(&self) -> &MovementPattern {
+        &self.pattern
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    pub fn velocity(&self) -> Vec2 {
+        self.velocity
+    }
+
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    pub fn origin(&self) -> Vec2 {
+        self.origin
+    }
+}
@@ -0,0 +1,307 @@
+use quad_net::http_request::{Method, Request, RequestBuilder};
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+// Finished requests kept for `HttpClient::take_result` and `take_events` (each) before the
+// oldest are dropped
+const MAX_EVENTS: usize = 256;
+
+/// HTTP method of a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl From<HttpMethod> for Method {
+    fn from(method: HttpMethod) -> Self {
+        match method {
+            HttpMethod::Get => Method::Get,
+            HttpMethod::Post => Method::Post,
+            HttpMethod::Put => Method::Put,
+            HttpMethod::Delete => Method::Delete,
+        }
+    }
+}
+
+/// Error of a request that failed after all its retries
+#[derive(Debug, Clone, PartialEq)]
+pub enum HttpError {
+    /// No response within the request's timeout
+    Timeout,
+    /// Connection, TLS or HTTP status error, as reported by the platform
+    Failed(String),
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HttpError::Timeout => write!(f, "request timed out"),
+            HttpError::Failed(msg) => write!(f, "request failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+/// A request to send with `HttpClient::send` (or `send_to_events`).
+///
+/// ```ignore
+/// let id = http.send_to_events(
+///     HttpRequest::post("https://example.com/scores", &json)
+///         .with_header("Content-Type", "application/json")
+///         .with_timeout(5.0)
+///         .with_retries(3, 1.0)
+///         .with_tag("leaderboard"),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub url: String,
+    pub method: HttpMethod,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+    /// Seconds to wait for each attempt (None = wait as long as the platform does)
+    pub timeout: Option<f32>,
+    /// Extra attempts after a failure or timeout
+    pub retries: u32,
+    /// Seconds before the first retry; doubles with each further retry
+    pub retry_delay: f32,
+    /// Copied to the event, to tell requests apart without keeping their ids
+    pub tag: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: HttpMethod, url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            method,
+            headers: vec![],
+            body: None,
+            timeout: Some(10.0),
+            retries: 0,
+            retry_delay: 1.0,
+            tag: None,
+        }
+    }
+
+    pub fn get(url: &str) -> Self {
+        Self::new(HttpMethod::Get, url)
+    }
+
+    pub fn post(url: &str, body: &str) -> Self {
+        Self::new(HttpMethod::Post, url).with_body(body)
+    }
+
+    pub fn put(url: &str, body: &str) -> Self {
+        Self::new(HttpMethod::Put, url).with_body(body)
+    }
+
+    pub fn delete(url: &str) -> Self {
+        Self::new(HttpMethod::Delete, url)
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn with_body(mut self, body: &str) -> Self {
+        self.body = Some(body.to_string());
+        self
+    }
+
+    pub fn with_timeout(mut self, seconds: f32) -> Self {
+        self.timeout = Some(seconds.max(0.0));
+        self
+    }
+
+    pub fn without_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Retry up to `retries` times, waiting `delay` seconds before the first retry
+    pub fn with_retries(mut self, retries: u32, delay: f32) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay.max(0.0);
+        self
+    }
+
+    pub fn with_tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_string());
+        self
+    }
+
+    fn start(&self) -> Request {
+        let mut builder = RequestBuilder::new(&self.url).method(self.method.into());
+        for (name, value) in &self.headers {
+            builder = builder.header(name, value);
+        }
+        if let Some(body) = &self.body {
+            builder = builder.body(body);
+        }
+        builder.send()
+    }
+}
+
+/// Handle to a request sent with `HttpClient::send`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(u32);
+
+/// A finished request
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpEvent {
+    pub id: RequestId,
+    pub tag: Option<String>,
+    /// Attempts made, including the first
+    pub attempts: u32,
+    /// The response body, or the error of the last attempt
+    pub result: Result<String, HttpError>,
+}
+
+struct PendingRequest {
+    id: RequestId,
+    request: HttpRequest,
+    // Reported through `take_events` rather than kept for `take_result`
+    to_events: bool,
+    // None while waiting to retry
+    in_flight: Option<Request>,
+    attempts: u32,
+    // Time spent on the current attempt, or left to wait before the next one
+    elapsed: f32,
+    wait: f32,
+}
+
+#[derive(Default)]
+struct HttpQueue {
+    pending: Vec<PendingRequest>,
+    // Finished requests waiting for `take_result`, by their owner's id
+    results: Vec<HttpEvent>,
+    // Finished requests sent with `send_to_events`
+    events: Vec<HttpEvent>,
+    next_id: u32,
+}
+
+/// Sends HTTP requests in the background (threads on native, `fetch` on WASM) and
+/// reports them as `HttpEvent`s on the main thread.
+///
+/// The client is a shared handle: `Game::get_http` hands out clones that entities can
+/// keep, and the game polls it once a frame. Each finished request goes to its owner: a
+/// request from `send` waits for `take_result` with its id, one from `send_to_events` for
+/// `take_events`. So code draining the events never takes a result someone else awaits.
+#[derive(Clone, Default)]
+pub struct HttpClient {
+    queue: Rc<RefCell<HttpQueue>>,
+}
+
+impl HttpClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a request whose result is taken with `take_result(id)`
+    pub fn send(&self, request: HttpRequest) -> RequestId {
+        self.start(request, false)
+    }
+
+    /// Start a request whose result is reported by `take_events` (tell them apart by tag)
+    pub fn send_to_events(&self, request: HttpRequest) -> RequestId {
+        self.start(request, true)
+    }
+
+    fn start(&self, request: HttpRequest, to_events: bool) -> RequestId {
+        let mut queue = self.queue.borrow_mut();
+        let id = RequestId(queue.next_id);
+        queue.next_id += 1;
+        queue.pending.push(PendingRequest {
+            id,
+            in_flight: Some(request.start()),
+            request,
+            to_events,
+            attempts: 1,
+            elapsed: 0.0,
+            wait: 0.0,
+        });
+        id
+    }
+
+    /// Stop waiting for a request; no event is reported for it
+    pub fn cancel(&self, id: RequestId) -> bool {
+        let mut queue = self.queue.borrow_mut();
+        let count = queue.pending.len();
+        queue.pending.retain(|pending| pending.id != id);
+        queue.pending.len() != count
+    }
+
+    pub fn is_pending(&self, id: RequestId) -> bool {
+        self.queue.borrow().pending.iter().any(|pending| pending.id == id)
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.queue.borrow().pending.len()
+    }
+
+    /// Take the result of a finished request from `send` (None while it's still pending)
+    pub fn take_result(&self, id: RequestId) -> Option<Result<String, HttpError>> {
+        let mut queue = self.queue.borrow_mut();
+        let index = queue.results.iter().position(|event| event.id == id)?;
+        Some(queue.results.remove(index).result)
+    }
+
+    /// Take every finished request from `send_to_events` not yet taken
+    pub fn take_events(&self) -> Vec<HttpEvent> {
+        std::mem::take(&mut self.queue.borrow_mut().events)
+    }
+
+    /// Poll requests in flight, time them out and start due retries. `dt` should be real
+    /// time, so time scale and hitstop don't stretch timeouts.
+    pub fn update(&self, dt: f32) {
+        let mut queue = self.queue.borrow_mut();
+        let mut finished = vec![];
+        for pending in &mut queue.pending {
+            let Some(in_flight) = &mut pending.in_flight else {
+                pending.wait -= dt;
+                if pending.wait <= 0.0 {
+                    pending.in_flight = Some(pending.request.start());
+                    pending.attempts += 1;
+                    pending.elapsed = 0.0;
+                }
+                continue;
+            };
+
+            pending.elapsed += dt;
+            let result = match in_flight.try_recv() {
+                Some(Ok(body)) => Ok(body),
+                Some(Err(err)) => Err(HttpError::Failed(err.to_string())),
+                // A timed out request keeps running on native, but its response is ignored
+                None if pending.request.timeout.is_some_and(|timeout| pending.elapsed >= timeout) => {
+                    Err(HttpError::Timeout)
+                }
+                None => continue,
+            };
+            if result.is_err() && pending.attempts <= pending.request.retries {
+                pending.in_flight = None;
+                pending.wait = pending.request.retry_delay * 2f32.powi(pending.attempts as i32 - 1);
+                continue;
+            }
+            finished.push((pending.to_events, HttpEvent {
+                id: pending.id,
+                tag: pending.request.tag.clone(),
+                attempts: pending.attempts,
+                result,
+            }));
+        }
+
+        queue.pending.retain(|pending| !finished.iter().any(|(_, event)| event.id == pending.id));
+        for (to_events, event) in finished {
+            let owner = if to_events { &mut queue.events } else { &mut queue.results };
+            owner.push(event);
+            let overflow = owner.len().saturating_sub(MAX_EVENTS);
+            owner.drain(..overflow);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod http;
+
+pub use http::{HttpClient, HttpError, HttpEvent, HttpMethod, HttpRequest, RequestId};
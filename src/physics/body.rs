@@ -0,0 +1,52 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Default downward gravity in pixels per second squared
+pub const DEFAULT_GRAVITY: Vec2 = Vec2::new(0.0, 980.0);
+
+/// Simple kinematic body: velocity integrated with gravity and drag
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Body {
+    #[serde(default)]
+    pub velocity: Vec2,
+    /// Multiplier applied to `DEFAULT_GRAVITY` (0 = floating)
+    pub gravity_scale: f32,
+    /// Velocity damping per second
+    #[serde(default)]
+    pub drag: f32,
+}
+
+impl Body {
+    pub fn new(gravity_scale: f32) -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            gravity_scale,
+            drag: 0.0,
+        }
+    }
+
+    pub fn with_velocity(mut self, velocity: Vec2) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    pub fn with_drag(mut self, drag: f32) -> Self {
+        self.drag = drag;
+        self
+    }
+
+    /// Advance the body and return how far it moved this frame
+    pub fn integrate(&mut self, dt: f32) -> Vec2 {
+        self.velocity += DEFAULT_GRAVITY * self.gravity_scale * dt;
+        if self.drag > 0.0 {
+            self.velocity *= 1.0 / (1.0 + self.drag * dt);
+        }
+        self.velocity * dt
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
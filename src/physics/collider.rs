@@ -0,0 +1,101 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Collision shape, centered on the owning entity's position
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Collider {
+    Circle { radius: f32 },
+    Rect { size: Vec2 },
+}
+
+impl Collider {
+    pub fn circle(radius: f32) -> Self {
+        Collider::Circle { radius }
+    }
+
+    pub fn rect(width: f32, height: f32) -> Self {
+        Collider::Rect { size: Vec2::new(width, height) }
+    }
+
+    /// Axis-aligned bounds as (top-left position, size) when placed at `position`
+    pub fn bounds(&self, position: Vec2) -> (Vec2, Vec2) {
+        match *self {
+            Collider::Circle { radius } => (position - Vec2::splat(radius), Vec2::splat(radius * 2.0)),
+            Collider::Rect { size } => (position - size * 0.5, size),
+        }
+    }
+
+    /// Check if a world point is inside this collider placed at `position`
+    pub fn contains_point(&self, position: Vec2, point: Vec2) -> bool {
+        match *self {
+            Collider::Circle { radius } => position.distance_squared(point) <= radius * radius,
+            Collider::Rect { size } => {
+                let half = size * 0.5;
+                (point.x - position.x).abs() <= half.x && (point.y - position.y).abs() <= half.y
+            }
+        }
+    }
+
+    /// Check if two colliders overlap
+    pub fn overlaps(&self, position: Vec2, other: &Collider, other_position: Vec2) -> bool {
+        match (*self, *other) {
+            (Collider::Circle { radius: a }, Collider::Circle { radius: b }) => {
+                position.distance_squared(other_position) <= (a + b) * (a + b)
+            }
+            (Collider::Rect { size: a }, Collider::Rect { size: b }) => {
+                let delta = (position - other_position).abs();
+                let half = (a + b) * 0.5;
+                delta.x <= half.x && delta.y <= half.y
+            }
+            (Collider::Circle { radius }, Collider::Rect { size }) => {
+                circle_overlaps_rect(position, radius, other_position, size)
+            }
+            (Collider::Rect { size }, Collider::Circle { radius }) => {
+                circle_overlaps_rect(other_position, radius, position, size)
+            }
+        }
+    }
+
+    /// Where two overlapping colliders touch, and the normal pointing from this collider
+    /// toward `other` (None if they don't overlap)
+    pub fn contact(&self, position: Vec2, other: &Collider, other_position: Vec2) -> Option<(Vec2, Vec2)> {
+        if !self.overlaps(position, other, other_position) {
+            return None;
+        }
+        match (*self, *other) {
+            (Collider::Circle { radius }, Collider::Circle { .. }) => {
+                let normal = (other_position - position).try_normalize().unwrap_or(Vec2::X);
+                Some((position + normal * radius, normal))
+            }
+            (Collider::Rect { size: a }, Collider::Rect { size: b }) => {
+                let min = (position - a * 0.5).max(other_position - b * 0.5);
+                let max = (position + a * 0.5).min(other_position + b * 0.5);
+                let overlap = max - min;
+                let delta = other_position - position;
+                // Push out along the axis with the least overlap
+                let normal = if overlap.x < overlap.y {
+                    vec2(if delta.x < 0.0 { -1.0 } else { 1.0 }, 0.0)
+                } else {
+                    vec2(0.0, if delta.y < 0.0 { -1.0 } else { 1.0 })
+                };
+                Some(((min + max) * 0.5, normal))
+            }
+            (Collider::Circle { .. }, Collider::Rect { size }) => {
+                let point = position.clamp(other_position - size * 0.5, other_position + size * 0.5);
+                let normal = (point - position).try_normalize()
+                    .or_else(|| (other_position - position).try_normalize())
+                    .unwrap_or(Vec2::X);
+                Some((point, normal))
+            }
+            (Collider::Rect { .. }, Collider::Circle { .. }) => {
+                other.contact(other_position, self, position).map(|(point, normal)| (point, -normal))
+            }
+        }
+    }
+}
+
+fn circle_overlaps_rect(circle_center: Vec2, radius: f32, rect_center: Vec2, size: Vec2) -> bool {
+    let half = size * 0.5;
+    let closest = circle_center.clamp(rect_center - half, rect_center + half);
+    closest.distance_squared(circle_center) <= radius * radius
+}
@@ -0,0 +1,173 @@
+use macroquad::prelude::*;
+use super::Collider;
+use crate::core::Scene;
+use crate::rendering::DebugTheme;
+use crate::ui::CheatMenu;
+
+// Seconds of motion each velocity arrow shows
+const VELOCITY_ARROW_SECONDS: f32 = 0.25;
+const NORMAL_LENGTH: f32 = 12.0;
+const LINE_WIDTH: f32 = 1.0;
+
+/// Picks one gizmo's on/off flag
+type FlagFn = fn(&mut PhysicsDebug) -> &mut bool;
+
+/// A ray cast this frame, reported with `PhysicsDebug::record_raycast`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugRay {
+    pub origin: Vec2,
+    /// Where the ray stopped: the hit point, or its full length if it hit nothing
+    pub end: Vec2,
+    pub hit: bool,
+}
+
+/// Which physics gizmos a scene draws over its entities, each switchable at runtime.
+///
+/// Colliders, trigger zones (portals), contacts and velocities come from the scene's
+/// entities (`Entity::get_collider` and `get_velocity`). Raycasts and spatial index cells
+/// are reported by whatever made them, and cleared at the start of each update:
+///
+/// ```ignore
+/// scene.physics_debug_mut().colliders = true;
+/// scene.physics_debug_mut().record_raycast(eye, hit.unwrap_or(eye + dir * range), hit.is_some());
+/// PhysicsDebug::add_cheats(game.get_cheats_mut());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PhysicsDebug {
+    pub colliders: bool,
+    pub triggers: bool,
+    /// Contact points and normals between overlapping colliders (checks every pair)
+    pub contacts: bool,
+    pub velocities: bool,
+    pub cells: bool,
+    pub raycasts: bool,
+    rays: Vec<DebugRay>,
+    cell_rects: Vec<Rect>,
+}
+
+impl PhysicsDebug {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything on
+    pub fn all() -> Self {
+        Self { colliders: true, triggers: true, contacts: true, velocities: true, cells: true, raycasts: true, ..Self::default() }
+    }
+
+    pub fn is_any_enabled(&self) -> bool {
+        self.colliders || self.triggers || self.contacts || self.velocities || self.cells || self.raycasts
+    }
+
+    /// Report a ray cast this frame (ignored unless `raycasts` is on)
+    pub fn record_raycast(&mut self, origin: Vec2, end: Vec2, hit: bool) {
+        if self.raycasts {
+            self.rays.push(DebugRay { origin, end, hit });
+        }
+    }
+
+    /// Report the cells of a spatial index, e.g. quadtree nodes (ignored unless `cells` is on)
+    pub fn record_cells<I: IntoIterator<Item = Rect>>(&mut self, cells: I) {
+        if self.cells {
+            self.cell_rects.extend(cells);
+        }
+    }
+
+    pub fn raycasts(&self) -> &[DebugRay] {
+        &self.rays
+    }
+
+    /// Forget last frame's rays and cells (the scene calls this before each update)
+    pub(crate) fn begin_frame(&mut self) {
+        self.rays.clear();
+        self.cell_rects.clear();
+    }
+
+    /// Add a "Physics" category to the cheat menu with a toggle for each gizmo
+    pub fn add_cheats(cheats: &mut CheatMenu) {
+        let toggles: [(&str, FlagFn); 6] = [
+            ("Colliders", |debug| &mut debug.colliders),
+            ("Trigger zones", |debug| &mut debug.triggers),
+            ("Contacts", |debug| &mut debug.contacts),
+            ("Velocities", |debug| &mut debug.velocities),
+            ("Spatial cells", |debug| &mut debug.cells),
+            ("Raycasts", |debug| &mut debug.raycasts),
+        ];
+        for (label, flag) in toggles {
+            cheats.toggle("Physics", label, false, move |scene, on| *flag(scene.physics_debug_mut()) = on);
+        }
+    }
+
+    /// Draw the enabled gizmos in world space (Game calls this once per view)
+    pub fn draw(&self, scene: &Scene, theme: &DebugTheme) {
+        if self.cells {
+            for cell in &self.cell_rects {
+                draw_rectangle_lines(cell.x, cell.y, cell.w, cell.h, LINE_WIDTH, theme.muted());
+            }
+        }
+        if self.triggers {
+            for (_, portal) in scene.portals() {
+                let area = portal.area;
+                draw_rectangle_lines(area.x, area.y, area.w, area.h, LINE_WIDTH, theme.trigger());
+            }
+        }
+
+        let bodies: Vec<_> = scene.get_entities()
+            .filter(|entity| entity.is_active())
+            .filter_map(|entity| entity.get_transform().map(|transform| (transform.position, entity.as_ref())))
+            .collect();
+        if self.colliders {
+            for (position, collider) in bodies.iter().filter_map(|(position, entity)| Some((*position, entity.get_collider()?))) {
+                match collider {
+                    Collider::Circle { radius } => {
+                        draw_circle_lines(position.x, position.y, radius, LINE_WIDTH, theme.collider())
+                    }
+                    Collider::Rect { size } => {
+                        let corner = position - size * 0.5;
+                        draw_rectangle_lines(corner.x, corner.y, size.x, size.y, LINE_WIDTH, theme.collider())
+                    }
+                }
+            }
+        }
+        if self.contacts {
+            let colliders: Vec<_> = bodies.iter()
+                .filter_map(|(position, entity)| Some((*position, entity.get_collider()?)))
+                .collect();
+            for (index, (position, collider)) in colliders.iter().enumerate() {
+                for (other_position, other) in &colliders[index + 1..] {
+                    if let Some((point, normal)) = collider.contact(*position, other, *other_position) {
+                        draw_circle(point.x, point.y, 2.0, theme.warning());
+                        let tip = point + normal * NORMAL_LENGTH;
+                        draw_line(point.x, point.y, tip.x, tip.y, LINE_WIDTH, theme.warning());
+                    }
+                }
+            }
+        }
+        if self.velocities {
+            for (position, velocity) in bodies.iter().filter_map(|(position, entity)| Some((*position, entity.get_velocity()?))) {
+                draw_arrow(position, position + velocity * VELOCITY_ARROW_SECONDS, theme.velocity());
+            }
+        }
+        if self.raycasts {
+            for ray in &self.rays {
+                let color = if ray.hit { theme.warning() } else { theme.muted() };
+                draw_line(ray.origin.x, ray.origin.y, ray.end.x, ray.end.y, LINE_WIDTH, color);
+                if ray.hit {
+                    draw_circle(ray.end.x, ray.end.y, 2.0, color);
+                }
+            }
+        }
+    }
+}
+
+fn draw_arrow(from: Vec2, to: Vec2, color: Color) {
+    draw_line(from.x, from.y, to.x, to.y, LINE_WIDTH, color);
+    let Some(direction) = (to - from).try_normalize() else {
+        return;
+    };
+    let head = direction * 5.0;
+    for side in [head.perp(), -head.perp()] {
+        let point = to - head + side * 0.6;
+        draw_line(to.x, to.y, point.x, point.y, LINE_WIDTH, color);
+    }
+}
@@ -0,0 +1,11 @@
+pub mod collider;
+pub mod body;
+pub mod debug;
+pub mod tiles;
+pub mod quadtree;
+
+pub use collider::Collider;
+pub use body::{Body, DEFAULT_GRAVITY};
+pub use debug::{DebugRay, PhysicsDebug};
+pub use tiles::{SolidTiles, TileCollision, TileQueryStats, CHUNK_SIZE};
+pub use quadtree::Quadtree;
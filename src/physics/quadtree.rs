@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+
+// Items a node holds before it splits
+const NODE_CAPACITY: usize = 8;
+const MAX_DEPTH: u32 = 8;
+
+struct Node<T> {
+    bounds: Rect,
+    depth: u32,
+    // Items that fit no single child (or all of them, before the node splits)
+    items: Vec<(Rect, T)>,
+    children: Option<Box<[Node<T>; 4]>>,
+}
+
+impl<T: Copy> Node<T> {
+    fn new(bounds: Rect, depth: u32) -> Self {
+        Self { bounds, depth, items: vec![], children: None }
+    }
+
+    fn insert(&mut self, rect: Rect, item: T) {
+        let fitting = self.children.as_mut().and_then(|children| children.iter_mut().find(|child| contains(child.bounds, rect)));
+        if let Some(child) = fitting {
+            child.insert(rect, item);
+            return;
+        }
+        self.items.push((rect, item));
+        if self.children.is_none() && self.items.len() > NODE_CAPACITY && self.depth < MAX_DEPTH {
+            self.split();
+        }
+    }
+
+    fn split(&mut self) {
+        let half = vec2(self.bounds.w, self.bounds.h) * 0.5;
+        let child = |x: f32, y: f32| Node::new(Rect::new(x, y, half.x, half.y), self.depth + 1);
+        let (x, y) = (self.bounds.x, self.bounds.y);
+        self.children = Some(Box::new([
+            child(x, y),
+            child(x + half.x, y),
+            child(x, y + half.y),
+            child(x + half.x, y + half.y),
+        ]));
+        for (rect, item) in std::mem::take(&mut self.items) {
+            self.insert(rect, item);
+        }
+    }
+
+    fn query(&self, area: Rect, found: &mut Vec<T>) {
+        found.extend(self.items.iter().filter(|(rect, _)| overlaps(*rect, area)).map(|(_, item)| *item));
+        for child in self.children.iter().flat_map(|children| children.iter()) {
+            if overlaps(child.bounds, area) {
+                child.query(area, found);
+            }
+        }
+    }
+
+    fn cells(&self, cells: &mut Vec<Rect>) {
+        cells.push(self.bounds);
+        for child in self.children.iter().flat_map(|children| children.iter()) {
+            child.cells(cells);
+        }
+    }
+}
+
+/// Items by their bounding rect, split into quadrants where they crowd, so an area query
+/// only visits the cells it overlaps. Rebuild it when the items move:
+///
+/// ```ignore
+/// let mut tree = Quadtree::new(world_rect);
+/// for enemy in &enemies {
+///     tree.insert(enemy.bounds(), enemy.id);
+/// }
+/// let near = tree.query(explosion_rect);
+/// ```
+///
+/// Items outside the bounds are kept at the root, so they're still found (just slower).
+pub struct Quadtree<T> {
+    root: Node<T>,
+    len: usize,
+}
+
+impl<T: Copy> Quadtree<T> {
+    pub fn new(bounds: Rect) -> Self {
+        Self { root: Node::new(bounds, 0), len: 0 }
+    }
+
+    /// A tree just big enough for `items`, with them inserted
+    pub fn from_items(items: &[(Rect, T)]) -> Self {
+        let bounds = items.iter()
+            .map(|(rect, _)| *rect)
+            .reduce(|a, b| a.combine_with(b))
+            .unwrap_or_default();
+        let mut tree = Self::new(bounds);
+        for (rect, item) in items {
+            tree.insert(*rect, *item);
+        }
+        tree
+    }
+
+    pub fn bounds(&self) -> Rect {
+        self.root.bounds
+    }
+
+    pub fn insert(&mut self, rect: Rect, item: T) {
+        self.root.insert(rect, item);
+        self.len += 1;
+    }
+
+    /// Items whose rects overlap `area`
+    pub fn query(&self, area: Rect) -> Vec<T> {
+        let mut found = vec![];
+        self.query_into(area, &mut found);
+        found
+    }
+
+    /// Like `query`, but appending to `found` to reuse its allocation
+    pub fn query_into(&self, area: Rect, found: &mut Vec<T>) {
+        self.root.query(area, found);
+    }
+
+    /// Every node's rect, for `PhysicsDebug::record_cells`
+    pub fn cells(&self) -> Vec<Rect> {
+        let mut cells = vec![];
+        self.root.cells(&mut cells);
+        cells
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove every item, keeping the bounds
+    pub fn clear(&mut self) {
+        self.root = Node::new(self.root.bounds, 0);
+        self.len = 0;
+    }
+}
+
+fn contains(outer: Rect, inner: Rect) -> bool {
+    inner.x >= outer.x && inner.y >= outer.y && inner.right() <= outer.right() && inner.bottom() <= outer.bottom()
+}
+
+// Touching edges count, like `Camera::is_rect_visible`
+fn overlaps(a: Rect, b: Rect) -> bool {
+    a.x <= b.right() && a.right() >= b.x && a.y <= b.bottom() && a.bottom() >= b.y
+}
@@ -0,0 +1,258 @@
+use macroquad::prelude::*;
+use std::cell::Cell;
+
+/// Tiles per chunk side; each chunk row is one `u32` bitmask
+pub const CHUNK_SIZE: u32 = 32;
+
+/// Solid bits of a `CHUNK_SIZE` square of tiles
+#[derive(Clone)]
+struct Chunk {
+    rows: [u32; CHUNK_SIZE as usize],
+    solid: u32,
+}
+
+/// Counters of the queries run on a `TileCollision`, to see what collision costs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TileQueryStats {
+    pub queries: u64,
+    /// Chunk rows read; empty chunks are skipped without reading
+    pub rows_scanned: u64,
+    pub tiles_returned: u64,
+}
+
+/// Which tiles of a map are solid, stored in chunks of bitmasks so that collision
+/// queries cost the area they cover, not the size of the map. Chunks with no solid
+/// tiles take no memory.
+///
+/// ```ignore
+/// let mut tiles = TileCollision::new(10_000, 10_000, 16.0);
+/// tiles.fill(0, 200, 10_000, 1, true);
+/// // in a character controller
+/// for (x, y) in tiles.solid_tiles_in_rect(player_bounds) {
+///     push_out_of(tiles.tile_rect(x, y));
+/// }
+/// ```
+pub struct TileCollision {
+    width: u32,
+    height: u32,
+    tile_size: f32,
+    chunks_wide: u32,
+    chunks: Vec<Option<Box<Chunk>>>,
+    /// Treat tiles outside the map as solid, so nothing leaves it
+    solid_bounds: bool,
+    stats: Cell<TileQueryStats>,
+}
+
+impl TileCollision {
+    /// An empty map of `width` x `height` tiles, each `tile_size` world units wide
+    pub fn new(width: u32, height: u32, tile_size: f32) -> Self {
+        let chunks_wide = width.div_ceil(CHUNK_SIZE);
+        let chunks_high = height.div_ceil(CHUNK_SIZE);
+        Self {
+            width,
+            height,
+            tile_size: tile_size.max(f32::EPSILON),
+            chunks_wide,
+            chunks: vec![None; (chunks_wide * chunks_high) as usize],
+            solid_bounds: false,
+            stats: Cell::new(TileQueryStats::default()),
+        }
+    }
+
+    pub fn with_solid_bounds(mut self, solid: bool) -> Self {
+        self.solid_bounds = solid;
+        self
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn tile_size(&self) -> f32 {
+        self.tile_size
+    }
+
+    pub fn set_solid(&mut self, x: u32, y: u32, solid: bool) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let index = self.chunk_index(x / CHUNK_SIZE, y / CHUNK_SIZE);
+        let bit = 1 << (x % CHUNK_SIZE);
+        let slot = &mut self.chunks[index];
+        if slot.is_none() && !solid {
+            return;
+        }
+        let chunk = slot.get_or_insert_with(|| Box::new(Chunk { rows: [0; CHUNK_SIZE as usize], solid: 0 }));
+        let row = &mut chunk.rows[(y % CHUNK_SIZE) as usize];
+        let was_solid = *row & bit != 0;
+        if solid && !was_solid {
+            *row |= bit;
+            chunk.solid += 1;
+        } else if !solid && was_solid {
+            *row &= !bit;
+            chunk.solid -= 1;
+            if chunk.solid == 0 {
+                *slot = None;
+            }
+        }
+    }
+
+    /// Set every tile of a `width` x `height` block
+    pub fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, solid: bool) {
+        for tile_y in y..y.saturating_add(height).min(self.height) {
+            for tile_x in x..x.saturating_add(width).min(self.width) {
+                self.set_solid(tile_x, tile_y, solid);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.iter_mut().for_each(|chunk| *chunk = None);
+    }
+
+    /// Whether a tile is solid (tiles outside the map follow `with_solid_bounds`)
+    pub fn is_solid(&self, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x as u32 >= self.width || y as u32 >= self.height {
+            return self.solid_bounds;
+        }
+        let (x, y) = (x as u32, y as u32);
+        self.chunks[self.chunk_index(x / CHUNK_SIZE, y / CHUNK_SIZE)]
+            .as_ref()
+            .is_some_and(|chunk| chunk.rows[(y % CHUNK_SIZE) as usize] & (1 << (x % CHUNK_SIZE)) != 0)
+    }
+
+    pub fn is_solid_at(&self, world: Vec2) -> bool {
+        let (x, y) = self.tile_at(world);
+        self.is_solid(x, y)
+    }
+
+    /// Tile containing a world point (may be outside the map)
+    pub fn tile_at(&self, world: Vec2) -> (i32, i32) {
+        let tile = (world / self.tile_size).floor();
+        (tile.x as i32, tile.y as i32)
+    }
+
+    /// World rect of a tile
+    pub fn tile_rect(&self, x: u32, y: u32) -> Rect {
+        Rect::new(x as f32 * self.tile_size, y as f32 * self.tile_size, self.tile_size, self.tile_size)
+    }
+
+    /// Solid tiles overlapping a world rect, row by row. Only the chunks the rect touches
+    /// are read and nothing is allocated, so this stays cheap on huge maps. Tiles outside
+    /// the map aren't returned, even with solid bounds (see `is_rect_blocked`).
+    pub fn solid_tiles_in_rect(&self, rect: Rect) -> SolidTiles<'_> {
+        let mut stats = self.stats.get();
+        stats.queries += 1;
+        self.stats.set(stats);
+
+        let (x0, y0, x1, y1) = self.tile_range(rect);
+        SolidTiles {
+            map: self,
+            x0,
+            x1,
+            y1,
+            y: y0,
+            chunk_x: x0 / CHUNK_SIZE,
+            bits: 0,
+            base_x: 0,
+        }
+    }
+
+    /// Whether any solid tile overlaps a world rect, including outside the map with solid bounds
+    pub fn is_rect_blocked(&self, rect: Rect) -> bool {
+        let outside = rect.x < 0.0
+            || rect.y < 0.0
+            || rect.right() > self.width as f32 * self.tile_size
+            || rect.bottom() > self.height as f32 * self.tile_size;
+        (self.solid_bounds && outside) || self.solid_tiles_in_rect(rect).next().is_some()
+    }
+
+    /// World rects of the chunks a rect touches that hold solid tiles, e.g. for
+    /// `PhysicsDebug::record_cells`
+    pub fn chunk_rects_in(&self, rect: Rect) -> impl Iterator<Item = Rect> + '_ {
+        let (x0, y0, x1, y1) = self.tile_range(rect);
+        let chunk_size = CHUNK_SIZE as f32 * self.tile_size;
+        let columns = x0 / CHUNK_SIZE..x1.div_ceil(CHUNK_SIZE);
+        (y0 / CHUNK_SIZE..y1.div_ceil(CHUNK_SIZE))
+            .flat_map(move |chunk_y| columns.clone().map(move |chunk_x| (chunk_x, chunk_y)))
+            .filter(|(chunk_x, chunk_y)| self.chunks[self.chunk_index(*chunk_x, *chunk_y)].is_some())
+            .map(move |(chunk_x, chunk_y)| Rect::new(chunk_x as f32 * chunk_size, chunk_y as f32 * chunk_size, chunk_size, chunk_size))
+    }
+
+    /// Query counters since the map was made or `reset_stats` was called
+    pub fn stats(&self) -> TileQueryStats {
+        self.stats.get()
+    }
+
+    pub fn reset_stats(&self) {
+        self.stats.set(TileQueryStats::default());
+    }
+
+    fn chunk_index(&self, chunk_x: u32, chunk_y: u32) -> usize {
+        (chunk_y * self.chunks_wide + chunk_x) as usize
+    }
+
+    /// Tiles a world rect covers, clamped to the map, as start (inclusive) and end (exclusive)
+    fn tile_range(&self, rect: Rect) -> (u32, u32, u32, u32) {
+        let start = |value: f32, max: u32| (value / self.tile_size).floor().clamp(0.0, max as f32) as u32;
+        let end = |value: f32, max: u32| (value / self.tile_size).ceil().clamp(0.0, max as f32) as u32;
+        (start(rect.x, self.width), start(rect.y, self.height), end(rect.right(), self.width), end(rect.bottom(), self.height))
+    }
+}
+
+/// Iterator over solid tiles from `TileCollision::solid_tiles_in_rect`
+pub struct SolidTiles<'a> {
+    map: &'a TileCollision,
+    x0: u32,
+    x1: u32,
+    y1: u32,
+    y: u32,
+    chunk_x: u32,
+    // Solid tiles left in the current chunk row
+    bits: u32,
+    base_x: u32,
+}
+
+impl Iterator for SolidTiles<'_> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<(u32, u32)> {
+        loop {
+            if self.bits != 0 {
+                let bit = self.bits.trailing_zeros();
+                self.bits &= self.bits - 1;
+                let mut stats = self.map.stats.get();
+                stats.tiles_returned += 1;
+                self.map.stats.set(stats);
+                return Some((self.base_x + bit, self.y));
+            }
+            if self.y >= self.y1 || self.x0 >= self.x1 {
+                return None;
+            }
+            // Move to the next chunk of this row, or the next row
+            if self.chunk_x * CHUNK_SIZE >= self.x1 {
+                self.y += 1;
+                self.chunk_x = self.x0 / CHUNK_SIZE;
+                continue;
+            }
+            let chunk_x = self.chunk_x;
+            self.chunk_x += 1;
+            let Some(chunk) = &self.map.chunks[self.map.chunk_index(chunk_x, self.y / CHUNK_SIZE)] else {
+                continue;
+            };
+            let mut stats = self.map.stats.get();
+            stats.rows_scanned += 1;
+            self.map.stats.set(stats);
+
+            self.base_x = chunk_x * CHUNK_SIZE;
+            let low = self.x0.max(self.base_x) - self.base_x;
+            let high = self.x1.min(self.base_x + CHUNK_SIZE) - self.base_x;
+            let mask = if high - low == CHUNK_SIZE { u32::MAX } else { ((1 << (high - low)) - 1) << low };
+            self.bits = chunk.rows[(self.y % CHUNK_SIZE) as usize] & mask;
+        }
+    }
+}
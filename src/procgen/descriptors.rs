@@ -0,0 +1,60 @@
+use crate::core::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use super::{pick, Choice};
+
+/// A generated thing: a value for each table, and the template filled in with them
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Descriptor {
+    pub fields: BTreeMap<String, String>,
+    pub text: String,
+}
+
+impl Descriptor {
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.fields.get(field).map(String::as_str)
+    }
+}
+
+/// Makes up planets, items, NPC quirks and the like by picking one entry from each table
+/// and filling `{table}` placeholders in a template.
+///
+/// ```ron
+/// (
+///     template: "A {size} {climate} world, known for its {feature}",
+///     tables: {
+///         "size": ["tiny", "vast", ("colossal", 0.1)],
+///         "climate": ["frozen", "arid", "tropical"],
+///         "feature": ["singing dunes", "glass forests", "ancient ruins"],
+///     },
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DescriptorGenerator {
+    pub template: String,
+    pub tables: BTreeMap<String, Vec<Choice>>,
+}
+
+impl DescriptorGenerator {
+    pub fn new(template: &str) -> Self {
+        Self { template: template.to_string(), tables: BTreeMap::new() }
+    }
+
+    pub fn with_table(mut self, name: &str, entries: &[&str]) -> Self {
+        self.tables.insert(name.to_string(), entries.iter().map(|text| Choice::from(*text)).collect());
+        self
+    }
+
+    pub fn generate(&self, rng: &mut Rng) -> Descriptor {
+        // Tables are rolled in name order, so a seed always gives the same result
+        let fields: BTreeMap<String, String> = self.tables.iter()
+            .filter_map(|(name, table)| Some((name.clone(), pick(table, rng)?.to_string())))
+            .collect();
+        let mut text = self.template.clone();
+        for (name, value) in &fields {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        Descriptor { fields, text }
+    }
+}
@@ -0,0 +1,149 @@
+pub mod names;
+pub mod palette;
+pub mod descriptors;
+
+pub use names::NameGenerator;
+pub use palette::{Harmony, PaletteGenerator};
+pub use descriptors::{Descriptor, DescriptorGenerator};
+
+use macroquad::prelude::Color;
+use crate::core::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// A seeded `core::Rng` for the procedural generators, so a seed always makes the same
+/// content. To follow the game's own seed instead, generate from the `Rng` in its
+/// resources or the engine's shared one (`core::rng::with_shared`).
+pub fn seeded_rng(seed: u64) -> Rng {
+    Rng::new(seed)
+}
+
+/// A table entry: plain text, or `(text, weight)` to make it more or less likely than
+/// the others (plain entries weigh 1.0)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Choice {
+    Plain(String),
+    Weighted(String, f32),
+}
+
+impl Choice {
+    pub fn text(&self) -> &str {
+        match self {
+            Choice::Plain(text) | Choice::Weighted(text, _) => text,
+        }
+    }
+
+    pub fn weight(&self) -> f32 {
+        match self {
+            Choice::Plain(_) => 1.0,
+            Choice::Weighted(_, weight) => weight.max(0.0),
+        }
+    }
+}
+
+impl From<&str> for Choice {
+    fn from(text: &str) -> Self {
+        Choice::Plain(text.to_string())
+    }
+}
+
+/// Weighted pick from a table (None if it's empty or every weight is zero)
+pub(crate) fn pick<'a>(table: &'a [Choice], rng: &mut Rng) -> Option<&'a str> {
+    let total: f32 = table.iter().map(Choice::weight).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut roll = rng.range(0.0, total);
+    for choice in table {
+        if roll < choice.weight() {
+            return Some(choice.text());
+        }
+        roll -= choice.weight();
+    }
+    table.iter().rev().find(|choice| choice.weight() > 0.0).map(Choice::text)
+}
+
+/// Error returned when loading generators
+#[derive(Debug)]
+pub enum ProcgenError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for ProcgenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProcgenError::Io(err) => write!(f, "failed to read generator file: {}", err),
+            ProcgenError::Format(msg) => write!(f, "invalid generator data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ProcgenError {}
+
+impl From<std::io::Error> for ProcgenError {
+    fn from(err: std::io::Error) -> Self {
+        ProcgenError::Io(err)
+    }
+}
+
+/// Named generators loaded from a RON file:
+///
+/// ```ignore
+/// let content = ContentLibrary::load("assets/content.ron")?;
+/// let mut rng = seeded_rng(run_seed);
+/// let name = content.name("planet", &mut rng).unwrap_or_default();
+/// let palette = content.palette("planet", &mut rng).unwrap_or_default();
+/// let planet = content.describe("planet", &mut rng).unwrap_or_default();
+/// ```
+///
+/// ```ron
+/// (
+///     names: { "planet": (starts: ["Ka", "Zor"], ends: ["ris", "on"]) },
+///     palettes: { "planet": (harmony: Triadic, count: 3) },
+///     descriptors: { "planet": (template: "A {climate} world", tables: { "climate": ["frozen", "arid"] }) },
+/// )
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContentLibrary {
+    pub names: HashMap<String, NameGenerator>,
+    pub palettes: HashMap<String, PaletteGenerator>,
+    pub descriptors: HashMap<String, DescriptorGenerator>,
+}
+
+impl ContentLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ProcgenError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    /// Parse generators from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, ProcgenError> {
+        ron::from_str(data).map_err(|err| ProcgenError::Format(err.to_string()))
+    }
+
+    pub fn to_ron_string(&self) -> Result<String, ProcgenError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new())
+            .map_err(|err| ProcgenError::Format(err.to_string()))
+    }
+
+    pub fn name(&self, generator: &str, rng: &mut Rng) -> Option<String> {
+        self.names.get(generator).map(|names| names.generate(rng))
+    }
+
+    pub fn palette(&self, generator: &str, rng: &mut Rng) -> Option<Vec<Color>> {
+        self.palettes.get(generator).map(|palette| palette.generate(rng))
+    }
+
+    pub fn describe(&self, generator: &str, rng: &mut Rng) -> Option<Descriptor> {
+        self.descriptors.get(generator).map(|descriptor| descriptor.generate(rng))
+    }
+}
@@ -0,0 +1,97 @@
+use crate::core::Rng;
+use serde::{Deserialize, Serialize};
+use super::{pick, Choice};
+
+/// Makes up names by joining syllables, e.g. "Zor" + "an" + "dell".
+///
+/// ```ron
+/// (
+///     starts: ["Zor", "Ka", "Mel", ("Vex", 0.2)],
+///     middles: ["an", "i", "or"],
+///     ends: ["dell", "ia", "us"],
+///     syllables: (2, 4),
+/// )
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NameGenerator {
+    pub starts: Vec<Choice>,
+    /// Used for every syllable between the first and the last
+    pub middles: Vec<Choice>,
+    pub ends: Vec<Choice>,
+    /// Range of syllable counts, inclusive
+    pub syllables: (u32, u32),
+    pub capitalize: bool,
+}
+
+impl Default for NameGenerator {
+    fn default() -> Self {
+        Self {
+            starts: vec![],
+            middles: vec![],
+            ends: vec![],
+            syllables: (2, 3),
+            capitalize: true,
+        }
+    }
+}
+
+impl NameGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_starts(mut self, starts: &[&str]) -> Self {
+        self.starts = starts.iter().map(|text| Choice::from(*text)).collect();
+        self
+    }
+
+    pub fn with_middles(mut self, middles: &[&str]) -> Self {
+        self.middles = middles.iter().map(|text| Choice::from(*text)).collect();
+        self
+    }
+
+    pub fn with_ends(mut self, ends: &[&str]) -> Self {
+        self.ends = ends.iter().map(|text| Choice::from(*text)).collect();
+        self
+    }
+
+    pub fn with_syllables(mut self, min: u32, max: u32) -> Self {
+        self.syllables = (min.max(1), max.max(min.max(1)));
+        self
+    }
+
+    pub fn generate(&self, rng: &mut Rng) -> String {
+        let (min, max) = self.syllables;
+        let count = if max > min { min + rng.index((max - min + 1) as usize) as u32 } else { min.max(1) };
+        let mut name = String::new();
+        for index in 0..count {
+            // Tables left empty fall back to the others, so a generator with only starts still works
+            let table = if index == 0 {
+                &self.starts
+            } else if index + 1 == count {
+                &self.ends
+            } else {
+                &self.middles
+            };
+            let table = [table, &self.middles, &self.starts, &self.ends].into_iter()
+                .find(|table| !table.is_empty());
+            if let Some(syllable) = table.and_then(|table| pick(table, rng)) {
+                name.push_str(syllable);
+            }
+        }
+        if self.capitalize {
+            capitalize(&name)
+        } else {
+            name
+        }
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
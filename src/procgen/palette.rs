@@ -0,0 +1,96 @@
+use macroquad::color::{hsl_to_rgb, Color};
+use crate::core::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How the hues of a palette relate to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Harmony {
+    /// Neighbouring hues
+    Analogous,
+    /// A base hue and its opposite
+    Complementary,
+    /// Three hues evenly spaced around the wheel
+    Triadic,
+    /// Shades of one hue
+    Monochrome,
+    /// Unrelated hues
+    Random,
+}
+
+/// Makes up color palettes from a random base hue.
+///
+/// ```ron
+/// (harmony: Analogous, count: 5, saturation: (0.4, 0.7), lightness: (0.3, 0.7))
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaletteGenerator {
+    pub harmony: Harmony,
+    pub count: usize,
+    /// Range the base hue is picked from, 0.0 - 1.0 around the wheel
+    pub hue: (f32, f32),
+    pub saturation: (f32, f32),
+    pub lightness: (f32, f32),
+    /// Random offset added to each hue, so palettes don't look too regular
+    pub hue_jitter: f32,
+}
+
+impl Default for PaletteGenerator {
+    fn default() -> Self {
+        Self {
+            harmony: Harmony::Analogous,
+            count: 5,
+            hue: (0.0, 1.0),
+            saturation: (0.4, 0.8),
+            lightness: (0.3, 0.7),
+            hue_jitter: 0.02,
+        }
+    }
+}
+
+impl PaletteGenerator {
+    pub fn new(harmony: Harmony, count: usize) -> Self {
+        Self { harmony, count, ..Default::default() }
+    }
+
+    pub fn with_hue(mut self, min: f32, max: f32) -> Self {
+        self.hue = (min, max);
+        self
+    }
+
+    pub fn with_saturation(mut self, min: f32, max: f32) -> Self {
+        self.saturation = (min, max);
+        self
+    }
+
+    pub fn with_lightness(mut self, min: f32, max: f32) -> Self {
+        self.lightness = (min, max);
+        self
+    }
+
+    /// Colors ordered from darkest to lightest
+    pub fn generate(&self, rng: &mut Rng) -> Vec<Color> {
+        let base = random_in(rng, self.hue);
+        let mut colors: Vec<(f32, Color)> = (0..self.count)
+            .map(|index| {
+                let hue = match self.harmony {
+                    Harmony::Analogous => base + (index as f32 - self.count as f32 / 2.0) * 0.06,
+                    Harmony::Complementary => base + (index % 2) as f32 * 0.5,
+                    Harmony::Triadic => base + (index % 3) as f32 / 3.0,
+                    Harmony::Monochrome => base,
+                    Harmony::Random => rng.next_f32(),
+                };
+                let hue = (hue + random_in(rng, (-self.hue_jitter, self.hue_jitter))).rem_euclid(1.0);
+                let saturation = random_in(rng, self.saturation).clamp(0.0, 1.0);
+                let lightness = random_in(rng, self.lightness).clamp(0.0, 1.0);
+                (lightness, hsl_to_rgb(hue, saturation, lightness))
+            })
+            .collect();
+        colors.sort_by(|a, b| a.0.total_cmp(&b.0));
+        colors.into_iter().map(|(_, color)| color).collect()
+    }
+}
+
+fn random_in(rng: &mut Rng, (min, max): (f32, f32)) -> f32 {
+    rng.range(min, max)
+}
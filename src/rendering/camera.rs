@@ -1,5 +1,8 @@
 use macroquad::prelude::*;
+use crate::core::{headless, EntityId};
 use crate::math::Vec2Utils;
+use super::camera_sequence::{CameraEvent, CameraSequence, SequencePlayer};
+use super::camera_shake::{AmbientShake, Shake, ShakeStack};
 
 /// Camera bounds for constraining camera movement
 #[derive(Debug, Clone)]
@@ -34,6 +37,99 @@ impl CameraBounds {
             point.y.clamp(self.min.y, self.max.y),
         )
     }
+    
+    /// Bounds part way between `self` (t = 0) and `other` (t = 1)
+    pub fn lerp(&self, other: &CameraBounds, t: f32) -> Self {
+        Self {
+            min: self.min.lerp(other.min, t),
+            max: self.max.lerp(other.max, t),
+        }
+    }
+}
+
+/// A region of the world with its own camera settings (a room).
+///
+/// When the follow target enters the zone's area the camera blends to the zone's
+/// bounds and switches to its zoom and offset. Where zones overlap, the one the
+/// target is already in wins, then the one added first.
+#[derive(Debug, Clone)]
+pub struct CameraZone {
+    /// Where the follow target has to be for the zone to be active
+    pub area: CameraBounds,
+    /// Bounds the camera is confined to while the zone is active
+    pub bounds: CameraBounds,
+    /// Zoom while in the zone (None = keep the current zoom)
+    pub zoom: Option<f32>,
+    /// Follow offset while in the zone (None = keep the current offset)
+    pub offset: Option<Vec2>,
+}
+
+impl CameraZone {
+    /// A zone that confines the camera to its own area
+    pub fn new(area: CameraBounds) -> Self {
+        Self { bounds: area.clone(), area, zoom: None, offset: None }
+    }
+    
+    pub fn with_bounds(mut self, bounds: CameraBounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+    
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = Some(zoom);
+        self
+    }
+    
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+}
+
+/// Area around the camera's target position where the follow target can move
+/// without the camera following
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DeadZone {
+    /// Radius; once the target leaves it the camera catches up all the way
+    Circle(f32),
+    /// Half width and half height; the camera only moves enough to keep the target inside
+    Rect(Vec2),
+}
+
+/// Something the camera keeps in view when framing several targets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CameraTarget {
+    /// An entity of the camera's scene
+    Entity(EntityId),
+    /// A fixed world position (e.g. the middle of a boss arena)
+    Point(Vec2),
+}
+
+impl From<EntityId> for CameraTarget {
+    fn from(id: EntityId) -> Self {
+        CameraTarget::Entity(id)
+    }
+}
+
+impl From<Vec2> for CameraTarget {
+    fn from(point: Vec2) -> Self {
+        CameraTarget::Point(point)
+    }
+}
+
+/// Limits for multi-target framing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Framing {
+    /// World units kept between the targets and the screen edges
+    pub padding: f32,
+    pub min_zoom: f32,
+    pub max_zoom: f32,
+}
+
+impl Default for Framing {
+    fn default() -> Self {
+        Self { padding: 100.0, min_zoom: 0.25, max_zoom: 2.0 }
+    }
 }
 
 /// Camera with following, screen shake, zoom, and bounds support
@@ -43,22 +139,42 @@ pub struct Camera {
     pub zoom: f32,
     pub rotation: f32,
     
-    // Screen shake
-    shake_intensity: f32,
-    shake_duration: f32,
-    shake_timer: f32,
+    // Screen shake: every playing shake, and their combined offset and rotation this frame
+    shakes: ShakeStack,
     shake_offset: Vec2,
+    shake_rotation: f32,
     
     // Target following (changed: now closure instead of static Vec2)
     pub follow_target: Option<Box<dyn Fn() -> Vec2>>,
+    // Entity to follow, resolved by the Scene each frame
+    follow_entity: Option<EntityId>,
+    follow_entity_position: Option<Vec2>,
+    // Several targets kept in view, with positions resolved by the Scene each frame
+    follow_targets: Vec<CameraTarget>,
+    follow_target_positions: Vec<Vec2>,
+    framing: Framing,
     follow_speed: f32,
     follow_offset: Vec2,
     
     // Camera bounds
     bounds: Option<CameraBounds>,
+    // Bounds being blended from, and the time spent blending
+    bounds_transition: Option<(CameraBounds, f32)>,
+    
+    // Camera zones
+    zones: Vec<CameraZone>,
+    active_zone: Option<usize>,
+    zone_transition_time: f32,
+    // Where the follow target was this frame (before the offset)
+    follow_position: Option<Vec2>,
+    
+    // Cinematic sequence overriding following, and its progress events
+    sequence: Option<SequencePlayer>,
+    events: Vec<CameraEvent>,
     
     // Screen properties
-    screen_center: Vec2,
+    // Part of the window this camera draws to, as fractions of its size (None = whole window)
+    viewport: Option<Rect>,
     
     // Smoothing
     target_position: Vec2,
@@ -66,35 +182,53 @@ pub struct Camera {
     zoom_speed: f32,
     
     // Dead zone (area where camera doesn't follow)
-    dead_zone: Option<f32>,
+    dead_zone: Option<DeadZone>,
+    // Per-axis follow speeds (None = follow_speed on both) and axis locks
+    axis_follow_speed: Option<Vec2>,
+    lock_x: bool,
+    lock_y: bool,
 }
 
 impl Camera {
     pub fn new() -> Self {
-        let screen_center = Vec2::new(screen_width() * 0.5, screen_height() * 0.5);
+        let screen_center = headless::screen_size() * 0.5;
         
         Self {
             position: screen_center,
             zoom: 1.0,
             rotation: 0.0,
             
-            shake_intensity: 0.0,
-            shake_duration: 0.0,
-            shake_timer: 0.0,
+            shakes: ShakeStack::default(),
             shake_offset: Vec2::ZERO,
+            shake_rotation: 0.0,
             
             follow_target: None,
+            follow_entity: None,
+            follow_entity_position: None,
+            follow_targets: vec![],
+            follow_target_positions: vec![],
+            framing: Framing::default(),
             follow_speed: 5.0,
             follow_offset: Vec2::ZERO,
             
             bounds: None,
-            screen_center,
+            bounds_transition: None,
+            zones: vec![],
+            active_zone: None,
+            zone_transition_time: 0.6,
+            follow_position: None,
+            sequence: None,
+            events: vec![],
+            viewport: None,
             
             target_position: screen_center,
             target_zoom: 1.0,
             zoom_speed: 5.0,
             
             dead_zone: None,
+            axis_follow_speed: None,
+            lock_x: false,
+            lock_y: false,
         }
     }
 
@@ -104,36 +238,122 @@ impl Camera {
         F: Fn() -> Vec2 + 'static,
     {
         self.follow_target = Some(Box::new(f));
+        self.follow_entity = None;
+        self.follow_targets.clear();
     }
 
     pub fn clear_follow_target(&mut self) {
         self.follow_target = None;
+        self.follow_entity = None;
+        self.follow_targets.clear();
+    }
+    
+    /// Follow an entity of the scene this camera belongs to (replaces any closure target).
+    /// The camera stays put while the entity doesn't exist.
+    pub fn follow_entity(&mut self, id: EntityId) {
+        self.follow_entity = Some(id);
+        self.follow_entity_position = None;
+        self.follow_target = None;
+        self.follow_targets.clear();
+    }
+    
+    /// The entity being followed, if any
+    pub fn followed_entity(&self) -> Option<EntityId> {
+        self.follow_entity
+    }
+    
+    /// Called by the Scene before `update` with the followed entity's position
+    pub(crate) fn set_follow_entity_position(&mut self, position: Option<Vec2>) {
+        self.follow_entity_position = position;
+    }
+    
+    /// Keep all targets in view by moving to their center and zooming out as they
+    /// spread apart (within the `Framing` limits). Replaces any other follow target.
+    ///
+    /// ```ignore
+    /// camera.follow_targets(vec![player_one.into(), player_two.into(), arena_center.into()]);
+    /// ```
+    pub fn follow_targets(&mut self, targets: Vec<CameraTarget>) {
+        self.follow_targets = targets;
+        self.follow_target_positions.clear();
+        self.follow_target = None;
+        self.follow_entity = None;
+    }
+    
+    pub fn get_follow_targets(&self) -> &[CameraTarget] {
+        &self.follow_targets
+    }
+    
+    /// Set padding and zoom limits for multi-target framing
+    pub fn set_framing(&mut self, framing: Framing) {
+        self.framing = framing;
+    }
+    
+    pub fn framing(&self) -> Framing {
+        self.framing
+    }
+    
+    /// Called by the Scene before `update` with the positions of the targets that exist
+    pub(crate) fn set_follow_target_positions(&mut self, positions: Vec<Vec2>) {
+        self.follow_target_positions = positions;
     }
     
     pub fn update(&mut self, dt: f32) {
-        self.screen_center = Vec2::new(screen_width() * 0.5, screen_height() * 0.5);
+        if self.sequence.is_some() {
+            self.update_sequence(dt);
+            self.update_screen_shake(dt);
+            return;
+        }
         self.update_following(dt);
+        self.update_zones(dt);
         self.update_smooth_movement(dt);
         self.update_screen_shake(dt);
         self.update_smooth_zoom(dt);
         self.apply_bounds();
     }
     
+    /// Where the follow target is this frame (before the offset)
+    fn follow_point(&mut self) -> Option<Vec2> {
+        match &self.follow_target {
+            Some(get_target) => Some(get_target()),
+            None if !self.follow_targets.is_empty() => self.frame_targets(),
+            None => self.follow_entity_position,
+        }
+    }
+    
     fn update_following(&mut self, dt: f32) {
-        if let Some(get_target) = &self.follow_target {
-            let target = get_target(); 
-            let target_with_offset = target + self.follow_offset;
+        let target = self.follow_point();
+        self.follow_position = target;
+        if let Some(target) = target {
+            let mut target_with_offset = target + self.follow_offset;
             
             // Dead zone
-            if let Some(dead_zone_radius) = self.dead_zone {
-                let distance = self.target_position.distance_to(target_with_offset);
-                if distance <= dead_zone_radius {
+            match self.dead_zone {
+                Some(DeadZone::Circle(radius)) if self.target_position.distance_to(target_with_offset) <= radius => {
                     return;
                 }
+                Some(DeadZone::Rect(half_size)) => {
+                    let diff = target_with_offset - self.target_position;
+                    target_with_offset = self.target_position + diff - diff.clamp(-half_size, half_size);
+                }
+                _ => {}
+            }
+            
+            // Locked axes stay where they are
+            if self.lock_x {
+                target_with_offset.x = self.target_position.x;
+            }
+            if self.lock_y {
+                target_with_offset.y = self.target_position.y;
             }
             
             // Smooth following
-            if self.follow_speed > 0.0 {
+            if let Some(speed) = self.axis_follow_speed {
+                self.target_position = Vec2::new(
+                    follow_axis(self.target_position.x, target_with_offset.x, speed.x, dt),
+                    follow_axis(self.target_position.y, target_with_offset.y, speed.y, dt),
+                );
+            } else if self.follow_speed > 0.0 {
                 let distance_factor_val = distance_factor(self.target_position, target_with_offset);
                 let move_amount = self.follow_speed * distance_factor_val * dt * 60.0;
                 self.target_position = self.target_position.move_toward(
@@ -146,6 +366,34 @@ impl Camera {
         }
     }
 
+    fn update_sequence(&mut self, dt: f32) {
+        let follow_point = self.follow_point().map(|point| point + self.follow_offset);
+        let Some(player) = &mut self.sequence else {
+            return;
+        };
+        (self.position, self.zoom) = player.update(dt, follow_point, &mut self.events);
+        if player.is_finished() {
+            self.sequence = None;
+            // Following picks up from where the sequence left the camera
+            self.target_position = self.position;
+            self.target_zoom = self.zoom;
+        }
+    }
+    
+    /// Zoom to fit the multi-target positions and return their center
+    fn frame_targets(&mut self) -> Option<Vec2> {
+        let first = *self.follow_target_positions.first()?;
+        let (min, max) = self.follow_target_positions.iter()
+            .fold((first, first), |(min, max), position| (min.min(*position), max.max(*position)));
+        
+        let extent = max - min + Vec2::splat(self.framing.padding * 2.0);
+        let view = self.screen_rect();
+        let fit = (view.w / extent.x.max(1.0)).min(view.h / extent.y.max(1.0));
+        self.target_zoom = fit.clamp(self.framing.min_zoom, self.framing.max_zoom.max(self.framing.min_zoom));
+        
+        Some((min + max) * 0.5)
+    }
+    
     fn update_smooth_movement(&mut self, dt: f32) {
         // Smooth position interpolation
         let move_speed = 10.0; // Adjust for responsiveness
@@ -153,21 +401,7 @@ impl Camera {
     }
     
     fn update_screen_shake(&mut self, dt: f32) {
-        if self.shake_timer > 0.0 {
-            self.shake_timer -= dt;
-            
-            // Calculate shake intensity (decreases over time)
-            let shake_factor = self.shake_timer / self.shake_duration;
-            let current_intensity = self.shake_intensity * shake_factor;
-            
-            // Generate random shake offset
-            self.shake_offset = Vec2::new(
-                rand::gen_range(-current_intensity, current_intensity),
-                rand::gen_range(-current_intensity, current_intensity),
-            );
-        } else {
-            self.shake_offset = Vec2::ZERO;
-        }
+        (self.shake_offset, self.shake_rotation) = self.shakes.update(dt);
     }
     
     fn update_smooth_zoom(&mut self, dt: f32) {
@@ -186,11 +420,62 @@ impl Camera {
         }
     }
     
+    fn update_zones(&mut self, dt: f32) {
+        if let Some((_, elapsed)) = &mut self.bounds_transition {
+            *elapsed += dt;
+            if *elapsed >= self.zone_transition_time {
+                self.bounds_transition = None;
+            }
+        }
+        
+        let Some(position) = self.follow_position else {
+            return;
+        };
+        // Stay in the current zone while the target is inside it, even where zones overlap
+        if self.active_zone.is_some_and(|index| self.zones[index].area.contains(position)) {
+            return;
+        }
+        // Outside every zone the camera keeps the last zone's settings
+        if let Some(index) = self.zones.iter().position(|zone| zone.area.contains(position)) {
+            self.enter_zone(index);
+        }
+    }
+    
+    fn enter_zone(&mut self, index: usize) {
+        let zone = self.zones[index].clone();
+        self.active_zone = Some(index);
+        
+        // Blend from wherever the bounds are right now (no bounds = snap)
+        let from = self.current_bounds();
+        self.bounds_transition = from.filter(|_| self.zone_transition_time > 0.0).map(|from| (from, 0.0));
+        self.bounds = Some(zone.bounds);
+        
+        if let Some(zoom) = zone.zoom {
+            self.set_target_zoom(zoom);
+        }
+        if let Some(offset) = zone.offset {
+            self.follow_offset = offset;
+        }
+    }
+    
+    /// Bounds in effect this frame, part way through a zone transition
+    fn current_bounds(&self) -> Option<CameraBounds> {
+        let bounds = self.bounds.as_ref()?;
+        match &self.bounds_transition {
+            Some((from, elapsed)) => {
+                let t = (elapsed / self.zone_transition_time).clamp(0.0, 1.0);
+                Some(from.lerp(bounds, t * t * (3.0 - 2.0 * t)))
+            }
+            None => Some(bounds.clone()),
+        }
+    }
+    
     fn apply_bounds(&mut self) {
-        if let Some(bounds) = &self.bounds {
+        if let Some(bounds) = &self.current_bounds() {
             // Calculate camera viewport in world space
-            let half_view_width = (screen_width() * 0.5) / self.zoom;
-            let half_view_height = (screen_height() * 0.5) / self.zoom;
+            let view = self.screen_rect();
+            let half_view_width = (view.w * 0.5) / self.zoom;
+            let half_view_height = (view.h * 0.5) / self.zoom;
             
             // Clamp camera position to keep viewport within bounds
             let min_camera_pos = Vec2::new(
@@ -202,6 +487,11 @@ impl Camera {
                 bounds.max.y - half_view_height,
             );
             
+            // Bounds smaller than the view (zoomed out, or mid-transition) keep it centered
+            let center = (bounds.min + bounds.max) * 0.5;
+            let max_camera_pos = max_camera_pos.max(center);
+            let min_camera_pos = min_camera_pos.min(max_camera_pos);
+            
             self.position.x = self.position.x.clamp(min_camera_pos.x, max_camera_pos.x);
             self.position.y = self.position.y.clamp(min_camera_pos.y, max_camera_pos.y);
             
@@ -300,11 +590,15 @@ impl Camera {
         F: Fn() -> Vec2 + 'static,
     {
         self.follow_target = Some(Box::new(f));
+        self.follow_entity = None;
+        self.follow_targets.clear();
     }
 
     /// Stop following target
     pub fn stop_following(&mut self) {
         self.follow_target = None;
+        self.follow_entity = None;
+        self.follow_targets.clear();
     }
 
     
@@ -320,28 +614,122 @@ impl Camera {
     
     /// Set dead zone radius (camera won't move if target is within this distance)
     pub fn set_dead_zone(&mut self, radius: Option<f32>) {
-        self.dead_zone = radius;
+        self.dead_zone = radius.map(DeadZone::Circle);
+    }
+    
+    /// Set a rectangular dead zone with separate horizontal and vertical half extents
+    pub fn set_dead_zone_rect(&mut self, half_size: Option<Vec2>) {
+        self.dead_zone = half_size.map(DeadZone::Rect);
+    }
+    
+    pub fn dead_zone(&self) -> Option<DeadZone> {
+        self.dead_zone
+    }
+    
+    /// Follow at different speeds horizontally and vertically (0 = instant on that axis).
+    /// None goes back to `set_follow_speed` on both axes.
+    pub fn set_axis_follow_speed(&mut self, speed: Option<Vec2>) {
+        self.axis_follow_speed = speed;
+    }
+    
+    /// Stop following on an axis, e.g. lock Y while a platformer character is airborne
+    pub fn set_axis_lock(&mut self, lock_x: bool, lock_y: bool) {
+        self.lock_x = lock_x;
+        self.lock_y = lock_y;
+    }
+    
+    pub fn axis_lock(&self) -> (bool, bool) {
+        (self.lock_x, self.lock_y)
     }
     
     // === Screen Shake ===
     
-    /// Add screen shake effect
+    /// Add a noise shake of up to `intensity` pixels that fades out over `duration`
     pub fn add_screen_shake(&mut self, intensity: f32, duration: f32) {
-        println!("camera is shaking");
-        self.shake_intensity = intensity;
-        self.shake_duration = duration;
-        self.shake_timer = duration;
+        self.add_shake(Shake::new(intensity, duration));
     }
     
-    /// Stop screen shake immediately
+    /// Add a shake on top of any already playing (see `Shake` for kicks and rotation)
+    pub fn add_shake(&mut self, shake: Shake) {
+        self.shakes.add(shake);
+    }
+    
+    /// Stop every impulse shake immediately (ambient shakes keep following their intensity)
     pub fn stop_screen_shake(&mut self) {
-        self.shake_timer = 0.0;
+        self.shakes.clear();
         self.shake_offset = Vec2::ZERO;
+        self.shake_rotation = 0.0;
+    }
+    
+    /// Add a continuous shake channel, starting at zero intensity. Setting a channel
+    /// that exists changes its shape but keeps its intensity.
+    pub fn set_ambient_shake(&mut self, name: &str, shake: AmbientShake) {
+        self.shakes.set_ambient(name, shake);
+    }
+    
+    pub fn remove_ambient_shake(&mut self, name: &str) -> bool {
+        self.shakes.remove_ambient(name)
+    }
+    
+    pub fn clear_ambient_shakes(&mut self) {
+        self.shakes.clear_ambient();
+    }
+    
+    /// Intensity (0 to 1) an ambient channel blends toward, e.g. from how close a boss is
+    pub fn set_shake_intensity(&mut self, name: &str, intensity: f32) {
+        self.shakes.set_intensity(name, intensity);
+    }
+    
+    /// Current, blended intensity of an ambient channel
+    pub fn shake_intensity(&self, name: &str) -> Option<f32> {
+        self.shakes.intensity(name)
+    }
+    
+    /// Scale every shake, impulse and ambient (1 = full, 0 = off). It multiplies with the
+    /// player's `MotionSettings`, which `Game` applies separately.
+    pub fn set_shake_scale(&mut self, scale: f32) {
+        self.shakes.set_scale(scale);
+    }
+    
+    pub fn shake_scale(&self) -> f32 {
+        self.shakes.scale()
+    }
+    
+    /// Shake multiplier from `MotionSettings::effective_shake_scale`
+    pub(crate) fn set_motion_shake_scale(&mut self, scale: f32) {
+        self.shakes.set_motion_scale(scale);
+    }
+    
+    // === Cinematic Sequences ===
+    
+    /// Play a scripted sequence of pans, zooms and holds. Following, zones, smoothing
+    /// and bounds are suspended until it ends; a playing sequence is replaced.
+    pub fn play_sequence(&mut self, sequence: CameraSequence) {
+        self.skip_sequence();
+        self.sequence = Some(SequencePlayer::new(sequence, self.position, self.zoom));
+    }
+    
+    pub fn is_playing_sequence(&self) -> bool {
+        self.sequence.is_some()
+    }
+    
+    /// Stop the sequence where it is and go back to following
+    pub fn skip_sequence(&mut self) {
+        if let Some(player) = self.sequence.take() {
+            self.events.push(CameraEvent::SequenceFinished { sequence: player.name().to_string(), skipped: true });
+            self.target_position = self.position;
+            self.target_zoom = self.zoom;
+        }
+    }
+    
+    /// Events since the last call (shots and sequences finishing)
+    pub fn take_events(&mut self) -> Vec<CameraEvent> {
+        std::mem::take(&mut self.events)
     }
     
     /// Check if camera is currently shaking
     pub fn is_shaking(&self) -> bool {
-        self.shake_timer > 0.0
+        self.shakes.scale() > 0.0 && (!self.shakes.is_empty() || self.shakes.is_ambient_active())
     }
     
     // === Bounds System ===
@@ -359,6 +747,35 @@ impl Camera {
     /// Remove camera bounds
     pub fn clear_bounds(&mut self) {
         self.bounds = None;
+        self.bounds_transition = None;
+    }
+    
+    // === Camera Zones ===
+    
+    /// Add a zone that takes over bounds, zoom and offset while the follow target is in it
+    pub fn add_zone(&mut self, zone: CameraZone) -> usize {
+        self.zones.push(zone);
+        self.zones.len() - 1
+    }
+    
+    pub fn zones(&self) -> &[CameraZone] {
+        &self.zones
+    }
+    
+    /// Remove all zones (the current bounds stay)
+    pub fn clear_zones(&mut self) {
+        self.zones.clear();
+        self.active_zone = None;
+    }
+    
+    /// Index of the zone the follow target is in
+    pub fn active_zone(&self) -> Option<usize> {
+        self.active_zone
+    }
+    
+    /// Seconds to blend between zone bounds (0 = snap)
+    pub fn set_zone_transition_time(&mut self, seconds: f32) {
+        self.zone_transition_time = seconds.max(0.0);
     }
     
     // === Coordinate Conversion ===
@@ -371,9 +788,10 @@ impl Camera {
         let mut relative_pos = world_pos - cam_pos;
         
         // Apply rotation
-        if self.rotation != 0.0 {
-            let cos_rot = self.rotation.cos();
-            let sin_rot = self.rotation.sin();
+        let rotation = self.get_final_rotation();
+        if rotation != 0.0 {
+            let cos_rot = rotation.cos();
+            let sin_rot = rotation.sin();
             relative_pos = Vec2::new(
                 relative_pos.x * cos_rot - relative_pos.y * sin_rot,
                 relative_pos.x * sin_rot + relative_pos.y * cos_rot,
@@ -381,7 +799,7 @@ impl Camera {
         }
         
         // Apply zoom and translate to screen center
-        relative_pos * self.zoom + self.screen_center
+        relative_pos * self.zoom + self.screen_center()
     }
     
     /// Convert screen position to world position
@@ -389,12 +807,13 @@ impl Camera {
         let cam_pos = self.position + self.shake_offset;
         
         // Translate relative to screen center and apply inverse zoom
-        let mut relative_pos = (screen_pos - self.screen_center) / self.zoom;
+        let mut relative_pos = (screen_pos - self.screen_center()) / self.zoom;
         
         // Apply inverse rotation
-        if self.rotation != 0.0 {
-            let cos_rot = (-self.rotation).cos();
-            let sin_rot = (-self.rotation).sin();
+        let rotation = self.get_final_rotation();
+        if rotation != 0.0 {
+            let cos_rot = (-rotation).cos();
+            let sin_rot = (-rotation).sin();
             relative_pos = Vec2::new(
                 relative_pos.x * cos_rot - relative_pos.y * sin_rot,
                 relative_pos.x * sin_rot + relative_pos.y * cos_rot,
@@ -407,8 +826,9 @@ impl Camera {
     
     /// Get the camera's view rectangle in world space
     pub fn get_view_rect(&self) -> (Vec2, Vec2) {
-        let half_width = (screen_width() * 0.5) / self.zoom;
-        let half_height = (screen_height() * 0.5) / self.zoom;
+        let view = self.screen_rect();
+        let half_width = (view.w * 0.5) / self.zoom;
+        let half_height = (view.h * 0.5) / self.zoom;
         let center = self.position + self.shake_offset;
         
         let min = Vec2::new(center.x - half_width, center.y - half_height);
@@ -431,6 +851,43 @@ impl Camera {
         world_pos.y + radius >= min.y && world_pos.y - radius <= max.y
     }
     
+    // === Viewport ===
+    
+    /// Draw to part of the window only (split-screen). `viewport` is in fractions of
+    /// the window size, e.g. `Rect::new(0.5, 0.0, 0.5, 1.0)` for the right half.
+    pub fn set_viewport(&mut self, viewport: Option<Rect>) {
+        self.viewport = viewport;
+    }
+    
+    pub fn with_viewport(mut self, viewport: Rect) -> Self {
+        self.set_viewport(Some(viewport));
+        self
+    }
+    
+    pub fn viewport(&self) -> Option<Rect> {
+        self.viewport
+    }
+    
+    /// The part of the window this camera draws to, in pixels
+    pub fn screen_rect(&self) -> Rect {
+        let Vec2 { x: width, y: height } = headless::screen_size();
+        match self.viewport {
+            Some(viewport) => Rect::new(viewport.x * width, viewport.y * height, viewport.w * width, viewport.h * height),
+            None => Rect::new(0.0, 0.0, width, height),
+        }
+    }
+    
+    /// Center of `screen_rect`, where the camera's position appears. It follows the window
+    /// size, so coordinate conversions stay right after a resize.
+    pub fn screen_center(&self) -> Vec2 {
+        self.screen_rect().center()
+    }
+    
+    /// Check if a screen position (e.g. the mouse) is inside this camera's viewport
+    pub fn contains_screen_point(&self, screen_pos: Vec2) -> bool {
+        self.screen_rect().contains(screen_pos)
+    }
+    
     // === Camera Application ===
     
     /// Apply camera transform for drawing world objects
@@ -441,16 +898,24 @@ impl Camera {
         push_camera_state();
         
         // Set camera
+        let view = self.screen_rect();
         set_camera(&Camera2D {
             target: final_pos,
-            zoom: Vec2::new(self.zoom / screen_width(), self.zoom / screen_height()),
-            rotation: self.rotation,
+            zoom: Vec2::new(self.zoom / view.w, self.zoom / view.h),
+            rotation: self.get_final_rotation(),
+            // GL viewports are measured from the bottom of the window
+            viewport: self.viewport.map(|_| (
+                view.x as i32,
+                (screen_height() - view.y - view.h) as i32,
+                view.w as i32,
+                view.h as i32,
+            )),
             ..Default::default()
         });
     }
     
     /// Reset camera transform (for UI drawing)
-    pub fn reset(&mut self ) {
+    pub fn reset(&self) {
         pop_camera_state();
     }
     
@@ -461,6 +926,11 @@ impl Camera {
         self.position + self.shake_offset
     }
     
+    /// Get current camera rotation (including shake)
+    pub fn get_final_rotation(&self) -> f32 {
+        self.rotation + self.shake_rotation
+    }
+    
     /// Get camera forward direction (based on rotation)
     pub fn get_forward(&self) -> Vec2 {
         Vec2::new(self.rotation.cos(), self.rotation.sin())
@@ -492,3 +962,34 @@ fn distance_factor(from: Vec2, to: Vec2) -> f32 {
     let distance = from.distance_to(to);
     (distance / 100.0).min(2.0).max(0.1)
 }
+
+/// One axis of smooth following, with the same speed curve as `distance_factor`
+fn follow_axis(from: f32, to: f32, speed: f32, dt: f32) -> f32 {
+    if speed <= 0.0 {
+        return to;
+    }
+    let diff = to - from;
+    let max_move = speed * (diff.abs() / 100.0).clamp(0.1, 2.0) * dt * 60.0;
+    from + diff.clamp(-max_move, max_move)
+}
+
+/// Viewports (in fractions of the window) for 1 to 4 players: side by side for two,
+/// one on top and two below for three, and quadrants for four
+pub fn split_screen_viewports(players: usize) -> Vec<Rect> {
+    match players {
+        0 => vec![],
+        1 => vec![Rect::new(0.0, 0.0, 1.0, 1.0)],
+        2 => vec![Rect::new(0.0, 0.0, 0.5, 1.0), Rect::new(0.5, 0.0, 0.5, 1.0)],
+        3 => vec![
+            Rect::new(0.0, 0.0, 1.0, 0.5),
+            Rect::new(0.0, 0.5, 0.5, 0.5),
+            Rect::new(0.5, 0.5, 0.5, 0.5),
+        ],
+        _ => vec![
+            Rect::new(0.0, 0.0, 0.5, 0.5),
+            Rect::new(0.5, 0.0, 0.5, 0.5),
+            Rect::new(0.0, 0.5, 0.5, 0.5),
+            Rect::new(0.5, 0.5, 0.5, 0.5),
+        ],
+    }
+}
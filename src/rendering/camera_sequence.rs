@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+use crate::math::Easing;
+
+/// One step of a camera sequence
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraShot {
+    /// Move to a world position over `duration` seconds
+    PanTo { position: Vec2, duration: f32, easing: Easing },
+    /// Change zoom over `duration` seconds
+    ZoomTo { zoom: f32, duration: f32, easing: Easing },
+    /// Stay put
+    Hold { duration: f32 },
+    /// Move back to the follow target and the zoom from before the sequence,
+    /// then hand control back to following
+    ReturnToFollow { duration: f32, easing: Easing },
+}
+
+impl CameraShot {
+    fn duration(&self) -> f32 {
+        match self {
+            CameraShot::PanTo { duration, .. }
+            | CameraShot::ZoomTo { duration, .. }
+            | CameraShot::Hold { duration }
+            | CameraShot::ReturnToFollow { duration, .. } => duration.max(0.0),
+        }
+    }
+}
+
+/// A scripted list of camera moves for cutscenes, played with `Camera::play_sequence`.
+///
+/// ```ignore
+/// camera.play_sequence(
+///     CameraSequence::new("boss_intro")
+///         .pan_to(boss_position, 1.5, Easing::CubicInOut)
+///         .zoom_to(1.5, 0.5, Easing::QuadOut)
+///         .hold(2.0)
+///         .return_to_follow(1.0, Easing::CubicInOut),
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CameraSequence {
+    pub name: String,
+    pub shots: Vec<CameraShot>,
+}
+
+impl CameraSequence {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), shots: vec![] }
+    }
+
+    pub fn with_shot(mut self, shot: CameraShot) -> Self {
+        self.shots.push(shot);
+        self
+    }
+
+    pub fn pan_to(self, position: Vec2, duration: f32, easing: Easing) -> Self {
+        self.with_shot(CameraShot::PanTo { position, duration, easing })
+    }
+
+    pub fn zoom_to(self, zoom: f32, duration: f32, easing: Easing) -> Self {
+        self.with_shot(CameraShot::ZoomTo { zoom, duration, easing })
+    }
+
+    pub fn hold(self, duration: f32) -> Self {
+        self.with_shot(CameraShot::Hold { duration })
+    }
+
+    pub fn return_to_follow(self, duration: f32, easing: Easing) -> Self {
+        self.with_shot(CameraShot::ReturnToFollow { duration, easing })
+    }
+}
+
+/// Progress reported by a playing camera sequence (see `Camera::take_events`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum CameraEvent {
+    ShotFinished { sequence: String, index: usize },
+    /// Sent when the last shot ends, and when a sequence is skipped or replaced
+    SequenceFinished { sequence: String, skipped: bool },
+}
+
+/// Playback state of a sequence
+pub(crate) struct SequencePlayer {
+    sequence: CameraSequence,
+    index: usize,
+    elapsed: f32,
+    // Where the current shot started from
+    shot_position: Vec2,
+    shot_zoom: f32,
+    // Zoom to go back to on ReturnToFollow
+    original_zoom: f32,
+}
+
+impl SequencePlayer {
+    pub(crate) fn new(sequence: CameraSequence, position: Vec2, zoom: f32) -> Self {
+        Self {
+            sequence,
+            index: 0,
+            elapsed: 0.0,
+            shot_position: position,
+            shot_zoom: zoom,
+            original_zoom: zoom,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &str {
+        &self.sequence.name
+    }
+
+    pub(crate) fn is_finished(&self) -> bool {
+        self.index >= self.sequence.shots.len()
+    }
+
+    /// Advance by `dt` and return the camera position and zoom. `follow_point` is
+    /// where following would put the camera right now.
+    pub(crate) fn update(&mut self, dt: f32, follow_point: Option<Vec2>, events: &mut Vec<CameraEvent>) -> (Vec2, f32) {
+        self.elapsed += dt;
+        let mut current = (self.shot_position, self.shot_zoom);
+        // Short shots can finish several per frame
+        while let Some(shot) = self.sequence.shots.get(self.index) {
+            let duration = shot.duration();
+            let t = if duration > 0.0 { self.elapsed / duration } else { 1.0 };
+            current = self.shot_state(shot, t.min(1.0), follow_point);
+            if t < 1.0 {
+                break;
+            }
+            events.push(CameraEvent::ShotFinished { sequence: self.sequence.name.clone(), index: self.index });
+            self.elapsed -= duration;
+            self.index += 1;
+            (self.shot_position, self.shot_zoom) = current;
+        }
+        if self.is_finished() {
+            events.push(CameraEvent::SequenceFinished { sequence: self.sequence.name.clone(), skipped: false });
+        }
+        current
+    }
+
+    fn shot_state(&self, shot: &CameraShot, t: f32, follow_point: Option<Vec2>) -> (Vec2, f32) {
+        let (position, zoom) = (self.shot_position, self.shot_zoom);
+        match shot {
+            CameraShot::PanTo { position: target, easing, .. } => (position.lerp(*target, easing.apply(t)), zoom),
+            CameraShot::ZoomTo { zoom: target, easing, .. } => (position, easing.interpolate(zoom, *target, t)),
+            CameraShot::Hold { .. } => (position, zoom),
+            CameraShot::ReturnToFollow { easing, .. } => {
+                let target = follow_point.unwrap_or(position);
+                (position.lerp(target, easing.apply(t)), easing.interpolate(zoom, self.original_zoom, t))
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+use crate::math::Noise;
+
+/// One screen shake, added with `Camera::add_shake`. Shakes stack: each one plays out
+/// on its own and their offsets add up.
+///
+/// ```ignore
+/// camera.add_shake(Shake::new(12.0, 0.4).with_rotation(0.05)); // explosion
+/// camera.add_shake(Shake::kick(-aim_direction, 6.0, 0.15));    // recoil
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shake {
+    /// Largest offset in pixels, at the start of the shake
+    pub amplitude: f32,
+    /// Largest rotation in radians, at the start of the shake
+    pub rotation: f32,
+    pub duration: f32,
+    /// How fast the noise moves; higher is more jittery
+    pub frequency: f32,
+    /// Shake only along this axis (None = any direction)
+    pub direction: Option<Vec2>,
+    /// Push along `direction` and ease back instead of oscillating (recoil)
+    pub kick: bool,
+}
+
+impl Shake {
+    /// Smooth noise shake in every direction
+    pub fn new(amplitude: f32, duration: f32) -> Self {
+        Self {
+            amplitude,
+            rotation: 0.0,
+            duration: duration.max(0.0),
+            frequency: 15.0,
+            direction: None,
+            kick: false,
+        }
+    }
+
+    /// A single push along `direction` that eases back to rest
+    pub fn kick(direction: Vec2, strength: f32, duration: f32) -> Self {
+        Self {
+            direction: Some(direction.normalize_or_zero()),
+            kick: true,
+            ..Self::new(strength, duration)
+        }
+    }
+
+    pub fn with_rotation(mut self, radians: f32) -> Self {
+        self.rotation = radians;
+        self
+    }
+
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    /// Oscillate along one axis only (e.g. vertical for landing impacts)
+    pub fn with_direction(mut self, direction: Vec2) -> Self {
+        self.direction = Some(direction.normalize_or_zero());
+        self
+    }
+}
+
+/// A continuous shake that never ends on its own, for rumble that follows the game's
+/// state (an engine idling, a boss stomping nearby). Its strength follows an intensity
+/// from 0 to 1 set with `Camera::set_shake_intensity`, and it plays alongside impulse
+/// shakes.
+///
+/// ```ignore
+/// camera.set_ambient_shake("engine", AmbientShake::new(3.0).with_frequency(25.0));
+/// // each frame
+/// camera.set_shake_intensity("engine", throttle);
+/// camera.set_shake_intensity("boss", 1.0 - boss_distance / 600.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientShake {
+    /// Offset in pixels at full intensity
+    pub amplitude: f32,
+    /// Rotation in radians at full intensity
+    pub rotation: f32,
+    /// How fast the noise moves; higher is more jittery
+    pub frequency: f32,
+    /// Shake only along this axis (None = any direction)
+    pub direction: Option<Vec2>,
+    /// Intensity change per second, so jumps in intensity ease in and out
+    pub blend_speed: f32,
+}
+
+impl AmbientShake {
+    pub fn new(amplitude: f32) -> Self {
+        Self {
+            amplitude,
+            rotation: 0.0,
+            frequency: 8.0,
+            direction: None,
+            blend_speed: 2.0,
+        }
+    }
+
+    pub fn with_rotation(mut self, radians: f32) -> Self {
+        self.rotation = radians;
+        self
+    }
+
+    pub fn with_frequency(mut self, frequency: f32) -> Self {
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Vec2) -> Self {
+        self.direction = Some(direction.normalize_or_zero());
+        self
+    }
+
+    pub fn with_blend_speed(mut self, per_second: f32) -> Self {
+        self.blend_speed = per_second.max(0.0);
+        self
+    }
+}
+
+struct AmbientChannel {
+    shake: AmbientShake,
+    intensity: f32,
+    target: f32,
+    elapsed: f32,
+    noise: Noise,
+}
+
+impl AmbientChannel {
+    fn sample(&self) -> (Vec2, f32) {
+        let shake = &self.shake;
+        // Squared like impulse trauma, so low intensities stay subtle
+        let strength = self.intensity * self.intensity;
+        let t = self.elapsed * shake.frequency;
+        let offset = match shake.direction {
+            Some(direction) => direction * self.noise.sample_2d(t, 0.5),
+            None => Vec2::new(self.noise.sample_2d(t, 0.5), self.noise.sample_2d(10.5, t)),
+        };
+        let rotation = self.noise.sample_2d(t, 20.5);
+        (offset * shake.amplitude * strength, rotation * shake.rotation * strength)
+    }
+}
+
+struct ActiveShake {
+    shake: Shake,
+    elapsed: f32,
+    // Each shake samples its own noise so stacked shakes don't move in lockstep
+    noise: Noise,
+}
+
+impl ActiveShake {
+    /// Offset and rotation right now
+    fn sample(&self) -> (Vec2, f32) {
+        let shake = &self.shake;
+        let remaining = if shake.duration > 0.0 { 1.0 - self.elapsed / shake.duration } else { 0.0 };
+        // Trauma falls off quadratically, so the tail of a shake is gentle
+        let trauma = remaining.clamp(0.0, 1.0).powi(2);
+        if shake.kick {
+            let direction = shake.direction.unwrap_or(Vec2::ZERO);
+            return (direction * shake.amplitude * trauma, shake.rotation * trauma);
+        }
+
+        // Sample between lattice lines, where gradient noise is never pinned to zero
+        let t = self.elapsed * shake.frequency;
+        let offset = match shake.direction {
+            Some(direction) => direction * self.noise.sample_2d(t, 0.5),
+            None => Vec2::new(self.noise.sample_2d(t, 0.5), self.noise.sample_2d(10.5, t)),
+        };
+        let rotation = self.noise.sample_2d(t, 20.5);
+        (offset * shake.amplitude * trauma, rotation * shake.rotation * trauma)
+    }
+}
+
+/// Shakes playing on a camera
+pub(crate) struct ShakeStack {
+    shakes: Vec<ActiveShake>,
+    ambient: HashMap<String, AmbientChannel>,
+    next_seed: u32,
+    // Multiplies every shake, for players who want less (or none)
+    scale: f32,
+    // From the player's `MotionSettings`, on top of `scale`
+    motion_scale: f32,
+}
+
+impl Default for ShakeStack {
+    fn default() -> Self {
+        Self {
+            shakes: vec![],
+            ambient: HashMap::new(),
+            next_seed: 0,
+            scale: 1.0,
+            motion_scale: 1.0,
+        }
+    }
+}
+
+impl ShakeStack {
+    fn next_noise(&mut self) -> Noise {
+        self.next_seed = self.next_seed.wrapping_add(1);
+        Noise::new(self.next_seed)
+    }
+
+    pub(crate) fn add(&mut self, shake: Shake) {
+        let noise = self.next_noise();
+        self.shakes.push(ActiveShake { shake, elapsed: 0.0, noise });
+    }
+
+    /// Stop impulse shakes; ambient channels keep going
+    pub(crate) fn clear(&mut self) {
+        self.shakes.clear();
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.shakes.is_empty()
+    }
+
+    /// Add or replace an ambient channel, keeping its current intensity if replaced
+    pub(crate) fn set_ambient(&mut self, name: &str, shake: AmbientShake) {
+        if let Some(channel) = self.ambient.get_mut(name) {
+            channel.shake = shake;
+            return;
+        }
+        let noise = self.next_noise();
+        self.ambient.insert(name.to_string(), AmbientChannel { shake, intensity: 0.0, target: 0.0, elapsed: 0.0, noise });
+    }
+
+    pub(crate) fn remove_ambient(&mut self, name: &str) -> bool {
+        self.ambient.remove(name).is_some()
+    }
+
+    pub(crate) fn clear_ambient(&mut self) {
+        self.ambient.clear();
+    }
+
+    pub(crate) fn set_intensity(&mut self, name: &str, intensity: f32) {
+        if let Some(channel) = self.ambient.get_mut(name) {
+            channel.target = intensity.clamp(0.0, 1.0);
+        }
+    }
+
+    pub(crate) fn intensity(&self, name: &str) -> Option<f32> {
+        self.ambient.get(name).map(|channel| channel.intensity)
+    }
+
+    /// Whether any ambient channel is shaking right now
+    pub(crate) fn is_ambient_active(&self) -> bool {
+        self.ambient.values().any(|channel| channel.intensity > 0.0)
+    }
+
+    pub(crate) fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+    }
+
+    pub(crate) fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    pub(crate) fn set_motion_scale(&mut self, scale: f32) {
+        self.motion_scale = scale.max(0.0);
+    }
+
+    /// Advance every shake and return their combined offset and rotation
+    pub(crate) fn update(&mut self, dt: f32) -> (Vec2, f32) {
+        for active in &mut self.shakes {
+            active.elapsed += dt;
+        }
+        self.shakes.retain(|active| active.elapsed < active.shake.duration);
+        for channel in self.ambient.values_mut() {
+            channel.elapsed += dt;
+            let step = channel.shake.blend_speed * dt;
+            channel.intensity += (channel.target - channel.intensity).clamp(-step, step);
+        }
+        let (offset, rotation) = self.shakes.iter()
+            .map(ActiveShake::sample)
+            .chain(self.ambient.values().map(AmbientChannel::sample))
+            .fold((Vec2::ZERO, 0.0), |(offset, rotation), (o, r)| (offset + o, rotation + r));
+        let scale = self.scale * self.motion_scale;
+        (offset * scale, rotation * scale)
+    }
+}
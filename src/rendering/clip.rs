@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+use macroquad::window::get_internal_gl;
+use std::cell::RefCell;
+
+thread_local! {
+    static CLIP_STACK: RefCell<Vec<Rect>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Restrict drawing to a rectangle in window pixels until the matching `pop_clip_rect`.
+/// Nested clips are intersected with the ones below them.
+pub fn push_clip_rect(rect: Rect) {
+    let clip = CLIP_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let clip = match stack.last() {
+            // No overlap clips everything
+            Some(current) => current.intersect(rect).unwrap_or(Rect::new(rect.x, rect.y, 0.0, 0.0)),
+            None => rect,
+        };
+        stack.push(clip);
+        clip
+    });
+    apply_scissor(Some(clip));
+}
+
+/// Remove the innermost clip rect, going back to the one below it (or no clipping)
+pub fn pop_clip_rect() {
+    let clip = CLIP_STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        stack.pop();
+        stack.last().copied()
+    });
+    apply_scissor(clip);
+}
+
+/// The clip rect drawing is currently restricted to
+pub fn current_clip_rect() -> Option<Rect> {
+    CLIP_STACK.with(|stack| stack.borrow().last().copied())
+}
+
+/// Run `draw` with drawing clipped to `rect`
+pub fn with_clip_rect<R>(rect: Rect, draw: impl FnOnce() -> R) -> R {
+    push_clip_rect(rect);
+    let result = draw();
+    pop_clip_rect();
+    result
+}
+
+fn apply_scissor(clip: Option<Rect>) {
+    // The scissor works in framebuffer pixels
+    let scale = screen_dpi_scale();
+    let scissor = clip.map(|rect| (
+        (rect.x * scale) as i32,
+        (rect.y * scale) as i32,
+        (rect.w * scale) as i32,
+        (rect.h * scale) as i32,
+    ));
+    let gl = unsafe { get_internal_gl() };
+    gl.quad_gl.scissor(scissor);
+}
@@ -0,0 +1,113 @@
+//! Shapes and labels in world space for debugging, callable from anywhere. `Game` draws
+//! them over the world with the base scene's cameras while `GameConfig::show_debug_draw`
+//! is on:
+//!
+//! ```ignore
+//! debug_draw::line(self.position, target, RED, 0.0);
+//! debug_draw::circle(hit, 4.0, YELLOW, 1.0);
+//! debug_draw::text(self.position - vec2(0.0, 20.0), &format!("{:?}", self.state), WHITE, 0.0);
+//! ```
+
+use macroquad::prelude::*;
+use std::cell::RefCell;
+use super::{with_clip_rect, Camera};
+
+// Pixels, in world space for lines and outlines
+const LINE_WIDTH: f32 = 1.0;
+const FONT_SIZE: f32 = 16.0;
+
+#[derive(Debug, Clone)]
+enum DebugShape {
+    Line { from: Vec2, to: Vec2 },
+    Circle { center: Vec2, radius: f32 },
+    Rect { rect: Rect },
+    Text { position: Vec2, text: String },
+}
+
+struct DebugEntry {
+    shape: DebugShape,
+    color: Color,
+    // Real seconds left after this frame
+    remaining: f32,
+}
+
+thread_local! {
+    static QUEUE: RefCell<Vec<DebugEntry>> = const { RefCell::new(Vec::new()) };
+}
+
+fn push(shape: DebugShape, color: Color, duration: f32) {
+    QUEUE.with(|queue| queue.borrow_mut().push(DebugEntry { shape, color, remaining: duration }));
+}
+
+/// Line between two world positions. A duration of 0 draws it for one frame; longer
+/// ones keep it up for that many real seconds, so one-off events can still be seen.
+pub fn line(from: Vec2, to: Vec2, color: Color, duration: f32) {
+    push(DebugShape::Line { from, to }, color, duration);
+}
+
+/// Circle outline
+pub fn circle(center: Vec2, radius: f32, color: Color, duration: f32) {
+    push(DebugShape::Circle { center, radius }, color, duration);
+}
+
+/// Rectangle outline
+pub fn rect(rect: Rect, color: Color, duration: f32) {
+    push(DebugShape::Rect { rect }, color, duration);
+}
+
+/// Label at a world position, drawn at the same size at any zoom
+pub fn text(position: Vec2, text: &str, color: Color, duration: f32) {
+    push(DebugShape::Text { position, text: text.to_string() }, color, duration);
+}
+
+/// Remove every queued shape, including ones with time left
+pub fn clear() {
+    QUEUE.with(|queue| queue.borrow_mut().clear());
+}
+
+/// Number of shapes waiting to be drawn
+pub fn len() -> usize {
+    QUEUE.with(|queue| queue.borrow().len())
+}
+
+/// Draw the queued shapes through one camera
+pub(crate) fn draw(camera: &Camera) {
+    QUEUE.with(|queue| {
+        let queue = queue.borrow();
+        if queue.is_empty() {
+            return;
+        }
+        camera.apply();
+        for entry in queue.iter() {
+            match &entry.shape {
+                DebugShape::Line { from, to } => draw_line(from.x, from.y, to.x, to.y, LINE_WIDTH, entry.color),
+                DebugShape::Circle { center, radius } => {
+                    draw_circle_lines(center.x, center.y, *radius, LINE_WIDTH, entry.color)
+                }
+                DebugShape::Rect { rect } => draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, LINE_WIDTH, entry.color),
+                DebugShape::Text { .. } => {}
+            }
+        }
+        camera.reset();
+
+        // Labels in screen space so zoom doesn't scale them, kept inside the view
+        with_clip_rect(camera.screen_rect(), || {
+            for entry in queue.iter() {
+                if let DebugShape::Text { position, text } = &entry.shape {
+                    let at = camera.world_to_screen(*position);
+                    draw_text(text, at.x, at.y, FONT_SIZE, entry.color);
+                }
+            }
+        });
+    });
+}
+
+/// Count down after a frame is drawn, dropping shapes whose time is up
+pub(crate) fn advance(dt: f32) {
+    QUEUE.with(|queue| {
+        queue.borrow_mut().retain_mut(|entry| {
+            entry.remaining -= dt;
+            entry.remaining > 0.0
+        })
+    });
+}
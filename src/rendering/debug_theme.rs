@@ -0,0 +1,178 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+
+/// Error returned when loading a debug theme
+#[derive(Debug)]
+pub enum DebugThemeError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for DebugThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DebugThemeError::Io(err) => write!(f, "failed to read debug theme: {}", err),
+            DebugThemeError::Format(msg) => write!(f, "invalid debug theme: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DebugThemeError {}
+
+impl From<std::io::Error> for DebugThemeError {
+    fn from(err: std::io::Error) -> Self {
+        DebugThemeError::Io(err)
+    }
+}
+
+/// Colors of every debug overlay, gizmo and error screen the engine draws.
+///
+/// The default uses the Okabe-Ito palette, which stays distinguishable with the common
+/// kinds of color blindness; states never rely on red vs green alone. Missing fields in
+/// theme files use the defaults:
+///
+/// ```ron
+/// (heading: (1.0, 0.6, 0.2, 1.0), collider: (0.3, 0.9, 0.9, 1.0))
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DebugTheme {
+    /// RGBA colors
+    pub text: [f32; 4],
+    /// Section titles
+    pub heading: [f32; 4],
+    /// Secondary details
+    pub muted: [f32; 4],
+    /// Something currently on or pressed
+    pub active: [f32; 4],
+    pub warning: [f32; 4],
+    pub error: [f32; 4],
+    /// Shadows behind overlay text
+    pub shadow: [f32; 4],
+    /// Full-screen debug panels and error screens
+    pub background: [f32; 4],
+    // Gizmos
+    pub collider: [f32; 4],
+    pub trigger: [f32; 4],
+    pub velocity: [f32; 4],
+    pub camera: [f32; 4],
+    /// Profiler bars: input, update, physics, camera and draw
+    pub profiler: [[f32; 4]; 5],
+}
+
+impl DebugTheme {
+    /// The color-blind-safe default
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The engine's original primary colors (white, yellow, green, gray, red)
+    pub fn classic() -> Self {
+        Self {
+            text: [1.0, 1.0, 1.0, 1.0],
+            heading: [0.99, 0.98, 0.0, 1.0],
+            muted: [0.51, 0.51, 0.51, 1.0],
+            active: [0.0, 0.89, 0.19, 1.0],
+            warning: [1.0, 0.63, 0.0, 1.0],
+            error: [0.9, 0.16, 0.22, 1.0],
+            collider: [0.0, 0.89, 0.19, 1.0],
+            trigger: [0.0, 0.47, 0.95, 1.0],
+            velocity: [0.9, 0.16, 0.22, 1.0],
+            camera: [0.99, 0.98, 0.0, 1.0],
+            ..Self::default()
+        }
+    }
+
+    /// Load a theme from a RON file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, DebugThemeError> {
+        let data = std::fs::read_to_string(path)?;
+        Self::load_from_str(&data)
+    }
+
+    /// Parse a theme from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, DebugThemeError> {
+        ron::from_str(data).map_err(|err| DebugThemeError::Format(err.to_string()))
+    }
+
+    pub fn text(&self) -> Color {
+        Color::from(self.text)
+    }
+
+    pub fn heading(&self) -> Color {
+        Color::from(self.heading)
+    }
+
+    pub fn muted(&self) -> Color {
+        Color::from(self.muted)
+    }
+
+    pub fn active(&self) -> Color {
+        Color::from(self.active)
+    }
+
+    pub fn warning(&self) -> Color {
+        Color::from(self.warning)
+    }
+
+    pub fn error(&self) -> Color {
+        Color::from(self.error)
+    }
+
+    pub fn shadow(&self) -> Color {
+        Color::from(self.shadow)
+    }
+
+    pub fn background(&self) -> Color {
+        Color::from(self.background)
+    }
+
+    pub fn collider(&self) -> Color {
+        Color::from(self.collider)
+    }
+
+    pub fn trigger(&self) -> Color {
+        Color::from(self.trigger)
+    }
+
+    pub fn velocity(&self) -> Color {
+        Color::from(self.velocity)
+    }
+
+    pub fn camera(&self) -> Color {
+        Color::from(self.camera)
+    }
+
+    /// Color of a profiler section, in `FrameStats::sections` order
+    pub fn profiler(&self, section: usize) -> Color {
+        Color::from(self.profiler[section % self.profiler.len()])
+    }
+}
+
+impl Default for DebugTheme {
+    fn default() -> Self {
+        // Okabe-Ito: sky blue, bluish green, yellow, orange, vermillion, reddish purple, blue
+        Self {
+            text: [0.95, 0.95, 0.95, 1.0],
+            heading: [0.94, 0.89, 0.26, 1.0],
+            muted: [0.6, 0.6, 0.6, 1.0],
+            active: [0.34, 0.71, 0.91, 1.0],
+            warning: [0.9, 0.62, 0.0, 1.0],
+            error: [0.84, 0.37, 0.0, 1.0],
+            shadow: [0.0, 0.0, 0.0, 0.6],
+            background: [0.09, 0.08, 0.1, 0.95],
+            collider: [0.0, 0.62, 0.45, 1.0],
+            trigger: [0.8, 0.47, 0.65, 1.0],
+            velocity: [0.9, 0.62, 0.0, 1.0],
+            camera: [0.34, 0.71, 0.91, 1.0],
+            profiler: [
+                [0.34, 0.71, 0.91, 1.0],
+                [0.0, 0.62, 0.45, 1.0],
+                [0.9, 0.62, 0.0, 1.0],
+                [0.8, 0.47, 0.65, 1.0],
+                [0.0, 0.45, 0.7, 1.0],
+            ],
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use macroquad::prelude::*;
+use super::Camera;
+
+/// How much detail an entity is drawn with, from most to least
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LodLevel {
+    /// The normal `Entity::draw`
+    Full,
+    /// A cheap stand-in, e.g. a flat shape instead of an animated sprite
+    Simple,
+    /// A dot or a few pixels
+    Point,
+}
+
+/// The level an entity is drawn at this frame, passed to `Entity::draw_lod`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lod {
+    pub level: LodLevel,
+    /// Size of one screen pixel in world units, so points stay visible at any zoom
+    pub pixel_size: f32,
+}
+
+/// When a scene switches entities to lower detail (see `Scene::set_lod`).
+///
+/// Detail drops once the camera zooms out past a threshold, or once an entity is far
+/// enough from the camera; the coarser of the two wins.
+///
+/// ```ignore
+/// scene.set_lod(Some(LodSettings::new().with_zoom(0.5, 0.15)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LodSettings {
+    /// Below this zoom, entities are drawn `Simple`
+    pub simple_zoom: f32,
+    /// Below this zoom, entities are drawn as points
+    pub point_zoom: f32,
+    /// World distance from the camera past which entities are drawn `Simple` (None = never)
+    pub simple_distance: Option<f32>,
+    pub point_distance: Option<f32>,
+}
+
+impl Default for LodSettings {
+    fn default() -> Self {
+        Self {
+            simple_zoom: 0.5,
+            point_zoom: 0.2,
+            simple_distance: None,
+            point_distance: None,
+        }
+    }
+}
+
+impl LodSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_zoom(mut self, simple: f32, point: f32) -> Self {
+        self.simple_zoom = simple;
+        self.point_zoom = point;
+        self
+    }
+
+    pub fn with_distance(mut self, simple: f32, point: f32) -> Self {
+        self.simple_distance = Some(simple);
+        self.point_distance = Some(point);
+        self
+    }
+
+    /// Level for the whole view, from the camera's zoom alone
+    pub fn zoom_level(&self, zoom: f32) -> LodLevel {
+        if zoom < self.point_zoom {
+            LodLevel::Point
+        } else if zoom < self.simple_zoom {
+            LodLevel::Simple
+        } else {
+            LodLevel::Full
+        }
+    }
+
+    /// Level for an entity at `position` (entities without a position go by zoom alone)
+    pub fn level(&self, camera: &Camera, position: Option<Vec2>) -> LodLevel {
+        let by_zoom = self.zoom_level(camera.zoom);
+        let Some(position) = position else {
+            return by_zoom;
+        };
+        let distance = position.distance(camera.get_final_position());
+        let by_distance = if self.point_distance.is_some_and(|limit| distance > limit) {
+            LodLevel::Point
+        } else if self.simple_distance.is_some_and(|limit| distance > limit) {
+            LodLevel::Simple
+        } else {
+            LodLevel::Full
+        };
+        by_zoom.max(by_distance)
+    }
+}
@@ -1,4 +1,23 @@
 pub mod camera;
+pub mod camera_sequence;
+pub mod camera_shake;
+pub mod shapes;
+pub mod sprite_batch;
+pub mod path;
+pub mod clip;
+pub mod debug_theme;
+pub mod lod;
+pub mod screenshot;
+pub mod debug_draw;
 
 pub use camera::Camera;
-pub use camera::CameraBounds;
\ No newline at end of file
+pub use camera::{split_screen_viewports, CameraBounds, CameraTarget, CameraZone, DeadZone, Framing};
+pub use camera_sequence::{CameraEvent, CameraSequence, CameraShot};
+pub use camera_shake::{AmbientShake, Shake};
+pub use shapes::{ShapeBatch, StrokeStyle, DashPattern, LineJoin, LineCap};
+pub use sprite_batch::{Sprite, SpriteBatch, SpriteBatchStats};
+pub use path::{Path, PathCommand, Polyline};
+pub use clip::{push_clip_rect, pop_clip_rect, current_clip_rect, with_clip_rect};
+pub use debug_theme::{DebugTheme, DebugThemeError};
+pub use lod::{Lod, LodLevel, LodSettings};
+pub use screenshot::{capture_screen, Screenshot};
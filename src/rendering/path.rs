@@ -0,0 +1,302 @@
+use macroquad::prelude::*;
+use crate::math::Transform;
+use super::shapes::{arc_points, ShapeBatch, StrokeStyle};
+
+/// Default flattening tolerance for curves (max distance from the true curve)
+const DEFAULT_TOLERANCE: f32 = 0.25;
+
+/// A single drawing command in a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Vec2),
+    LineTo(Vec2),
+    QuadTo { control: Vec2, to: Vec2 },
+    CubicTo { control1: Vec2, control2: Vec2, to: Vec2 },
+    Close,
+}
+
+/// A flattened sub-path, ready for stroking or filling
+#[derive(Debug, Clone)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub closed: bool,
+}
+
+/// SVG-style path builder for outlines made of lines and curves.
+///
+/// ```ignore
+/// let mut path = Path::new();
+/// path.move_to(vec2(0.0, 0.0))
+///     .line_to(vec2(100.0, 0.0))
+///     .quad_to(vec2(150.0, 50.0), vec2(100.0, 100.0))
+///     .close();
+/// shapes.fill_path(&path, ORANGE);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    commands: Vec<PathCommand>,
+    start: Vec2,
+    current: Vec2,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new sub-path at `point`
+    pub fn move_to(&mut self, point: Vec2) -> &mut Self {
+        self.commands.push(PathCommand::MoveTo(point));
+        self.start = point;
+        self.current = point;
+        self
+    }
+
+    /// Straight line from the current point
+    pub fn line_to(&mut self, point: Vec2) -> &mut Self {
+        self.ensure_started();
+        self.commands.push(PathCommand::LineTo(point));
+        self.current = point;
+        self
+    }
+
+    /// Quadratic Bézier curve from the current point
+    pub fn quad_to(&mut self, control: Vec2, to: Vec2) -> &mut Self {
+        self.ensure_started();
+        self.commands.push(PathCommand::QuadTo { control, to });
+        self.current = to;
+        self
+    }
+
+    /// Cubic Bézier curve from the current point
+    pub fn cubic_to(&mut self, control1: Vec2, control2: Vec2, to: Vec2) -> &mut Self {
+        self.ensure_started();
+        self.commands.push(PathCommand::CubicTo { control1, control2, to });
+        self.current = to;
+        self
+    }
+
+    /// Circular arc around `center`, connected to the current point with a line
+    pub fn arc(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep: f32) -> &mut Self {
+        let points = arc_points(center, radius, start_angle, sweep);
+        for (i, point) in points.into_iter().enumerate() {
+            if i == 0 && self.commands.is_empty() {
+                self.move_to(point);
+            } else {
+                self.line_to(point);
+            }
+        }
+        self
+    }
+
+    /// Close the current sub-path back to its starting point
+    pub fn close(&mut self) -> &mut Self {
+        self.commands.push(PathCommand::Close);
+        self.current = self.start;
+        self
+    }
+
+    /// Add a closed rectangle sub-path
+    pub fn rect(&mut self, position: Vec2, size: Vec2) -> &mut Self {
+        self.move_to(position)
+            .line_to(position + Vec2::new(size.x, 0.0))
+            .line_to(position + size)
+            .line_to(position + Vec2::new(0.0, size.y))
+            .close()
+    }
+
+    /// Add a closed circle sub-path
+    pub fn circle(&mut self, center: Vec2, radius: f32) -> &mut Self {
+        self.move_to(center + Vec2::new(radius, 0.0));
+        self.arc(center, radius, 0.0, std::f32::consts::TAU);
+        self.close()
+    }
+
+    pub fn commands(&self) -> &[PathCommand] {
+        &self.commands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.start = Vec2::ZERO;
+        self.current = Vec2::ZERO;
+    }
+
+    /// Copy of this path with every point moved by a transform (scale, then rotate, then translate)
+    pub fn transformed(&self, transform: &Transform) -> Path {
+        let map = |point: Vec2| transform.position + Vec2::from_angle(transform.rotation).rotate(point * transform.scale);
+
+        let commands = self.commands.iter().map(|command| match *command {
+            PathCommand::MoveTo(point) => PathCommand::MoveTo(map(point)),
+            PathCommand::LineTo(point) => PathCommand::LineTo(map(point)),
+            PathCommand::QuadTo { control, to } => PathCommand::QuadTo { control: map(control), to: map(to) },
+            PathCommand::CubicTo { control1, control2, to } => PathCommand::CubicTo {
+                control1: map(control1),
+                control2: map(control2),
+                to: map(to),
+            },
+            PathCommand::Close => PathCommand::Close,
+        }).collect();
+
+        Path {
+            commands,
+            start: map(self.start),
+            current: map(self.current),
+        }
+    }
+
+    /// Bounding box of the path's control points as (position, size)
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let mut points = self.commands.iter().flat_map(|command| match *command {
+            PathCommand::MoveTo(point) | PathCommand::LineTo(point) => vec![point],
+            PathCommand::QuadTo { control, to } => vec![control, to],
+            PathCommand::CubicTo { control1, control2, to } => vec![control1, control2, to],
+            PathCommand::Close => vec![],
+        });
+
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| (min.min(p), max.max(p)));
+        Some((min, max - min))
+    }
+
+    /// Convert curves to line segments, keeping within `tolerance` of the true shape
+    pub fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+        let tolerance = tolerance.max(0.001);
+        let mut result = vec![];
+        let mut current: Vec<Vec2> = vec![];
+        let mut last = Vec2::ZERO;
+
+        let finish = |points: &mut Vec<Vec2>, closed: bool, result: &mut Vec<Polyline>| {
+            if points.len() > 1 {
+                result.push(Polyline { points: std::mem::take(points), closed });
+            } else {
+                points.clear();
+            }
+        };
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(point) => {
+                    finish(&mut current, false, &mut result);
+                    current.push(point);
+                    last = point;
+                }
+                PathCommand::LineTo(point) => {
+                    current.push(point);
+                    last = point;
+                }
+                PathCommand::QuadTo { control, to } => {
+                    let deviation = (last - control * 2.0 + to).length();
+                    let segments = segment_count(deviation / (4.0 * tolerance));
+                    for i in 1..=segments {
+                        let t = i as f32 / segments as f32;
+                        let u = 1.0 - t;
+                        current.push(last * (u * u) + control * (2.0 * u * t) + to * (t * t));
+                    }
+                    last = to;
+                }
+                PathCommand::CubicTo { control1, control2, to } => {
+                    let deviation = (last - control1 * 2.0 + control2)
+                        .length()
+                        .max((control1 - control2 * 2.0 + to).length());
+                    let segments = segment_count(deviation * 3.0 / (4.0 * tolerance));
+                    for i in 1..=segments {
+                        let t = i as f32 / segments as f32;
+                        let u = 1.0 - t;
+                        current.push(
+                            last * (u * u * u)
+                                + control1 * (3.0 * u * u * t)
+                                + control2 * (3.0 * u * t * t)
+                                + to * (t * t * t),
+                        );
+                    }
+                    last = to;
+                }
+                PathCommand::Close => {
+                    let start = current.first().copied().unwrap_or(last);
+                    finish(&mut current, true, &mut result);
+                    // Drawing can continue from the start of the closed sub-path
+                    current.push(start);
+                    last = start;
+                }
+            }
+        }
+
+        finish(&mut current, false, &mut result);
+        result
+    }
+
+    fn ensure_started(&mut self) {
+        if self.commands.is_empty() {
+            self.move_to(Vec2::ZERO);
+        }
+    }
+}
+
+fn segment_count(squared_segments: f32) -> usize {
+    (squared_segments.sqrt().ceil() as usize).clamp(1, 256)
+}
+
+fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y) && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+impl ShapeBatch {
+    /// Fill a path using the even-odd rule (nested sub-paths become holes)
+    pub fn fill_path(&mut self, path: &Path, color: Color) {
+        let rings: Vec<Vec<Vec2>> = path
+            .flatten(DEFAULT_TOLERANCE)
+            .into_iter()
+            .map(|polyline| polyline.points)
+            .filter(|points| points.len() >= 3)
+            .collect();
+
+        // Nesting depth of each ring: even = filled outline, odd = hole
+        let depths: Vec<usize> = rings
+            .iter()
+            .enumerate()
+            .map(|(i, ring)| {
+                rings
+                    .iter()
+                    .enumerate()
+                    .filter(|&(j, other)| j != i && point_in_polygon(ring[0], other))
+                    .count()
+            })
+            .collect();
+
+        for (i, outline) in rings.iter().enumerate() {
+            if depths[i] % 2 == 1 {
+                continue;
+            }
+
+            let holes: Vec<Vec<Vec2>> = rings
+                .iter()
+                .enumerate()
+                .filter(|&(j, hole)| depths[j] == depths[i] + 1 && point_in_polygon(hole[0], outline))
+                .map(|(_, hole)| hole.clone())
+                .collect();
+
+            self.fill_polygon_with_holes(outline, &holes, color);
+        }
+    }
+
+    /// Stroke every sub-path of a path
+    pub fn stroke_path(&mut self, path: &Path, style: StrokeStyle) {
+        for polyline in path.flatten(DEFAULT_TOLERANCE) {
+            self.polyline(&polyline.points, polyline.closed, style);
+        }
+    }
+}
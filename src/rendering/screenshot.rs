@@ -0,0 +1,139 @@
+use macroquad::prelude::*;
+use std::io;
+
+/// Pixels grabbed from the screen with `capture_screen`
+#[derive(Debug, Clone)]
+pub struct Screenshot {
+    pub width: u32,
+    pub height: u32,
+    /// RGBA rows, top row first
+    pub pixels: Vec<u8>,
+}
+
+/// Grab what has been drawn to the screen so far this frame. `Game::take_screenshot`
+/// calls this once the frame is drawn; call it yourself only after drawing.
+pub fn capture_screen() -> Screenshot {
+    let image = get_screen_data();
+    let (width, height) = (image.width as u32, image.height as u32);
+    // The framebuffer is read bottom row first
+    let row = width as usize * 4;
+    let pixels = image.bytes.chunks_exact(row).rev().flatten().copied().collect();
+    Screenshot { width, height, pixels }
+}
+
+impl Screenshot {
+    /// The screenshot as a PNG file
+    pub fn to_png(&self) -> Vec<u8> {
+        encode_png(self.width, self.height, &self.pixels)
+    }
+
+    /// Write a PNG to `path`, creating its folder. On WASM the browser downloads it
+    /// instead, named after the path's file name (see `js/lastor.js`).
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(dir) = std::path::Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, self.to_png())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+            let png = self.to_png();
+            unsafe { lastor_download(name.as_ptr(), name.len(), png.as_ptr(), png.len()) };
+            Ok(())
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+unsafe extern "C" {
+    /// Offer bytes as a file download; provided by the `js/lastor.js` plugin
+    fn lastor_download(name: *const u8, name_len: usize, data: *const u8, data_len: usize);
+}
+
+// Largest stored (uncompressed) deflate block
+const STORED_BLOCK: usize = 65_535;
+
+/// Encode RGBA pixels as a PNG with uncompressed image data. Files are larger than an
+/// image editor's, but encoding is quick and needs no extra dependency.
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    // Each row starts with its filter type (0 = none)
+    let row = width as usize * 4;
+    let mut raw = Vec::with_capacity((row + 1) * height as usize);
+    for line in pixels.chunks_exact(row.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(line);
+    }
+
+    // zlib stream of stored deflate blocks
+    let mut zlib = Vec::with_capacity(raw.len() + raw.len() / STORED_BLOCK * 5 + 16);
+    zlib.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = raw.chunks(STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGBA, default compression, filtering and no interlacing
+    header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &zlib);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut n = 0;
+    while n < 256 {
+        let mut crc = n as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[n] = crc;
+        n += 1;
+    }
+    table
+};
+
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, byte| CRC_TABLE[((crc ^ *byte as u32) & 0xff) as usize] ^ (crc >> 8))
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65_521;
+        b %= 65_521;
+    }
+    (b << 16) | a
+}
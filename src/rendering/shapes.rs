@@ -0,0 +1,786 @@
+use macroquad::prelude::*;
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+// Keep every submitted mesh comfortably below macroquad's default draw call capacity
+// (10000 vertices / 5000 indices), otherwise the geometry gets clamped.
+const MAX_CHUNK_VERTICES: usize = 4000;
+const MAX_CHUNK_INDICES: usize = 4998;
+
+/// How two stroked segments are connected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+/// How the open ends of a stroke are finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+/// Stroke settings for lines, outlines and arcs
+#[derive(Debug, Clone, Copy)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub color: Color,
+    pub join: LineJoin,
+    pub cap: LineCap,
+    /// Miters longer than `miter_limit * width / 2` fall back to a bevel join
+    pub miter_limit: f32,
+}
+
+impl StrokeStyle {
+    pub fn new(width: f32, color: Color) -> Self {
+        Self {
+            width,
+            color,
+            join: LineJoin::Miter,
+            cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        self.miter_limit = limit.max(1.0);
+        self
+    }
+}
+
+/// Dash pattern for dashed lines (lengths are along the path)
+#[derive(Debug, Clone, Copy)]
+pub struct DashPattern {
+    pub dash: f32,
+    pub gap: f32,
+    /// Shifts the pattern along the path - animate it for "marching ants"
+    pub offset: f32,
+}
+
+impl DashPattern {
+    pub fn new(dash: f32, gap: f32) -> Self {
+        Self { dash, gap, offset: 0.0 }
+    }
+
+    pub fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+/// One cross-section of a stroke ribbon: the left/right edges are `center + left * half_width`
+/// and `center + right * half_width`
+#[derive(Clone, Copy)]
+struct Section {
+    center: Vec2,
+    left: Vec2,
+    right: Vec2,
+}
+
+/// Batched vector shape renderer with anti-aliased edges.
+///
+/// Queue shapes during draw, then call `draw()` once to submit them all as a handful of meshes.
+/// Works both in world space (inside `camera.apply()`) and in screen space.
+pub struct ShapeBatch {
+    chunks: Vec<Mesh>,
+    feather: f32,
+    scratch_vertices: Vec<Vertex>,
+    // Wider than a mesh's u16 indices, so shapes with more vertices than a mesh can hold
+    // don't wrap around before they're split into chunks
+    scratch_indices: Vec<u32>,
+}
+
+impl ShapeBatch {
+    pub fn new() -> Self {
+        Self {
+            chunks: vec![],
+            feather: 1.0,
+            scratch_vertices: vec![],
+            scratch_indices: vec![],
+        }
+    }
+
+    /// Set the width of the anti-aliasing fringe (0 = hard edges).
+    /// When drawing in world space, use `1.0 / camera.zoom` to keep edges one pixel wide.
+    pub fn set_feather(&mut self, feather: f32) {
+        self.feather = feather.max(0.0);
+    }
+
+    pub fn with_feather(mut self, feather: f32) -> Self {
+        self.set_feather(feather);
+        self
+    }
+
+    pub fn feather(&self) -> f32 {
+        self.feather
+    }
+
+    /// Check if nothing has been queued since the last draw
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Number of draw calls the queued shapes will take
+    pub fn draw_call_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Submit all queued shapes and clear the batch
+    pub fn draw(&mut self) {
+        for mesh in self.chunks.drain(..) {
+            draw_mesh(&mesh);
+        }
+    }
+
+    /// Drop all queued shapes without drawing them
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    // === Lines ===
+
+    /// Stroke a single line segment
+    pub fn line(&mut self, from: Vec2, to: Vec2, style: StrokeStyle) {
+        self.polyline(&[from, to], false, style);
+    }
+
+    /// Stroke a connected series of points with joins and caps
+    pub fn polyline(&mut self, points: &[Vec2], closed: bool, style: StrokeStyle) {
+        let points = dedup_points(points, closed);
+        if points.len() < 2 || style.width <= 0.0 {
+            return;
+        }
+
+        let closed = closed && points.len() > 2;
+        let sections = build_sections(&points, closed, &style);
+        self.emit_ribbon(&sections, closed, style.width * 0.5, style.color);
+    }
+
+    /// Stroke a dashed polyline
+    pub fn dashed_polyline(&mut self, points: &[Vec2], closed: bool, pattern: DashPattern, style: StrokeStyle) {
+        if pattern.dash <= 0.0 {
+            return;
+        }
+        if pattern.gap <= 0.0 {
+            self.polyline(points, closed, style);
+            return;
+        }
+
+        let mut path = dedup_points(points, closed);
+        if closed && path.len() > 2 {
+            path.push(path[0]);
+        }
+
+        for dash in split_dashes(&path, pattern) {
+            self.polyline(&dash, false, style);
+        }
+    }
+
+    /// Stroke a dashed line segment
+    pub fn dashed_line(&mut self, from: Vec2, to: Vec2, pattern: DashPattern, style: StrokeStyle) {
+        self.dashed_polyline(&[from, to], false, pattern, style);
+    }
+
+    // === Filled shapes ===
+
+    /// Fill an arbitrary simple polygon (convex or concave, either winding)
+    pub fn fill_polygon(&mut self, points: &[Vec2], color: Color) {
+        self.fill_polygon_with_holes(points, &[], color);
+    }
+
+    /// Fill a simple polygon with holes cut out of it (holes must lie inside the outline)
+    pub fn fill_polygon_with_holes(&mut self, outline: &[Vec2], holes: &[Vec<Vec2>], color: Color) {
+        let mut outer = dedup_points(outline, true);
+        if outer.len() < 3 {
+            return;
+        }
+        if signed_area(&outer) < 0.0 {
+            outer.reverse();
+        }
+
+        // Outline counter-clockwise, holes clockwise: the vertex normals then all point
+        // away from the filled area
+        let mut rings = vec![outer];
+        for hole in holes {
+            let mut hole = dedup_points(hole, true);
+            if hole.len() < 3 {
+                continue;
+            }
+            if signed_area(&hole) > 0.0 {
+                hole.reverse();
+            }
+            rings.push(hole);
+        }
+
+        let half_feather = self.feather * 0.5;
+        let normals: Vec<Vec<Vec2>> = rings.iter().map(|ring| ring_normals(ring)).collect();
+
+        self.scratch_vertices.clear();
+        self.scratch_indices.clear();
+
+        // Inset the solid body by half the fringe so the shape keeps its apparent size
+        let mut body: Vec<Vec2> = vec![];
+        let mut ring_starts = vec![];
+        for (ring, normals) in rings.iter().zip(&normals) {
+            ring_starts.push(body.len());
+            body.extend(ring.iter().zip(normals).map(|(&point, &normal)| point - normal * half_feather));
+        }
+        self.scratch_vertices.extend(body.iter().map(|&point| vertex(point, color)));
+
+        let merged = bridge_holes(&body, &ring_starts);
+        let merged_points: Vec<Vec2> = merged.iter().map(|&i| body[i]).collect();
+        self.scratch_indices.extend(triangulate(&merged_points).into_iter().map(|i| merged[i] as u32));
+
+        if self.feather > 0.0 {
+            let transparent = Color::new(color.r, color.g, color.b, 0.0);
+            for ((ring, normals), &start) in rings.iter().zip(&normals).zip(&ring_starts) {
+                let outer_start = self.scratch_vertices.len();
+                let n = ring.len();
+                for (&point, &normal) in ring.iter().zip(normals) {
+                    self.scratch_vertices.push(vertex(point + normal * half_feather, transparent));
+                }
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    push_quad(&mut self.scratch_indices, start + i, start + j, outer_start + j, outer_start + i);
+                }
+            }
+        }
+
+        self.flush_scratch();
+    }
+
+    /// Fill a rectangle
+    pub fn fill_rect(&mut self, position: Vec2, size: Vec2, color: Color) {
+        self.fill_rounded_rect(position, size, 0.0, color);
+    }
+
+    /// Fill a rectangle with rounded corners
+    pub fn fill_rounded_rect(&mut self, position: Vec2, size: Vec2, radius: f32, color: Color) {
+        let outline = rounded_rect_points(position, size, radius);
+        self.fill_polygon(&outline, color);
+    }
+
+    /// Outline a rectangle with rounded corners
+    pub fn stroke_rounded_rect(&mut self, position: Vec2, size: Vec2, radius: f32, style: StrokeStyle) {
+        let outline = rounded_rect_points(position, size, radius);
+        self.polyline(&outline, true, style);
+    }
+
+    /// Fill a circle
+    pub fn fill_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let mut outline = arc_points(center, radius, 0.0, TAU);
+        outline.pop(); // last point duplicates the first
+        self.fill_polygon(&outline, color);
+    }
+
+    /// Outline a circle
+    pub fn stroke_circle(&mut self, center: Vec2, radius: f32, style: StrokeStyle) {
+        let mut outline = arc_points(center, radius, 0.0, TAU);
+        outline.pop();
+        self.polyline(&outline, true, style);
+    }
+
+    // === Arcs ===
+
+    /// Stroke an arc from `start_angle` sweeping by `sweep` radians (positive = clockwise on screen)
+    pub fn stroke_arc(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep: f32, style: StrokeStyle) {
+        let points = arc_points(center, radius, start_angle, sweep);
+        self.polyline(&points, false, style);
+    }
+
+    /// Fill a pie slice from `start_angle` sweeping by `sweep` radians
+    pub fn fill_arc(&mut self, center: Vec2, radius: f32, start_angle: f32, sweep: f32, color: Color) {
+        if sweep.abs() >= TAU {
+            self.fill_circle(center, radius, color);
+            return;
+        }
+        let mut points = vec![center];
+        points.extend(arc_points(center, radius, start_angle, sweep));
+        self.fill_polygon(&points, color);
+    }
+
+    // === Internals ===
+
+    fn emit_ribbon(&mut self, sections: &[Section], closed: bool, half_width: f32, color: Color) {
+        self.scratch_vertices.clear();
+        self.scratch_indices.clear();
+
+        let feather = self.feather;
+        let (inner, outer, body_color) = if feather > 0.0 {
+            // Lines thinner than the fringe fade out instead of getting wider
+            let coverage = (half_width * 2.0 / feather).min(1.0);
+            let body = Color::new(color.r, color.g, color.b, color.a * coverage);
+            ((half_width - feather * 0.5).max(0.0), half_width.max(feather * 0.5) + feather * 0.5, body)
+        } else {
+            (half_width, half_width, color)
+        };
+        let transparent = Color::new(color.r, color.g, color.b, 0.0);
+
+        for section in sections {
+            let c = section.center;
+            self.scratch_vertices.push(vertex(c + section.left * outer, transparent));
+            self.scratch_vertices.push(vertex(c + section.left * inner, body_color));
+            self.scratch_vertices.push(vertex(c + section.right * inner, body_color));
+            self.scratch_vertices.push(vertex(c + section.right * outer, transparent));
+        }
+
+        let count = sections.len();
+        let pairs = if closed { count } else { count - 1 };
+        for s in 0..pairs {
+            let a = s * 4;
+            let b = ((s + 1) % count) * 4;
+            if feather > 0.0 {
+                push_quad(&mut self.scratch_indices, a, a + 1, b + 1, b);
+                push_quad(&mut self.scratch_indices, a + 2, a + 3, b + 3, b + 2);
+            }
+            push_quad(&mut self.scratch_indices, a + 1, a + 2, b + 2, b + 1);
+        }
+
+        self.flush_scratch();
+    }
+
+    /// Move the scratch geometry of one shape into the batch, splitting it if needed
+    fn flush_scratch(&mut self) {
+        let vertices = std::mem::take(&mut self.scratch_vertices);
+        let indices = std::mem::take(&mut self.scratch_indices);
+
+        if vertices.len() <= MAX_CHUNK_VERTICES && indices.len() <= MAX_CHUNK_INDICES {
+            let chunk = self.chunk_with_room(vertices.len(), indices.len());
+            // Chunks are far smaller than u16::MAX, so the indices fit
+            let base = chunk.vertices.len() as u16;
+            chunk.vertices.extend_from_slice(&vertices);
+            chunk.indices.extend(indices.iter().map(|&i| base + i as u16));
+        } else {
+            // Oversized shape: emit triangle by triangle so it can span several chunks
+            for tri in indices.chunks(3) {
+                let chunk = self.chunk_with_room(3, 3);
+                let base = chunk.vertices.len() as u16;
+                chunk.vertices.extend(tri.iter().map(|&i| vertices[i as usize]));
+                chunk.indices.extend_from_slice(&[base, base + 1, base + 2]);
+            }
+        }
+
+        self.scratch_vertices = vertices;
+        self.scratch_indices = indices;
+        self.scratch_vertices.clear();
+        self.scratch_indices.clear();
+    }
+
+    fn chunk_with_room(&mut self, vertex_count: usize, index_count: usize) -> &mut Mesh {
+        let needs_new = match self.chunks.last() {
+            Some(chunk) => {
+                chunk.vertices.len() + vertex_count > MAX_CHUNK_VERTICES
+                    || chunk.indices.len() + index_count > MAX_CHUNK_INDICES
+            }
+            None => true,
+        };
+
+        if needs_new {
+            self.chunks.push(Mesh {
+                vertices: Vec::with_capacity(vertex_count),
+                indices: Vec::with_capacity(index_count),
+                texture: None,
+            });
+        }
+
+        self.chunks.last_mut().unwrap()
+    }
+}
+
+impl Default for ShapeBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// === Geometry helpers ===
+
+fn vertex(position: Vec2, color: Color) -> Vertex {
+    Vertex::new(position.x, position.y, 0.0, 0.0, 0.0, color)
+}
+
+fn push_quad(indices: &mut Vec<u32>, a: usize, b: usize, c: usize, d: usize) {
+    indices.extend_from_slice(&[a as u32, b as u32, c as u32, a as u32, c as u32, d as u32]);
+}
+
+fn perp(direction: Vec2) -> Vec2 {
+    Vec2::new(-direction.y, direction.x)
+}
+
+/// Remove consecutive duplicate points (and the closing duplicate for closed shapes)
+fn dedup_points(points: &[Vec2], closed: bool) -> Vec<Vec2> {
+    let mut result: Vec<Vec2> = Vec::with_capacity(points.len());
+    for &point in points {
+        if result.last().is_none_or(|last| last.distance_squared(point) > 1e-8) {
+            result.push(point);
+        }
+    }
+    if closed && result.len() > 1 && result[0].distance_squared(result[result.len() - 1]) <= 1e-8 {
+        result.pop();
+    }
+    result
+}
+
+fn signed_area(points: &[Vec2]) -> f32 {
+    let n = points.len();
+    (0..n)
+        .map(|i| points[i].perp_dot(points[(i + 1) % n]))
+        .sum::<f32>()
+        * 0.5
+}
+
+fn ring_normals(ring: &[Vec2]) -> Vec<Vec2> {
+    let n = ring.len();
+    (0..n)
+        .map(|i| vertex_normal(ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]))
+        .collect()
+}
+
+/// Outward miter normal of a vertex on a polygon with positive signed area
+fn vertex_normal(prev: Vec2, current: Vec2, next: Vec2) -> Vec2 {
+    let n1 = -perp((current - prev).normalize_or_zero());
+    let n2 = -perp((next - current).normalize_or_zero());
+    let sum = n1 + n2;
+    if sum.length_squared() < 1e-6 {
+        return n1;
+    }
+    let miter = sum.normalize();
+    let scale = 1.0 / miter.dot(n1).max(0.25);
+    miter * scale
+}
+
+/// Number of segments needed to keep an arc within a quarter pixel of the true curve
+pub(crate) fn arc_segments(radius: f32, sweep: f32) -> usize {
+    let radius = radius.abs().max(0.5);
+    let step = 2.0 * (1.0 - 0.25 / radius).clamp(-1.0, 1.0).acos();
+    let step = if step > 0.0 { step } else { FRAC_PI_2 };
+    ((sweep.abs() / step).ceil() as usize).clamp(2, 256)
+}
+
+/// Points along an arc (inclusive of both ends)
+pub(crate) fn arc_points(center: Vec2, radius: f32, start_angle: f32, sweep: f32) -> Vec<Vec2> {
+    let segments = arc_segments(radius, sweep);
+    (0..=segments)
+        .map(|i| {
+            let angle = start_angle + sweep * (i as f32 / segments as f32);
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+fn rounded_rect_points(position: Vec2, size: Vec2, radius: f32) -> Vec<Vec2> {
+    let radius = radius.min(size.x * 0.5).min(size.y * 0.5).max(0.0);
+    let (x, y, w, h) = (position.x, position.y, size.x, size.y);
+
+    if radius <= 0.0 {
+        return vec![
+            Vec2::new(x, y),
+            Vec2::new(x + w, y),
+            Vec2::new(x + w, y + h),
+            Vec2::new(x, y + h),
+        ];
+    }
+
+    let corners = [
+        (Vec2::new(x + radius, y + radius), PI),
+        (Vec2::new(x + w - radius, y + radius), PI + FRAC_PI_2),
+        (Vec2::new(x + w - radius, y + h - radius), 0.0),
+        (Vec2::new(x + radius, y + h - radius), FRAC_PI_2),
+    ];
+
+    let mut points = vec![];
+    for (center, start) in corners {
+        points.extend(arc_points(center, radius, start, FRAC_PI_2));
+    }
+    points
+}
+
+/// Build the stroke cross-sections for a polyline, including joins and caps
+fn build_sections(points: &[Vec2], closed: bool, style: &StrokeStyle) -> Vec<Section> {
+    let n = points.len();
+    let half_width = style.width * 0.5;
+    let mut sections = Vec::with_capacity(n + 8);
+
+    let first_dir = (points[1] - points[0]).normalize();
+    if !closed {
+        push_cap(&mut sections, points[0], -first_dir, half_width, style.cap, true);
+    }
+
+    let (start, end) = if closed { (0, n) } else { (1, n - 1) };
+    for i in start..end {
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        push_join(&mut sections, prev, points[i], next, half_width, style);
+    }
+
+    if !closed {
+        let last_dir = (points[n - 1] - points[n - 2]).normalize();
+        push_cap(&mut sections, points[n - 1], last_dir, half_width, style.cap, false);
+    }
+
+    sections
+}
+
+/// `outward` points away from the line; `leading` caps come before the line body
+fn push_cap(sections: &mut Vec<Section>, point: Vec2, outward: Vec2, half_width: f32, cap: LineCap, leading: bool) {
+    // The ribbon's left side is always the left of the direction of travel
+    let travel = if leading { -outward } else { outward };
+    let normal = perp(travel);
+
+    match cap {
+        LineCap::Butt => sections.push(Section { center: point, left: normal, right: -normal }),
+        LineCap::Square => sections.push(Section {
+            center: point + outward * half_width,
+            left: normal,
+            right: -normal,
+        }),
+        LineCap::Round => {
+            let steps = (arc_segments(half_width, FRAC_PI_2)).max(2);
+            let mut cap_sections: Vec<Section> = (0..=steps)
+                .map(|k| {
+                    let angle = FRAC_PI_2 * k as f32 / steps as f32;
+                    Section {
+                        center: point + outward * half_width * angle.cos(),
+                        left: normal * angle.sin(),
+                        right: -normal * angle.sin(),
+                    }
+                })
+                .collect();
+            if !leading {
+                cap_sections.reverse();
+            }
+            sections.extend(cap_sections);
+        }
+    }
+}
+
+fn push_join(sections: &mut Vec<Section>, prev: Vec2, point: Vec2, next: Vec2, half_width: f32, style: &StrokeStyle) {
+    let d1 = (point - prev).normalize();
+    let d2 = (next - point).normalize();
+    let n1 = perp(d1);
+    let n2 = perp(d2);
+    let turn = d1.perp_dot(d2);
+
+    let sum = n1 + n2;
+    let (miter, scale) = if sum.length_squared() < 1e-6 {
+        (n1, f32::INFINITY)
+    } else {
+        let miter = sum.normalize();
+        (miter, 1.0 / miter.dot(n1).max(1e-4))
+    };
+
+    // Nearly straight: a single section is exact
+    if turn.abs() < 1e-4 && d1.dot(d2) > 0.0 {
+        sections.push(Section { center: point, left: n1, right: -n1 });
+        return;
+    }
+
+    if style.join == LineJoin::Miter && scale <= style.miter_limit {
+        sections.push(Section { center: point, left: miter * scale, right: -miter * scale });
+        return;
+    }
+
+    // Bevel and round joins: the inner side meets at the (clamped) miter point,
+    // the outer side sweeps from the incoming normal to the outgoing one
+    let inner = if scale.is_finite() { miter * scale.min(4.0) } else { Vec2::ZERO };
+    let outer_is_left = turn < 0.0;
+    let (outer_start, outer_end) = if outer_is_left { (n1, n2) } else { (-n1, -n2) };
+
+    let mut outer_offsets = vec![outer_start];
+    if style.join == LineJoin::Round {
+        let angle = outer_start.angle_between(outer_end);
+        let steps = arc_segments(half_width, angle);
+        for k in 1..steps {
+            let t = angle * k as f32 / steps as f32;
+            outer_offsets.push(Vec2::from_angle(t).rotate(outer_start));
+        }
+    }
+    outer_offsets.push(outer_end);
+
+    for outer in outer_offsets {
+        let section = if outer_is_left {
+            Section { center: point, left: outer, right: -inner }
+        } else {
+            Section { center: point, left: inner, right: outer }
+        };
+        sections.push(section);
+    }
+}
+
+/// Split a path into dash sub-paths following a dash pattern
+fn split_dashes(path: &[Vec2], pattern: DashPattern) -> Vec<Vec<Vec2>> {
+    let period = pattern.dash + pattern.gap;
+    let mut dashes = vec![];
+    let mut current: Vec<Vec2> = vec![];
+
+    // Position inside the pattern period at the start of the path
+    let mut phase = pattern.offset.rem_euclid(period);
+    let mut drawing = phase < pattern.dash;
+    if drawing && !path.is_empty() {
+        current.push(path[0]);
+    }
+
+    for window in path.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        let length = a.distance(b);
+        let mut travelled = 0.0;
+
+        while travelled < length {
+            let boundary = if drawing { pattern.dash } else { period };
+            let step = (boundary - phase).min(length - travelled);
+            travelled += step;
+            phase += step;
+
+            let point = a.lerp(b, travelled / length);
+            if phase >= boundary - 1e-5 {
+                if drawing {
+                    current.push(point);
+                    dashes.push(std::mem::take(&mut current));
+                } else {
+                    current.push(point);
+                    phase = 0.0;
+                }
+                drawing = !drawing;
+            }
+        }
+
+        if drawing {
+            current.push(b);
+        }
+    }
+
+    if current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+/// Ear-clipping triangulation of a simple polygon with positive signed area.
+/// Returns indices into `points`, three per triangle.
+pub(crate) fn triangulate(points: &[Vec2]) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (0..points.len()).collect();
+    let mut triangles = Vec::with_capacity((points.len().saturating_sub(2)) * 3);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m).find(|&i| {
+            let prev = remaining[(i + m - 1) % m];
+            let cur = remaining[i];
+            let next = remaining[(i + 1) % m];
+            let (a, b, c) = (points[prev], points[cur], points[next]);
+
+            if (b - a).perp_dot(c - b) <= 1e-6 {
+                return false; // reflex or degenerate corner
+            }
+
+            // Bridged holes repeat vertex positions, so compare positions rather than indices
+            !remaining
+                .iter()
+                .map(|&j| points[j])
+                .filter(|&p| p != a && p != b && p != c)
+                .any(|p| point_in_triangle(p, a, b, c))
+        });
+
+        match ear {
+            Some(i) => {
+                triangles.extend_from_slice(&[remaining[(i + m - 1) % m], remaining[i], remaining[(i + 1) % m]]);
+                remaining.remove(i);
+            }
+            None => {
+                // Self-intersecting or degenerate input: fan out the rest rather than loop forever
+                for i in 1..m - 1 {
+                    triangles.extend_from_slice(&[remaining[0], remaining[i], remaining[i + 1]]);
+                }
+                return triangles;
+            }
+        }
+    }
+
+    if remaining.len() == 3 {
+        triangles.extend_from_slice(&remaining);
+    }
+    triangles
+}
+
+/// Merge holes into the outline by cutting a zero-width bridge to each of them.
+/// `points` holds all rings back to back (outline first); returns one polygon as indices into it.
+fn bridge_holes(points: &[Vec2], ring_starts: &[usize]) -> Vec<usize> {
+    let ring_range = |r: usize| {
+        let end = ring_starts.get(r + 1).copied().unwrap_or(points.len());
+        ring_starts[r]..end
+    };
+
+    let mut polygon: Vec<usize> = ring_range(0).collect();
+    let mut holes: Vec<Vec<usize>> = (1..ring_starts.len()).map(|r| ring_range(r).collect()).collect();
+
+    // Rightmost holes first, so earlier bridges don't block later ones
+    let rightmost = |hole: &Vec<usize>| hole.iter().map(|&i| points[i].x).fold(f32::MIN, f32::max);
+    holes.sort_by(|a, b| rightmost(b).total_cmp(&rightmost(a)));
+
+    for h in 0..holes.len() {
+        let hole = &holes[h];
+        let (m_pos, &m) = hole
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| points[**a].x.total_cmp(&points[**b].x))
+            .unwrap();
+        let m_point = points[m];
+
+        let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+        candidates.sort_by(|&a, &b| {
+            points[polygon[a]].distance_squared(m_point).total_cmp(&points[polygon[b]].distance_squared(m_point))
+        });
+
+        let rings_to_avoid = std::iter::once(&polygon).chain(holes[h..].iter());
+        let edges: Vec<(Vec2, Vec2)> = rings_to_avoid
+            .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+            .map(|(a, b)| (points[a], points[b]))
+            .collect();
+
+        let bridge = candidates.into_iter().find(|&pos| {
+            let v_point = points[polygon[pos]];
+            !edges.iter().any(|&(a, b)| {
+                let shares_end = a == m_point || b == m_point || a == v_point || b == v_point;
+                !shares_end && segments_cross(m_point, v_point, a, b)
+            })
+        });
+
+        let Some(pos) = bridge else {
+            continue; // hole outside the outline - leave it out
+        };
+
+        let mut merged = Vec::with_capacity(polygon.len() + hole.len() + 2);
+        merged.extend_from_slice(&polygon[..=pos]);
+        merged.extend_from_slice(&hole[m_pos..]);
+        merged.extend_from_slice(&hole[..=m_pos]);
+        merged.extend_from_slice(&polygon[pos..]);
+        polygon = merged;
+    }
+
+    polygon
+}
+
+/// Proper intersection test (touching endpoints don't count)
+fn segments_cross(p1: Vec2, p2: Vec2, q1: Vec2, q2: Vec2) -> bool {
+    let d1 = (p2 - p1).perp_dot(q1 - p1);
+    let d2 = (p2 - p1).perp_dot(q2 - p1);
+    let d3 = (q2 - q1).perp_dot(p1 - q1);
+    let d4 = (q2 - q1).perp_dot(p2 - q1);
+    d1 * d2 < 0.0 && d3 * d4 < 0.0
+}
+
+fn point_in_triangle(p: Vec2, a: Vec2, b: Vec2, c: Vec2) -> bool {
+    (b - a).perp_dot(p - a) >= 0.0 && (c - b).perp_dot(p - b) >= 0.0 && (a - c).perp_dot(p - c) >= 0.0
+}
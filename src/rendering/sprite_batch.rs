@@ -0,0 +1,231 @@
+use macroquad::prelude::*;
+use std::fmt;
+
+// Same limits as `ShapeBatch`, under macroquad's per-draw buffer sizes
+const MAX_CHUNK_VERTICES: usize = 4000;
+const MAX_CHUNK_INDICES: usize = 4998;
+
+/// One textured quad, placed like `draw_texture_ex`: `position` is the top-left corner
+/// and rotation turns it around `pivot` (its center if None)
+#[derive(Debug, Clone)]
+pub struct Sprite {
+    pub texture: Texture2D,
+    pub position: Vec2,
+    /// Size drawn in world units (the texture's or source rect's size if None)
+    pub size: Option<Vec2>,
+    /// Part of the texture to draw, e.g. a frame of an atlas page (all of it if None)
+    pub source: Option<Rect>,
+    pub rotation: f32,
+    pub pivot: Option<Vec2>,
+    pub flip_x: bool,
+    pub flip_y: bool,
+    pub color: Color,
+}
+
+impl Sprite {
+    pub fn new(texture: &Texture2D, position: Vec2) -> Self {
+        Self {
+            texture: texture.clone(),
+            position,
+            size: None,
+            source: None,
+            rotation: 0.0,
+            pivot: None,
+            flip_x: false,
+            flip_y: false,
+            color: WHITE,
+        }
+    }
+
+    /// A sprite of `size` centered on `center`
+    pub fn centered(texture: &Texture2D, center: Vec2, size: Vec2) -> Self {
+        Self::new(texture, center - size * 0.5).with_size(size)
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_source(mut self, source: Rect) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_pivot(mut self, pivot: Vec2) -> Self {
+        self.pivot = Some(pivot);
+        self
+    }
+
+    pub fn with_flip(mut self, flip_x: bool, flip_y: bool) -> Self {
+        self.flip_x = flip_x;
+        self.flip_y = flip_y;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Corners (clockwise from top-left) and their texture coordinates
+    fn quad(&self) -> [(Vec2, Vec2); 4] {
+        let texture_size = self.texture.size().max(Vec2::ONE);
+        let source = self.source.unwrap_or(Rect::new(0.0, 0.0, texture_size.x, texture_size.y));
+        let size = self.size.unwrap_or(vec2(source.w, source.h));
+        let pivot = self.pivot.unwrap_or(self.position + size * 0.5);
+        let rotation = Vec2::from_angle(self.rotation);
+        let corner = |offset: Vec2| pivot + rotation.rotate(self.position + offset - pivot);
+
+        let (mut u0, mut u1) = (source.x / texture_size.x, (source.x + source.w) / texture_size.x);
+        let (mut v0, mut v1) = (source.y / texture_size.y, (source.y + source.h) / texture_size.y);
+        if self.flip_x {
+            std::mem::swap(&mut u0, &mut u1);
+        }
+        if self.flip_y {
+            std::mem::swap(&mut v0, &mut v1);
+        }
+        [
+            (corner(Vec2::ZERO), vec2(u0, v0)),
+            (corner(vec2(size.x, 0.0)), vec2(u1, v0)),
+            (corner(size), vec2(u1, v1)),
+            (corner(vec2(0.0, size.y)), vec2(u0, v1)),
+        ]
+    }
+}
+
+/// Sprites and draw calls of the batches submitted since the counters were last reset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SpriteBatchStats {
+    pub sprites: usize,
+    pub draw_calls: usize,
+}
+
+impl fmt::Display for SpriteBatchStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} sprites in {} draw calls", self.sprites, self.draw_calls)
+    }
+}
+
+/// Batched sprite renderer: queue sprites during draw, then `draw()` submits each layer's
+/// sprites grouped by texture, one mesh per texture (or atlas page) instead of one draw per
+/// sprite. Tile maps and particle swarms sharing a few textures come down to a few calls.
+///
+/// ```ignore
+/// let mut batch = SpriteBatch::new();
+/// for (x, y, frame) in visible_tiles {
+///     batch.push(Sprite::new(&atlas, vec2(x, y)).with_source(frame));
+/// }
+/// batch.draw();
+/// ```
+///
+/// Within a layer, sprites draw grouped by texture in the order each texture first came up,
+/// so overlapping sprites that must stack a certain way need different layers.
+pub struct SpriteBatch {
+    // Queued sprites with their layer
+    sprites: Vec<(i32, Sprite)>,
+    layer: i32,
+    stats: SpriteBatchStats,
+}
+
+impl SpriteBatch {
+    pub fn new() -> Self {
+        Self { sprites: vec![], layer: 0, stats: SpriteBatchStats::default() }
+    }
+
+    /// Layer of the sprites pushed from now on; lower layers draw first
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    pub fn layer(&self) -> i32 {
+        self.layer
+    }
+
+    pub fn push(&mut self, sprite: Sprite) {
+        self.sprites.push((self.layer, sprite));
+    }
+
+    pub fn len(&self) -> usize {
+        self.sprites.len()
+    }
+
+    /// Check if nothing has been queued since the last draw
+    pub fn is_empty(&self) -> bool {
+        self.sprites.is_empty()
+    }
+
+    /// Number of draw calls the queued sprites will take
+    pub fn draw_call_count(&self) -> usize {
+        self.groups().iter().map(|(_, sprites)| sprites.len().div_ceil(MAX_CHUNK_VERTICES / 4)).sum()
+    }
+
+    /// Submit every queued sprite and empty the queue
+    pub fn draw(&mut self) {
+        let groups = self.groups();
+        for (texture, sprites) in groups {
+            for chunk in sprites.chunks(MAX_CHUNK_VERTICES / 4) {
+                let mut mesh = Mesh {
+                    vertices: Vec::with_capacity(chunk.len() * 4),
+                    indices: Vec::with_capacity(chunk.len() * 6),
+                    texture: Some(texture.clone()),
+                };
+                for &index in chunk {
+                    let sprite = &self.sprites[index].1;
+                    let first = mesh.vertices.len() as u16;
+                    for (position, uv) in sprite.quad() {
+                        mesh.vertices.push(Vertex::new(position.x, position.y, 0.0, uv.x, uv.y, sprite.color));
+                    }
+                    mesh.indices.extend([0, 1, 2, 0, 2, 3].map(|corner| first + corner));
+                }
+                debug_assert!(mesh.indices.len() <= MAX_CHUNK_INDICES);
+                draw_mesh(&mesh);
+                self.stats.draw_calls += 1;
+            }
+        }
+        self.stats.sprites += self.sprites.len();
+        self.sprites.clear();
+    }
+
+    /// Drop all queued sprites without drawing them
+    pub fn clear(&mut self) {
+        self.sprites.clear();
+    }
+
+    /// Counters since the batch was made or `reset_stats` was called
+    pub fn stats(&self) -> SpriteBatchStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = SpriteBatchStats::default();
+    }
+
+    /// Indices of queued sprites per texture, by layer, then by first use within the layer
+    fn groups(&self) -> Vec<(Texture2D, Vec<usize>)> {
+        let mut order: Vec<usize> = (0..self.sprites.len()).collect();
+        order.sort_by_key(|index| self.sprites[*index].0);
+        let mut groups: Vec<(i32, Texture2D, Vec<usize>)> = vec![];
+        for index in order {
+            let (layer, sprite) = &self.sprites[index];
+            // Only this layer's groups; earlier layers are done
+            let layer_start = groups.iter().rposition(|(group_layer, _, _)| group_layer != layer).map_or(0, |last| last + 1);
+            match groups[layer_start..].iter_mut().find(|(_, texture, _)| *texture == sprite.texture) {
+                Some((_, _, sprites)) => sprites.push(index),
+                None => groups.push((*layer, sprite.texture.clone(), vec![index])),
+            }
+        }
+        groups.into_iter().map(|(_, texture, sprites)| (texture, sprites)).collect()
+    }
+}
+
+impl Default for SpriteBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
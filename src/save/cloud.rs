@@ -0,0 +1,392 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use super::slots::{is_valid_slot, write_atomic, SAVE_EXTENSION};
+
+/// Error returned by a save storage backend
+#[derive(Debug)]
+pub enum CloudError {
+    Io(std::io::Error),
+    /// Reported by the backend (network down, quota exceeded, not signed in, ...)
+    Backend(String),
+    /// The slot name would leave the save folder (see `is_valid_slot`)
+    InvalidSlot(String),
+}
+
+impl fmt::Display for CloudError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloudError::Io(err) => write!(f, "failed to access save storage: {}", err),
+            CloudError::Backend(msg) => write!(f, "save storage error: {}", msg),
+            CloudError::InvalidSlot(slot) => write!(f, "invalid save slot name '{}'", slot),
+        }
+    }
+}
+
+impl std::error::Error for CloudError {}
+
+impl From<std::io::Error> for CloudError {
+    fn from(err: std::io::Error) -> Self {
+        CloudError::Io(err)
+    }
+}
+
+/// A save file in a storage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveInfo {
+    pub slot: String,
+    /// Seconds since the Unix epoch when the save was last written
+    pub modified: u64,
+    pub size: u64,
+}
+
+/// Somewhere save files live: the local save folder, Steam Cloud, a custom server.
+///
+/// Saves are opaque bytes by slot name, so a backend never needs to know the game's
+/// save format. Implement this to plug a new backend into `CloudSync`.
+pub trait SaveStorage {
+    fn list(&mut self) -> Result<Vec<SaveInfo>, CloudError>;
+
+    /// None if the slot doesn't exist
+    fn read(&mut self, slot: &str) -> Result<Option<Vec<u8>>, CloudError>;
+
+    /// Write a slot, keeping `modified` as its timestamp so both sides of a sync agree
+    fn write(&mut self, slot: &str, data: &[u8], modified: u64) -> Result<(), CloudError>;
+
+    fn delete(&mut self, slot: &str) -> Result<(), CloudError>;
+}
+
+/// Saves as files in a folder, named `<slot>.sav` like `SaveManager` writes them.
+/// Reference backend, and the local side of most syncs.
+#[derive(Debug, Clone)]
+pub struct LocalFolderStorage {
+    root: PathBuf,
+}
+
+impl LocalFolderStorage {
+    /// The folder is created on the first write
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, slot: &str) -> Result<PathBuf, CloudError> {
+        if !is_valid_slot(slot) {
+            return Err(CloudError::InvalidSlot(slot.to_string()));
+        }
+        Ok(self.root.join(format!("{}.{}", slot, SAVE_EXTENSION)))
+    }
+}
+
+impl SaveStorage for LocalFolderStorage {
+    fn list(&mut self) -> Result<Vec<SaveInfo>, CloudError> {
+        let entries = match std::fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(err) => return Err(err.into()),
+        };
+        let mut saves = vec![];
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(SAVE_EXTENSION) {
+                continue;
+            }
+            let Some(slot) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let metadata = std::fs::metadata(&path)?;
+            saves.push(SaveInfo {
+                slot: slot.to_string(),
+                modified: metadata.modified().map(unix_seconds).unwrap_or(0),
+                size: metadata.len(),
+            });
+        }
+        saves.sort_by(|a, b| a.slot.cmp(&b.slot));
+        Ok(saves)
+    }
+
+    fn read(&mut self, slot: &str) -> Result<Option<Vec<u8>>, CloudError> {
+        match std::fs::read(self.path(slot)?) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn write(&mut self, slot: &str, data: &[u8], modified: u64) -> Result<(), CloudError> {
+        std::fs::create_dir_all(&self.root)?;
+        let path = self.path(slot)?;
+        write_atomic(&path, data)?;
+        File::options().write(true).open(&path)?.set_modified(UNIX_EPOCH + Duration::from_secs(modified))?;
+        Ok(())
+    }
+
+    fn delete(&mut self, slot: &str) -> Result<(), CloudError> {
+        match std::fs::remove_file(self.path(slot)?) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Both sides of a slot changed since they were last in sync
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveConflict<'a> {
+    pub slot: &'a str,
+    pub local: &'a [u8],
+    pub local_modified: u64,
+    pub cloud: &'a [u8],
+    pub cloud_modified: u64,
+}
+
+/// How a conflict is settled
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    KeepLocal,
+    KeepCloud,
+    /// Write these bytes to both sides (e.g. the union of unlocked items)
+    Merged(Vec<u8>),
+    /// Leave both sides alone for now (e.g. to ask the player later)
+    Skip,
+}
+
+/// What syncing a slot did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncOutcome {
+    UpToDate,
+    Uploaded,
+    Downloaded,
+    /// Deleted from the cloud since the last sync, so the local copy was deleted too
+    DeletedLocal,
+    /// Deleted locally since the last sync, so the cloud copy was deleted too
+    DeletedCloud,
+    /// Settled by the conflict resolver
+    Resolved(Resolution),
+}
+
+/// A slot's name and how syncing it went
+pub type SlotSync = (String, Result<SyncOutcome, CloudError>);
+
+/// Callback that settles conflicting saves
+type ConflictResolver = Box<dyn FnMut(&SaveConflict) -> Resolution>;
+
+/// Keeps a local save storage and a cloud one in step, slot by slot.
+///
+/// The newer side wins when only one side changed since the last sync. When both did, or
+/// when there's no record of a last sync and the contents differ, the conflict resolver
+/// decides (by default the newer save wins). A slot that was synced before and is now
+/// missing on one side was deleted there, so it's deleted on the other side too, unless
+/// that side changed since (then the change is kept). Sync timestamps are kept in a state
+/// file so conflicts and deletions are still noticed across sessions.
+///
+/// ```ignore
+/// let mut sync = CloudSync::new(LocalFolderStorage::new(save_dir), SteamCloud::new())
+///     .with_state_file(save_dir.join("sync.ron"))
+///     .on_conflict(|conflict| if conflict.local.len() >= conflict.cloud.len() { Resolution::KeepLocal } else { Resolution::KeepCloud });
+/// for (slot, result) in sync.sync_all()? { ... }
+/// ```
+pub struct CloudSync<L: SaveStorage, C: SaveStorage> {
+    local: L,
+    cloud: C,
+    resolver: ConflictResolver,
+    // Timestamp each slot had on both sides after its last sync
+    synced: BTreeMap<String, u64>,
+    state_path: Option<PathBuf>,
+}
+
+impl<L: SaveStorage, C: SaveStorage> CloudSync<L, C> {
+    pub fn new(local: L, cloud: C) -> Self {
+        Self {
+            local,
+            cloud,
+            resolver: Box::new(|conflict| {
+                if conflict.local_modified >= conflict.cloud_modified { Resolution::KeepLocal } else { Resolution::KeepCloud }
+            }),
+            synced: BTreeMap::new(),
+            state_path: None,
+        }
+    }
+
+    /// Remember sync timestamps in `path` (a missing or unreadable file starts fresh)
+    pub fn with_state_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        if let Some(synced) = std::fs::read_to_string(&path).ok().and_then(|data| ron::from_str(&data).ok()) {
+            self.synced = synced;
+        }
+        self.state_path = Some(path);
+        self
+    }
+
+    pub fn on_conflict<F: FnMut(&SaveConflict) -> Resolution + 'static>(mut self, resolver: F) -> Self {
+        self.resolver = Box::new(resolver);
+        self
+    }
+
+    pub fn local(&mut self) -> &mut L {
+        &mut self.local
+    }
+
+    pub fn cloud(&mut self) -> &mut C {
+        &mut self.cloud
+    }
+
+    /// Sync every slot on either side. A slot that fails doesn't stop the others.
+    pub fn sync_all(&mut self) -> Result<Vec<SlotSync>, CloudError> {
+        // Each side is listed once, however many slots there are
+        let mut slots: BTreeMap<String, (Option<SaveInfo>, Option<SaveInfo>)> = BTreeMap::new();
+        for info in self.local.list()? {
+            let slot = info.slot.clone();
+            slots.entry(slot).or_default().0 = Some(info);
+        }
+        for info in self.cloud.list()? {
+            let slot = info.slot.clone();
+            slots.entry(slot).or_default().1 = Some(info);
+        }
+        // Slots deleted on both sides only need their sync record dropped
+        let gone: BTreeSet<String> = self.synced.keys().filter(|slot| !slots.contains_key(*slot)).cloned().collect();
+        for slot in gone {
+            self.synced.remove(&slot);
+        }
+        let results = slots.into_iter()
+            .map(|(slot, (local, cloud))| {
+                let result = self.sync_listed(&slot, local, cloud);
+                (slot, result)
+            })
+            .collect();
+        self.save_state()?;
+        Ok(results)
+    }
+
+    /// Sync one slot
+    pub fn sync_slot(&mut self, slot: &str) -> Result<SyncOutcome, CloudError> {
+        let local_info = self.local.list()?.into_iter().find(|info| info.slot == slot);
+        let cloud_info = self.cloud.list()?.into_iter().find(|info| info.slot == slot);
+        self.sync_listed(slot, local_info, cloud_info)
+    }
+
+    /// Sync one slot, given what each side's listing says about it
+    fn sync_listed(&mut self, slot: &str, local_info: Option<SaveInfo>, cloud_info: Option<SaveInfo>) -> Result<SyncOutcome, CloudError> {
+        let synced = self.synced.get(slot).copied();
+
+        let outcome = match (local_info, cloud_info) {
+            (None, None) => {
+                self.synced.remove(slot);
+                SyncOutcome::UpToDate
+            }
+            // Synced before and unchanged here, so the other side deleted it
+            (Some(local), None) if synced == Some(local.modified) => {
+                self.local.delete(slot)?;
+                self.synced.remove(slot);
+                SyncOutcome::DeletedLocal
+            }
+            (None, Some(cloud)) if synced == Some(cloud.modified) => {
+                self.cloud.delete(slot)?;
+                self.synced.remove(slot);
+                SyncOutcome::DeletedCloud
+            }
+            (Some(local), None) => {
+                self.copy_to_cloud(slot, local.modified)?;
+                SyncOutcome::Uploaded
+            }
+            (None, Some(cloud)) => {
+                self.copy_to_local(slot, cloud.modified)?;
+                SyncOutcome::Downloaded
+            }
+            (Some(local), Some(cloud)) if local.modified == cloud.modified => {
+                self.synced.insert(slot.to_string(), local.modified);
+                SyncOutcome::UpToDate
+            }
+            (Some(local), Some(cloud)) => {
+                let local_changed = synced.is_none_or(|time| local.modified != time);
+                let cloud_changed = synced.is_none_or(|time| cloud.modified != time);
+                match (local_changed, cloud_changed) {
+                    (true, false) => {
+                        self.copy_to_cloud(slot, local.modified)?;
+                        SyncOutcome::Uploaded
+                    }
+                    (false, true) => {
+                        self.copy_to_local(slot, cloud.modified)?;
+                        SyncOutcome::Downloaded
+                    }
+                    _ => self.resolve(slot, local.modified, cloud.modified)?,
+                }
+            }
+        };
+        Ok(outcome)
+    }
+
+    /// Delete a slot on both sides
+    pub fn delete_slot(&mut self, slot: &str) -> Result<(), CloudError> {
+        self.local.delete(slot)?;
+        self.cloud.delete(slot)?;
+        self.synced.remove(slot);
+        self.save_state()
+    }
+
+    fn resolve(&mut self, slot: &str, local_modified: u64, cloud_modified: u64) -> Result<SyncOutcome, CloudError> {
+        let local = self.local.read(slot)?.unwrap_or_default();
+        let cloud = self.cloud.read(slot)?.unwrap_or_default();
+        // Same bytes written at different times aren't a conflict
+        if local == cloud {
+            let modified = local_modified.max(cloud_modified);
+            self.local.write(slot, &local, modified)?;
+            self.cloud.write(slot, &cloud, modified)?;
+            self.synced.insert(slot.to_string(), modified);
+            return Ok(SyncOutcome::UpToDate);
+        }
+
+        let resolution = (self.resolver)(&SaveConflict {
+            slot,
+            local: &local,
+            local_modified,
+            cloud: &cloud,
+            cloud_modified,
+        });
+        match &resolution {
+            Resolution::KeepLocal => self.copy_to_cloud(slot, local_modified)?,
+            Resolution::KeepCloud => self.copy_to_local(slot, cloud_modified)?,
+            Resolution::Merged(data) => {
+                let modified = unix_seconds(SystemTime::now()).max(local_modified).max(cloud_modified);
+                self.local.write(slot, data, modified)?;
+                self.cloud.write(slot, data, modified)?;
+                self.synced.insert(slot.to_string(), modified);
+            }
+            Resolution::Skip => {}
+        }
+        Ok(SyncOutcome::Resolved(resolution))
+    }
+
+    fn copy_to_cloud(&mut self, slot: &str, modified: u64) -> Result<(), CloudError> {
+        if let Some(data) = self.local.read(slot)? {
+            self.cloud.write(slot, &data, modified)?;
+            self.synced.insert(slot.to_string(), modified);
+        }
+        Ok(())
+    }
+
+    fn copy_to_local(&mut self, slot: &str, modified: u64) -> Result<(), CloudError> {
+        if let Some(data) = self.cloud.read(slot)? {
+            self.local.write(slot, &data, modified)?;
+            self.synced.insert(slot.to_string(), modified);
+        }
+        Ok(())
+    }
+
+    fn save_state(&self) -> Result<(), CloudError> {
+        let Some(path) = &self.state_path else {
+            return Ok(());
+        };
+        let data = ron::to_string(&self.synced).map_err(|err| CloudError::Backend(err.to_string()))?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
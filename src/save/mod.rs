@@ -0,0 +1,5 @@
+pub mod slots;
+pub mod cloud;
+
+pub use slots::{is_valid_slot, Migrations, SaveData, SaveError, SaveManager};
+pub use cloud::{CloudError, CloudSync, LocalFolderStorage, Resolution, SaveConflict, SaveInfo, SaveStorage, SlotSync, SyncOutcome};
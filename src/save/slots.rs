@@ -0,0 +1,412 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use super::cloud::SaveInfo;
+use crate::core::BuildInfo;
+#[cfg(not(target_arch = "wasm32"))]
+use super::cloud::LocalFolderStorage;
+
+/// Error returned when saving or loading game state
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Format(String),
+    /// The slot has never been saved (or was deleted)
+    NotFound(String),
+    /// Written by a newer version of the game
+    Version { found: u32, supported: u32 },
+    /// No migration upgrades saves from this version, or it failed
+    Migration { from: u32, message: String },
+    /// The slot name would leave the save folder (see `is_valid_slot`)
+    InvalidSlot(String),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(err) => write!(f, "failed to access save: {}", err),
+            SaveError::Format(msg) => write!(f, "invalid save: {}", msg),
+            SaveError::NotFound(slot) => write!(f, "no save in slot '{}'", slot),
+            SaveError::Version { found, supported } => {
+                write!(f, "save is version {}, newer than the supported version {}", found, supported)
+            }
+            SaveError::Migration { from, message } => write!(f, "failed to upgrade save from version {}: {}", from, message),
+            SaveError::InvalidSlot(slot) => write!(f, "invalid save slot name '{}'", slot),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+impl From<std::io::Error> for SaveError {
+    fn from(err: std::io::Error) -> Self {
+        SaveError::Io(err)
+    }
+}
+
+/// Game state that can be written to a save slot.
+///
+/// Any serde type works. Saves record `VERSION`; when the struct changes, bump it and
+/// register a migration from the old version so existing saves still load:
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct ProgressV1 { level: u32, gold: u64 }
+///
+/// #[derive(Serialize, Deserialize, Default)]
+/// struct Progress { level: u32, coins: u64, unlocked: Vec<String> }
+///
+/// impl SaveData for Progress {
+///     const VERSION: u32 = 2;
+///
+///     fn migrations(migrations: &mut Migrations) {
+///         migrations.add(1, |old: ProgressV1| Progress { level: old.level, coins: old.gold, unlocked: vec![] });
+///     }
+/// }
+/// ```
+pub trait SaveData: Serialize + DeserializeOwned {
+    /// Current version of the format (saves written before versioning count as 1)
+    const VERSION: u32 = 1;
+
+    /// Register the upgrades from each older version to the next
+    fn migrations(_migrations: &mut Migrations) {}
+}
+
+/// Upgrade of a save's text from one version to the next
+type Migration = Box<dyn Fn(&str) -> Result<String, String>>;
+
+/// Upgrades from older save versions, registered by `SaveData::migrations`
+#[derive(Default)]
+pub struct Migrations {
+    steps: HashMap<u32, Migration>,
+}
+
+impl Migrations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Upgrade saves of version `from` to `from + 1` by converting the old struct into the new one
+    pub fn add<Old, New, F>(&mut self, from: u32, migrate: F)
+    where
+        Old: DeserializeOwned,
+        New: Serialize,
+        F: Fn(Old) -> New + 'static,
+    {
+        self.add_text(from, move |text| {
+            let old: Old = ron::from_str(text).map_err(|err| err.to_string())?;
+            ron::ser::to_string_pretty(&migrate(old), ron::ser::PrettyConfig::new()).map_err(|err| err.to_string())
+        });
+    }
+
+    /// Upgrade saves of version `from` to `from + 1` by editing the RON text,
+    /// e.g. renaming a field without keeping the old struct around
+    pub fn add_text<F: Fn(&str) -> Result<String, String> + 'static>(&mut self, from: u32, migrate: F) {
+        self.steps.insert(from, Box::new(migrate));
+    }
+
+    pub fn contains(&self, from: u32) -> bool {
+        self.steps.contains_key(&from)
+    }
+
+    /// Run the migrations from `version` up to `target`
+    pub fn upgrade(&self, mut text: String, mut version: u32, target: u32) -> Result<String, SaveError> {
+        while version < target {
+            let Some(migrate) = self.steps.get(&version) else {
+                return Err(SaveError::Migration { from: version, message: "no migration registered".to_string() });
+            };
+            text = migrate(&text).map_err(|message| SaveError::Migration { from: version, message })?;
+            version += 1;
+        }
+        Ok(text)
+    }
+}
+
+/// Reads and writes save slots in the platform's save location.
+///
+/// On desktop, slots are `<slot>.sav` files in the user data directory (e.g.
+/// `~/.local/share/<game>/saves` or `%APPDATA%\<game>\saves`), written atomically so a
+/// crash mid-save never leaves a half-written file. On WASM they live in the browser's
+/// localStorage. Saves are RON text, starting with a `// version: N` comment line
+/// (see `SaveData::VERSION`) and, with `with_build_info`, a `// build: ...` line naming
+/// the build that wrote them.
+///
+/// ```ignore
+/// let saves = SaveManager::new("Skyfall");
+/// saves.save("slot1", &progress)?;
+/// let progress: Progress = saves.load("slot1")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SaveManager {
+    #[cfg(not(target_arch = "wasm32"))]
+    dir: PathBuf,
+    // localStorage key prefix, so several games on one origin don't mix saves
+    #[cfg(target_arch = "wasm32")]
+    prefix: String,
+    // Build line written into each save's header
+    build: Option<String>,
+}
+
+// File extension of save slots, shared with `LocalFolderStorage`
+pub(crate) const SAVE_EXTENSION: &str = "sav";
+
+impl SaveManager {
+    /// Saves for the game called `game_name` (usually `GameConfig::title`)
+    pub fn new(game_name: &str) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Fall back to a folder next to the game when there's no user data directory
+            let base = dirs::data_dir().unwrap_or_else(|| PathBuf::from("."));
+            Self { dir: base.join(sanitize(game_name)).join("saves"), build: None }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            Self { prefix: format!("{}/", sanitize(game_name)), build: None }
+        }
+    }
+
+    /// Keep saves in `dir` instead of the user data directory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Record `build` in the header of every save written from now on (see `build_of`)
+    pub fn with_build_info(mut self, build: &BuildInfo) -> Self {
+        self.build = Some(build.to_string().replace(['\r', '\n'], " "));
+        self
+    }
+
+    /// Folder the slots are written to
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The save folder as a storage, e.g. the local side of a `CloudSync`
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn storage(&self) -> LocalFolderStorage {
+        LocalFolderStorage::new(&self.dir)
+    }
+
+    pub fn save<T: SaveData>(&self, slot: &str, data: &T) -> Result<(), SaveError> {
+        let text = ron::ser::to_string_pretty(data, ron::ser::PrettyConfig::new())
+            .map_err(|err| SaveError::Format(err.to_string()))?;
+        let mut header = format!("{}{}\n", VERSION_HEADER, T::VERSION);
+        if let Some(build) = &self.build {
+            header.push_str(&format!("{}{}\n", BUILD_HEADER, build));
+        }
+        self.write_raw(slot, format!("{}{}", header, text).as_bytes())
+    }
+
+    /// Load a slot, upgrading it first if it was saved by an older version
+    pub fn load<T: SaveData>(&self, slot: &str) -> Result<T, SaveError> {
+        let data = self.read_raw(slot)?;
+        let text = String::from_utf8(data).map_err(|err| SaveError::Format(err.to_string()))?;
+        let (version, body) = split_version(&text);
+        let (_, body) = split_build(body);
+        if version > T::VERSION {
+            return Err(SaveError::Version { found: version, supported: T::VERSION });
+        }
+        let body = if version < T::VERSION {
+            let mut migrations = Migrations::new();
+            T::migrations(&mut migrations);
+            migrations.upgrade(body.to_string(), version, T::VERSION)?
+        } else {
+            body.to_string()
+        };
+        ron::from_str(&body).map_err(|err| SaveError::Format(err.to_string()))
+    }
+
+    /// Format version a slot was saved with
+    pub fn version_of(&self, slot: &str) -> Result<u32, SaveError> {
+        let data = self.read_raw(slot)?;
+        Ok(split_version(&String::from_utf8_lossy(&data)).0)
+    }
+
+    /// Build that wrote a slot, if the save recorded one (see `with_build_info`)
+    pub fn build_of(&self, slot: &str) -> Result<Option<String>, SaveError> {
+        let data = self.read_raw(slot)?;
+        let text = String::from_utf8_lossy(&data);
+        let (build, _) = split_build(split_version(&text).1);
+        Ok(build.map(str::to_string))
+    }
+
+    /// Load a slot, or the default state if it was never saved
+    pub fn load_or_default<T: SaveData + Default>(&self, slot: &str) -> Result<T, SaveError> {
+        match self.load(slot) {
+            Err(SaveError::NotFound(_)) => Ok(T::default()),
+            result => result,
+        }
+    }
+
+    /// Saved slots, sorted by name
+    pub fn slots(&self) -> Vec<SaveInfo> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            use super::cloud::SaveStorage;
+            self.storage().list().unwrap_or_default()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let storage = quad_storage::STORAGE.lock().unwrap();
+            let mut slots: Vec<SaveInfo> = (0..storage.len())
+                .filter_map(|index| storage.key(index))
+                .filter_map(|key| {
+                    let slot = key.strip_prefix(&self.prefix)?.to_string();
+                    let size = storage.get(&key).map_or(0, |value| value.len() as u64);
+                    Some(SaveInfo { slot, modified: 0, size })
+                })
+                .collect();
+            slots.sort_by(|a, b| a.slot.cmp(&b.slot));
+            slots
+        }
+    }
+
+    pub fn exists(&self, slot: &str) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.path(slot).is_ok_and(|path| path.is_file())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.key(slot).is_ok_and(|key| quad_storage::STORAGE.lock().unwrap().get(&key).is_some())
+        }
+    }
+
+    /// Delete a slot (deleting a missing slot is fine)
+    pub fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match std::fs::remove_file(self.path(slot)?) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+                _ => Ok(()),
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            quad_storage::STORAGE.lock().unwrap().remove(&self.key(slot)?);
+            Ok(())
+        }
+    }
+
+    /// Copy a slot, e.g. to keep a backup before overwriting it
+    pub fn copy(&self, from: &str, to: &str) -> Result<(), SaveError> {
+        let data = self.read_raw(from)?;
+        self.write_raw(to, &data)
+    }
+
+    /// The slot's bytes as written
+    pub fn read_raw(&self, slot: &str) -> Result<Vec<u8>, SaveError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match std::fs::read(self.path(slot)?) {
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Err(SaveError::NotFound(slot.to_string())),
+                result => Ok(result?),
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            quad_storage::STORAGE.lock().unwrap()
+                .get(&self.key(slot)?)
+                .map(String::into_bytes)
+                .ok_or_else(|| SaveError::NotFound(slot.to_string()))
+        }
+    }
+
+    /// Replace the slot's bytes
+    pub fn write_raw(&self, slot: &str, data: &[u8]) -> Result<(), SaveError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::create_dir_all(&self.dir)?;
+            write_atomic(&self.path(slot)?, data)?;
+            Ok(())
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            // localStorage only holds text
+            let text = std::str::from_utf8(data).map_err(|err| SaveError::Format(err.to_string()))?;
+            quad_storage::STORAGE.lock().unwrap().set(&self.key(slot)?, text);
+            Ok(())
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn path(&self, slot: &str) -> Result<PathBuf, SaveError> {
+        if !is_valid_slot(slot) {
+            return Err(SaveError::InvalidSlot(slot.to_string()));
+        }
+        Ok(self.dir.join(format!("{}.{}", slot, SAVE_EXTENSION)))
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn key(&self, slot: &str) -> Result<String, SaveError> {
+        if !is_valid_slot(slot) {
+            return Err(SaveError::InvalidSlot(slot.to_string()));
+        }
+        Ok(format!("{}{}", self.prefix, slot))
+    }
+}
+
+// First line of every save, followed by the format version
+const VERSION_HEADER: &str = "// version: ";
+// Optional second line, followed by the build that wrote the save
+const BUILD_HEADER: &str = "// build: ";
+
+/// The version from a save's header line and the RON after it
+fn split_version(text: &str) -> (u32, &str) {
+    let Some(rest) = text.strip_prefix(VERSION_HEADER) else {
+        return (1, text);
+    };
+    let (line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+    match line.trim().parse() {
+        Ok(version) => (version, body),
+        Err(_) => (1, text),
+    }
+}
+
+/// The build from the header line after the version, if there is one, and the RON after it
+fn split_build(body: &str) -> (Option<&str>, &str) {
+    let Some(rest) = body.strip_prefix(BUILD_HEADER) else {
+        return (None, body);
+    };
+    let (line, body) = rest.split_once('\n').unwrap_or((rest, ""));
+    (Some(line.trim()), body)
+}
+
+/// Write to a temporary file next to `path`, then rename it over `path`, so readers see
+/// either the old contents or the new ones
+pub(crate) fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp = path.with_file_name(temp_name);
+    let mut file = std::fs::File::create(&temp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&temp, path)
+}
+
+/// Whether a slot name stays a single file name inside the save folder: not empty, not
+/// `.` or `..`, and without path separators, drive colons or control characters
+pub fn is_valid_slot(slot: &str) -> bool {
+    !slot.is_empty()
+        && slot != "."
+        && slot != ".."
+        && !slot.chars().any(|c| matches!(c, '/' | '\\' | ':') || c.is_control())
+}
+
+/// Game name as a folder name
+fn sanitize(name: &str) -> String {
+    let name: String = name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' { c } else { '_' })
+        .collect();
+    let name = name.trim();
+    if name.is_empty() { "game".to_string() } else { name.to_string() }
+}
@@ -0,0 +1,3 @@
+pub mod preview;
+
+pub use preview::{Target, TargetShape, TargetValidity, TargetingEvent, TargetingPreview};
@@ -0,0 +1,337 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::core::{EntityId, Scene};
+use crate::input::{Action, InputManager};
+use crate::rendering::Camera;
+
+// Segments used to draw circle and cone edges
+const ARC_SEGMENTS: usize = 24;
+const OUTLINE_WIDTH: f32 = 2.0;
+// Alpha of the filled area, relative to the outline
+const FILL_ALPHA: f32 = 0.25;
+
+/// Finds the first blocker between two world points, returning where it was hit
+type RaycastFn = Box<dyn Fn(Vec2, Vec2) -> Option<Vec2>>;
+
+/// Area an ability affects
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TargetShape {
+    /// A circle centered on the aimed point
+    Circle { radius: f32 },
+    /// A wedge from the caster toward the aimed point, `spread` radians wide
+    Cone { length: f32, spread: f32 },
+    /// A beam from the caster toward the aimed point
+    Line { length: f32, width: f32 },
+}
+
+/// Whether the aimed target can be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetValidity {
+    Valid,
+    /// Farther than the ability's range
+    OutOfRange,
+    /// Line of sight is blocked (see `TargetingPreview::with_raycast`)
+    Blocked,
+}
+
+/// A confirmed target, ready for the ability to apply its effect
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Target {
+    pub shape: TargetShape,
+    /// Caster position when confirmed
+    pub origin: Vec2,
+    /// Aimed point: a circle's center, or where a cone or line points
+    pub point: Vec2,
+    /// Unit direction from the origin toward the point (+X when they coincide)
+    pub direction: Vec2,
+}
+
+impl Target {
+    /// Whether a world point is inside the area
+    pub fn contains(&self, point: Vec2) -> bool {
+        match self.shape {
+            TargetShape::Circle { radius } => point.distance_squared(self.point) <= radius * radius,
+            TargetShape::Cone { length, spread } => {
+                let offset = point - self.origin;
+                let distance = offset.length();
+                distance <= length && (distance == 0.0 || self.direction.angle_between(offset).abs() <= spread * 0.5)
+            }
+            TargetShape::Line { length, width } => {
+                let offset = point - self.origin;
+                let along = offset.dot(self.direction);
+                let across = offset.dot(self.direction.perp()).abs();
+                (0.0..=length).contains(&along) && across <= width * 0.5
+            }
+        }
+    }
+
+    /// Entities of a scene whose position is inside the area
+    pub fn entities_in(&self, scene: &Scene) -> Vec<EntityId> {
+        scene.entity_ids()
+            .filter(|id| scene.entity_position(*id).is_some_and(|position| self.contains(position)))
+            .collect()
+    }
+}
+
+/// What happened to a targeting preview this frame
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TargetingEvent {
+    Confirmed(Target),
+    /// Confirm was pressed while the target was invalid (e.g. to play an error sound)
+    Rejected(TargetValidity),
+    Canceled,
+}
+
+/// Telegraph of an ability's area that follows the mouse (or the movement actions on
+/// a gamepad or keyboard), colored by whether the target is valid, until the player
+/// confirms or cancels:
+///
+/// ```ignore
+/// let mut preview = TargetingPreview::new(TargetShape::Circle { radius: 64.0 })
+///     .with_range(300.0)
+///     .with_raycast(move |from, to| walls.first_hit(from, to));
+/// preview.start(player_position);
+/// // each frame
+/// preview.set_origin(player_position);
+/// match preview.update(&input, scene.get_camera(), dt) {
+///     Some(TargetingEvent::Confirmed(target)) => cast_fireball(scene, target.entities_in(scene)),
+///     Some(TargetingEvent::Rejected(_)) => audio.play("error", 1.0),
+///     _ => {}
+/// }
+/// // under the scene's camera, before entities so it lies on the ground
+/// preview.draw();
+/// ```
+pub struct TargetingPreview {
+    shape: TargetShape,
+    origin: Vec2,
+    aim: Vec2,
+    /// Farthest the aimed point can be from the caster (None = anywhere)
+    range: Option<f32>,
+    /// Keep the aimed point within range instead of marking it out of range
+    clamp_to_range: bool,
+    /// World units per second the aim moves with the movement actions
+    aim_speed: f32,
+    confirm: Action,
+    cancel: Action,
+    raycast: Option<RaycastFn>,
+    validity: TargetValidity,
+    // Where line of sight stopped, when blocked
+    blocked_at: Option<Vec2>,
+    active: bool,
+    valid_color: Color,
+    out_of_range_color: Color,
+    blocked_color: Color,
+}
+
+impl TargetingPreview {
+    pub fn new(shape: TargetShape) -> Self {
+        Self {
+            shape,
+            origin: Vec2::ZERO,
+            aim: Vec2::ZERO,
+            range: None,
+            clamp_to_range: false,
+            aim_speed: 400.0,
+            confirm: Action::Attack,
+            cancel: Action::Defend,
+            raycast: None,
+            validity: TargetValidity::Valid,
+            blocked_at: None,
+            active: false,
+            valid_color: Color::new(0.3, 0.8, 1.0, 1.0),
+            out_of_range_color: Color::new(0.6, 0.6, 0.6, 1.0),
+            blocked_color: Color::new(1.0, 0.3, 0.25, 1.0),
+        }
+    }
+
+    pub fn with_range(mut self, range: f32) -> Self {
+        self.range = Some(range.max(0.0));
+        self
+    }
+
+    pub fn with_clamp_to_range(mut self, clamp: bool) -> Self {
+        self.clamp_to_range = clamp;
+        self
+    }
+
+    pub fn with_aim_speed(mut self, speed: f32) -> Self {
+        self.aim_speed = speed.max(0.0);
+        self
+    }
+
+    /// Actions that confirm and cancel (Attack and Defend by default; the left and right
+    /// mouse buttons always do)
+    pub fn with_actions(mut self, confirm: Action, cancel: Action) -> Self {
+        self.confirm = confirm;
+        self.cancel = cancel;
+        self
+    }
+
+    /// Check line of sight from the caster to the aimed point; `raycast` returns where
+    /// something blocks it
+    pub fn with_raycast<F: Fn(Vec2, Vec2) -> Option<Vec2> + 'static>(mut self, raycast: F) -> Self {
+        self.raycast = Some(Box::new(raycast));
+        self
+    }
+
+    pub fn with_colors(mut self, valid: Color, out_of_range: Color, blocked: Color) -> Self {
+        self.valid_color = valid;
+        self.out_of_range_color = out_of_range;
+        self.blocked_color = blocked;
+        self
+    }
+
+    /// Show the preview, aiming at the caster's position to start
+    pub fn start(&mut self, origin: Vec2) {
+        self.origin = origin;
+        self.aim = origin;
+        self.active = true;
+        self.refresh();
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = false;
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Move the caster end of the preview, e.g. to follow the player
+    pub fn set_origin(&mut self, origin: Vec2) {
+        self.origin = origin;
+        self.refresh();
+    }
+
+    /// Aim at a world point from code, e.g. for auto-targeting
+    pub fn set_aim(&mut self, point: Vec2) {
+        self.aim = point;
+        self.refresh();
+    }
+
+    pub fn shape(&self) -> TargetShape {
+        self.shape
+    }
+
+    pub fn set_shape(&mut self, shape: TargetShape) {
+        self.shape = shape;
+    }
+
+    pub fn validity(&self) -> TargetValidity {
+        self.validity
+    }
+
+    /// The target as currently aimed
+    pub fn target(&self) -> Target {
+        let direction = (self.aim - self.origin).try_normalize().unwrap_or(Vec2::X);
+        Target { shape: self.shape, origin: self.origin, point: self.aim, direction }
+    }
+
+    /// Follow the mouse when it moves, otherwise the movement actions, and handle
+    /// confirm and cancel. Does nothing while inactive.
+    pub fn update(&mut self, input: &InputManager, camera: &Camera, dt: f32) -> Option<TargetingEvent> {
+        if !self.active {
+            return None;
+        }
+        if input.mouse_delta() != Vec2::ZERO {
+            self.aim = camera.screen_to_world(input.mouse_position());
+        } else {
+            self.aim += input.get_movement_input() * self.aim_speed * dt;
+        }
+        self.refresh();
+
+        if input.is_action_just_activated(&self.cancel) || input.is_mouse_button_just_pressed(MouseButton::Right) {
+            self.active = false;
+            return Some(TargetingEvent::Canceled);
+        }
+        if input.is_action_just_activated(&self.confirm) || input.is_mouse_button_just_pressed(MouseButton::Left) {
+            if self.validity != TargetValidity::Valid {
+                return Some(TargetingEvent::Rejected(self.validity));
+            }
+            self.active = false;
+            return Some(TargetingEvent::Confirmed(self.target()));
+        }
+        None
+    }
+
+    /// Recheck range and line of sight after the origin or aim moved
+    fn refresh(&mut self) {
+        if let Some(range) = self.range.filter(|_| self.clamp_to_range) {
+            let offset = self.aim - self.origin;
+            if offset.length() > range {
+                self.aim = self.origin + offset.normalize() * range;
+            }
+        }
+
+        // Cones and lines reach their full length whatever the aim distance
+        let target = self.target();
+        let reach = match self.shape {
+            TargetShape::Circle { .. } => self.aim,
+            TargetShape::Cone { length, .. } | TargetShape::Line { length, .. } => self.origin + target.direction * length,
+        };
+        self.blocked_at = self.raycast.as_ref().and_then(|raycast| raycast(self.origin, reach));
+        let in_range = self.range.is_none_or(|range| self.aim.distance(self.origin) <= range + f32::EPSILON);
+        self.validity = if self.blocked_at.is_some() {
+            TargetValidity::Blocked
+        } else if !in_range && matches!(self.shape, TargetShape::Circle { .. }) {
+            TargetValidity::OutOfRange
+        } else {
+            TargetValidity::Valid
+        };
+    }
+
+    /// Draw the area in world space, colored by validity
+    pub fn draw(&self) {
+        if !self.active {
+            return;
+        }
+        let color = match self.validity {
+            TargetValidity::Valid => self.valid_color,
+            TargetValidity::OutOfRange => self.out_of_range_color,
+            TargetValidity::Blocked => self.blocked_color,
+        };
+        let fill = Color::new(color.r, color.g, color.b, color.a * FILL_ALPHA);
+        let target = self.target();
+
+        if let Some(range) = self.range {
+            draw_circle_lines(self.origin.x, self.origin.y, range, 1.0, fill);
+        }
+        match self.shape {
+            TargetShape::Circle { radius } => {
+                draw_circle(target.point.x, target.point.y, radius, fill);
+                draw_circle_lines(target.point.x, target.point.y, radius, OUTLINE_WIDTH, color);
+            }
+            TargetShape::Cone { length, spread } => {
+                let start = target.direction.to_angle() - spread * 0.5;
+                let edge: Vec<Vec2> = (0..=ARC_SEGMENTS)
+                    .map(|step| {
+                        let angle = start + spread * step as f32 / ARC_SEGMENTS as f32;
+                        self.origin + Vec2::from_angle(angle) * length
+                    })
+                    .collect();
+                for pair in edge.windows(2) {
+                    draw_triangle(self.origin, pair[0], pair[1], fill);
+                    draw_line(pair[0].x, pair[0].y, pair[1].x, pair[1].y, OUTLINE_WIDTH, color);
+                }
+                for side in [edge[0], edge[ARC_SEGMENTS]] {
+                    draw_line(self.origin.x, self.origin.y, side.x, side.y, OUTLINE_WIDTH, color);
+                }
+            }
+            TargetShape::Line { length, width } => {
+                let along = target.direction * length;
+                let across = target.direction.perp() * width * 0.5;
+                let corners = [self.origin - across, self.origin + along - across, self.origin + along + across, self.origin + across];
+                draw_triangle(corners[0], corners[1], corners[2], fill);
+                draw_triangle(corners[0], corners[2], corners[3], fill);
+                for index in 0..4 {
+                    let (a, b) = (corners[index], corners[(index + 1) % 4]);
+                    draw_line(a.x, a.y, b.x, b.y, OUTLINE_WIDTH, color);
+                }
+            }
+        }
+        if let Some(hit) = self.blocked_at {
+            draw_line(self.origin.x, self.origin.y, hit.x, hit.y, 1.0, color);
+            draw_circle(hit.x, hit.y, 3.0, color);
+        }
+    }
+}
@@ -0,0 +1,328 @@
+use macroquad::prelude::*;
+use std::collections::HashSet;
+use crate::core::Scene;
+use crate::input::InputManager;
+use crate::rendering::DebugTheme;
+use super::scale::ui_scale;
+
+// Size of the panel rows, in unscaled UI pixels
+const ROW_HEIGHT: f32 = 22.0;
+const FONT_SIZE: f32 = 16.0;
+const PADDING: f32 = 6.0;
+
+/// Callbacks run on the base scene
+type ButtonFn = Box<dyn FnMut(&mut Scene)>;
+type ToggleFn = Box<dyn FnMut(&mut Scene, bool)>;
+type SliderFn = Box<dyn FnMut(&mut Scene, f32)>;
+
+enum CheatKind {
+    Button(ButtonFn),
+    Toggle { on: bool, apply: ToggleFn },
+    Slider { min: f32, max: f32, step: f32, value: f32, apply: SliderFn },
+}
+
+struct Cheat {
+    category: String,
+    label: String,
+    kind: CheatKind,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Row<'a> {
+    Category(&'a str),
+    Cheat(usize),
+}
+
+/// A debug overlay of cheats grouped by category, toggled with a key (F1 by default).
+///
+/// `Game` owns one, shown only when `GameConfig::enable_cheats` is set (the default in
+/// debug builds). Cheats run on the base scene when clicked; category headers collapse.
+///
+/// ```ignore
+/// let cheats = game.get_cheats_mut();
+/// cheats.button("Player", "Full health", |scene| heal_player(scene));
+/// cheats.toggle("Player", "God mode", false, move |_, on| god_mode.set(on));
+/// cheats.slider("World", "Enemy speed", (0.0, 4.0), 0.25, 1.0, |scene, scale| scene.set_group_time_scale("enemy", scale));
+/// cheats.button("Levels", "Unlock all", move |_| progress.borrow_mut().unlock_all());
+/// ```
+pub struct CheatMenu {
+    cheats: Vec<Cheat>,
+    collapsed: HashSet<String>,
+    open: bool,
+    toggle_key: KeyCode,
+    position: Vec2,
+    width: f32,
+    // Slider being dragged
+    dragging: Option<usize>,
+}
+
+impl Default for CheatMenu {
+    fn default() -> Self {
+        Self {
+            cheats: vec![],
+            collapsed: HashSet::new(),
+            open: false,
+            toggle_key: KeyCode::F1,
+            position: vec2(10.0, 10.0),
+            width: 300.0,
+            dragging: None,
+        }
+    }
+}
+
+impl CheatMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_toggle_key(mut self, key: KeyCode) -> Self {
+        self.toggle_key = key;
+        self
+    }
+
+    /// Top-left corner of the panel in screen pixels, and its width in UI pixels (scaled by
+    /// `ui::ui_scale` like the rows and text)
+    pub fn with_layout(mut self, position: Vec2, width: f32) -> Self {
+        self.position = position;
+        self.width = width;
+        self
+    }
+
+    /// A cheat run once per click
+    pub fn button<F: FnMut(&mut Scene) + 'static>(&mut self, category: &str, label: &str, apply: F) {
+        self.add(category, label, CheatKind::Button(Box::new(apply)));
+    }
+
+    /// An on/off cheat; `apply` gets the new state on each click
+    pub fn toggle<F: FnMut(&mut Scene, bool) + 'static>(&mut self, category: &str, label: &str, on: bool, apply: F) {
+        self.add(category, label, CheatKind::Toggle { on, apply: Box::new(apply) });
+    }
+
+    /// A value cheat in `(min, max)`; `apply` gets the new value whenever dragging changes it
+    /// (`step` 0.0 = continuous)
+    pub fn slider<F>(&mut self, category: &str, label: &str, (min, max): (f32, f32), step: f32, value: f32, apply: F)
+    where
+        F: FnMut(&mut Scene, f32) + 'static,
+    {
+        let (min, max) = (min.min(max), max.max(min));
+        let value = value.clamp(min, max);
+        self.add(category, label, CheatKind::Slider { min, max, step: step.max(0.0), value, apply: Box::new(apply) });
+    }
+
+    /// Remove a cheat; returns false if there was none
+    pub fn remove(&mut self, category: &str, label: &str) -> bool {
+        let before = self.cheats.len();
+        self.cheats.retain(|cheat| cheat.category != category || cheat.label != label);
+        self.dragging = None;
+        self.cheats.len() != before
+    }
+
+    pub fn len(&self) -> usize {
+        self.cheats.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cheats.is_empty()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn set_open(&mut self, open: bool) {
+        self.open = open;
+        self.dragging = None;
+    }
+
+    /// Categories in the order their first cheat was registered
+    pub fn categories(&self) -> Vec<&str> {
+        let mut categories: Vec<&str> = vec![];
+        for cheat in &self.cheats {
+            if !categories.contains(&cheat.category.as_str()) {
+                categories.push(&cheat.category);
+            }
+        }
+        categories
+    }
+
+    /// Run a cheat by name, as if clicked (toggles flip; sliders are left alone)
+    pub fn trigger(&mut self, category: &str, label: &str, scene: &mut Scene) -> bool {
+        let Some(index) = self.cheats.iter().position(|cheat| cheat.category == category && cheat.label == label) else {
+            return false;
+        };
+        self.press(index, scene);
+        true
+    }
+
+    /// Handle the toggle key and mouse, running clicked cheats on `scene`. While the mouse
+    /// is over the open panel (or dragging a slider) its buttons are consumed, so clicks
+    /// don't reach the game.
+    pub fn update(&mut self, input: &mut InputManager, scene: &mut Scene) {
+        if input.is_key_just_pressed(self.toggle_key) {
+            self.set_open(!self.open);
+        }
+        if !self.open {
+            return;
+        }
+
+        let mouse = input.mouse_position();
+        let over_panel = self.dragging.is_some() || self.panel(&self.layout()).contains(mouse);
+        self.update_mouse(input, mouse, scene);
+        if over_panel {
+            input.consume_mouse_buttons();
+        }
+    }
+
+    fn update_mouse(&mut self, input: &InputManager, mouse: Vec2, scene: &mut Scene) {
+        if let Some(index) = self.dragging {
+            if input.is_mouse_button_down(MouseButton::Left) {
+                self.drag(index, mouse.x, scene);
+            } else {
+                self.dragging = None;
+            }
+            return;
+        }
+        if !input.is_mouse_button_just_pressed(MouseButton::Left) {
+            return;
+        }
+
+        let clicked = self.layout().into_iter().find(|(_, rect)| rect.contains(mouse)).map(|(row, _)| match row {
+            Row::Category(category) => Err(category.to_string()),
+            Row::Cheat(index) => Ok(index),
+        });
+        match clicked {
+            Some(Err(category)) if !self.collapsed.remove(&category) => {
+                self.collapsed.insert(category);
+            }
+            Some(Ok(index)) if matches!(self.cheats[index].kind, CheatKind::Slider { .. }) => {
+                self.dragging = Some(index);
+                self.drag(index, mouse.x, scene);
+            }
+            Some(Ok(index)) => self.press(index, scene),
+            _ => {}
+        }
+    }
+
+    /// Draw the panel in screen space (nothing while closed)
+    pub fn draw(&self, theme: &DebugTheme) {
+        if !self.open {
+            return;
+        }
+        let layout = self.layout();
+        let panel = self.panel(&layout);
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, theme.background());
+
+        for (row, rect) in layout {
+            let baseline = rect.y + row_height() * 0.5 + font_size() * 0.3;
+            match row {
+                Row::Category(category) => {
+                    let marker = if self.collapsed.contains(category) { "+" } else { "-" };
+                    draw_text(&format!("{} {}", marker, category), rect.x, baseline, font_size(), theme.heading());
+                }
+                Row::Cheat(index) => self.draw_cheat(&self.cheats[index], rect, baseline, theme),
+            }
+        }
+        let hint = format!("{:?} to close", self.toggle_key);
+        draw_text(&hint, panel.x + padding(), panel.bottom() + font_size(), font_size(), theme.muted());
+    }
+
+    fn draw_cheat(&self, cheat: &Cheat, rect: Rect, baseline: f32, theme: &DebugTheme) {
+        let text_x = rect.x + padding();
+        match &cheat.kind {
+            CheatKind::Button(_) => {
+                draw_rectangle_lines(rect.x, rect.y + 1.0, rect.w, rect.h - 2.0, 1.0, theme.muted());
+                draw_text(&cheat.label, text_x, baseline, font_size(), theme.text());
+            }
+            CheatKind::Toggle { on, .. } => {
+                let (mark, color) = if *on { ("[x]", theme.active()) } else { ("[ ]", theme.text()) };
+                draw_text(&format!("{} {}", mark, cheat.label), text_x, baseline, font_size(), color);
+            }
+            CheatKind::Slider { min, max, value, .. } => {
+                draw_text(&format!("{} {:.2}", cheat.label, value), text_x, baseline, font_size(), theme.text());
+                let bar = slider_bar(rect);
+                let fraction = if max > min { (value - min) / (max - min) } else { 0.0 };
+                draw_rectangle(bar.x, bar.y, bar.w, bar.h, theme.muted());
+                draw_rectangle(bar.x, bar.y, bar.w * fraction, bar.h, theme.active());
+            }
+        }
+    }
+
+    fn add(&mut self, category: &str, label: &str, kind: CheatKind) {
+        // Registering a cheat again replaces it
+        self.remove(category, label);
+        self.cheats.push(Cheat { category: category.to_string(), label: label.to_string(), kind });
+    }
+
+    fn press(&mut self, index: usize, scene: &mut Scene) {
+        match &mut self.cheats[index].kind {
+            CheatKind::Button(apply) => apply(scene),
+            CheatKind::Toggle { on, apply } => {
+                *on = !*on;
+                apply(scene, *on);
+            }
+            CheatKind::Slider { .. } => {}
+        }
+    }
+
+    /// Set a slider from the mouse position over its bar
+    fn drag(&mut self, index: usize, mouse_x: f32, scene: &mut Scene) {
+        let Some((_, rect)) = self.layout().into_iter().find(|(row, _)| *row == Row::Cheat(index)) else {
+            return;
+        };
+        let bar = slider_bar(rect);
+        let CheatKind::Slider { min, max, step, value, apply } = &mut self.cheats[index].kind else {
+            return;
+        };
+        let fraction = ((mouse_x - bar.x) / bar.w).clamp(0.0, 1.0);
+        let mut new_value = *min + (*max - *min) * fraction;
+        if *step > 0.0 {
+            new_value = *min + ((new_value - *min) / *step).round() * *step;
+        }
+        if new_value != *value {
+            *value = new_value;
+            apply(scene, new_value);
+        }
+    }
+
+    /// Background behind the rows of `layout`
+    fn panel(&self, layout: &[(Row<'_>, Rect)]) -> Rect {
+        let height = layout.last().map_or(row_height(), |(_, rect)| rect.bottom() - self.position.y) + padding();
+        Rect::new(self.position.x, self.position.y, self.width * ui_scale(), height)
+    }
+
+    /// Visible rows and their screen rects, categories in registration order
+    fn layout(&self) -> Vec<(Row<'_>, Rect)> {
+        let mut rows = vec![];
+        let mut y = self.position.y + padding();
+        let width = self.width * ui_scale() - padding() * 2.0;
+        for category in self.categories() {
+            rows.push((Row::Category(category), Rect::new(self.position.x + padding(), y, width, row_height())));
+            y += row_height();
+            if self.collapsed.contains(category) {
+                continue;
+            }
+            for index in (0..self.cheats.len()).filter(|index| self.cheats[*index].category == category) {
+                rows.push((Row::Cheat(index), Rect::new(self.position.x + padding() * 3.0, y, width - padding() * 2.0, row_height())));
+                y += row_height();
+            }
+        }
+        rows
+    }
+}
+
+/// The draggable part of a slider row: its right half
+fn slider_bar(rect: Rect) -> Rect {
+    Rect::new(rect.x + rect.w * 0.5, rect.y + row_height() * 0.35, rect.w * 0.5 - padding(), row_height() * 0.3)
+}
+
+fn row_height() -> f32 {
+    ROW_HEIGHT * ui_scale()
+}
+
+fn font_size() -> f32 {
+    FONT_SIZE * ui_scale()
+}
+
+fn padding() -> f32 {
+    PADDING * ui_scale()
+}
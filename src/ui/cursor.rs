@@ -0,0 +1,163 @@
+use macroquad::miniquad::{window::set_mouse_cursor, CursorIcon};
+use macroquad::prelude::*;
+use super::scale::ui_scale;
+
+/// Standard cursors the operating system draws itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SystemCursor {
+    #[default]
+    Default,
+    /// Hand, for clickable things
+    Pointer,
+    Text,
+    Crosshair,
+    Move,
+    NotAllowed,
+    Wait,
+    Help,
+    ResizeHorizontal,
+    ResizeVertical,
+}
+
+impl SystemCursor {
+    fn icon(self) -> CursorIcon {
+        match self {
+            SystemCursor::Default => CursorIcon::Default,
+            SystemCursor::Pointer => CursorIcon::Pointer,
+            SystemCursor::Text => CursorIcon::Text,
+            SystemCursor::Crosshair => CursorIcon::Crosshair,
+            SystemCursor::Move => CursorIcon::Move,
+            SystemCursor::NotAllowed => CursorIcon::NotAllowed,
+            SystemCursor::Wait => CursorIcon::Wait,
+            SystemCursor::Help => CursorIcon::Help,
+            SystemCursor::ResizeHorizontal => CursorIcon::EWResize,
+            SystemCursor::ResizeVertical => CursorIcon::NSResize,
+        }
+    }
+}
+
+/// A texture drawn at the mouse in place of the OS cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct CursorSprite {
+    pub texture: Texture2D,
+    /// Point of the texture that sits on the mouse position, in texture pixels
+    /// (the top-left corner by default; the center for a crosshair)
+    pub hotspot: Vec2,
+    /// Drawn size in UI pixels, scaled by `ui::ui_scale` (None = the texture's size)
+    pub size: Option<Vec2>,
+    pub color: Color,
+}
+
+impl CursorSprite {
+    pub fn new(texture: Texture2D) -> Self {
+        Self { texture, hotspot: Vec2::ZERO, size: None, color: WHITE }
+    }
+
+    pub fn with_hotspot(mut self, hotspot: Vec2) -> Self {
+        self.hotspot = hotspot;
+        self
+    }
+
+    /// Put the hotspot in the middle of the texture
+    pub fn centered(mut self) -> Self {
+        self.hotspot = self.texture.size() * 0.5;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+}
+
+/// What the mouse cursor looks like (see `Game::set_cursor`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cursor {
+    /// The OS cursor, which stays smooth when the game runs slowly
+    System(SystemCursor),
+    /// Hide the OS cursor and draw a texture over everything else
+    Sprite(CursorSprite),
+    /// Hide the OS cursor and draw a ring with a cross, `size` UI pixels across
+    Crosshair { color: Color, size: f32 },
+    /// No cursor at all
+    Hidden,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::System(SystemCursor::Default)
+    }
+}
+
+/// Keeps the OS cursor in sync with a `Cursor` and draws custom ones. `Game` owns one
+/// and draws it last, after the debug overlays and plugins.
+#[derive(Default)]
+pub struct CursorManager {
+    cursor: Cursor,
+    // The OS cursor is only touched when the cursor changes
+    dirty: bool,
+}
+
+impl CursorManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(&mut self, cursor: Cursor) {
+        if cursor != self.cursor {
+            self.cursor = cursor;
+            self.dirty = true;
+        }
+    }
+
+    pub fn get(&self) -> &Cursor {
+        &self.cursor
+    }
+
+    /// Whether the cursor is drawn by the game rather than the OS
+    pub fn is_custom(&self) -> bool {
+        matches!(self.cursor, Cursor::Sprite(_) | Cursor::Crosshair { .. })
+    }
+
+    /// Apply a changed cursor to the OS and draw a custom cursor at the mouse, in screen space
+    /// at the UI scale (the mouse position is already in screen pixels, so only the size scales)
+    pub fn draw(&mut self) {
+        if std::mem::take(&mut self.dirty) {
+            match &self.cursor {
+                Cursor::System(system) => {
+                    show_mouse(true);
+                    set_mouse_cursor(system.icon());
+                }
+                _ => show_mouse(false),
+            }
+        }
+
+        let mouse = Vec2::from(mouse_position());
+        let scale = ui_scale();
+        match &self.cursor {
+            Cursor::Sprite(sprite) => {
+                let size = sprite.size.unwrap_or(sprite.texture.size()) * scale;
+                // The hotspot is in texture pixels, so scale it to the drawn size
+                let hotspot = sprite.hotspot * size / sprite.texture.size().max(Vec2::ONE);
+                let corner = mouse - hotspot;
+                draw_texture_ex(&sprite.texture, corner.x, corner.y, sprite.color, DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                });
+            }
+            Cursor::Crosshair { color, size } => {
+                let radius = size * scale * 0.5;
+                let arm = radius * 0.5;
+                draw_circle_lines(mouse.x, mouse.y, radius, 2.0 * scale, *color);
+                draw_line(mouse.x - arm, mouse.y, mouse.x + arm, mouse.y, 2.0 * scale, *color);
+                draw_line(mouse.x, mouse.y - arm, mouse.x, mouse.y + arm, 2.0 * scale, *color);
+            }
+            Cursor::System(_) | Cursor::Hidden => {}
+        }
+    }
+}
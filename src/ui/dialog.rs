@@ -0,0 +1,156 @@
+use macroquad::prelude::*;
+use super::style::Style;
+use super::theme::Theme;
+use super::widget::ButtonAction;
+
+/// One button of a dialog
+pub struct DialogButton {
+    pub label: String,
+    /// Menu behaviour when pressed (report an action, open a screen, go back)
+    pub action: Option<ButtonAction>,
+    callback: Option<Box<dyn FnOnce()>>,
+}
+
+impl DialogButton {
+    pub fn new(label: &str) -> Self {
+        Self { label: label.to_string(), action: None, callback: None }
+    }
+
+    pub fn with_action(mut self, action: ButtonAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    pub fn with_callback<F: FnOnce() + 'static>(mut self, callback: F) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+
+    pub(crate) fn take_callback(&mut self) -> Option<Box<dyn FnOnce()>> {
+        self.callback.take()
+    }
+}
+
+/// A modal message with a row of buttons, shown over a `Menu` with `Menu::show_dialog`.
+///
+/// While open it takes all of the menu's input. Confirm presses the focused button
+/// (the default one when the dialog opens) and Pause presses the cancel button.
+pub struct Dialog {
+    pub message: String,
+    pub buttons: Vec<DialogButton>,
+    /// Button focused when the dialog opens
+    pub default_button: usize,
+    /// Button pressed by Pause (None = Pause does nothing)
+    pub cancel_button: Option<usize>,
+    /// RGBA color drawn over everything behind the dialog
+    pub dim: [f32; 4],
+    /// Named styles for the panel and its buttons
+    pub style: String,
+    pub button_style: String,
+    pub(crate) focus: usize,
+}
+
+impl Dialog {
+    pub fn new(message: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            buttons: vec![],
+            default_button: 0,
+            cancel_button: None,
+            dim: [0.0, 0.0, 0.0, 0.6],
+            style: "dialog".to_string(),
+            button_style: "dialog_button".to_string(),
+            focus: 0,
+        }
+    }
+
+    /// A Yes/No prompt; No is the default and the cancel button
+    pub fn confirm<Y, N>(message: &str, on_yes: Y, on_no: N) -> Self
+    where
+        Y: FnOnce() + 'static,
+        N: FnOnce() + 'static,
+    {
+        Self::new(message)
+            .with_button(DialogButton::new("Yes").with_callback(on_yes))
+            .with_button(DialogButton::new("No").with_callback(on_no))
+            .with_default_button(1)
+            .with_cancel_button(1)
+    }
+
+    pub fn with_button(mut self, button: DialogButton) -> Self {
+        self.buttons.push(button);
+        self
+    }
+
+    pub fn with_default_button(mut self, index: usize) -> Self {
+        self.default_button = index;
+        self
+    }
+
+    pub fn with_cancel_button(mut self, index: usize) -> Self {
+        self.cancel_button = Some(index);
+        self
+    }
+
+    pub fn with_dim(mut self, color: [f32; 4]) -> Self {
+        self.dim = color;
+        self
+    }
+
+    pub fn with_style(mut self, style: &str, button_style: &str) -> Self {
+        self.style = style.to_string();
+        self.button_style = button_style.to_string();
+        self
+    }
+
+    pub fn focused_button(&self) -> usize {
+        self.focus
+    }
+
+    /// Panel and button rects, centered in the window. The styles are already scaled by `scale`.
+    pub(crate) fn layout(&self, style: &Style, button_style: &Style, theme: &Theme, scale: f32) -> (Rect, Vec<Rect>) {
+        let font = style.font.as_deref().and_then(|name| theme.font(name));
+        let line_height = style.font_size * 1.2;
+        let lines: Vec<&str> = self.message.lines().collect();
+        let text_width = lines.iter()
+            .map(|line| measure_text(line, font, style.font_size as u16, 1.0).width)
+            .fold(0.0, f32::max);
+
+        let button_font = button_style.font.as_deref().and_then(|name| theme.font(name));
+        let button_height = button_style.font_size + button_style.padding * 2.0;
+        let button_widths: Vec<f32> = self.buttons.iter()
+            .map(|button| {
+                let label = measure_text(&button.label, button_font, button_style.font_size as u16, 1.0).width;
+                (label + button_style.padding * 4.0).max(100.0 * scale)
+            })
+            .collect();
+        let spacing = style.padding;
+        let buttons_width = button_widths.iter().sum::<f32>() + spacing * self.buttons.len().saturating_sub(1) as f32;
+
+        let width = (text_width.max(buttons_width) + style.padding * 2.0).max(320.0 * scale).min(screen_width() * 0.9);
+        let height = style.padding * 3.0 + lines.len() as f32 * line_height + button_height;
+        let panel = Rect::new((screen_width() - width) * 0.5, (screen_height() - height) * 0.5, width, height);
+
+        let mut x = panel.x + (panel.w - buttons_width) * 0.5;
+        let y = panel.y + panel.h - style.padding - button_height;
+        let buttons = button_widths.iter()
+            .map(|width| {
+                let rect = Rect::new(x, y, *width, button_height);
+                x += width + spacing;
+                rect
+            })
+            .collect();
+        (panel, buttons)
+    }
+}
+
+/// Panel style used when neither the theme nor the UI file defines the dialog's style
+pub(crate) fn default_dialog_style() -> Style {
+    Style {
+        background: [0.12, 0.12, 0.15, 0.97],
+        border_color: [1.0, 1.0, 1.0, 0.3],
+        border_width: 2.0,
+        padding: 20.0,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,51 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Where a widget sits inside its parent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Anchor point as a fraction of the parent size (0,0 = top-left, 1,1 = bottom-right)
+    pub fn fraction(&self) -> Vec2 {
+        match self {
+            Anchor::TopLeft => Vec2::new(0.0, 0.0),
+            Anchor::Top => Vec2::new(0.5, 0.0),
+            Anchor::TopRight => Vec2::new(1.0, 0.0),
+            Anchor::Left => Vec2::new(0.0, 0.5),
+            Anchor::Center => Vec2::new(0.5, 0.5),
+            Anchor::Right => Vec2::new(1.0, 0.5),
+            Anchor::BottomLeft => Vec2::new(0.0, 1.0),
+            Anchor::Bottom => Vec2::new(0.5, 1.0),
+            Anchor::BottomRight => Vec2::new(1.0, 1.0),
+        }
+    }
+
+    /// Place a box of `size` inside `parent`, shifted by `offset`.
+    /// The same point of the child and parent line up (a Center child is centered).
+    pub fn place(&self, parent: Rect, size: Vec2, offset: Vec2) -> Rect {
+        let fraction = self.fraction();
+        let parent_point = Vec2::new(parent.x, parent.y) + Vec2::new(parent.w, parent.h) * fraction;
+        let position = parent_point - size * fraction + offset;
+        Rect::new(position.x, position.y, size.x, size.y)
+    }
+}
+
+/// Resolve a widget size: zero components fill the available space
+pub fn resolve_size(size: Vec2, available: Vec2) -> Vec2 {
+    Vec2::new(
+        if size.x > 0.0 { size.x } else { available.x },
+        if size.y > 0.0 { size.y } else { available.y },
+    )
+}
@@ -0,0 +1,963 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::input::{Action, InputManager};
+use crate::rendering::with_clip_rect;
+use super::layout::resolve_size;
+use super::scale::ui_scale;
+use super::dialog::{default_dialog_style, Dialog};
+use super::style::Style;
+use super::theme::{draw_nine_slice, Theme, UiSound};
+use super::widget::{ButtonAction, ScreenDef, WidgetDef, WidgetKind};
+
+/// Error returned when loading UI files
+#[derive(Debug)]
+pub enum UiError {
+    Io(std::io::Error),
+    Format(String),
+}
+
+impl fmt::Display for UiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UiError::Io(err) => write!(f, "failed to read UI file: {}", err),
+            UiError::Format(msg) => write!(f, "invalid UI data: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for UiError {}
+
+impl From<std::io::Error> for UiError {
+    fn from(err: std::io::Error) -> Self {
+        UiError::Io(err)
+    }
+}
+
+/// Contents of a UI file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UiDefinition {
+    #[serde(default)]
+    pub styles: HashMap<String, Style>,
+    pub screens: HashMap<String, ScreenDef>,
+}
+
+/// Screens and styles loaded from a RON file, reloadable while the game runs.
+///
+/// ```ron
+/// (
+///     styles: { "title": (font_size: 48.0) },
+///     screens: {
+///         "main": (root: (
+///             kind: Column(spacing: 12.0, children: [
+///                 (kind: Label(text: "My Game"), size: (300.0, 60.0), style: Some("title")),
+///                 (kind: Button(text: "Play", on_press: Action(Custom("play"))), size: (300.0, 48.0)),
+///                 (kind: Button(text: "Options", on_press: Open("options")), size: (300.0, 48.0)),
+///             ]),
+///             anchor: Center,
+///             size: (300.0, 300.0),
+///         )),
+///     },
+/// )
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct UiLibrary {
+    definition: UiDefinition,
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+impl UiLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a UI file and remember it for reloading
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, UiError> {
+        let mut library = Self::new();
+        library.path = Some(path.as_ref().to_path_buf());
+        library.reload()?;
+        Ok(library)
+    }
+
+    /// Parse a UI definition from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, UiError> {
+        let mut library = Self::new();
+        library.definition = parse_definition(data)?;
+        Ok(library)
+    }
+
+    /// Re-read the UI file. On error the current screens are kept.
+    pub fn reload(&mut self) -> Result<(), UiError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        let data = std::fs::read_to_string(path)?;
+        // Remember the timestamp even if parsing fails, so a broken file isn't re-parsed every frame
+        self.modified = modified;
+        self.definition = parse_definition(&data)?;
+        Ok(())
+    }
+
+    /// Reload if the file changed on disk. Returns true if the UI was reloaded.
+    pub fn reload_if_changed(&mut self) -> Result<bool, UiError> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        let modified = std::fs::metadata(path)?.modified().ok();
+        if modified.is_none() || modified == self.modified {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Add or replace a screen from code
+    pub fn register_screen(&mut self, name: &str, screen: ScreenDef) {
+        self.definition.screens.insert(name.to_string(), screen);
+    }
+
+    /// Add or replace a style from code
+    pub fn register_style(&mut self, name: &str, style: Style) {
+        self.definition.styles.insert(name.to_string(), style);
+    }
+
+    pub fn screen(&self, name: &str) -> Option<&ScreenDef> {
+        self.definition.screens.get(name)
+    }
+
+    /// Look up a style, falling back to "default" and then the built-in style
+    pub fn style(&self, name: Option<&str>) -> Style {
+        name.and_then(|name| self.definition.styles.get(name))
+            .or_else(|| self.definition.styles.get("default"))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn definition(&self) -> &UiDefinition {
+        &self.definition
+    }
+}
+
+fn parse_definition(data: &str) -> Result<UiDefinition, UiError> {
+    ron::from_str(data).map_err(|err| UiError::Format(err.to_string()))
+}
+
+// Scroll bars and wheel speed, in pixels
+const SCROLL_BAR_WIDTH: f32 = 6.0;
+const MIN_THUMB_SIZE: f32 = 16.0;
+const WHEEL_SCROLL_SPEED: f32 = 40.0;
+
+/// What activating a focusable node does
+#[derive(Debug, Clone)]
+enum NodePress {
+    Button(ButtonAction),
+    ListItem { list: String, index: usize },
+}
+
+/// A widget after layout, ready to draw
+#[derive(Debug, Clone)]
+struct UiNode {
+    rect: Rect,
+    style: Style,
+    text: Option<String>,
+    press: Option<NodePress>,
+    /// Visible area when inside a scroll view or list
+    clip: Option<Rect>,
+    /// Scroll region the node scrolls with
+    region: Option<usize>,
+}
+
+impl UiNode {
+    fn is_hovered(&self, mouse: Vec2) -> bool {
+        self.rect.contains(mouse) && self.clip.is_none_or(|clip| clip.contains(mouse))
+    }
+}
+
+/// A scroll view or list after layout
+#[derive(Debug, Clone)]
+struct ScrollRegion {
+    key: String,
+    viewport: Rect,
+    content_height: f32,
+    offset: f32,
+    /// Clip of the widget containing the region
+    clip: Option<Rect>,
+    style: Style,
+    /// List id and row height, for scrolling to rows that aren't laid out
+    list: Option<(String, f32)>,
+    /// Scale of the scroll bar
+    scale: f32,
+}
+
+impl ScrollRegion {
+    fn max_offset(&self) -> f32 {
+        (self.content_height - self.viewport.h).max(0.0)
+    }
+
+    /// Track and thumb rects, if the content overflows
+    fn scroll_bar(&self) -> Option<(Rect, Rect)> {
+        if self.max_offset() <= 0.0 {
+            return None;
+        }
+        let viewport = self.viewport;
+        let bar_width = SCROLL_BAR_WIDTH * self.scale;
+        let track = Rect::new(viewport.x + viewport.w - bar_width, viewport.y, bar_width, viewport.h);
+        let min_thumb = (MIN_THUMB_SIZE * self.scale).min(viewport.h);
+        let thumb_height = (viewport.h * viewport.h / self.content_height).clamp(min_thumb, viewport.h);
+        let thumb_y = track.y + (track.h - thumb_height) * self.offset / self.max_offset();
+        Some((track, Rect::new(track.x, thumb_y, track.w, thumb_height)))
+    }
+
+    fn is_hovered(&self, mouse: Vec2) -> bool {
+        self.viewport.contains(mouse) && self.clip.is_none_or(|clip| clip.contains(mouse))
+    }
+}
+
+/// A stack of screens driven by mouse, keyboard and gamepad actions.
+///
+/// Navigate with MoveUp/MoveDown (or MoveLeft/MoveRight), confirm with
+/// Interact, Jump or Enter, and go back with Pause. Scroll views and lists
+/// scroll with the mouse wheel, by dragging their scroll bar, or by moving focus.
+///
+/// ```ignore
+/// let mut menu = Menu::new(UiLibrary::load("assets/ui.ron")?, "main");
+/// menu.set_theme(Theme::load("assets/ui_dark.ron").await?);
+/// menu.set_list_items("inventory", items.iter().map(|item| item.name.clone()).collect());
+/// // each frame
+/// menu.update(&input);
+/// for action in menu.take_actions() { /* start game, quit, ... */ }
+/// for (list, index) in menu.take_selections() { /* use item */ }
+/// menu.draw();
+/// ```
+pub struct Menu {
+    library: UiLibrary,
+    stack: Vec<String>,
+    focus: usize,
+    nodes: Vec<UiNode>,
+    actions: Vec<Action>,
+    text_overrides: HashMap<String, String>,
+    theme: Theme,
+    regions: Vec<ScrollRegion>,
+    scroll_offsets: HashMap<String, f32>,
+    list_items: HashMap<String, Vec<String>>,
+    selections: Vec<(String, usize)>,
+    /// Key of the region whose scroll bar is being dragged
+    dragging: Option<String>,
+    /// Open modal dialogs, topmost last
+    dialogs: Vec<Dialog>,
+}
+
+impl Menu {
+    pub fn new(library: UiLibrary, root_screen: &str) -> Self {
+        let mut menu = Self {
+            library,
+            stack: vec![root_screen.to_string()],
+            focus: 0,
+            nodes: vec![],
+            actions: vec![],
+            text_overrides: HashMap::new(),
+            theme: Theme::default(),
+            regions: vec![],
+            scroll_offsets: HashMap::new(),
+            list_items: HashMap::new(),
+            selections: vec![],
+            dragging: None,
+            dialogs: vec![],
+        };
+        menu.layout();
+        menu
+    }
+
+    /// Logical pixels per UI pixel, which every widget size, font, spacing and hit area is
+    /// scaled by: the game-wide `ui::ui_scale` (which follows `GameConfig::ui_scale`)
+    pub fn scale(&self) -> f32 {
+        ui_scale()
+    }
+
+    pub fn library(&self) -> &UiLibrary {
+        &self.library
+    }
+
+    pub fn library_mut(&mut self) -> &mut UiLibrary {
+        &mut self.library
+    }
+
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn theme_mut(&mut self) -> &mut Theme {
+        &mut self.theme
+    }
+
+    /// Switch the theme; takes effect on the next layout
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.layout();
+    }
+
+    /// Hot-reload the UI file if it changed, keeping the open screens
+    pub fn reload_if_changed(&mut self) -> Result<bool, UiError> {
+        let reloaded = self.library.reload_if_changed()?;
+        if reloaded {
+            self.layout();
+            self.clamp_focus();
+        }
+        Ok(reloaded)
+    }
+
+    /// Open a screen on top of the current one
+    pub fn open(&mut self, screen: &str) {
+        self.stack.push(screen.to_string());
+        self.focus = 0;
+        self.layout();
+    }
+
+    /// Return to the previous screen (the root screen stays open)
+    pub fn back(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+            self.focus = 0;
+            self.layout();
+        }
+    }
+
+    /// Close every screen above the root
+    pub fn back_to_root(&mut self) {
+        self.stack.truncate(1);
+        self.focus = 0;
+        self.layout();
+    }
+
+    /// Name of the screen on top
+    pub fn current_screen(&self) -> &str {
+        self.stack.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// How many screens are open
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Actions from buttons pressed since the last call
+    pub fn take_actions(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.actions)
+    }
+
+    /// List rows chosen since the last call, as (list id, row index)
+    pub fn take_selections(&mut self) -> Vec<(String, usize)> {
+        std::mem::take(&mut self.selections)
+    }
+
+    /// Change the text of a widget with an id (e.g. a volume label)
+    pub fn set_text(&mut self, id: &str, text: &str) {
+        self.text_overrides.insert(id.to_string(), text.to_string());
+        self.layout();
+    }
+
+    /// Go back to the text from the UI file
+    pub fn clear_text(&mut self, id: &str) {
+        self.text_overrides.remove(id);
+        self.layout();
+    }
+
+    /// Set the rows of the list widget with this id
+    pub fn set_list_items(&mut self, id: &str, items: Vec<String>) {
+        self.list_items.insert(id.to_string(), items);
+        self.layout();
+        self.clamp_focus();
+    }
+
+    pub fn list_items(&self, id: &str) -> Option<&[String]> {
+        self.list_items.get(id).map(Vec::as_slice)
+    }
+
+    /// Scroll position of a scroll view or list with this id, in pixels
+    pub fn scroll_offset(&self, id: &str) -> f32 {
+        self.scroll_offsets.get(id).copied().unwrap_or(0.0)
+    }
+
+    /// Scroll a scroll view or list with this id (clamped to its content)
+    pub fn set_scroll_offset(&mut self, id: &str, offset: f32) {
+        self.scroll_offsets.insert(id.to_string(), offset);
+        self.layout();
+    }
+
+    /// Open a modal dialog on top of everything (including other dialogs)
+    pub fn show_dialog(&mut self, mut dialog: Dialog) {
+        dialog.focus = dialog.default_button.min(dialog.buttons.len().saturating_sub(1));
+        self.dialogs.push(dialog);
+    }
+
+    /// Ask a yes/no question; the matching closure runs when the player answers
+    ///
+    /// ```ignore
+    /// menu.confirm("Quit without saving?", move || quit.set(true), || {});
+    /// ```
+    pub fn confirm<Y, N>(&mut self, message: &str, on_yes: Y, on_no: N)
+    where
+        Y: FnOnce() + 'static,
+        N: FnOnce() + 'static,
+    {
+        self.show_dialog(Dialog::confirm(message, on_yes, on_no));
+    }
+
+    /// Check if a dialog is open. Games should ignore gameplay input while it is.
+    pub fn has_dialog(&self) -> bool {
+        !self.dialogs.is_empty()
+    }
+
+    /// Close the topmost dialog without pressing any of its buttons
+    pub fn close_dialog(&mut self) -> Option<Dialog> {
+        self.dialogs.pop()
+    }
+
+    pub fn update(&mut self, input: &InputManager) {
+        // Layout every frame so window resizes apply immediately
+        self.layout();
+
+        // An open dialog captures all input
+        if !self.dialogs.is_empty() {
+            self.update_dialog(input);
+            return;
+        }
+        self.update_scrolling(input);
+
+        let button_count = self.button_count();
+        if button_count == 0 {
+            if input.is_action_just_activated(&Action::Pause) {
+                self.go_back_with_sound();
+            }
+            return;
+        }
+        let previous_focus = self.focus;
+
+        // Keyboard / gamepad navigation
+        let next = input.is_action_just_activated(&Action::MoveDown) || input.is_action_just_activated(&Action::MoveRight);
+        let previous = input.is_action_just_activated(&Action::MoveUp) || input.is_action_just_activated(&Action::MoveLeft);
+        if next || previous {
+            self.move_focus(next);
+            self.reveal_focused();
+        }
+
+        // Mouse hover moves focus only when the mouse moves, so it doesn't fight the keyboard
+        let mouse = input.mouse_position();
+        let hovered = self.buttons().position(|node| node.is_hovered(mouse));
+        if let Some(index) = hovered.filter(|_| input.mouse_delta() != Vec2::ZERO && self.dragging.is_none()) {
+            self.focus = index;
+        }
+
+        let clicked = hovered.is_some() && self.dragging.is_none() && input.is_mouse_button_just_pressed(MouseButton::Left);
+        let confirmed = input.is_action_just_activated(&Action::Interact)
+            || input.is_action_just_activated(&Action::Jump)
+            || input.is_key_just_pressed(KeyCode::Enter);
+
+        if clicked {
+            self.focus = hovered.unwrap_or(self.focus);
+        }
+        if clicked || confirmed {
+            self.theme.play(UiSound::Press);
+            self.press_focused();
+        } else if input.is_action_just_activated(&Action::Pause) {
+            self.go_back_with_sound();
+        } else if self.focus != previous_focus {
+            self.theme.play(UiSound::Focus);
+        }
+    }
+
+    pub fn draw(&self) {
+        if let Some(color) = self.library.screen(self.current_screen()).and_then(|screen| screen.backdrop) {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from(color));
+        }
+
+        let mut button_index = 0;
+        for node in &self.nodes {
+            let focused = node.press.is_some() && button_index == self.focus;
+            if node.press.is_some() {
+                button_index += 1;
+            }
+            match node.clip {
+                Some(clip) => with_clip_rect(clip, || draw_node(node, focused, &self.theme)),
+                None => draw_node(node, focused, &self.theme),
+            }
+        }
+
+        for region in &self.regions {
+            let Some((track, thumb)) = region.scroll_bar() else {
+                continue;
+            };
+            let draw_bar = || {
+                draw_rectangle(track.x, track.y, track.w, track.h, Color::from(region.style.scroll_track_color));
+                draw_rectangle(thumb.x, thumb.y, thumb.w, thumb.h, Color::from(region.style.scroll_thumb_color));
+            };
+            match region.clip {
+                Some(clip) => with_clip_rect(clip, draw_bar),
+                None => draw_bar(),
+            }
+        }
+
+        for (index, dialog) in self.dialogs.iter().enumerate() {
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::from(dialog.dim));
+            let (style, button_style) = self.dialog_styles(dialog);
+            let (panel, buttons) = dialog.layout(&style, &button_style, &self.theme, self.scale());
+            draw_node(&UiNode { rect: panel, style: style.clone(), text: None, press: None, clip: None, region: None }, false, &self.theme);
+
+            let line_height = style.font_size * 1.2;
+            for (line_index, line) in dialog.message.lines().enumerate() {
+                let y = panel.y + style.padding + line_index as f32 * line_height;
+                draw_centered_text(line, Rect::new(panel.x, y, panel.w, line_height), &style, false, &self.theme);
+            }
+
+            // Only the topmost dialog shows focus
+            let is_top = index + 1 == self.dialogs.len();
+            for (button_index, (button, rect)) in dialog.buttons.iter().zip(buttons).enumerate() {
+                let node = UiNode { rect, style: button_style.clone(), text: Some(button.label.clone()), press: None, clip: None, region: None };
+                draw_node(&node, is_top && button_index == dialog.focus, &self.theme);
+            }
+        }
+    }
+
+    fn update_dialog(&mut self, input: &InputManager) {
+        let Some(dialog) = self.dialogs.last() else {
+            return;
+        };
+        let (style, button_style) = self.dialog_styles(dialog);
+        let (_, buttons) = dialog.layout(&style, &button_style, &self.theme, self.scale());
+        let count = buttons.len();
+        let cancel = dialog.cancel_button.filter(|index| *index < count);
+        let mut focus = dialog.focus;
+
+        let next = input.is_action_just_activated(&Action::MoveRight) || input.is_action_just_activated(&Action::MoveDown);
+        let previous = input.is_action_just_activated(&Action::MoveLeft) || input.is_action_just_activated(&Action::MoveUp);
+        if count > 0 && next {
+            focus = (focus + 1) % count;
+        } else if count > 0 && previous {
+            focus = (focus + count - 1) % count;
+        }
+
+        let mouse = input.mouse_position();
+        let hovered = buttons.iter().position(|rect| rect.contains(mouse));
+        if let Some(index) = hovered.filter(|_| input.mouse_delta() != Vec2::ZERO) {
+            focus = index;
+        }
+        let clicked = hovered.is_some() && input.is_mouse_button_just_pressed(MouseButton::Left);
+        if clicked {
+            focus = hovered.unwrap_or(focus);
+        }
+        let confirmed = input.is_action_just_activated(&Action::Interact)
+            || input.is_action_just_activated(&Action::Jump)
+            || input.is_key_just_pressed(KeyCode::Enter);
+
+        if focus != dialog.focus {
+            self.theme.play(UiSound::Focus);
+        }
+        if let Some(dialog) = self.dialogs.last_mut() {
+            dialog.focus = focus;
+        }
+
+        if (clicked || confirmed) && count > 0 {
+            self.theme.play(UiSound::Press);
+            self.press_dialog_button(focus);
+        } else if let Some(cancel) = cancel.filter(|_| input.is_action_just_activated(&Action::Pause)) {
+            self.theme.play(UiSound::Back);
+            self.press_dialog_button(cancel);
+        }
+    }
+
+    /// Close the top dialog, then run the button's callback and menu action
+    fn press_dialog_button(&mut self, index: usize) {
+        let Some(mut dialog) = self.dialogs.pop() else {
+            return;
+        };
+        let Some(button) = dialog.buttons.get_mut(index) else {
+            return;
+        };
+        if let Some(callback) = button.take_callback() {
+            callback();
+        }
+        if let Some(action) = button.action.clone() {
+            self.apply_button_action(action);
+        }
+    }
+
+    fn dialog_styles(&self, dialog: &Dialog) -> (Style, Style) {
+        let style = find_style(&self.library, &self.theme, &dialog.style).unwrap_or_else(default_dialog_style);
+        let button_style = resolve_style(&self.library, &self.theme, Some(&dialog.button_style));
+        (style.scaled(self.scale()), button_style.scaled(self.scale()))
+    }
+
+    /// Mouse wheel over a region and scroll bar dragging
+    fn update_scrolling(&mut self, input: &InputManager) {
+        let mouse = input.mouse_position();
+        let mut scrolled = false;
+
+        // The innermost region under the mouse takes the wheel
+        let wheel = input.scroll_delta().y;
+        let hovered = self.regions.iter().rposition(|region| region.is_hovered(mouse));
+        if let Some(index) = hovered.filter(|_| wheel != 0.0) {
+            scrolled |= self.scroll_region_by(index, -wheel * WHEEL_SCROLL_SPEED * self.scale());
+        }
+
+        if !input.is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        } else if input.is_mouse_button_just_pressed(MouseButton::Left) {
+            self.dragging = self.regions.iter()
+                .rev()
+                .find(|region| region.scroll_bar().is_some_and(|(track, _)| track.contains(mouse)))
+                .map(|region| region.key.clone());
+        }
+
+        let dragged = self.dragging.as_ref()
+            .and_then(|key| self.regions.iter().position(|region| &region.key == key));
+        if let Some(index) = dragged {
+            // Move the content so the thumb follows the mouse
+            let region = &self.regions[index];
+            if let Some((track, thumb)) = region.scroll_bar() {
+                let travel = (track.h - thumb.h).max(1.0);
+                let delta = input.mouse_delta().y * region.max_offset() / travel;
+                scrolled |= self.scroll_region_by(index, delta);
+            }
+        }
+
+        if scrolled {
+            self.layout();
+        }
+    }
+
+    /// Returns true if the offset changed
+    fn scroll_region_by(&mut self, index: usize, delta: f32) -> bool {
+        let region = &self.regions[index];
+        let offset = (region.offset + delta).clamp(0.0, region.max_offset());
+        if offset == region.offset {
+            return false;
+        }
+        self.scroll_offsets.insert(region.key.clone(), offset);
+        true
+    }
+
+    fn move_focus(&mut self, forward: bool) {
+        // Step through every row of a list, not just the ones laid out
+        if let Some(NodePress::ListItem { list, index }) = self.focused_press() {
+            let len = self.list_items.get(&list).map_or(0, Vec::len);
+            let target = if forward { index + 1 } else { index.wrapping_sub(1) };
+            if target < len {
+                self.focus_list_item(&list, target);
+                return;
+            }
+        }
+
+        let button_count = self.button_count();
+        self.focus = if forward {
+            (self.focus + 1) % button_count
+        } else {
+            (self.focus + button_count - 1) % button_count
+        };
+    }
+
+    fn focus_list_item(&mut self, list: &str, index: usize) {
+        let Some(region) = self.regions.iter().find(|region| region.list.as_ref().is_some_and(|(id, _)| id == list)) else {
+            return;
+        };
+        let item_height = region.list.as_ref().map_or(0.0, |(_, height)| *height);
+        let top = index as f32 * item_height;
+        let offset = region.offset.min(top).max(top + item_height - region.viewport.h);
+        self.scroll_offsets.insert(region.key.clone(), offset);
+        self.layout();
+
+        let row = self.buttons().position(|node| {
+            matches!(&node.press, Some(NodePress::ListItem { list: id, index: row }) if id == list && *row == index)
+        });
+        if let Some(row) = row {
+            self.focus = row;
+        }
+    }
+
+    /// Scroll the focused node's region so the node is fully visible
+    fn reveal_focused(&mut self) {
+        let Some(node) = self.buttons().nth(self.focus) else {
+            return;
+        };
+        let Some(region) = node.region.map(|index| &self.regions[index]) else {
+            return;
+        };
+        let viewport = region.viewport;
+        let delta = if node.rect.y < viewport.y {
+            node.rect.y - viewport.y
+        } else if node.rect.y + node.rect.h > viewport.y + viewport.h {
+            (node.rect.y + node.rect.h - viewport.y - viewport.h).min(node.rect.y - viewport.y)
+        } else {
+            0.0
+        };
+        if delta != 0.0 {
+            let index = node.region.unwrap_or_default();
+            if self.scroll_region_by(index, delta) {
+                self.layout();
+            }
+        }
+    }
+
+    fn focused_press(&self) -> Option<NodePress> {
+        self.buttons().nth(self.focus).and_then(|node| node.press.clone())
+    }
+
+    fn clamp_focus(&mut self) {
+        self.focus = self.focus.min(self.button_count().saturating_sub(1));
+    }
+
+    fn go_back_with_sound(&mut self) {
+        if self.stack.len() > 1 {
+            self.theme.play(UiSound::Back);
+        }
+        self.back();
+    }
+
+    fn press_focused(&mut self) {
+        let Some(press) = self.focused_press() else {
+            return;
+        };
+        match press {
+            NodePress::Button(action) => self.apply_button_action(action),
+            NodePress::ListItem { list, index } => self.selections.push((list, index)),
+        }
+    }
+
+    fn apply_button_action(&mut self, action: ButtonAction) {
+        match action {
+            ButtonAction::Action(action) => self.actions.push(action),
+            ButtonAction::Open(screen) => self.open(&screen),
+            ButtonAction::Back => self.back(),
+        }
+    }
+
+    /// Focusable nodes: buttons and list rows
+    fn buttons(&self) -> impl Iterator<Item = &UiNode> {
+        self.nodes.iter().filter(|node| node.press.is_some())
+    }
+
+    fn button_count(&self) -> usize {
+        self.buttons().count()
+    }
+
+    fn layout(&mut self) {
+        self.nodes.clear();
+        self.regions.clear();
+        let Some(screen) = self.library.screen(self.current_screen()) else {
+            return;
+        };
+        let window = Rect::new(0.0, 0.0, screen_width(), screen_height());
+        let mut layout = LayoutContext {
+            library: &self.library,
+            theme: &self.theme,
+            screen: self.current_screen(),
+            text_overrides: &self.text_overrides,
+            list_items: &self.list_items,
+            scroll_offsets: &self.scroll_offsets,
+            scale: self.scale(),
+            nodes: vec![],
+            regions: vec![],
+        };
+        layout.layout_widget(&screen.root, window, None, None, None);
+        let (nodes, regions) = (layout.nodes, layout.regions);
+
+        // Keep offsets clamped to the current content
+        for region in &regions {
+            self.scroll_offsets.insert(region.key.clone(), region.offset);
+        }
+        self.nodes = nodes;
+        self.regions = regions;
+    }
+}
+
+/// State shared while laying out one screen
+struct LayoutContext<'a> {
+    library: &'a UiLibrary,
+    theme: &'a Theme,
+    screen: &'a str,
+    text_overrides: &'a HashMap<String, String>,
+    list_items: &'a HashMap<String, Vec<String>>,
+    scroll_offsets: &'a HashMap<String, f32>,
+    /// Physical pixels per logical pixel of the widget definitions
+    scale: f32,
+    nodes: Vec<UiNode>,
+    regions: Vec<ScrollRegion>,
+}
+
+impl LayoutContext<'_> {
+    /// Lay out a widget inside `parent`. `placed` is set when a column/row already positioned it.
+    fn layout_widget(&mut self, widget: &WidgetDef, parent: Rect, placed: Option<Rect>, clip: Option<Rect>, region: Option<usize>) {
+        let rect = placed.unwrap_or_else(|| {
+            let size = resolve_size(widget.size * self.scale, Vec2::new(parent.w, parent.h));
+            widget.anchor.place(parent, size, widget.offset * self.scale)
+        });
+        let style = resolve_style(self.library, self.theme, widget.style.as_deref()).scaled(self.scale);
+
+        let (text, press) = match &widget.kind {
+            WidgetKind::Label { text } => (Some(text.clone()), None),
+            WidgetKind::Button { text, on_press } => (Some(text.clone()), Some(NodePress::Button(on_press.clone()))),
+            _ => (None, None),
+        };
+        let text = widget.id.as_ref().and_then(|id| self.text_overrides.get(id)).cloned().or(text);
+        self.nodes.push(UiNode { rect, style: style.clone(), text, press, clip, region });
+
+        let inner = Rect::new(
+            rect.x + style.padding,
+            rect.y + style.padding,
+            (rect.w - style.padding * 2.0).max(0.0),
+            (rect.h - style.padding * 2.0).max(0.0),
+        );
+
+        match &widget.kind {
+            WidgetKind::Panel { children } => {
+                for child in children {
+                    self.layout_widget(child, inner, None, clip, region);
+                }
+            }
+            WidgetKind::Column { spacing, children } => {
+                self.layout_column(children, *spacing * self.scale, inner, inner.y, clip, region);
+            }
+            WidgetKind::Row { spacing, children } => {
+                let spacing = spacing * self.scale;
+                let mut x = inner.x;
+                for child in children {
+                    let size = resolve_size(child.size * self.scale, Vec2::new(0.0, inner.h));
+                    let offset = child.offset * self.scale;
+                    let y = inner.y + (inner.h - size.y) * child.anchor.fraction().y;
+                    let child_rect = Rect::new(x + offset.x, y + offset.y, size.x, size.y);
+                    self.layout_widget(child, inner, Some(child_rect), clip, region);
+                    x += size.x + spacing;
+                }
+            }
+            WidgetKind::ScrollView { spacing, children } => {
+                let key = widget.id.clone().unwrap_or_else(|| format!("{}#{}", self.screen, self.regions.len()));
+                let spacing = spacing * self.scale;
+                let content_height = children.iter()
+                    .map(|child| resolve_size(child.size * self.scale, Vec2::new(inner.w, 0.0)).y)
+                    .sum::<f32>() + spacing * children.len().saturating_sub(1) as f32;
+                let index = self.push_region(key, inner, content_height, clip, &style, None);
+
+                let content = self.content_rect(index);
+                let offset = self.regions[index].offset;
+                let child_clip = Some(clip_within(clip, inner));
+                self.layout_column(children, spacing, content, inner.y - offset, child_clip, Some(index));
+            }
+            WidgetKind::List { item_height, item_style } => {
+                // Without an id there's no way to fill the list
+                let Some(id) = &widget.id else {
+                    return;
+                };
+                let items: &[String] = self.list_items.get(id).map(Vec::as_slice).unwrap_or_default();
+                let item_height = (item_height * self.scale).max(1.0);
+                let list = Some((id.clone(), item_height));
+                let index = self.push_region(id.clone(), inner, items.len() as f32 * item_height, clip, &style, list);
+
+                let content = self.content_rect(index);
+                let offset = self.regions[index].offset;
+                let row_style = resolve_style(self.library, self.theme, item_style.as_deref()).scaled(self.scale);
+                let child_clip = Some(clip_within(clip, inner));
+                let first = (offset / item_height) as usize;
+                let last = (((offset + inner.h) / item_height).ceil() as usize).min(items.len());
+                for (row, item) in items.iter().enumerate().take(last).skip(first) {
+                    let y = inner.y + row as f32 * item_height - offset;
+                    self.nodes.push(UiNode {
+                        rect: Rect::new(content.x, y, content.w, item_height),
+                        style: row_style.clone(),
+                        text: Some(item.clone()),
+                        press: Some(NodePress::ListItem { list: id.clone(), index: row }),
+                        clip: child_clip,
+                        region: Some(index),
+                    });
+                }
+            }
+            WidgetKind::Label { .. } | WidgetKind::Button { .. } => {}
+        }
+    }
+
+    /// Stack children top to bottom from `top` (anchors align them horizontally)
+    fn layout_column(&mut self, children: &[WidgetDef], spacing: f32, area: Rect, top: f32, clip: Option<Rect>, region: Option<usize>) {
+        let mut y = top;
+        for child in children {
+            let size = resolve_size(child.size * self.scale, Vec2::new(area.w, 0.0));
+            let offset = child.offset * self.scale;
+            let x = area.x + (area.w - size.x) * child.anchor.fraction().x;
+            let child_rect = Rect::new(x + offset.x, y + offset.y, size.x, size.y);
+            self.layout_widget(child, area, Some(child_rect), clip, region);
+            y += size.y + spacing;
+        }
+    }
+
+    fn push_region(&mut self, key: String, viewport: Rect, content_height: f32, clip: Option<Rect>, style: &Style, list: Option<(String, f32)>) -> usize {
+        let mut region = ScrollRegion { key, viewport, content_height, offset: 0.0, clip, style: style.clone(), list, scale: self.scale };
+        region.offset = self.scroll_offsets.get(&region.key).copied().unwrap_or(0.0).clamp(0.0, region.max_offset());
+        self.regions.push(region);
+        self.regions.len() - 1
+    }
+
+    /// A region's viewport minus its scroll bar
+    fn content_rect(&self, index: usize) -> Rect {
+        let region = &self.regions[index];
+        let bar = if region.scroll_bar().is_some() { SCROLL_BAR_WIDTH * region.scale } else { 0.0 };
+        Rect::new(region.viewport.x, region.viewport.y, (region.viewport.w - bar).max(0.0), region.viewport.h)
+    }
+}
+
+/// `rect` limited to the enclosing clip
+fn clip_within(clip: Option<Rect>, rect: Rect) -> Rect {
+    match clip {
+        Some(clip) => clip.intersect(rect).unwrap_or(Rect::new(rect.x, rect.y, 0.0, 0.0)),
+        None => rect,
+    }
+}
+
+/// Find a widget's style: theme before UI file, then each one's "default", then the built-in style
+fn resolve_style(library: &UiLibrary, theme: &Theme, name: Option<&str>) -> Style {
+    name.and_then(|name| find_style(library, theme, name))
+        .or_else(|| find_style(library, theme, "default"))
+        .unwrap_or_default()
+}
+
+/// A named style from the theme or else the UI file, without fallbacks
+fn find_style(library: &UiLibrary, theme: &Theme, name: &str) -> Option<Style> {
+    theme.style(name).or_else(|| library.definition().styles.get(name)).cloned()
+}
+
+fn draw_node(node: &UiNode, focused: bool, theme: &Theme) {
+    let rect = node.rect;
+    let style = &node.style;
+
+    let nine_slice = style.nine_slice.as_ref()
+        .and_then(|slice| theme.texture(slice.texture(focused)).map(|texture| (texture, slice.margins)));
+    if let Some((texture, margins)) = nine_slice {
+        draw_nine_slice(texture, rect, margins, WHITE);
+    } else {
+        let background = style.background(focused);
+        if background.a > 0.0 {
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, background);
+        }
+    }
+    if style.border_width > 0.0 {
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, style.border_width, style.border_color());
+    }
+
+    if let Some(text) = &node.text {
+        draw_centered_text(text, rect, style, focused, theme);
+    }
+}
+
+fn draw_centered_text(text: &str, rect: Rect, style: &Style, focused: bool, theme: &Theme) {
+    let font = style.font.as_deref().and_then(|name| theme.font(name));
+    let font_size = style.font_size as u16;
+    let dimensions = measure_text(text, font, font_size, 1.0);
+    let x = rect.x + (rect.w - dimensions.width) * 0.5;
+    let y = rect.y + (rect.h - dimensions.height) * 0.5 + dimensions.offset_y;
+    draw_text_ex(text, x, y, TextParams {
+        font,
+        font_size,
+        color: style.text_color(focused),
+        ..Default::default()
+    });
+}
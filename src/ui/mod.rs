@@ -0,0 +1,23 @@
+pub mod layout;
+pub mod scale;
+pub mod style;
+pub mod widget;
+pub mod menu;
+pub mod theme;
+pub mod dialog;
+pub mod radial;
+pub mod cheats;
+pub mod cursor;
+
+pub use layout::Anchor;
+pub use scale::{
+    display_scale, from_ui, logical_screen_size, physical_screen_size, set_ui_scale, to_logical, to_physical, to_ui, ui_scale,
+};
+pub use style::{NineSlice, Style};
+pub use widget::{ButtonAction, ScreenDef, WidgetDef, WidgetKind};
+pub use menu::{Menu, UiDefinition, UiError, UiLibrary};
+pub use theme::{draw_nine_slice, Theme, ThemeDef, UiSound, UiSounds};
+pub use dialog::{Dialog, DialogButton};
+pub use radial::{RadialItem, RadialMenu};
+pub use cheats::CheatMenu;
+pub use cursor::{Cursor, CursorManager, CursorSprite, SystemCursor};
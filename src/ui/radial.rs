@@ -0,0 +1,292 @@
+use macroquad::prelude::*;
+use std::f32::consts::{FRAC_PI_2, TAU};
+use crate::input::{Action, InputManager};
+use super::scale::ui_scale;
+use super::style::Style;
+use super::theme::{Theme, UiSound};
+
+// Triangles per segment edge, and the angular gap between segments (radians)
+const ARC_STEPS: usize = 8;
+const SEGMENT_GAP: f32 = 0.03;
+
+/// One segment of a radial menu
+#[derive(Debug, Clone)]
+pub struct RadialItem {
+    pub label: String,
+    pub icon: Option<Texture2D>,
+    /// Disabled items can be highlighted but not selected
+    pub enabled: bool,
+}
+
+impl RadialItem {
+    pub fn new(label: &str) -> Self {
+        Self { label: label.to_string(), icon: None, enabled: true }
+    }
+
+    pub fn with_icon(mut self, icon: Texture2D) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// A wheel of items opened by holding an action (weapon or emote selection).
+///
+/// While open, the stick, movement keys or mouse direction highlights a segment;
+/// releasing the action selects it. Pause closes the wheel without selecting.
+/// Segments start at the top and go clockwise.
+///
+/// ```ignore
+/// let mut wheel = RadialMenu::new(Action::custom("weapon_wheel"), vec![
+///     RadialItem::new("Sword"),
+///     RadialItem::new("Bow"),
+///     RadialItem::new("Bombs").with_enabled(bombs > 0),
+/// ]);
+/// // each frame
+/// wheel.update(&input);
+/// if let Some(index) = wheel.take_selection() { equip(index); }
+/// wheel.draw();
+/// ```
+pub struct RadialMenu {
+    items: Vec<RadialItem>,
+    open_action: Action,
+    is_open: bool,
+    highlighted: Option<usize>,
+    selection: Option<usize>,
+    /// Screen position of the wheel (None = window center)
+    center: Option<Vec2>,
+    radius: f32,
+    inner_radius: f32,
+    /// Analog stick direction fed by a gamepad backend
+    stick: Vec2,
+    stick_dead_zone: f32,
+    style: Style,
+    pending_sound: Option<UiSound>,
+}
+
+impl RadialMenu {
+    pub fn new(open_action: Action, items: Vec<RadialItem>) -> Self {
+        Self {
+            items,
+            open_action,
+            is_open: false,
+            highlighted: None,
+            selection: None,
+            center: None,
+            radius: 160.0,
+            inner_radius: 50.0,
+            stick: Vec2::ZERO,
+            stick_dead_zone: 0.4,
+            style: Style {
+                background: [0.1, 0.1, 0.12, 0.85],
+                background_focused: [0.3, 0.3, 0.4, 0.95],
+                font_size: 20.0,
+                ..Default::default()
+            },
+            pending_sound: None,
+        }
+    }
+
+    /// Outer and inner radius in UI pixels (scaled by `ui::ui_scale`, like the font)
+    pub fn with_radius(mut self, radius: f32, inner_radius: f32) -> Self {
+        self.radius = radius;
+        self.inner_radius = inner_radius.min(radius);
+        self
+    }
+
+    pub fn with_center(mut self, center: Vec2) -> Self {
+        self.center = Some(center);
+        self
+    }
+
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn items(&self) -> &[RadialItem] {
+        &self.items
+    }
+
+    /// Replace the items (closes the wheel if it was open)
+    pub fn set_items(&mut self, items: Vec<RadialItem>) {
+        self.items = items;
+        self.close();
+    }
+
+    pub fn set_item_enabled(&mut self, index: usize, enabled: bool) {
+        if let Some(item) = self.items.get_mut(index) {
+            item.enabled = enabled;
+        }
+    }
+
+    /// Feed an analog stick direction (-1..1 per axis, y down); used over keys and mouse
+    pub fn set_stick(&mut self, direction: Vec2) {
+        self.stick = direction;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// The segment under the current direction
+    pub fn highlighted(&self) -> Option<usize> {
+        self.highlighted
+    }
+
+    /// The item selected since the last call (returns it only once)
+    pub fn take_selection(&mut self) -> Option<usize> {
+        self.selection.take()
+    }
+
+    pub fn center(&self) -> Vec2 {
+        self.center.unwrap_or_else(|| Vec2::new(screen_width() * 0.5, screen_height() * 0.5))
+    }
+
+    pub fn update(&mut self, input: &InputManager) {
+        if !self.is_open {
+            if input.is_action_just_activated(&self.open_action) && !self.items.is_empty() {
+                self.is_open = true;
+                self.highlighted = None;
+            }
+            return;
+        }
+
+        if input.is_action_just_activated(&Action::Pause) {
+            self.close();
+            self.pending_sound = Some(UiSound::Back);
+            return;
+        }
+
+        // The highlight sticks when the direction returns to center, so a stick can be let go first
+        if let Some(index) = self.direction(input).and_then(|direction| self.segment_at(direction)) {
+            if self.highlighted != Some(index) {
+                self.pending_sound = Some(UiSound::Focus);
+            }
+            self.highlighted = Some(index);
+        }
+
+        if !input.is_action_active(&self.open_action) {
+            let selected = self.highlighted.filter(|index| self.items[*index].enabled);
+            self.close();
+            if selected.is_some() {
+                self.selection = selected;
+                self.pending_sound = Some(UiSound::Press);
+            }
+        }
+    }
+
+    /// Play the sound for what happened during the last `update` through a theme
+    pub fn play_sounds(&mut self, theme: &Theme) {
+        if let Some(sound) = self.pending_sound.take() {
+            theme.play(sound);
+        }
+    }
+
+    pub fn draw(&self) {
+        self.draw_with_font(None);
+    }
+
+    /// Draw using the theme's fonts
+    pub fn draw_with_theme(&self, theme: &Theme) {
+        self.draw_with_font(self.style.font.as_deref().and_then(|name| theme.font(name)));
+    }
+
+    fn draw_with_font(&self, font: Option<&Font>) {
+        if !self.is_open || self.items.is_empty() {
+            return;
+        }
+        let center = self.center();
+        let segment = TAU / self.items.len() as f32;
+        let (inner_radius, radius) = self.radii();
+        let style = self.style.scaled(ui_scale());
+
+        for (index, item) in self.items.iter().enumerate() {
+            let highlighted = self.highlighted == Some(index);
+            let start = index as f32 * segment - segment * 0.5 + SEGMENT_GAP;
+            let end = start + segment - SEGMENT_GAP * 2.0;
+            let mut color = style.background(highlighted);
+            if !item.enabled {
+                color.a *= 0.5;
+            }
+            draw_ring_segment(center, inner_radius, radius, start, end, color);
+
+            let middle = center + wheel_direction(index as f32 * segment) * (inner_radius + radius) * 0.5;
+            let mut text_color = style.text_color(highlighted);
+            if !item.enabled {
+                text_color.a *= 0.4;
+            }
+            let font_size = style.font_size as u16;
+            let dimensions = measure_text(&item.label, font, font_size, 1.0);
+            let mut label_y = middle.y + dimensions.offset_y * 0.5;
+
+            if let Some(icon) = &item.icon {
+                let size = (radius - inner_radius) * 0.4;
+                let tint = if item.enabled { WHITE } else { Color::new(1.0, 1.0, 1.0, 0.4) };
+                draw_texture_ex(icon, middle.x - size * 0.5, middle.y - size * 0.75, tint, DrawTextureParams {
+                    dest_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                });
+                label_y = middle.y + size * 0.25 + dimensions.offset_y;
+            }
+            draw_text_ex(&item.label, middle.x - dimensions.width * 0.5, label_y, TextParams {
+                font,
+                font_size,
+                color: text_color,
+                ..Default::default()
+            });
+        }
+    }
+
+    fn close(&mut self) {
+        self.is_open = false;
+        self.highlighted = None;
+    }
+
+    /// Direction from the stick, then movement actions, then the mouse
+    fn direction(&self, input: &InputManager) -> Option<Vec2> {
+        if self.stick.length() >= self.stick_dead_zone {
+            return Some(self.stick);
+        }
+        let movement = input.get_movement_input();
+        if movement != Vec2::ZERO {
+            return Some(movement);
+        }
+        let offset = input.mouse_position() - self.center();
+        (offset.length() >= self.radii().0).then_some(offset)
+    }
+
+    /// Inner and outer radius on screen
+    fn radii(&self) -> (f32, f32) {
+        (self.inner_radius * ui_scale(), self.radius * ui_scale())
+    }
+
+    fn segment_at(&self, direction: Vec2) -> Option<usize> {
+        if self.items.is_empty() || direction == Vec2::ZERO {
+            return None;
+        }
+        // Angle clockwise from straight up
+        let angle = (direction.to_angle() + FRAC_PI_2).rem_euclid(TAU);
+        let segment = TAU / self.items.len() as f32;
+        Some(((angle + segment * 0.5) / segment) as usize % self.items.len())
+    }
+}
+
+/// Unit vector for an angle measured clockwise from straight up
+fn wheel_direction(angle: f32) -> Vec2 {
+    Vec2::from_angle(angle - FRAC_PI_2)
+}
+
+fn draw_ring_segment(center: Vec2, inner: f32, outer: f32, start: f32, end: f32, color: Color) {
+    let step = (end - start) / ARC_STEPS as f32;
+    for i in 0..ARC_STEPS {
+        let a = wheel_direction(start + step * i as f32);
+        let b = wheel_direction(start + step * (i + 1) as f32);
+        draw_triangle(center + a * inner, center + a * outer, center + b * outer, color);
+        draw_triangle(center + a * inner, center + b * outer, center + b * inner, color);
+    }
+}
@@ -0,0 +1,60 @@
+use macroquad::prelude::*;
+use macroquad::miniquad::window::dpi_scale;
+use std::cell::Cell;
+
+thread_local! {
+    static UI_SCALE: Cell<f32> = const { Cell::new(1.0) };
+}
+
+/// Physical pixels per logical pixel of the display: 2.0 on most high-DPI screens, 1.0
+/// unless `GameConfig::high_dpi` is set.
+///
+/// macroquad's screen sizes, mouse positions and drawing are in logical pixels; physical
+/// pixels are what the framebuffer (e.g. a scissor rect or a screenshot) uses.
+pub fn display_scale() -> f32 {
+    dpi_scale().max(f32::EPSILON)
+}
+
+/// Scale of the UI drawn in screen space (menus, the cheat and radial menus, custom
+/// cursors): `GameConfig::ui_scale`, which `Game` applies (see `Game::set_ui_scale`).
+///
+/// Widget definitions are in UI pixels, laid out at this many logical pixels each. It's a
+/// player preference on top of the display's DPI, which macroquad already handles.
+pub fn ui_scale() -> f32 {
+    UI_SCALE.with(Cell::get)
+}
+
+/// Change the UI scale (at least 0.1); `Game::set_ui_scale` also keeps the config in step
+pub fn set_ui_scale(scale: f32) {
+    UI_SCALE.with(|cell| cell.set(scale.max(0.1)));
+}
+
+/// Window size in logical pixels, like `screen_width` and `screen_height`
+pub fn logical_screen_size() -> Vec2 {
+    Vec2::new(screen_width(), screen_height())
+}
+
+/// Window size in physical pixels
+pub fn physical_screen_size() -> Vec2 {
+    to_physical(logical_screen_size())
+}
+
+/// Convert a physical pixel position to logical pixels
+pub fn to_logical(physical: Vec2) -> Vec2 {
+    physical / display_scale()
+}
+
+/// Convert a logical pixel position (e.g. `InputManager::mouse_position`) to physical pixels
+pub fn to_physical(logical: Vec2) -> Vec2 {
+    logical * display_scale()
+}
+
+/// Convert a logical pixel position to UI pixels, undoing `ui_scale`
+pub fn to_ui(logical: Vec2) -> Vec2 {
+    logical / ui_scale()
+}
+
+/// Convert a position in UI pixels to logical pixels
+pub fn from_ui(ui: Vec2) -> Vec2 {
+    ui * ui_scale()
+}
@@ -0,0 +1,90 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Visual style of a widget. Missing fields in data files use the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Style {
+    /// RGBA colors
+    pub background: [f32; 4],
+    pub background_focused: [f32; 4],
+    pub text_color: [f32; 4],
+    pub text_color_focused: [f32; 4],
+    pub border_color: [f32; 4],
+    /// 0 = no border
+    pub border_width: f32,
+    pub font_size: f32,
+    /// Space between the widget's edge and its content
+    pub padding: f32,
+    /// Theme font name (None = macroquad's built-in font)
+    pub font: Option<String>,
+    /// Textured background drawn instead of `background`
+    pub nine_slice: Option<NineSlice>,
+    /// Scroll bar track and thumb of scroll views and lists
+    pub scroll_track_color: [f32; 4],
+    pub scroll_thumb_color: [f32; 4],
+}
+
+/// A texture stretched over a widget with its corners kept intact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NineSlice {
+    /// Theme texture name
+    pub texture: String,
+    /// Texture used while the widget is focused
+    #[serde(default)]
+    pub focused_texture: Option<String>,
+    /// Corner sizes in pixels: left, top, right, bottom
+    pub margins: [f32; 4],
+}
+
+impl NineSlice {
+    pub fn texture(&self, focused: bool) -> &str {
+        match &self.focused_texture {
+            Some(texture) if focused => texture,
+            _ => &self.texture,
+        }
+    }
+}
+
+impl Style {
+    pub fn background(&self, focused: bool) -> Color {
+        Color::from(if focused { self.background_focused } else { self.background })
+    }
+
+    pub fn text_color(&self, focused: bool) -> Color {
+        Color::from(if focused { self.text_color_focused } else { self.text_color })
+    }
+
+    pub fn border_color(&self) -> Color {
+        Color::from(self.border_color)
+    }
+
+    /// This style with its font size, padding and border multiplied by `scale`
+    pub fn scaled(&self, scale: f32) -> Style {
+        Style {
+            font_size: self.font_size * scale,
+            padding: self.padding * scale,
+            border_width: self.border_width * scale,
+            ..self.clone()
+        }
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            background: [0.0, 0.0, 0.0, 0.0],
+            background_focused: [0.25, 0.25, 0.3, 0.9],
+            text_color: [0.9, 0.9, 0.9, 1.0],
+            text_color_focused: [1.0, 0.85, 0.3, 1.0],
+            border_color: [1.0, 1.0, 1.0, 0.3],
+            border_width: 0.0,
+            font_size: 24.0,
+            padding: 8.0,
+            font: None,
+            nine_slice: None,
+            scroll_track_color: [1.0, 1.0, 1.0, 0.1],
+            scroll_thumb_color: [1.0, 1.0, 1.0, 0.5],
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use macroquad::audio::{load_sound, play_sound_once, Sound};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use super::menu::UiError;
+use super::style::Style;
+
+/// Interface sounds a theme can provide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiSound {
+    /// Focus moved to another button
+    Focus,
+    /// A button was pressed
+    Press,
+    /// Went back a screen
+    Back,
+}
+
+/// Sound file paths of a theme
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSounds {
+    pub focus: Option<String>,
+    pub press: Option<String>,
+    pub back: Option<String>,
+}
+
+/// Contents of a theme file. Assets are referenced by path and loaded by `Theme::load`.
+///
+/// ```ron
+/// (
+///     styles: {
+///         "default": (text_color: (0.1, 0.1, 0.1, 1.0), font: Some("body")),
+///         "button": (nine_slice: Some((texture: "button", focused_texture: Some("button_hover"), margins: (8.0, 8.0, 8.0, 8.0)))),
+///     },
+///     fonts: { "body": "assets/ui/body.ttf" },
+///     textures: { "button": "assets/ui/button.png", "button_hover": "assets/ui/button_hover.png" },
+///     sounds: (focus: Some("assets/ui/tick.wav"), press: Some("assets/ui/click.wav")),
+/// )
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeDef {
+    pub styles: HashMap<String, Style>,
+    /// Font name -> TTF path
+    pub fonts: HashMap<String, String>,
+    /// Texture name -> image path
+    pub textures: HashMap<String, String>,
+    pub sounds: UiSounds,
+}
+
+impl ThemeDef {
+    /// Parse a theme definition from a RON string
+    pub fn load_from_str(data: &str) -> Result<Self, UiError> {
+        ron::from_str(data).map_err(|err| UiError::Format(err.to_string()))
+    }
+}
+
+/// Styles, fonts, textures and sounds shared by every widget of a `Menu`.
+///
+/// Theme styles take priority over same-named styles in the UI file, so swapping
+/// the theme at runtime (light/dark, per-game skins) restyles every screen.
+#[derive(Clone, Default)]
+pub struct Theme {
+    def: ThemeDef,
+    fonts: HashMap<String, Font>,
+    textures: HashMap<String, Texture2D>,
+    sounds: HashMap<UiSound, Sound>,
+}
+
+impl Theme {
+    /// A theme from a definition, without loading its assets
+    pub fn new(def: ThemeDef) -> Self {
+        Self { def, ..Default::default() }
+    }
+
+    /// Built-in dark theme
+    pub fn dark() -> Self {
+        let mut theme = Self::default();
+        theme.register_style("default", Style::default());
+        theme
+    }
+
+    /// Built-in light theme
+    pub fn light() -> Self {
+        let mut theme = Self::default();
+        theme.register_style("default", Style {
+            background: [0.0, 0.0, 0.0, 0.0],
+            background_focused: [0.8, 0.85, 0.95, 0.95],
+            text_color: [0.15, 0.15, 0.2, 1.0],
+            text_color_focused: [0.1, 0.3, 0.8, 1.0],
+            border_color: [0.0, 0.0, 0.0, 0.3],
+            ..Default::default()
+        });
+        theme
+    }
+
+    /// Load a theme file and every asset it references
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Self, UiError> {
+        let data = std::fs::read_to_string(path)?;
+        let mut theme = Self::new(ThemeDef::load_from_str(&data)?);
+        theme.load_assets().await?;
+        Ok(theme)
+    }
+
+    /// Load the fonts, textures and sounds listed in the definition
+    pub async fn load_assets(&mut self) -> Result<(), UiError> {
+        for (name, path) in &self.def.fonts {
+            let font = load_ttf_font(path).await.map_err(|err| asset_error(path, err))?;
+            self.fonts.insert(name.clone(), font);
+        }
+        for (name, path) in &self.def.textures {
+            let texture = load_texture(path).await.map_err(|err| asset_error(path, err))?;
+            self.textures.insert(name.clone(), texture);
+        }
+
+        let sounds = [
+            (UiSound::Focus, &self.def.sounds.focus),
+            (UiSound::Press, &self.def.sounds.press),
+            (UiSound::Back, &self.def.sounds.back),
+        ];
+        for (kind, path) in sounds {
+            if let Some(path) = path {
+                let sound = load_sound(path).await.map_err(|err| asset_error(path, err))?;
+                self.sounds.insert(kind, sound);
+            }
+        }
+        Ok(())
+    }
+
+    /// Add or replace a style from code
+    pub fn register_style(&mut self, name: &str, style: Style) {
+        self.def.styles.insert(name.to_string(), style);
+    }
+
+    pub fn register_font(&mut self, name: &str, font: Font) {
+        self.fonts.insert(name.to_string(), font);
+    }
+
+    pub fn register_texture(&mut self, name: &str, texture: Texture2D) {
+        self.textures.insert(name.to_string(), texture);
+    }
+
+    pub fn register_sound(&mut self, kind: UiSound, sound: Sound) {
+        self.sounds.insert(kind, sound);
+    }
+
+    pub fn style(&self, name: &str) -> Option<&Style> {
+        self.def.styles.get(name)
+    }
+
+    pub fn font(&self, name: &str) -> Option<&Font> {
+        self.fonts.get(name)
+    }
+
+    pub fn texture(&self, name: &str) -> Option<&Texture2D> {
+        self.textures.get(name)
+    }
+
+    /// Play one of the theme's sounds, if it has one
+    pub fn play(&self, kind: UiSound) {
+        if let Some(sound) = self.sounds.get(&kind) {
+            play_sound_once(sound);
+        }
+    }
+
+    pub fn definition(&self) -> &ThemeDef {
+        &self.def
+    }
+}
+
+fn asset_error(path: &str, err: impl std::fmt::Display) -> UiError {
+    UiError::Format(format!("failed to load '{}': {}", path, err))
+}
+
+/// Draw a texture over `rect`, stretching the edges and center but keeping the
+/// corners (`margins`: left, top, right, bottom) at their original size
+pub fn draw_nine_slice(texture: &Texture2D, rect: Rect, margins: [f32; 4], color: Color) {
+    let [left, top, right, bottom] = margins;
+    let (width, height) = (texture.width(), texture.height());
+    let source_x = [0.0, left, width - right, width];
+    let source_y = [0.0, top, height - bottom, height];
+    let dest_x = [rect.x, rect.x + left, rect.x + rect.w - right, rect.x + rect.w];
+    let dest_y = [rect.y, rect.y + top, rect.y + rect.h - bottom, rect.y + rect.h];
+
+    for row in 0..3 {
+        for column in 0..3 {
+            let dest_size = Vec2::new(dest_x[column + 1] - dest_x[column], dest_y[row + 1] - dest_y[row]);
+            if dest_size.x <= 0.0 || dest_size.y <= 0.0 {
+                continue;
+            }
+            let source = Rect::new(
+                source_x[column],
+                source_y[row],
+                source_x[column + 1] - source_x[column],
+                source_y[row + 1] - source_y[row],
+            );
+            draw_texture_ex(texture, dest_x[column], dest_y[row], color, DrawTextureParams {
+                dest_size: Some(dest_size),
+                source: Some(source),
+                ..Default::default()
+            });
+        }
+    }
+}
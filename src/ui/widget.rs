@@ -0,0 +1,127 @@
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::input::Action;
+use super::layout::Anchor;
+
+/// What pressing a button does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ButtonAction {
+    /// Report a game action (read with `Menu::take_actions`)
+    Action(Action),
+    /// Open another screen on top of this one
+    Open(String),
+    /// Return to the previous screen
+    Back,
+}
+
+/// The kind of a widget and its content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum WidgetKind {
+    /// Container placing each child by its own anchor
+    Panel { children: Vec<WidgetDef> },
+    /// Container stacking children top to bottom (anchors align them horizontally)
+    Column {
+        #[serde(default)]
+        spacing: f32,
+        children: Vec<WidgetDef>,
+    },
+    /// Container stacking children left to right (anchors align them vertically)
+    Row {
+        #[serde(default)]
+        spacing: f32,
+        children: Vec<WidgetDef>,
+    },
+    /// Column that scrolls vertically (mouse wheel, scroll bar drag, or moving focus)
+    /// when its children don't fit
+    ScrollView {
+        #[serde(default)]
+        spacing: f32,
+        children: Vec<WidgetDef>,
+    },
+    /// Scrolling list of text rows filled from code with `Menu::set_list_items`.
+    /// Needs an `id`. Only the visible rows are laid out, so long lists stay cheap.
+    List {
+        item_height: f32,
+        /// Style of each row (falls back to "default")
+        #[serde(default)]
+        item_style: Option<String>,
+    },
+    Label { text: String },
+    Button { text: String, on_press: ButtonAction },
+}
+
+/// One node of a declarative widget tree
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WidgetDef {
+    pub kind: WidgetKind,
+    #[serde(default)]
+    pub anchor: Anchor,
+    #[serde(default)]
+    pub offset: Vec2,
+    /// Size in pixels; 0 on an axis fills the parent
+    #[serde(default)]
+    pub size: Vec2,
+    /// Named style from the UI file (falls back to "default")
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Optional name for finding the widget from code
+    #[serde(default)]
+    pub id: Option<String>,
+}
+
+impl WidgetDef {
+    pub fn new(kind: WidgetKind) -> Self {
+        Self {
+            kind,
+            anchor: Anchor::TopLeft,
+            offset: Vec2::ZERO,
+            size: Vec2::ZERO,
+            style: None,
+            id: None,
+        }
+    }
+
+    pub fn with_anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn with_size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_style(mut self, style: &str) -> Self {
+        self.style = Some(style.to_string());
+        self
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn children(&self) -> &[WidgetDef] {
+        match &self.kind {
+            WidgetKind::Panel { children }
+            | WidgetKind::Column { children, .. }
+            | WidgetKind::Row { children, .. }
+            | WidgetKind::ScrollView { children, .. } => children,
+            WidgetKind::List { .. } | WidgetKind::Label { .. } | WidgetKind::Button { .. } => &[],
+        }
+    }
+}
+
+/// A full screen: a widget tree plus an optional backdrop color
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenDef {
+    pub root: WidgetDef,
+    /// RGBA color drawn over the whole window behind the screen
+    #[serde(default)]
+    pub backdrop: Option<[f32; 4]>,
+}